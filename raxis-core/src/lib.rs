@@ -1,6 +1,7 @@
+#[cfg(windows)]
 use windows_numerics::Vector2;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PathCommand {
     MoveTo {
         x: f32,
@@ -39,7 +40,7 @@ pub enum PathCommand {
     },
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SvgPathCommands {
     Path(&'static [PathCommand]),
     Circle {
@@ -57,7 +58,7 @@ pub enum SvgPathCommands {
     },
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct SvgPathList {
     pub paths: &'static [SvgPathCommands],
 }
@@ -71,6 +72,7 @@ macro_rules! svg {
     };
 }
 
+#[cfg(windows)]
 impl SvgPathList {
     /// Creates a Direct2D path geometry from this SVG path
     pub fn create_geometry(
@@ -740,10 +740,8 @@ fn generate_trait_impl(
     // Generate input_padding method if an expression is specified
     let input_padding_impl = if let Some(expr_str) = &attrs.input_padding_field {
         // Parse the expression string as a Rust expression
-        let expr: syn::Expr = syn::parse_str(expr_str).expect(&format!(
-            "Failed to parse input_padding expression: {}",
-            expr_str
-        ));
+        let expr: syn::Expr = syn::parse_str(expr_str)
+            .unwrap_or_else(|_| panic!("Failed to parse input_padding expression: {}", expr_str));
         quote! {
             fn input_padding(&self) -> f32 {
                 #expr
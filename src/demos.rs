@@ -0,0 +1,502 @@
+//! Views from the demo application.
+//!
+//! They live in the library so the golden snapshot tests can render exactly
+//! what the demo shows, on every target.
+
+use std::time::Duration;
+
+#[cfg(windows)]
+use crate::gfx::effects::builtins::{BoxBlurEffect, LiquidGlassEffect};
+use crate::{
+    HookManager,
+    layout::model::{
+        Alignment, BackdropFilter, Border, BorderPlacement, BorderRadius, BoxAmount, Color,
+        Direction, Element, Sizing, StrokeDashStyle, StrokeLineCap,
+    },
+    math::easing::Easing,
+    util::unique::combine_id,
+    w_id,
+    widgets::{
+        text::{ParagraphAlignment, Text, TextAlignment},
+        toggle::Toggle,
+        widget,
+    },
+};
+
+fn demo_box<Message: 'static + Send>(
+    label: &'static str,
+    border: Border,
+    radius: Option<BorderRadius>,
+) -> Element<Message> {
+    Element {
+        id: Some(combine_id(w_id!(), label)),
+        width: Sizing::grow(), //Sizing::fixed(160.0),
+        height: Sizing::fixed(80.0),
+        background_color: Some(0xFAFAFAFF.into()),
+        padding: BoxAmount::all(8.0),
+        border: Some(border),
+        border_radius: radius,
+        cross_align_self: Some(Alignment::Center),
+        content: widget(Text::new(label).with_paragraph_alignment(ParagraphAlignment::Center)),
+        ..Default::default()
+    }
+}
+
+/// Border placements and dash styles.
+pub fn border_demos<Message: 'static + Send>() -> Element<Message> {
+    let inset = Border {
+        width: 4.0,
+        color: Color::from(0x1976D2FF),
+        placement: BorderPlacement::Inset,
+        ..Default::default()
+    };
+    let center = Border {
+        width: 6.0,
+        color: Color::from(0xE53935FF),
+        placement: BorderPlacement::Center,
+        ..Default::default()
+    };
+    let outset = Border {
+        width: 8.0,
+        color: Color::from(0xFB8C00FF),
+        placement: BorderPlacement::Outset,
+        ..Default::default()
+    };
+
+    let dashed = Border {
+        width: 3.0,
+        color: Color::from(0x424242FF),
+        placement: BorderPlacement::Center,
+        dash_style: Some(StrokeDashStyle::Dash),
+        dash_cap: StrokeLineCap::Round,
+        ..Default::default()
+    };
+    let dotted = Border {
+        width: 3.0,
+        color: Color::from(0x424242FF),
+        placement: BorderPlacement::Center,
+        dash_style: Some(StrokeDashStyle::Dot),
+        dash_cap: StrokeLineCap::Square,
+        ..Default::default()
+    };
+    let dash_dot = Border {
+        width: 3.0,
+        color: Color::from(0x424242FF),
+        placement: BorderPlacement::Center,
+        dash_style: Some(StrokeDashStyle::DashDot),
+        dash_cap: StrokeLineCap::Triangle,
+        ..Default::default()
+    };
+    let dash_dot_dot = Border {
+        width: 3.0,
+        color: Color::from(0x424242FF),
+        placement: BorderPlacement::Center,
+        dash_style: Some(StrokeDashStyle::DashDotDot),
+        dash_cap: StrokeLineCap::Square,
+        ..Default::default()
+    };
+    let custom = Border {
+        width: 3.0,
+        color: Color::from(0x424242FF),
+        placement: BorderPlacement::Center,
+        dash_style: Some(StrokeDashStyle::Custom {
+            dashes: &[6.0, 2.0, 2.0, 2.0],
+            offset: 0.0,
+        }),
+        dash_cap: StrokeLineCap::Round,
+        ..Default::default()
+    };
+
+    Element {
+        id: Some(w_id!()),
+        direction: Direction::TopToBottom,
+        width: Sizing::grow(),
+        height: Sizing::fit(),
+        child_gap: 10.0,
+        padding: BoxAmount::all(12.0),
+        background_color: Some(Color::WHITE),
+        border: Some(Border {
+            width: 1.0,
+            color: Color {
+                r: 0.85,
+                g: 0.85,
+                b: 0.85,
+                a: 1.0,
+            },
+            ..Default::default()
+        }),
+        border_radius: Some(BorderRadius::all(8.0)),
+        // drop_shadows: vec![DropShadow::simple(3.0, 3.0).blur_radius(0.0).inset(false)],
+        children: vec![
+            // Title
+            Element {
+                id: Some(w_id!()),
+                width: Sizing::grow(),
+                height: Sizing::fit(),
+                content: widget(Text::new("Border demos").with_font_size(20.0)),
+                ..Default::default()
+            },
+            // Placements row
+            Element {
+                direction: Direction::LeftToRight,
+                width: Sizing::grow(),
+                height: Sizing::fixed(300.0),
+                cross_align_items: Alignment::Center,
+                // wrap: true,
+                child_gap: 10.0,
+                children: vec![
+                    demo_box("Inset 4px", inset, None),
+                    demo_box("Center 6px", center, Some(BorderRadius::all(10.0))),
+                    demo_box("Outset 8px", outset, Some(BorderRadius::all(12.0))),
+                    demo_box("Dashed", dashed, Some(BorderRadius::tl_br(8.0))),
+                    demo_box("Dotted", dotted, Some(BorderRadius::tr_bl(8.0))),
+                    demo_box("DashDot", dash_dot, Some(BorderRadius::top(8.0))),
+                    demo_box("DashDotDot", dash_dot_dot, Some(BorderRadius::bottom(8.0))),
+                    demo_box("Custom", custom, None),
+                    // Element {
+                    //     id: Some(w_id!()),
+                    //     width: Sizing::fit(),
+                    //     height: Sizing::fit(),
+                    //     content: Some(ElementContent::Widget(Box::new(Spinner::default()))),
+                    //     ..Default::default()
+                    // },
+                ],
+                ..Default::default()
+            },
+            // Dash styles row
+            // Element {
+            //     direction: Direction::LeftToRight,
+            //     width: Sizing::grow(),
+            //     height: Sizing::fit(),
+            //     child_gap: 10.0,
+            //     children: vec![
+            //         demo_box("Dashed", dashed, Some(BorderRadius::tl_br(8.0))),
+            //         demo_box("Dotted", dotted, Some(BorderRadius::tr_bl(8.0))),
+            //         demo_box("DashDot", dash_dot, Some(BorderRadius::top(8.0))),
+            //     ],
+            //     ..Default::default()
+            // },
+            // Element {
+            //     direction: Direction::LeftToRight,
+            //     width: Sizing::grow(),
+            //     height: Sizing::fit(),
+            //     child_gap: 10.0,
+            //     children: vec![
+            //         demo_box("DashDotDot", dash_dot_dot, Some(BorderRadius::bottom(8.0))),
+            //         demo_box("Custom", custom, None),
+            //     ],
+            //     ..Default::default()
+            // },
+        ],
+        ..Default::default()
+    }
+}
+
+/// Toggles with custom colors, sizes and animations, and a disabled one.
+pub fn toggle_demos<Message: 'static + Send>(hook: &mut HookManager<Message>) -> Element<Message> {
+    let mut instance = hook.instance(w_id!());
+    let wifi = instance.use_state(|| true);
+    let bluetooth = instance.use_state(|| false);
+    let notifications = instance.use_state(|| true);
+    let dark_mode = instance.use_state(|| false);
+
+    Element {
+        id: Some(w_id!()),
+        direction: Direction::TopToBottom,
+        width: Sizing::grow(),
+        height: Sizing::fit(),
+        background_color: Some(Color::WHITE),
+        padding: BoxAmount::all(12.0),
+        border: Some(Border {
+            width: 1.0,
+            color: Color {
+                r: 0.85,
+                g: 0.85,
+                b: 0.85,
+                a: 1.0,
+            },
+            ..Default::default()
+        }),
+        border_radius: Some(BorderRadius::all(28.0)),
+        // drop_shadows: vec![
+        //     DropShadow::simple(0.0, 30.0)
+        //         .blur_radius(15.0)
+        //         .spread_radius(10.0)
+        //         .inset(true),
+        //     DropShadow::simple(0.0, 30.0)
+        //         .blur_radius(15.0)
+        //         .spread_radius(-10.0)
+        //         .inset(false),
+        // ],
+        child_gap: 16.0,
+        children: vec![
+            // Title
+            Element {
+                id: Some(w_id!()),
+                width: Sizing::grow(),
+                height: Sizing::fit(),
+                content: widget(Text::new("Toggle/Switch demos").with_font_size(20.0)),
+                ..Default::default()
+            },
+            // WiFi toggle
+            Element {
+                id: Some(w_id!()),
+                direction: Direction::LeftToRight,
+                width: Sizing::grow(),
+                height: Sizing::fit(),
+                child_gap: 12.0,
+                children: vec![
+                    Toggle::new(*wifi.borrow())
+                        .with_toggle_handler({
+                            let wifi = wifi.clone();
+                            move |checked, _, _| {
+                                *wifi.borrow_mut() = checked;
+                            }
+                        })
+                        .as_element(w_id!()),
+                    Element {
+                        id: Some(w_id!()),
+                        width: Sizing::grow(),
+                        height: Sizing::fit(),
+                        content: widget(
+                            Text::new(format!(
+                                "WiFi: {}",
+                                if *wifi.borrow() { "On" } else { "Off" }
+                            ))
+                            .with_font_size(14.0),
+                        ),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+            // Bluetooth toggle with custom colors
+            Element {
+                id: Some(w_id!()),
+                direction: Direction::LeftToRight,
+                width: Sizing::grow(),
+                height: Sizing::fit(),
+                child_gap: 12.0,
+                children: vec![
+                    Toggle::new(*bluetooth.borrow())
+                        .with_track_colors(
+                            Color::from(0xE2E8F0FF), // Off: Neutral-200
+                            Color::from(0x3B82F6FF), // On: Blue
+                        )
+                        .with_toggle_handler({
+                            let bluetooth = bluetooth.clone();
+                            move |checked, _, _| {
+                                *bluetooth.borrow_mut() = checked;
+                            }
+                        })
+                        .as_element(w_id!()),
+                    Element {
+                        id: Some(w_id!()),
+                        width: Sizing::grow(),
+                        height: Sizing::fit(),
+                        content: widget(
+                            Text::new(format!(
+                                "Bluetooth: {}",
+                                if *bluetooth.borrow() {
+                                    "Connected"
+                                } else {
+                                    "Disconnected"
+                                }
+                            ))
+                            .with_font_size(14.0),
+                        ),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+            // Notifications toggle with green accent
+            Element {
+                id: Some(w_id!()),
+                direction: Direction::LeftToRight,
+                width: Sizing::grow(),
+                height: Sizing::fit(),
+                child_gap: 12.0,
+                children: vec![
+                    Toggle::new(*notifications.borrow())
+                        .with_track_colors(
+                            Color::from(0xE2E8F0FF), // Off: Neutral-200
+                            Color::from(0x10B981FF), // On: Green
+                        )
+                        .with_toggle_handler({
+                            let notifications = notifications.clone();
+                            move |checked, _, _| {
+                                *notifications.borrow_mut() = checked;
+                            }
+                        })
+                        .as_element(w_id!()),
+                    Element {
+                        id: Some(w_id!()),
+                        width: Sizing::grow(),
+                        height: Sizing::fit(),
+                        content: widget(
+                            Text::new(format!(
+                                "Notifications: {}",
+                                if *notifications.borrow() {
+                                    "Enabled"
+                                } else {
+                                    "Disabled"
+                                }
+                            ))
+                            .with_font_size(14.0),
+                        ),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+            // Dark mode toggle with custom size and animation
+            Element {
+                id: Some(w_id!()),
+                direction: Direction::LeftToRight,
+                width: Sizing::grow(),
+                height: Sizing::fit(),
+                child_gap: 12.0,
+                children: vec![
+                    Toggle::new(*dark_mode.borrow())
+                        .with_size(52.0, 28.0) // Larger toggle
+                        .with_track_colors(
+                            Color::from(0xE2E8F0FF), // Off: Neutral-200
+                            Color::from(0x8B5CF6FF), // On: Purple
+                        )
+                        .with_animation_duration(Duration::from_millis(400)) // Slower animation
+                        .with_animation_easing(Easing::EaseInOutCubic)
+                        .with_toggle_handler({
+                            let dark_mode = dark_mode.clone();
+                            move |checked, _, _| {
+                                *dark_mode.borrow_mut() = checked;
+                            }
+                        })
+                        .as_element(w_id!()),
+                    Element {
+                        id: Some(w_id!()),
+                        width: Sizing::grow(),
+                        height: Sizing::fit(),
+                        content: widget(
+                            Text::new(format!(
+                                "Dark Mode: {}",
+                                if *dark_mode.borrow() {
+                                    "🌙 Night"
+                                } else {
+                                    "☀️ Day"
+                                }
+                            ))
+                            .with_font_size(14.0),
+                        ),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+            // Disabled toggle
+            Element {
+                id: Some(w_id!()),
+                direction: Direction::LeftToRight,
+                width: Sizing::grow(),
+                height: Sizing::fit(),
+                child_gap: 12.0,
+                children: vec![
+                    Toggle::new(false).disabled().as_element(w_id!()),
+                    Element {
+                        id: Some(w_id!()),
+                        width: Sizing::grow(),
+                        height: Sizing::fit(),
+                        content: widget(
+                            Text::new("Disabled Toggle")
+                                .with_font_size(14.0)
+                                .with_color(Color::from(0x94A3B8FF)),
+                        ),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    }
+}
+
+/// Blurred and distorted panes over whatever is drawn behind them.
+pub fn backdrop_filter_demo<Message: 'static + Send>() -> Element<Message> {
+    Element {
+        id: Some(w_id!()),
+        direction: Direction::TopToBottom,
+        width: Sizing::grow(),
+        height: Sizing::fixed(540.0),
+        padding: BoxAmount::all(12.0),
+        background_color: Some(Color::from(0xF0F0F000)), // Light background
+        border_radius: Some(BorderRadius::all(8.0)),
+        child_gap: 10.0,
+        children: vec![
+            Element {
+                id: Some(w_id!()),
+                width: Sizing::grow(),
+                height: Sizing::fit(),
+                content: widget(Text::new("Backdrop Filter Demo").with_font_size(20.0)),
+                ..Default::default()
+            },
+            Element {
+                id: Some(w_id!()),
+                width: Sizing::fixed(350.0),
+                height: Sizing::fixed(100.0),
+                background_color: Some(Color::from_rgba(1.0, 1.0, 1.0, 0.25)), // Semi-transparent white
+                backdrop_filter: Some(BackdropFilter::blur(10.0)),             // D2D Gaussian blur
+                border_radius: Some(BorderRadius::all(15.0)),
+                content: widget(
+                    Text::new("Gaussian Blur (D2D)")
+                        .with_paragraph_alignment(ParagraphAlignment::Center)
+                        .with_text_alignment(TextAlignment::Center)
+                        .with_color(Color::BLACK),
+                ),
+                ..Default::default()
+            },
+            Element {
+                id: Some(w_id!()),
+                width: Sizing::fixed(350.0),
+                height: Sizing::fixed(100.0),
+                background_color: Some(Color::from_rgba(1.0, 1.0, 1.0, 0.25)), // Semi-transparent white
+                // Custom shader effects only exist on Direct2D
+                #[cfg(windows)]
+                backdrop_filter: Some(BackdropFilter::custom(BoxBlurEffect {
+                    radius: 16.0,
+                    intensity: 1.0,
+                })), // Custom box blur shader
+                border_radius: Some(BorderRadius::all(15.0)),
+                content: widget(
+                    Text::new("Box Blur (Custom Shader)")
+                        .with_paragraph_alignment(ParagraphAlignment::Center)
+                        .with_text_alignment(TextAlignment::Center)
+                        .with_color(Color::BLACK),
+                ),
+                ..Default::default()
+            },
+            Element {
+                id: Some(w_id!()),
+                width: Sizing::fixed(350.0),
+                height: Sizing::fixed(100.0),
+                background_color: Some(Color::from_rgba(1.0, 1.0, 1.0, 0.0)), // Fully transparent
+                #[cfg(windows)]
+                backdrop_filter: Some(BackdropFilter::custom(LiquidGlassEffect {
+                    refraction: 3.0,
+                    glow: 0.25,
+                    noise: 0.04,
+                    size: 1.0,
+                })), // Liquid glass distortion
+                // border_radius: Some(BorderRadius::all(15.0)),
+                content: widget(
+                    Text::new("Liquid Glass (Distortion)")
+                        .with_paragraph_alignment(ParagraphAlignment::Center)
+                        .with_text_alignment(TextAlignment::Center)
+                        .with_color(Color::BLACK),
+                ),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    }
+}
@@ -1,5 +1,7 @@
 use crate::gfx::RectDIP;
 use crate::gfx::draw_commands::{DrawCommand, DrawCommandList};
+use crate::gfx::resource_cache::RealizedResource;
+use crate::gfx::resources::ResourceTable;
 use crate::layout::model::{BackdropFilter, BorderRadius, Color};
 use crate::widgets::renderer::Renderer;
 use std::mem::ManuallyDrop;
@@ -32,15 +34,20 @@ impl CommandExecutor {
         commands: &DrawCommandList,
         screen_bounds: Option<RectDIP>,
     ) -> windows::core::Result<()> {
+        let resources = &commands.resources;
+        let commands = commands.commands.as_slice();
+
         let Some(bounds) = screen_bounds else {
             // No culling if no bounds provided - execute all commands
             for i in 0..commands.len() {
-                Self::execute_command(renderer, commands, i)?;
+                Self::execute_command(renderer, resources, commands, i)?;
             }
             return Ok(());
         };
 
         let mut skip_depth = 0u32; // Track depth of skipped clip regions
+        // Commands under a transform aren't in screen coordinates and aren't culled
+        let mut transform_depth = 0u32;
 
         for i in 0..commands.len() {
             let should_execute = if skip_depth > 0 {
@@ -57,13 +64,16 @@ impl CommandExecutor {
                     }
                     _ => false, // Skip all other commands
                 }
+            } else if transform_depth > 0 {
+                true
             } else {
                 // Not in a skipped region - check if we should execute normally
                 Self::should_execute_command_simple(&commands[i], &bounds, &mut skip_depth)
             };
 
             if should_execute {
-                Self::execute_command(renderer, commands, i)?;
+                Self::track_transform_depth(&commands[i], &mut transform_depth);
+                Self::execute_command(renderer, resources, commands, i)?;
             }
         }
         Ok(())
@@ -83,6 +93,8 @@ impl CommandExecutor {
             DrawCommand::PopRoundedClip => true,
             DrawCommand::PushLayer { .. } => true,
             DrawCommand::PopLayer => true,
+            DrawCommand::PushTransform { .. } => true,
+            DrawCommand::PopTransform => true,
             DrawCommand::SetBrushColor { .. } => true,
 
             // Commands with rectangles that can be culled
@@ -136,8 +148,8 @@ impl CommandExecutor {
             DrawCommand::DrawCircleArc { center, radius, .. } => {
                 // Check if circle intersects with bounds
                 let circle_rect = RectDIP {
-                    x: center.X - radius,
-                    y: center.Y - radius,
+                    x: center.x - radius,
+                    y: center.y - radius,
                     width: radius * 2.0,
                     height: radius * 2.0,
                 };
@@ -175,6 +187,15 @@ impl CommandExecutor {
         }
     }
 
+    /// Count the transforms an executed command pushes or pops
+    fn track_transform_depth(command: &DrawCommand, transform_depth: &mut u32) {
+        match command {
+            DrawCommand::PushTransform { .. } => *transform_depth += 1,
+            DrawCommand::PopTransform => *transform_depth = transform_depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
     /// Check if a rectangle intersects with screen bounds
     fn rect_intersects_bounds(rect: &RectDIP, bounds: &RectDIP) -> bool {
         !(rect.x >= bounds.x + bounds.width
@@ -204,9 +225,11 @@ impl CommandExecutor {
         }
     }
 
-    /// Execute a single drawing command
+    /// Execute a single drawing command, looking up the resources it references
+    /// in `resources`
     pub fn execute_command(
         renderer: &Renderer,
+        resources: &ResourceTable,
         command_list: &[DrawCommand],
         command_index: usize,
     ) -> windows::core::Result<()> {
@@ -250,11 +273,17 @@ impl CommandExecutor {
 
                 DrawCommand::DrawText {
                     rect,
-                    layout,
+                    text,
                     color,
                     text_shadows,
-                    text_hash,
                 } => {
+                    let Some(RealizedResource::Text(layout)) =
+                        renderer.realize_resource(*text, resources)
+                    else {
+                        return Ok(());
+                    };
+                    let layout = &layout;
+
                     // Draw text shadows first (in order, so first shadow is bottom-most)
                     for shadow in text_shadows.iter() {
                         let shadow_position = Vector2 {
@@ -268,7 +297,7 @@ impl CommandExecutor {
                                 &shadow_position,
                                 layout,
                                 shadow,
-                                *text_hash,
+                                text.raw(),
                             );
                         } else {
                             // Simple unblurred shadow - just draw text with offset
@@ -398,6 +427,14 @@ impl CommandExecutor {
                     renderer.render_target.PopLayer();
                 }
 
+                DrawCommand::PushTransform { transform } => {
+                    renderer.push_transform(transform);
+                }
+
+                DrawCommand::PopTransform => {
+                    renderer.pop_transform();
+                }
+
                 DrawCommand::DrawRectangleOutline {
                     rect,
                     color,
@@ -435,7 +472,12 @@ impl CommandExecutor {
                 }
 
                 DrawCommand::SetBrushColor { color } => {
-                    renderer.brush.SetColor(color);
+                    renderer.brush.SetColor(&D2D1_COLOR_F {
+                        r: color.r,
+                        g: color.g,
+                        b: color.b,
+                        a: color.a,
+                    });
                 }
 
                 DrawCommand::DrawCircleArc {
@@ -454,7 +496,10 @@ impl CommandExecutor {
                     });
                     // Create and draw circle arc using existing CircleArc utility
                     let arc = crate::gfx::circle_arc::CircleArc::new(
-                        *center,
+                        Vector2 {
+                            X: center.x,
+                            Y: center.y,
+                        },
                         *radius,
                         *start_angle_deg,
                         *end_angle_deg,
@@ -469,8 +514,12 @@ impl CommandExecutor {
                     }
                 }
 
-                DrawCommand::DrawSvg { rect, svg_document } => {
-                    renderer.draw_svg(rect, svg_document);
+                DrawCommand::DrawSvg { rect, svg } => {
+                    if let Some(RealizedResource::Svg(svg_document)) =
+                        renderer.realize_resource(*svg, resources)
+                    {
+                        renderer.draw_svg(rect, &svg_document);
+                    }
                 }
 
                 DrawCommand::DrawBitmap {
@@ -478,22 +527,36 @@ impl CommandExecutor {
                     bitmap,
                     opacity,
                 } => {
-                    renderer.draw_bitmap(rect, bitmap, *opacity);
+                    if let Some(RealizedResource::Bitmap(bitmap)) =
+                        renderer.realize_resource(*bitmap, resources)
+                    {
+                        renderer.draw_bitmap(rect, &bitmap, *opacity);
+                    }
                 }
 
                 DrawCommand::FillPathGeometry {
                     rect,
-                    path_geometry,
+                    path,
                     color,
                     scale_x,
                     scale_y,
                 } => {
-                    renderer.fill_path_geometry(rect, path_geometry, *color, *scale_x, *scale_y);
+                    if let Some(RealizedResource::Path(path_geometry)) =
+                        renderer.realize_resource(*path, resources)
+                    {
+                        renderer.fill_path_geometry(
+                            rect,
+                            &path_geometry,
+                            *color,
+                            *scale_x,
+                            *scale_y,
+                        );
+                    }
                 }
 
                 DrawCommand::StrokePathGeometry {
                     rect,
-                    path_geometry,
+                    path,
                     color,
                     stroke_width,
                     scale_x,
@@ -501,9 +564,14 @@ impl CommandExecutor {
                     stroke_cap,
                     stroke_join,
                 } => {
+                    let Some(RealizedResource::Path(path_geometry)) =
+                        renderer.realize_resource(*path, resources)
+                    else {
+                        return Ok(());
+                    };
                     renderer.stroke_path_geometry(
                         rect,
-                        path_geometry,
+                        &path_geometry,
                         *color,
                         *stroke_width,
                         *scale_x,
@@ -545,6 +613,7 @@ impl CommandExecutor {
                     // This should not be reached in normal execution
                     Self::execute_backdrop_filter(
                         renderer,
+                        resources,
                         command_list,
                         command_index,
                         *rect,
@@ -559,8 +628,10 @@ impl CommandExecutor {
     }
 
     /// Execute a backdrop-filter command: render background, apply filter, composite, render element
+    #[allow(clippy::too_many_arguments)]
     fn execute_backdrop_filter(
         renderer: &Renderer,
+        resources: &ResourceTable,
         commands: &[DrawCommand],
         command_index: usize,
         bounds: RectDIP,
@@ -571,6 +642,7 @@ impl CommandExecutor {
         // Find background commands (commands before this one that intersect filter bounds)
         let mut background_commands = Vec::new();
         let mut skip_depth = 0;
+        let mut transform_depth = 0;
         for (index, command) in commands.iter().enumerate() {
             if index >= command_index {
                 break;
@@ -590,12 +662,15 @@ impl CommandExecutor {
                     }
                     _ => false, // Skip all other commands
                 }
+            } else if transform_depth > 0 {
+                true
             } else {
                 // Not in a skipped region - check if we should execute normally
                 Self::should_execute_command_simple(&commands[index], &bounds, &mut skip_depth)
             };
 
             if should_execute {
+                Self::track_transform_depth(command, &mut transform_depth);
                 background_commands.push((index, command));
             }
         }
@@ -619,7 +694,7 @@ impl CommandExecutor {
             .map(|(_, cmd)| (*cmd).clone())
             .collect();
         let background_bitmap =
-            renderer.render_commands_to_bitmap(&background_cmds, &expanded_bounds)?;
+            renderer.render_commands_to_bitmap(&background_cmds, resources, &expanded_bounds)?;
 
         // Apply the appropriate effect based on filter type
         let effect_output = Self::apply_backdrop_effect(renderer, &background_bitmap, filter)?;
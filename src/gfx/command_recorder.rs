@@ -1,16 +1,16 @@
 use crate::layout::model::{
-    BackdropFilter, Border, BorderRadius, Color, DropShadow, StrokeDashStyle, TextShadow,
+    BackdropFilter, Border, BorderRadius, Color, DropShadow, StrokeDashStyle, StrokeLineJoin,
+    TextShadow,
 };
 use crate::{
+    SvgPathList,
     gfx::{
-        RectDIP,
+        Matrix2D, PointDIP, RectDIP,
         draw_commands::{DrawCommand, DrawCommandList},
+        resources::{BitmapResource, Resource, SvgResource, TextResource},
     },
-    layout::model::{StrokeLineCap, StrokeLineJoin},
+    layout::model::StrokeLineCap,
 };
-use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
-use windows::Win32::Graphics::Direct2D::{ID2D1Bitmap, ID2D1PathGeometry, ID2D1SvgDocument};
-use windows::Win32::Graphics::DirectWrite::IDWriteTextLayout;
 
 /// Records drawing operations as commands instead of executing them immediately
 pub struct CommandRecorder {
@@ -110,17 +110,16 @@ impl CommandRecorder {
     pub fn draw_text(
         &mut self,
         rect: &RectDIP,
-        layout: &IDWriteTextLayout,
+        text: TextResource,
         color: impl Into<Color>,
         text_shadows: &[TextShadow],
-        text_hash: u64,
     ) {
+        let text = self.commands.add_resource(Resource::Text(text));
         self.commands.push(DrawCommand::DrawText {
             rect: *rect,
-            layout: layout.clone(),
+            text,
             color: color.into(),
             text_shadows: text_shadows.to_vec(),
-            text_hash,
         });
     }
 
@@ -158,6 +157,18 @@ impl CommandRecorder {
         self.commands.push(DrawCommand::PopLayer);
     }
 
+    /// Record pushing a transform on top of the current one
+    pub fn push_transform(&mut self, transform: &Matrix2D) {
+        self.commands.push(DrawCommand::PushTransform {
+            transform: *transform,
+        });
+    }
+
+    /// Record popping the current transform
+    pub fn pop_transform(&mut self) {
+        self.commands.push(DrawCommand::PopTransform);
+    }
+
     /// Record a rectangle outline drawing command
     pub fn draw_rectangle(&mut self, rect: &RectDIP, color: impl Into<Color>, thickness: f32) {
         self.commands.push(DrawCommand::DrawRectangleOutline {
@@ -170,7 +181,7 @@ impl CommandRecorder {
     /// Record a circle arc drawing command
     pub fn draw_circle_arc(
         &mut self,
-        center: windows_numerics::Vector2,
+        center: PointDIP,
         radius: f32,
         start_angle_deg: f32,
         end_angle_deg: f32,
@@ -219,41 +230,44 @@ impl CommandRecorder {
     }
 
     /// Record setting brush color
-    pub fn set_brush_color(&mut self, color: D2D1_COLOR_F) {
-        self.commands.push(DrawCommand::SetBrushColor { color });
+    pub fn set_brush_color(&mut self, color: impl Into<Color>) {
+        self.commands.push(DrawCommand::SetBrushColor {
+            color: color.into(),
+        });
     }
 
     /// Record drawing an SVG document
-    pub fn draw_svg(&mut self, rect: &RectDIP, svg_document: &ID2D1SvgDocument) {
-        self.commands.push(DrawCommand::DrawSvg {
-            rect: *rect,
-            svg_document: svg_document.clone(),
-        });
+    pub fn draw_svg(&mut self, rect: &RectDIP, svg: SvgResource) {
+        let svg = self.commands.add_resource(Resource::Svg(svg));
+        self.commands
+            .push(DrawCommand::DrawSvg { rect: *rect, svg });
     }
 
-    /// Record filling a path geometry
+    /// Record filling a path
     pub fn fill_path_geometry(
         &mut self,
         rect: &RectDIP,
-        path_geometry: &ID2D1PathGeometry,
+        path: SvgPathList,
         color: impl Into<Color>,
         scale_x: f32,
         scale_y: f32,
     ) {
+        let path = self.commands.add_resource(Resource::Path(path));
         self.commands.push(DrawCommand::FillPathGeometry {
             rect: *rect,
-            path_geometry: path_geometry.clone(),
+            path,
             color: color.into(),
             scale_x,
             scale_y,
         });
     }
 
-    /// Record stroking a path geometry
+    /// Record stroking a path
+    #[allow(clippy::too_many_arguments)]
     pub fn stroke_path_geometry(
         &mut self,
         rect: &RectDIP,
-        path_geometry: &ID2D1PathGeometry,
+        path: SvgPathList,
         color: impl Into<Color>,
         stroke_width: f32,
         scale_x: f32,
@@ -261,9 +275,10 @@ impl CommandRecorder {
         stroke_cap: Option<StrokeLineCap>,
         stroke_join: Option<StrokeLineJoin>,
     ) {
+        let path = self.commands.add_resource(Resource::Path(path));
         self.commands.push(DrawCommand::StrokePathGeometry {
             rect: *rect,
-            path_geometry: path_geometry.clone(),
+            path,
             color: color.into(),
             stroke_width,
             scale_x,
@@ -274,6 +289,7 @@ impl CommandRecorder {
     }
 
     /// Record drawing a line
+    #[allow(clippy::too_many_arguments)]
     pub fn draw_line(
         &mut self,
         start_x: f32,
@@ -298,10 +314,11 @@ impl CommandRecorder {
     }
 
     /// Record drawing a bitmap
-    pub fn draw_bitmap(&mut self, rect: &RectDIP, bitmap: &ID2D1Bitmap, opacity: f32) {
+    pub fn draw_bitmap(&mut self, rect: &RectDIP, bitmap: BitmapResource, opacity: f32) {
+        let bitmap = self.commands.add_resource(Resource::Bitmap(bitmap));
         self.commands.push(DrawCommand::DrawBitmap {
             rect: *rect,
-            bitmap: bitmap.clone(),
+            bitmap,
             opacity,
         });
     }
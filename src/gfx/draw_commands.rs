@@ -1,11 +1,9 @@
-use crate::gfx::RectDIP;
+use crate::gfx::resources::{Resource, ResourceId, ResourceTable};
+use crate::gfx::{Matrix2D, PointDIP, RectDIP};
 use crate::layout::model::{
     BackdropFilter, BorderRadius, Color, DropShadow, StrokeDashStyle, StrokeLineCap,
     StrokeLineJoin, TextShadow,
 };
-use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
-use windows::Win32::Graphics::Direct2D::{ID2D1Bitmap, ID2D1PathGeometry, ID2D1SvgDocument};
-use windows::Win32::Graphics::DirectWrite::IDWriteTextLayout;
 
 /// A single drawing command that can be executed later
 #[derive(Clone, Debug)]
@@ -38,14 +36,12 @@ pub enum DrawCommand {
         shadow: DropShadow,
         border_radius: Option<BorderRadius>,
     },
-    /// Draw text using DirectWrite text layout
+    /// Draw a text resource
     DrawText {
         rect: RectDIP,
-        layout: IDWriteTextLayout,
+        text: ResourceId,
         color: Color,
         text_shadows: Vec<TextShadow>,
-        /// Hash of the text layout content for cache identity
-        text_hash: u64,
     },
     /// Draw a rectangle outline (stroke)
     DrawRectangleOutline {
@@ -68,7 +64,7 @@ pub enum DrawCommand {
     },
     /// Draw a circle arc
     DrawCircleArc {
-        center: windows_numerics::Vector2,
+        center: PointDIP,
         radius: f32,
         start_angle_deg: f32,
         end_angle_deg: f32,
@@ -92,27 +88,33 @@ pub enum DrawCommand {
         opacity: f32,
     },
     PopLayer,
+    /// Apply a transform on top of the current one to subsequent commands
+    PushTransform {
+        transform: Matrix2D,
+    },
+    /// Pop the current transform
+    PopTransform,
     /// Set brush color for subsequent operations
     SetBrushColor {
-        color: D2D1_COLOR_F,
+        color: Color,
     },
-    /// Draw SVG document
+    /// Draw an SVG document resource
     DrawSvg {
         rect: RectDIP,
-        svg_document: ID2D1SvgDocument,
+        svg: ResourceId,
     },
-    /// Fill a path geometry
+    /// Fill a path resource
     FillPathGeometry {
         rect: RectDIP,
-        path_geometry: ID2D1PathGeometry,
+        path: ResourceId,
         color: Color,
         scale_x: f32,
         scale_y: f32,
     },
-    /// Stroke a path geometry
+    /// Stroke a path resource
     StrokePathGeometry {
         rect: RectDIP,
-        path_geometry: ID2D1PathGeometry,
+        path: ResourceId,
         color: Color,
         stroke_width: f32,
         scale_x: f32,
@@ -131,13 +133,78 @@ pub enum DrawCommand {
         dash_style: Option<StrokeDashStyle>,
         stroke_cap: Option<StrokeLineCap>,
     },
-    /// Draw a bitmap
+    /// Draw a bitmap resource
     DrawBitmap {
         rect: RectDIP,
-        bitmap: ID2D1Bitmap,
+        bitmap: ResourceId,
         opacity: f32,
     },
 }
 
-/// A list of drawing commands that can be generated and executed separately
-pub type DrawCommandList = Vec<DrawCommand>;
+/// A list of drawing commands that can be generated and executed separately,
+/// together with the resources they reference
+#[derive(Clone, Debug, Default)]
+pub struct DrawCommandList {
+    pub commands: Vec<DrawCommand>,
+    pub resources: ResourceTable,
+}
+
+impl DrawCommandList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, command: DrawCommand) {
+        self.commands.push(command);
+    }
+
+    /// Stores a resource for commands in this list to reference
+    pub fn add_resource(&mut self, resource: Resource) -> ResourceId {
+        self.resources.insert(resource)
+    }
+
+    pub fn resource(&self, id: ResourceId) -> Option<&Resource> {
+        self.resources.get(id)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, DrawCommand> {
+        self.commands.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.commands.clear();
+        self.resources.clear();
+    }
+
+    /// Moves the commands and resources of `other` to the end of this list
+    pub fn append(&mut self, other: DrawCommandList) {
+        self.commands.extend(other.commands);
+        self.resources.extend(other.resources);
+    }
+}
+
+impl From<Vec<DrawCommand>> for DrawCommandList {
+    fn from(commands: Vec<DrawCommand>) -> Self {
+        Self {
+            commands,
+            resources: ResourceTable::new(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a DrawCommandList {
+    type Item = &'a DrawCommand;
+    type IntoIter = std::slice::Iter<'a, DrawCommand>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.commands.iter()
+    }
+}
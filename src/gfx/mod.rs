@@ -1,14 +1,24 @@
-use windows::Win32::Foundation::{HWND, RECT};
-
-use crate::dips_scale;
-
+#[cfg(windows)]
 pub mod circle_arc;
 pub mod color;
+#[cfg(windows)]
 pub mod command_executor;
 pub mod command_recorder;
 pub mod draw_commands;
+#[cfg(windows)]
+pub mod resource_cache;
+pub mod resources;
+pub mod software;
+pub mod svg_export;
+pub mod text_engine;
+pub mod text_style;
+#[cfg(windows)]
 pub mod effects;
 
+use serde::{Deserialize, Serialize};
+
+use crate::layout::model::BorderRadius;
+
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct PointDIP {
     pub x: f32,
@@ -22,9 +32,35 @@ impl PointDIP {
             && self.y >= rect.y
             && self.y <= rect.y + rect.height
     }
+
+    /// Whether the point is within `rect` with its corners rounded by `border_radius`
+    pub fn within_rounded(&self, rect: RectDIP, border_radius: &BorderRadius) -> bool {
+        if !self.within(rect) {
+            return false;
+        }
+
+        let max_radius = rect.width.min(rect.height) / 2.0;
+        let right = rect.x + rect.width;
+        let bottom = rect.y + rect.height;
+        let corners = [
+            (border_radius.top_left, rect.x, rect.y, 1.0, 1.0),
+            (border_radius.top_right, right, rect.y, -1.0, 1.0),
+            (border_radius.bottom_right, right, bottom, -1.0, -1.0),
+            (border_radius.bottom_left, rect.x, bottom, 1.0, -1.0),
+        ];
+        corners
+            .into_iter()
+            .all(|(radius, x, y, toward_x, toward_y)| {
+                let radius = radius.min(max_radius);
+                // Offsets from the center of the corner's circle toward the corner
+                let dx = radius - (self.x - x) * toward_x;
+                let dy = radius - (self.y - y) * toward_y;
+                dx <= 0.0 || dy <= 0.0 || dx * dx + dy * dy <= radius * radius
+            })
+    }
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct RectDIP {
     pub x: f32,
     pub y: f32,
@@ -32,15 +68,103 @@ pub struct RectDIP {
     pub height: f32,
 }
 
-impl RectDIP {
-    pub fn from(hwnd: HWND, rc: RECT) -> Self {
-        let to_dip = dips_scale(hwnd);
+/// Affine transform of DIP coordinates, in the row vector convention of Direct2D's `Matrix3x2`:
+/// `(x, y)` maps to `(x * m11 + y * m21 + dx, x * m12 + y * m22 + dy)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix2D {
+    pub m11: f32,
+    pub m12: f32,
+    pub m21: f32,
+    pub m22: f32,
+    pub dx: f32,
+    pub dy: f32,
+}
+
+impl Default for Matrix2D {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Matrix2D {
+    pub const IDENTITY: Self = Self {
+        m11: 1.0,
+        m12: 0.0,
+        m21: 0.0,
+        m22: 1.0,
+        dx: 0.0,
+        dy: 0.0,
+    };
+
+    pub const fn translation(x: f32, y: f32) -> Self {
+        Self {
+            dx: x,
+            dy: y,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub const fn scale(x: f32, y: f32) -> Self {
+        Self {
+            m11: x,
+            m22: y,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Clockwise rotation, as the y axis points down
+    pub fn rotation(degrees: f32) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Self {
+            m11: cos,
+            m12: sin,
+            m21: -sin,
+            m22: cos,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Skew by `x_degrees` along the x axis and `y_degrees` along the y axis
+    pub fn skew(x_degrees: f32, y_degrees: f32) -> Self {
+        Self {
+            m12: y_degrees.to_radians().tan(),
+            m21: x_degrees.to_radians().tan(),
+            ..Self::IDENTITY
+        }
+    }
 
+    /// Applies `self`, then `next`
+    pub fn then(&self, next: &Self) -> Self {
         Self {
-            x: rc.left as f32 * to_dip,
-            y: rc.top as f32 * to_dip,
-            width: (rc.right - rc.left) as f32 * to_dip,
-            height: (rc.bottom - rc.top) as f32 * to_dip,
+            m11: self.m11 * next.m11 + self.m12 * next.m21,
+            m12: self.m11 * next.m12 + self.m12 * next.m22,
+            m21: self.m21 * next.m11 + self.m22 * next.m21,
+            m22: self.m21 * next.m12 + self.m22 * next.m22,
+            dx: self.dx * next.m11 + self.dy * next.m21 + next.dx,
+            dy: self.dx * next.m12 + self.dy * next.m22 + next.dy,
+        }
+    }
+
+    /// The transform undoing this one, `None` if it collapses the plane
+    pub fn invert(&self) -> Option<Self> {
+        let determinant = self.m11 * self.m22 - self.m12 * self.m21;
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+        Some(Self {
+            m11: self.m22 / determinant,
+            m12: -self.m12 / determinant,
+            m21: -self.m21 / determinant,
+            m22: self.m11 / determinant,
+            dx: (self.m21 * self.dy - self.m22 * self.dx) / determinant,
+            dy: (self.m12 * self.dx - self.m11 * self.dy) / determinant,
+        })
+    }
+
+    pub fn transform_point(&self, point: PointDIP) -> PointDIP {
+        PointDIP {
+            x: point.x * self.m11 + point.y * self.m21 + self.dx,
+            y: point.x * self.m12 + point.y * self.m22 + self.dy,
         }
     }
 }
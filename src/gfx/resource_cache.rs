@@ -0,0 +1,200 @@
+//! Direct2D/DirectWrite realizations of the backend-neutral resources in
+//! [`crate::gfx::resources`], cached across frames by [`ResourceId`].
+
+use std::collections::HashMap;
+use std::mem::ManuallyDrop;
+
+use windows::Win32::Graphics::Direct2D::Common::{
+    D2D_SIZE_U, D2D1_ALPHA_MODE_PREMULTIPLIED, D2D1_COLOR_F, D2D1_PIXEL_FORMAT,
+};
+use windows::Win32::Graphics::Direct2D::{
+    D2D1_BITMAP_OPTIONS_NONE, D2D1_BITMAP_PROPERTIES1, ID2D1Bitmap, ID2D1DeviceContext6,
+    ID2D1Factory, ID2D1PathGeometry, ID2D1SvgDocument,
+};
+use windows::Win32::Graphics::DirectWrite::{
+    DWRITE_TEXT_RANGE, IDWriteFactory6, IDWriteTextLayout,
+};
+use windows::Win32::Graphics::Dxgi::Common::DXGI_FORMAT_B8G8R8A8_UNORM;
+use windows::core::Result;
+
+use crate::gfx::resources::{BitmapResource, Resource, ResourceId, ResourceTable, TextResource};
+use crate::gfx::text_engine::byte_to_utf16_index;
+use crate::gfx::text_engine::direct_write::create_text_layout;
+use crate::widgets::svg::create_svg_document;
+
+/// A resource realized for Direct2D. Cloning only adds a COM reference.
+#[derive(Clone, Debug)]
+pub enum RealizedResource {
+    Text(IDWriteTextLayout),
+    Path(ID2D1PathGeometry),
+    Svg(ID2D1SvgDocument),
+    Bitmap(ID2D1Bitmap),
+}
+
+struct CachedResource {
+    /// `None` when realizing failed, so it isn't retried every frame
+    resource: Option<RealizedResource>,
+    last_frame_used: u64,
+}
+
+/// Frames a resource may go unused before it is evicted
+const MAX_RESOURCE_AGE: u64 = 10;
+
+/// Realizes resources on first use and keeps them while they are referenced
+pub struct ResourceCache {
+    dwrite_factory: IDWriteFactory6,
+    cache: HashMap<ResourceId, CachedResource>,
+    current_frame: u64,
+}
+
+impl ResourceCache {
+    pub fn new(dwrite_factory: IDWriteFactory6) -> Self {
+        Self {
+            dwrite_factory,
+            cache: HashMap::new(),
+            current_frame: 0,
+        }
+    }
+
+    /// Drops every realized resource, e.g. when the device is lost
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    pub fn start_frame(&mut self) {
+        self.current_frame += 1;
+    }
+
+    pub fn evict_unused(&mut self) {
+        let current_frame = self.current_frame;
+        self.cache
+            .retain(|_, cached| current_frame - cached.last_frame_used < MAX_RESOURCE_AGE);
+    }
+
+    /// Returns the realization of the resource `id` refers to in `resources`,
+    /// creating it with `factory` and `device_context` if it isn't cached yet.
+    pub fn get_or_realize(
+        &mut self,
+        id: ResourceId,
+        resources: &ResourceTable,
+        factory: &ID2D1Factory,
+        device_context: &ID2D1DeviceContext6,
+    ) -> Option<RealizedResource> {
+        if let Some(cached) = self.cache.get_mut(&id) {
+            cached.last_frame_used = self.current_frame;
+            return cached.resource.clone();
+        }
+
+        let resource = resources.get(id)?;
+        let realized = match resource {
+            Resource::Text(text) => self
+                .realize_text(text, device_context)
+                .map(RealizedResource::Text),
+            Resource::Path(path) => path.create_geometry(factory).map(RealizedResource::Path),
+            Resource::Svg(svg) => create_svg_document(device_context, &svg.content, svg.recolor)
+                .map(RealizedResource::Svg),
+            Resource::Bitmap(bitmap) => {
+                realize_bitmap(bitmap, device_context).map(RealizedResource::Bitmap)
+            }
+        }
+        .ok();
+
+        self.cache.insert(
+            id,
+            CachedResource {
+                resource: realized.clone(),
+                last_frame_used: self.current_frame,
+            },
+        );
+        realized
+    }
+
+    fn realize_text(
+        &self,
+        text: &TextResource,
+        device_context: &ID2D1DeviceContext6,
+    ) -> Result<IDWriteTextLayout> {
+        let layout = create_text_layout(
+            &self.dwrite_factory,
+            &text.text,
+            &text.style,
+            text.max_width,
+            text.max_height,
+        )?;
+
+        unsafe {
+            for run in &text.runs {
+                // DirectWrite ranges are in UTF-16 code units
+                let start = byte_to_utf16_index(&text.text, run.start);
+                let end = byte_to_utf16_index(&text.text, run.end);
+                let range = DWRITE_TEXT_RANGE {
+                    startPosition: start as u32,
+                    length: end.saturating_sub(start) as u32,
+                };
+
+                if let Some(color) = run.color {
+                    let brush = device_context.CreateSolidColorBrush(
+                        &D2D1_COLOR_F {
+                            r: color.r,
+                            g: color.g,
+                            b: color.b,
+                            a: color.a,
+                        },
+                        None,
+                    )?;
+                    layout.SetDrawingEffect(&brush, range)?;
+                }
+                if run.underline {
+                    layout.SetUnderline(true, range)?;
+                }
+            }
+
+            Ok(layout)
+        }
+    }
+}
+
+/// Uploads straight RGBA pixels as a premultiplied BGRA bitmap
+fn realize_bitmap(
+    bitmap: &BitmapResource,
+    device_context: &ID2D1DeviceContext6,
+) -> Result<ID2D1Bitmap> {
+    let pixels: Vec<u8> = bitmap
+        .pixels
+        .chunks_exact(4)
+        .flat_map(|rgba| {
+            let alpha = rgba[3] as u32;
+            let premultiply = |channel: u8| ((channel as u32 * alpha + 127) / 255) as u8;
+            [
+                premultiply(rgba[2]),
+                premultiply(rgba[1]),
+                premultiply(rgba[0]),
+                rgba[3],
+            ]
+        })
+        .collect();
+
+    let properties = D2D1_BITMAP_PROPERTIES1 {
+        pixelFormat: D2D1_PIXEL_FORMAT {
+            format: DXGI_FORMAT_B8G8R8A8_UNORM,
+            alphaMode: D2D1_ALPHA_MODE_PREMULTIPLIED,
+        },
+        dpiX: 96.0,
+        dpiY: 96.0,
+        bitmapOptions: D2D1_BITMAP_OPTIONS_NONE,
+        colorContext: ManuallyDrop::new(None),
+    };
+
+    let bitmap = unsafe {
+        device_context.CreateBitmap(
+            D2D_SIZE_U {
+                width: bitmap.width,
+                height: bitmap.height,
+            },
+            Some(pixels.as_ptr() as *const _),
+            bitmap.width * 4,
+            &properties,
+        )?
+    };
+    Ok(bitmap.into())
+}
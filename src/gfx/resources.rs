@@ -0,0 +1,317 @@
+//! Backend-neutral resources referenced by draw commands.
+//!
+//! Draw commands never hold backend objects. Text, paths, SVG documents and
+//! bitmaps are described here and stored in a [`ResourceTable`] next to the
+//! commands; each backend realizes them on demand and caches the result by
+//! [`ResourceId`].
+
+use std::collections::BTreeMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::gfx::text_style::TextStyle;
+use crate::layout::model::Color;
+use crate::{PathCommand, SvgPathCommands, SvgPathList};
+
+/// Opaque handle to a [`Resource`] in a [`ResourceTable`].
+///
+/// Ids are derived from the resource content, so describing the same resource
+/// on consecutive frames yields the same id and lets backends reuse what they
+/// realized before.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ResourceId(u64);
+
+impl ResourceId {
+    pub const fn from_raw(raw: u64) -> Self {
+        Self(raw)
+    }
+
+    pub const fn raw(&self) -> u64 {
+        self.0
+    }
+}
+
+/// A styled range of a [`TextResource`], in byte offsets into its text
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextRun {
+    pub start: usize,
+    pub end: usize,
+    /// Overrides the color the text is drawn with
+    pub color: Option<Color>,
+    pub underline: bool,
+}
+
+/// A block of text laid out within `max_width` x `max_height`
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextResource {
+    pub text: String,
+    pub style: TextStyle,
+    pub max_width: f32,
+    pub max_height: f32,
+    pub runs: Vec<TextRun>,
+}
+
+/// An SVG document, optionally recolored with a single fill color
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgResource {
+    pub content: String,
+    pub recolor: Option<Color>,
+}
+
+/// Decoded pixels in straight (non-premultiplied) RGBA8, row by row
+#[derive(Clone, Debug)]
+pub struct BitmapResource {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Arc<[u8]>,
+    /// Identifies the pixel data without hashing it on every frame
+    key: u64,
+}
+
+impl BitmapResource {
+    pub fn new(width: u32, height: u32, pixels: impl Into<Arc<[u8]>>) -> Self {
+        static NEXT_KEY: AtomicU64 = AtomicU64::new(1);
+
+        let pixels = pixels.into();
+        debug_assert_eq!(pixels.len(), width as usize * height as usize * 4);
+
+        Self {
+            width,
+            height,
+            pixels,
+            key: NEXT_KEY.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+}
+
+impl PartialEq for BitmapResource {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Resource {
+    Text(TextResource),
+    Path(SvgPathList),
+    Svg(SvgResource),
+    Bitmap(BitmapResource),
+}
+
+impl Resource {
+    /// The content-derived id this resource is stored under
+    pub fn id(&self) -> ResourceId {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        ResourceId(hasher.finish())
+    }
+}
+
+impl Hash for Resource {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Resource::Text(text) => {
+                text.text.hash(state);
+                text.style.hash(state);
+                text.max_width.to_bits().hash(state);
+                text.max_height.to_bits().hash(state);
+                for run in &text.runs {
+                    run.start.hash(state);
+                    run.end.hash(state);
+                    hash_optional_color(run.color.as_ref(), state);
+                    run.underline.hash(state);
+                }
+            }
+            Resource::Path(list) => hash_path_list(list, state),
+            Resource::Svg(svg) => {
+                svg.content.hash(state);
+                hash_optional_color(svg.recolor.as_ref(), state);
+            }
+            Resource::Bitmap(bitmap) => bitmap.key.hash(state),
+        }
+    }
+}
+
+fn hash_optional_color<H: Hasher>(color: Option<&Color>, state: &mut H) {
+    color.is_some().hash(state);
+    if let Some(color) = color {
+        color.r.to_bits().hash(state);
+        color.g.to_bits().hash(state);
+        color.b.to_bits().hash(state);
+        color.a.to_bits().hash(state);
+    }
+}
+
+fn hash_path_list<H: Hasher>(list: &SvgPathList, state: &mut H) {
+    let floats = |values: &[f32], state: &mut H| {
+        for value in values {
+            value.to_bits().hash(state);
+        }
+    };
+
+    for commands in list.paths {
+        match commands {
+            SvgPathCommands::Path(commands) => {
+                0u8.hash(state);
+                for command in *commands {
+                    match *command {
+                        PathCommand::MoveTo { x, y } => {
+                            0u8.hash(state);
+                            floats(&[x, y], state);
+                        }
+                        PathCommand::LineTo { x, y } => {
+                            1u8.hash(state);
+                            floats(&[x, y], state);
+                        }
+                        PathCommand::Arc {
+                            radius_x,
+                            radius_y,
+                            rotation,
+                            large_arc,
+                            sweep,
+                            end_x,
+                            end_y,
+                        } => {
+                            2u8.hash(state);
+                            floats(&[radius_x, radius_y, rotation, end_x, end_y], state);
+                            large_arc.hash(state);
+                            sweep.hash(state);
+                        }
+                        PathCommand::CubicBezier {
+                            cp1_x,
+                            cp1_y,
+                            cp2_x,
+                            cp2_y,
+                            end_x,
+                            end_y,
+                        } => {
+                            3u8.hash(state);
+                            floats(&[cp1_x, cp1_y, cp2_x, cp2_y, end_x, end_y], state);
+                        }
+                        PathCommand::QuadraticBezier {
+                            cp_x,
+                            cp_y,
+                            end_x,
+                            end_y,
+                        } => {
+                            4u8.hash(state);
+                            floats(&[cp_x, cp_y, end_x, end_y], state);
+                        }
+                        PathCommand::ClosePath => 5u8.hash(state),
+                    }
+                }
+            }
+            SvgPathCommands::Circle { cx, cy, r } => {
+                1u8.hash(state);
+                floats(&[*cx, *cy, *r], state);
+            }
+            SvgPathCommands::Rect {
+                x,
+                y,
+                width,
+                height,
+                rx,
+                ry,
+            } => {
+                2u8.hash(state);
+                floats(&[*x, *y, *width, *height, *rx, *ry], state);
+            }
+        }
+    }
+}
+
+/// Resources referenced by a draw command list, keyed by id
+#[derive(Clone, Debug, Default)]
+pub struct ResourceTable {
+    resources: BTreeMap<ResourceId, Resource>,
+}
+
+impl ResourceTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores a resource and returns its id. Inserting an equal resource again
+    /// returns the same id.
+    pub fn insert(&mut self, resource: Resource) -> ResourceId {
+        let id = resource.id();
+        self.resources.entry(id).or_insert(resource);
+        id
+    }
+
+    pub fn get(&self, id: ResourceId) -> Option<&Resource> {
+        self.resources.get(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ResourceId, &Resource)> {
+        self.resources.iter().map(|(id, resource)| (*id, resource))
+    }
+
+    pub fn len(&self) -> usize {
+        self.resources.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.resources.clear();
+    }
+
+    /// Moves all resources of `other` into this table
+    pub fn extend(&mut self, other: ResourceTable) {
+        for (id, resource) in other.resources {
+            self.resources.entry(id).or_insert(resource);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gfx::text_style::{FontAxes, ParagraphAlignment, TextAlignment};
+
+    fn text(content: &str) -> Resource {
+        Resource::Text(TextResource {
+            text: content.to_string(),
+            style: TextStyle {
+                font_id: "Segoe UI".into(),
+                font_size: 14.0,
+                font_axes: FontAxes::default(),
+                line_spacing: None,
+                text_alignment: TextAlignment::Leading,
+                paragraph_alignment: ParagraphAlignment::Top,
+                word_wrap: true,
+            },
+            max_width: 100.0,
+            max_height: 20.0,
+            runs: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn ids_follow_content() {
+        let mut table = ResourceTable::new();
+        let a = table.insert(text("hello"));
+        let b = table.insert(text("hello"));
+        let c = table.insert(text("world"));
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.get(c), Some(&text("world")));
+    }
+
+    #[test]
+    fn bitmaps_are_identified_per_decode() {
+        let first = Resource::Bitmap(BitmapResource::new(1, 1, vec![0u8; 4]));
+        let second = Resource::Bitmap(BitmapResource::new(1, 1, vec![0u8; 4]));
+
+        assert_eq!(first.id(), first.clone().id());
+        assert_ne!(first.id(), second.id());
+    }
+}
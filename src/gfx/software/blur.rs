@@ -0,0 +1,101 @@
+use tiny_skia::Pixmap;
+
+/// Approximates a gaussian blur with the given standard deviation (in pixels) by
+/// three successive box blurs over the premultiplied pixels. Pixels outside the
+/// pixmap count as transparent, matching Direct2D's soft border mode.
+pub fn gaussian_blur(pixmap: &mut Pixmap, sigma: f32) {
+    if sigma <= 0.0 {
+        return;
+    }
+
+    let width = pixmap.width() as usize;
+    let height = pixmap.height() as usize;
+    let data = pixmap.data_mut();
+    let mut scratch = vec![0u8; data.len()];
+
+    for radius in box_radii(sigma) {
+        if radius == 0 {
+            continue;
+        }
+        // Rows, then columns
+        box_blur(data, &mut scratch, height, width, radius, width * 4, 4);
+        box_blur(&scratch, data, width, height, radius, 4, width * 4);
+    }
+}
+
+/// Radii of three box blurs whose combination approximates a gaussian,
+/// see "Fast Almost-Gaussian Filtering" (Kovesi 2010).
+fn box_radii(sigma: f32) -> [usize; 3] {
+    const PASSES: f32 = 3.0;
+
+    let ideal_width = (12.0 * sigma * sigma / PASSES + 1.0).sqrt();
+    let mut lower = ideal_width.floor() as i32;
+    if lower % 2 == 0 {
+        lower -= 1;
+    }
+    let upper = lower + 2;
+
+    let lower_f = lower as f32;
+    let ideal_lower_count =
+        (12.0 * sigma * sigma - PASSES * lower_f * lower_f - 4.0 * PASSES * lower_f - 3.0 * PASSES)
+            / (-4.0 * lower_f - 4.0);
+    let lower_count = ideal_lower_count.round() as i32;
+
+    let mut radii = [0; 3];
+    for (i, radius) in radii.iter_mut().enumerate() {
+        let size = if (i as i32) < lower_count {
+            lower
+        } else {
+            upper
+        };
+        *radius = (size.max(1) as usize - 1) / 2;
+    }
+    radii
+}
+
+/// Blurs `lines` runs of `len` pixels from `src` into `dst`. `stride` is the byte
+/// distance between runs and `step` the distance between pixels in a run, so the
+/// same routine serves rows and columns.
+fn box_blur(
+    src: &[u8],
+    dst: &mut [u8],
+    lines: usize,
+    len: usize,
+    radius: usize,
+    stride: usize,
+    step: usize,
+) {
+    let window = (radius * 2 + 1) as u32;
+
+    for line in 0..lines {
+        let base = line * stride;
+        let mut sum = [0u32; 4];
+
+        for i in 0..=radius.min(len - 1) {
+            let offset = base + i * step;
+            for c in 0..4 {
+                sum[c] += src[offset + c] as u32;
+            }
+        }
+
+        for i in 0..len {
+            let offset = base + i * step;
+            for c in 0..4 {
+                dst[offset + c] = ((sum[c] + window / 2) / window) as u8;
+            }
+
+            if i + radius + 1 < len {
+                let incoming = base + (i + radius + 1) * step;
+                for c in 0..4 {
+                    sum[c] += src[incoming + c] as u32;
+                }
+            }
+            if i >= radius {
+                let outgoing = base + (i - radius) * step;
+                for c in 0..4 {
+                    sum[c] -= src[outgoing + c] as u32;
+                }
+            }
+        }
+    }
+}
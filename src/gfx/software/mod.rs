@@ -0,0 +1,1069 @@
+//! A CPU renderer that executes [`DrawCommandList`]s into RGBA pixel buffers.
+//!
+//! It mirrors the Direct2D semantics of `widgets::renderer::Renderer`, so frames can
+//! be rendered without a GPU or Direct2D (CI, servers, thumbnails) and compared
+//! pixel by pixel in tests.
+
+mod blur;
+pub mod path;
+pub mod snapshot;
+mod text;
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use tiny_skia::{
+    BlendMode, ColorU8, FillRule, FilterQuality, IntRect, Mask, Paint, Path, PathBuilder, Pattern,
+    Pixmap, PixmapPaint, Rect, SpreadMode, Stroke, Transform,
+};
+
+use crate::SvgPathList;
+use crate::gfx::draw_commands::{DrawCommand, DrawCommandList};
+use crate::gfx::resources::{BitmapResource, Resource, ResourceId, ResourceTable, TextResource};
+use crate::gfx::text_engine::TextEngine;
+use crate::gfx::{Matrix2D, RectDIP};
+use crate::layout::model::{
+    BackdropFilter, Border, BorderPlacement, BorderRadius, Color, DropShadow, StrokeDashStyle,
+    StrokeLineCap, StrokeLineJoin, TextShadow,
+};
+
+use text::TextOutlines;
+
+/// An image with straight (non-premultiplied) RGBA8 pixels, row by row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl RgbaImage {
+    /// Returns the `[r, g, b, a]` value of a pixel, or `None` when out of bounds.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let offset = (y as usize * self.width as usize + x as usize) * 4;
+        self.data[offset..offset + 4].try_into().ok()
+    }
+}
+
+enum StackEntry {
+    Clip { previous: Option<Mask> },
+    Layer { opacity: f32 },
+    Transform { previous: Transform },
+}
+
+/// Executes drawing commands on the CPU.
+///
+/// Commands are in DIPs; the renderer scales them by its DIP scale into a pixel
+/// buffer of the size given at creation.
+pub struct SoftwareRenderer {
+    target: Pixmap,
+    /// Offscreen targets for pushed layers, innermost last
+    layers: Vec<Pixmap>,
+    stack: Vec<StackEntry>,
+    /// Coverage of the current clip, `None` when nothing is clipped
+    clip: Option<Mask>,
+    transform: Transform,
+    /// Resources realized so far, `None` when one could not be realized
+    paths: HashMap<ResourceId, Option<Rc<Path>>>,
+    bitmaps: HashMap<ResourceId, Option<Rc<Pixmap>>>,
+    texts: HashMap<ResourceId, Option<Rc<TextOutlines>>>,
+    /// Lays out text for [`DrawCommand::DrawText`], which is skipped without one
+    text_engine: Option<Rc<dyn TextEngine>>,
+}
+
+impl SoftwareRenderer {
+    /// Creates a transparent target of `width` x `height` pixels (at least 1x1).
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            target: Pixmap::new(width.max(1), height.max(1)).expect("pixmap size is too large"),
+            layers: Vec::new(),
+            stack: Vec::new(),
+            clip: None,
+            transform: Transform::identity(),
+            paths: HashMap::new(),
+            bitmaps: HashMap::new(),
+            texts: HashMap::new(),
+            text_engine: None,
+        }
+    }
+
+    /// Sets how many pixels one DIP covers.
+    pub fn with_dip_scale(mut self, dip_scale: f32) -> Self {
+        self.transform = Transform::from_scale(dip_scale, dip_scale);
+        self
+    }
+
+    /// Draws text with glyph outlines from `text_engine`.
+    pub fn with_text_engine(mut self, text_engine: Rc<dyn TextEngine>) -> Self {
+        self.text_engine = Some(text_engine);
+        self.texts.clear();
+        self
+    }
+
+    pub fn width(&self) -> u32 {
+        self.target.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.target.height()
+    }
+
+    /// Renders a command list into a new image at a DIP scale of 1.
+    pub fn render(commands: &DrawCommandList, width: u32, height: u32) -> RgbaImage {
+        let mut renderer = Self::new(width, height);
+        renderer.execute_commands(commands);
+        renderer.into_image()
+    }
+
+    /// Execute a list of drawing commands
+    pub fn execute_commands(&mut self, commands: &DrawCommandList) {
+        for command in commands {
+            self.execute_command(command, &commands.resources);
+        }
+    }
+
+    /// Execute a single drawing command, looking up the resources it references
+    /// in `resources`
+    pub fn execute_command(&mut self, command: &DrawCommand, resources: &ResourceTable) {
+        match command {
+            DrawCommand::Clear { color } => self.clear(*color),
+            DrawCommand::FillRectangle { rect, color } => {
+                if let Some(path) = path::rect_path(rect) {
+                    self.fill(&path, *color);
+                }
+            }
+            DrawCommand::FillRoundedRectangle {
+                rect,
+                border_radius,
+                color,
+            } => {
+                if let Some(path) = path::rounded_rect_path(rect, border_radius) {
+                    self.fill(&path, *color);
+                }
+            }
+            DrawCommand::FillRectangleWithBackdropFilter {
+                rect,
+                color,
+                filter,
+                border_radius,
+            } => {
+                self.fill_with_backdrop_filter(rect, *color, filter, border_radius.as_ref());
+            }
+            DrawCommand::DrawBlurredShadow {
+                rect,
+                shadow,
+                border_radius,
+            } => {
+                self.draw_blurred_shadow(rect, shadow, border_radius.as_ref());
+            }
+            DrawCommand::DrawRectangleOutline {
+                rect,
+                color,
+                stroke_width,
+            } => {
+                if let Some(path) = path::rect_path(rect) {
+                    self.stroke(&path, &path::plain_stroke(*stroke_width), *color);
+                }
+            }
+            DrawCommand::DrawRoundedRectangleOutline {
+                rect,
+                border_radius,
+                color,
+                stroke_width,
+            } => {
+                if let Some(path) = path::rounded_rect_path(rect, border_radius) {
+                    self.stroke(&path, &path::plain_stroke(*stroke_width), *color);
+                }
+            }
+            DrawCommand::DrawBorder {
+                rect,
+                border_radius,
+                border,
+            } => self.draw_border(rect, border_radius.as_ref(), border),
+            DrawCommand::DrawCircleArc {
+                center,
+                radius,
+                start_angle_deg,
+                end_angle_deg,
+                stroke_width,
+                color,
+            } => {
+                if let Some(path) = path::circle_arc_path(
+                    center.x,
+                    center.y,
+                    *radius,
+                    *start_angle_deg,
+                    *end_angle_deg,
+                ) {
+                    self.stroke(&path, &path::plain_stroke(*stroke_width), *color);
+                }
+            }
+            DrawCommand::PushAxisAlignedClip { rect } => self.push_clip(path::rect_path(rect)),
+            DrawCommand::PushRoundedClip {
+                rect,
+                border_radius,
+            } => self.push_clip(path::rounded_rect_path(rect, border_radius)),
+            DrawCommand::PopAxisAlignedClip
+            | DrawCommand::PopRoundedClip
+            | DrawCommand::PopLayer
+            | DrawCommand::PopTransform => self.pop(),
+            DrawCommand::PushLayer { opacity } => self.push_layer(*opacity),
+            DrawCommand::PushTransform { transform } => self.push_transform(transform),
+            // Every command carries its own color
+            DrawCommand::SetBrushColor { .. } => {}
+            DrawCommand::DrawLine {
+                start_x,
+                start_y,
+                end_x,
+                end_y,
+                color,
+                stroke_width,
+                dash_style,
+                stroke_cap,
+            } => self.draw_line(
+                (*start_x, *start_y),
+                (*end_x, *end_y),
+                *color,
+                *stroke_width,
+                dash_style.as_ref(),
+                *stroke_cap,
+            ),
+            DrawCommand::FillPathGeometry {
+                rect,
+                path,
+                color,
+                scale_x,
+                scale_y,
+            } => {
+                if let Some(path) = self.realize_path(*path, resources) {
+                    self.fill_geometry(&path, rect, *color, *scale_x, *scale_y);
+                }
+            }
+            DrawCommand::StrokePathGeometry {
+                rect,
+                path,
+                color,
+                stroke_width,
+                scale_x,
+                scale_y,
+                stroke_cap,
+                stroke_join,
+            } => {
+                if let Some(path) = self.realize_path(*path, resources) {
+                    self.stroke_geometry(
+                        &path,
+                        rect,
+                        *color,
+                        *stroke_width,
+                        *scale_x,
+                        *scale_y,
+                        *stroke_cap,
+                        *stroke_join,
+                    );
+                }
+            }
+            DrawCommand::DrawBitmap {
+                rect,
+                bitmap,
+                opacity,
+            } => {
+                if let Some(bitmap) = self.realize_bitmap(*bitmap, resources) {
+                    self.draw_bitmap(&bitmap, rect, *opacity);
+                }
+            }
+            DrawCommand::DrawText {
+                rect,
+                text,
+                color,
+                text_shadows,
+            } => {
+                if let Some(Resource::Text(resource)) = resources.get(*text)
+                    && let Some(outlines) = self.realize_text(*text, resource)
+                {
+                    self.draw_text(&outlines, resource, rect, *color, text_shadows);
+                }
+            }
+            // Rendering SVG documents needs an engine the software backend
+            // doesn't have yet
+            DrawCommand::DrawSvg { .. } => {}
+        }
+    }
+
+    /// Fill an SVG path list placed at `rect.x, rect.y` and scaled by `scale_x, scale_y`
+    pub fn fill_path(
+        &mut self,
+        paths: &SvgPathList,
+        rect: &RectDIP,
+        color: Color,
+        scale_x: f32,
+        scale_y: f32,
+    ) {
+        if let Some(path) = path::svg_path(paths) {
+            self.fill_geometry(&path, rect, color, scale_x, scale_y);
+        }
+    }
+
+    /// Stroke an SVG path list placed at `rect.x, rect.y` and scaled by `scale_x, scale_y`
+    #[allow(clippy::too_many_arguments)]
+    pub fn stroke_path(
+        &mut self,
+        paths: &SvgPathList,
+        rect: &RectDIP,
+        color: Color,
+        stroke_width: f32,
+        scale_x: f32,
+        scale_y: f32,
+        stroke_cap: Option<StrokeLineCap>,
+        stroke_join: Option<StrokeLineJoin>,
+    ) {
+        if let Some(path) = path::svg_path(paths) {
+            self.stroke_geometry(
+                &path,
+                rect,
+                color,
+                stroke_width,
+                scale_x,
+                scale_y,
+                stroke_cap,
+                stroke_join,
+            );
+        }
+    }
+
+    /// Returns a copy of the rendered pixels.
+    ///
+    /// Layers that are still pushed are not included.
+    pub fn to_image(&self) -> RgbaImage {
+        let data = self
+            .target
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect();
+
+        RgbaImage {
+            width: self.target.width(),
+            height: self.target.height(),
+            data,
+        }
+    }
+
+    pub fn into_image(self) -> RgbaImage {
+        self.to_image()
+    }
+
+    fn realize_path(&mut self, id: ResourceId, resources: &ResourceTable) -> Option<Rc<Path>> {
+        self.paths
+            .entry(id)
+            .or_insert_with(|| match resources.get(id) {
+                Some(Resource::Path(paths)) => path::svg_path(paths).map(Rc::new),
+                _ => None,
+            })
+            .clone()
+    }
+
+    fn realize_bitmap(&mut self, id: ResourceId, resources: &ResourceTable) -> Option<Rc<Pixmap>> {
+        self.bitmaps
+            .entry(id)
+            .or_insert_with(|| match resources.get(id) {
+                Some(Resource::Bitmap(bitmap)) => bitmap_pixmap(bitmap).map(Rc::new),
+                _ => None,
+            })
+            .clone()
+    }
+
+    fn realize_text(&mut self, id: ResourceId, text: &TextResource) -> Option<Rc<TextOutlines>> {
+        let engine = self.text_engine.as_deref()?;
+        self.texts
+            .entry(id)
+            .or_insert_with(|| TextOutlines::new(engine, text).map(Rc::new))
+            .clone()
+    }
+
+    /// Fills the glyphs of `text` at `rect.x, rect.y`, shadows first. Glyphs
+    /// and underlines take the color of their run, or `color` outside of runs.
+    fn draw_text(
+        &mut self,
+        outlines: &TextOutlines,
+        text: &TextResource,
+        rect: &RectDIP,
+        color: Color,
+        text_shadows: &[TextShadow],
+    ) {
+        let placement = Transform::from_translate(rect.x, rect.y);
+
+        if !text_shadows.is_empty() {
+            let mut pb = PathBuilder::new();
+            for (_, glyph) in &outlines.glyphs {
+                pb.push_path(glyph);
+            }
+            if let Some(path) = pb.finish() {
+                for shadow in text_shadows {
+                    let offset = placement.pre_translate(shadow.offset_x, shadow.offset_y);
+                    if let Some(path) = path.clone().transform(offset) {
+                        self.fill_blurred(
+                            &path,
+                            FillRule::Winding,
+                            shadow.color,
+                            shadow.blur_radius,
+                            None,
+                        );
+                    }
+                }
+            }
+        }
+
+        let run_color = |position: usize| {
+            text.runs
+                .iter()
+                .rev()
+                .find(|run| run.start <= position && position < run.end)
+                .and_then(|run| run.color)
+                .unwrap_or(color)
+        };
+
+        let transform = placement.post_concat(self.transform);
+        for (position, glyph) in &outlines.glyphs {
+            self.fill_with_transform(glyph, run_color(*position), transform);
+        }
+        for (run, underline) in &outlines.underlines {
+            if let Some(path) = path::rect_path(underline) {
+                let color = text.runs[*run].color.unwrap_or(color);
+                self.fill_with_transform(&path, color, transform);
+            }
+        }
+    }
+
+    fn fill_geometry(
+        &mut self,
+        path: &Path,
+        rect: &RectDIP,
+        color: Color,
+        scale_x: f32,
+        scale_y: f32,
+    ) {
+        let placement = Transform::from_scale(scale_x, scale_y).post_translate(rect.x, rect.y);
+        self.fill_with_transform(path, color, placement.post_concat(self.transform));
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn stroke_geometry(
+        &mut self,
+        path: &Path,
+        rect: &RectDIP,
+        color: Color,
+        stroke_width: f32,
+        scale_x: f32,
+        scale_y: f32,
+        stroke_cap: Option<StrokeLineCap>,
+        stroke_join: Option<StrokeLineJoin>,
+    ) {
+        // For 1px stroke, offset by 0.5 to avoid antialiasing
+        let (x, y) = if stroke_width == 1.0 {
+            (rect.x - 0.5, rect.y - 0.5)
+        } else {
+            (rect.x, rect.y)
+        };
+        let placement = Transform::from_scale(scale_x, scale_y).post_translate(x, y);
+
+        let stroke = if stroke_cap.is_some() || stroke_join.is_some() {
+            path::stroke(
+                stroke_width,
+                None,
+                stroke_cap.unwrap_or(StrokeLineCap::Square),
+                stroke_join.unwrap_or(StrokeLineJoin::Miter),
+            )
+        } else {
+            path::plain_stroke(stroke_width)
+        };
+
+        let paint = paint(color);
+        let transform = placement.post_concat(self.transform);
+        let (target, clip) = self.surface();
+        target.stroke_path(path, &paint, &stroke, transform, clip);
+    }
+
+    /// Draws a bitmap stretched over `rect`
+    fn draw_bitmap(&mut self, bitmap: &Pixmap, rect: &RectDIP, opacity: f32) {
+        let placement = Transform::from_scale(
+            rect.width / bitmap.width() as f32,
+            rect.height / bitmap.height() as f32,
+        )
+        .post_translate(rect.x, rect.y);
+        let paint = PixmapPaint {
+            opacity: opacity.clamp(0.0, 1.0),
+            quality: FilterQuality::Bilinear,
+            ..PixmapPaint::default()
+        };
+        let transform = placement.post_concat(self.transform);
+        let (target, clip) = self.surface();
+        target.draw_pixmap(0, 0, bitmap.as_ref(), &paint, transform, clip);
+    }
+
+    /// The pixmap commands currently draw into, with the clip that applies to it
+    fn surface(&mut self) -> (&mut Pixmap, Option<&Mask>) {
+        let target = self.layers.last_mut().unwrap_or(&mut self.target);
+        (target, self.clip.as_ref())
+    }
+
+    fn clear(&mut self, color: Color) {
+        let mut paint = paint(color);
+        paint.blend_mode = BlendMode::Source;
+        let (target, clip) = self.surface();
+        match clip {
+            None => target.fill(paint_color(color)),
+            Some(clip) => {
+                let full = Rect::from_xywh(0.0, 0.0, target.width() as f32, target.height() as f32)
+                    .expect("pixmap is never empty");
+                target.fill_rect(full, &paint, Transform::identity(), Some(clip));
+            }
+        }
+    }
+
+    fn fill(&mut self, path: &Path, color: Color) {
+        self.fill_with_transform(path, color, self.transform);
+    }
+
+    fn fill_with_transform(&mut self, path: &Path, color: Color, transform: Transform) {
+        let paint = paint(color);
+        let (target, clip) = self.surface();
+        target.fill_path(path, &paint, FillRule::Winding, transform, clip);
+    }
+
+    fn stroke(&mut self, path: &Path, stroke: &Stroke, color: Color) {
+        let paint = paint(color);
+        let transform = self.transform;
+        let (target, clip) = self.surface();
+        target.stroke_path(path, &paint, stroke, transform, clip);
+    }
+
+    fn push_clip(&mut self, path: Option<Path>) {
+        let mut mask = match self.clip.clone() {
+            Some(mask) => mask,
+            None => {
+                let mut mask = Mask::new(self.target.width(), self.target.height())
+                    .expect("mask matches the target size");
+                mask.data_mut().fill(u8::MAX);
+                mask
+            }
+        };
+
+        match path {
+            Some(path) => mask.intersect_path(&path, FillRule::Winding, true, self.transform),
+            // An empty clip hides everything
+            None => mask.clear(),
+        }
+
+        let previous = self.clip.replace(mask);
+        self.stack.push(StackEntry::Clip { previous });
+    }
+
+    fn push_layer(&mut self, opacity: f32) {
+        let layer = Pixmap::new(self.target.width(), self.target.height())
+            .expect("layer matches the target size");
+        self.layers.push(layer);
+        self.stack.push(StackEntry::Layer { opacity });
+    }
+
+    fn push_transform(&mut self, matrix: &Matrix2D) {
+        let transform = Transform::from_row(
+            matrix.m11, matrix.m12, matrix.m21, matrix.m22, matrix.dx, matrix.dy,
+        );
+        let previous = self.transform;
+        self.transform = previous.pre_concat(transform);
+        self.stack.push(StackEntry::Transform { previous });
+    }
+
+    /// Pops whatever clip, layer or transform was pushed last, like Direct2D does for
+    /// `PopRoundedClip` (which is a layer there).
+    fn pop(&mut self) {
+        match self.stack.pop() {
+            Some(StackEntry::Clip { previous }) => self.clip = previous,
+            Some(StackEntry::Transform { previous }) => self.transform = previous,
+            Some(StackEntry::Layer { opacity }) => {
+                if let Some(layer) = self.layers.pop() {
+                    let paint = PixmapPaint {
+                        opacity: opacity.clamp(0.0, 1.0),
+                        ..PixmapPaint::default()
+                    };
+                    let (target, clip) = self.surface();
+                    target.draw_pixmap(0, 0, layer.as_ref(), &paint, Transform::identity(), clip);
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn draw_border(
+        &mut self,
+        rect: &RectDIP,
+        border_radius: Option<&BorderRadius>,
+        border: &Border,
+    ) {
+        // Adjust rect and radius for placement
+        let half = border.width * 0.5;
+        let mut adjusted = *rect;
+        match border.placement {
+            BorderPlacement::Center => {}
+            BorderPlacement::Inset => {
+                adjusted.x += half;
+                adjusted.y += half;
+                adjusted.width = (adjusted.width - border.width).max(0.0);
+                adjusted.height = (adjusted.height - border.width).max(0.0);
+            }
+            BorderPlacement::Outset => {
+                adjusted.x -= half;
+                adjusted.y -= half;
+                adjusted.width += border.width;
+                adjusted.height += border.width;
+            }
+        }
+
+        let adjusted_radius = border_radius.map(|r| {
+            let delta = match border.placement {
+                BorderPlacement::Center => 0.0,
+                BorderPlacement::Inset => -half,
+                BorderPlacement::Outset => half,
+            };
+            BorderRadius {
+                top_left: (r.top_left + delta).max(0.0),
+                top_right: (r.top_right + delta).max(0.0),
+                bottom_right: (r.bottom_right + delta).max(0.0),
+                bottom_left: (r.bottom_left + delta).max(0.0),
+            }
+        });
+
+        let stroke = path::stroke(
+            border.width,
+            border.dash_style.as_ref(),
+            border.dash_cap,
+            border.stroke_join,
+        );
+        if let Some(path) = path::shape_path(&adjusted, adjusted_radius.as_ref()) {
+            self.stroke(&path, &stroke, border.color);
+        }
+    }
+
+    fn draw_line(
+        &mut self,
+        start: (f32, f32),
+        end: (f32, f32),
+        color: Color,
+        stroke_width: f32,
+        dash_style: Option<&StrokeDashStyle>,
+        stroke_cap: Option<StrokeLineCap>,
+    ) {
+        let mut pb = PathBuilder::new();
+        pb.move_to(start.0, start.1);
+        pb.line_to(end.0, end.1);
+        let Some(path) = pb.finish() else {
+            return;
+        };
+
+        let stroke = if dash_style.is_some() || stroke_cap.is_some() {
+            path::stroke(
+                stroke_width,
+                dash_style,
+                stroke_cap.unwrap_or(StrokeLineCap::Square),
+                // Line join doesn't matter for single lines
+                StrokeLineJoin::Miter,
+            )
+        } else {
+            path::plain_stroke(stroke_width)
+        };
+        self.stroke(&path, &stroke, color);
+    }
+
+    fn draw_blurred_shadow(
+        &mut self,
+        rect: &RectDIP,
+        shadow: &DropShadow,
+        border_radius: Option<&BorderRadius>,
+    ) {
+        // The shadow shape: the element offset and expanded by the spread
+        let shadow_rect = RectDIP {
+            x: rect.x + shadow.offset_x - shadow.spread_radius,
+            y: rect.y + shadow.offset_y - shadow.spread_radius,
+            width: rect.width + shadow.spread_radius * 2.0,
+            height: rect.height + shadow.spread_radius * 2.0,
+        };
+        let sigma = shadow.blur_radius.max(0.0) / 2.0;
+
+        if !shadow.inset {
+            if let Some(path) = path::shape_path(&shadow_rect, border_radius) {
+                self.fill_blurred(&path, FillRule::Winding, shadow.color, sigma, None);
+            }
+            return;
+        }
+
+        // Inset shadows fill a frame around the shadow shape, clipped to the element
+        let Some(element) = path::shape_path(rect, border_radius) else {
+            return;
+        };
+        let margin = shadow.blur_radius.max(0.0) * 3.0
+            + shadow.spread_radius.abs()
+            + shadow.offset_x.abs().max(shadow.offset_y.abs());
+        let outer = RectDIP {
+            x: rect.x - margin,
+            y: rect.y - margin,
+            width: rect.width + margin * 2.0,
+            height: rect.height + margin * 2.0,
+        };
+
+        let mut pb = PathBuilder::new();
+        if let Some(outer) = Rect::from_xywh(outer.x, outer.y, outer.width, outer.height) {
+            pb.push_rect(outer);
+        }
+        match border_radius {
+            Some(border_radius) if border_radius.is_some() => {
+                path::push_rounded_rect(&mut pb, &shadow_rect, border_radius)
+            }
+            _ => {
+                if let Some(hole) = Rect::from_xywh(
+                    shadow_rect.x,
+                    shadow_rect.y,
+                    shadow_rect.width,
+                    shadow_rect.height,
+                ) {
+                    pb.push_rect(hole);
+                }
+            }
+        }
+        if let Some(frame) = pb.finish() {
+            let mask = self.clip_with(&element);
+            self.fill_blurred(&frame, FillRule::EvenOdd, shadow.color, sigma, Some(&mask));
+        }
+    }
+
+    /// Fills `path` into a scratch pixmap, blurs it with a gaussian of standard
+    /// deviation `sigma` (in DIPs) and composites the result onto the target.
+    fn fill_blurred(
+        &mut self,
+        path: &Path,
+        fill_rule: FillRule,
+        color: Color,
+        sigma: f32,
+        mask: Option<&Mask>,
+    ) {
+        let sigma = sigma * self.transform.sx;
+        let padding = (sigma * 3.0).ceil() as i32;
+
+        let Some(bounds) = path
+            .clone()
+            .transform(self.transform)
+            .map(|path| path.bounds())
+        else {
+            return;
+        };
+
+        // Only what can bleed into the target matters
+        let left = (bounds.left().floor() as i32 - padding).max(-padding);
+        let top = (bounds.top().floor() as i32 - padding).max(-padding);
+        let right = (bounds.right().ceil() as i32 + padding).min(self.width() as i32 + padding);
+        let bottom = (bounds.bottom().ceil() as i32 + padding).min(self.height() as i32 + padding);
+        if right <= left || bottom <= top {
+            return;
+        }
+
+        let Some(mut scratch) = Pixmap::new((right - left) as u32, (bottom - top) as u32) else {
+            return;
+        };
+        scratch.fill_path(
+            path,
+            &paint(color),
+            fill_rule,
+            self.transform.post_translate(-left as f32, -top as f32),
+            None,
+        );
+        blur::gaussian_blur(&mut scratch, sigma);
+
+        let (target, clip) = self.surface();
+        target.draw_pixmap(
+            left,
+            top,
+            scratch.as_ref(),
+            &PixmapPaint::default(),
+            Transform::identity(),
+            mask.or(clip),
+        );
+    }
+
+    fn fill_with_backdrop_filter(
+        &mut self,
+        rect: &RectDIP,
+        color: Color,
+        filter: &BackdropFilter,
+        border_radius: Option<&BorderRadius>,
+    ) {
+        let Some(shape) = path::shape_path(rect, border_radius) else {
+            return;
+        };
+
+        match filter {
+            BackdropFilter::Blur { radius } if *radius > 0.0 => {
+                let sigma = radius / 2.0 * self.transform.sx;
+                let padding = (sigma * 3.0).ceil();
+
+                let bounds = shape
+                    .clone()
+                    .transform(self.transform)
+                    .map(|path| path.bounds());
+                let region = bounds.and_then(|bounds| {
+                    let left = (bounds.left() - padding).floor().max(0.0);
+                    let top = (bounds.top() - padding).floor().max(0.0);
+                    let right = (bounds.right() + padding).ceil().min(self.width() as f32);
+                    let bottom = (bounds.bottom() + padding).ceil().min(self.height() as f32);
+                    IntRect::from_ltrb(left as i32, top as i32, right as i32, bottom as i32)
+                });
+
+                if let Some(region) = region {
+                    let transform = self.transform;
+                    let (target, clip) = self.surface();
+                    if let Some(mut backdrop) = target.clone_rect(region) {
+                        blur::gaussian_blur(&mut backdrop, sigma);
+                        // Only the shape is replaced, the pattern is placed back in device pixels
+                        let (x, y) = (region.x() as f32, region.y() as f32);
+                        let placement = Transform::from_translate(x, y)
+                            .post_concat(transform.invert().unwrap_or_default());
+                        let paint = Paint {
+                            shader: Pattern::new(
+                                backdrop.as_ref(),
+                                SpreadMode::Pad,
+                                FilterQuality::Nearest,
+                                1.0,
+                                placement,
+                            ),
+                            blend_mode: BlendMode::Source,
+                            anti_alias: true,
+                            ..Paint::default()
+                        };
+                        target.fill_path(&shape, &paint, FillRule::Winding, transform, clip);
+                    }
+                }
+            }
+            // Custom shader effects only exist on Direct2D
+            _ => {}
+        }
+
+        // Draw the fill color on top of the filtered backdrop
+        self.fill(&shape, color);
+    }
+
+    /// The current clip narrowed to `path`
+    fn clip_with(&self, path: &Path) -> Mask {
+        match &self.clip {
+            Some(clip) => {
+                let mut mask = clip.clone();
+                mask.intersect_path(path, FillRule::Winding, true, self.transform);
+                mask
+            }
+            None => {
+                let mut mask = Mask::new(self.target.width(), self.target.height())
+                    .expect("mask matches the target size");
+                mask.fill_path(path, FillRule::Winding, true, self.transform);
+                mask
+            }
+        }
+    }
+}
+
+/// Converts straight RGBA pixels into a premultiplied pixmap
+fn bitmap_pixmap(bitmap: &BitmapResource) -> Option<Pixmap> {
+    let mut pixmap = Pixmap::new(bitmap.width, bitmap.height)?;
+    for (pixel, rgba) in pixmap
+        .pixels_mut()
+        .iter_mut()
+        .zip(bitmap.pixels.chunks_exact(4))
+    {
+        *pixel = ColorU8::from_rgba(rgba[0], rgba[1], rgba[2], rgba[3]).premultiply();
+    }
+    Some(pixmap)
+}
+
+fn paint_color(color: Color) -> tiny_skia::Color {
+    tiny_skia::Color::from_rgba(
+        color.r.clamp(0.0, 1.0),
+        color.g.clamp(0.0, 1.0),
+        color.b.clamp(0.0, 1.0),
+        color.a.clamp(0.0, 1.0),
+    )
+    .unwrap_or(tiny_skia::Color::TRANSPARENT)
+}
+
+fn paint(color: Color) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color(paint_color(color));
+    paint.anti_alias = true;
+    paint
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SvgPathCommands;
+    use crate::gfx::PointDIP;
+    use crate::gfx::command_recorder::CommandRecorder;
+
+    const RED: Color = Color {
+        r: 1.0,
+        g: 0.0,
+        b: 0.0,
+        a: 1.0,
+    };
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> RectDIP {
+        RectDIP {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn fills_rectangles_and_clears() {
+        let image = SoftwareRenderer::render(
+            &DrawCommandList::from(vec![
+                DrawCommand::Clear {
+                    color: Color::WHITE,
+                },
+                DrawCommand::FillRectangle {
+                    rect: rect(2.0, 2.0, 4.0, 4.0),
+                    color: RED,
+                },
+            ]),
+            8,
+            8,
+        );
+
+        assert_eq!(image.pixel(3, 3), Some([255, 0, 0, 255]));
+        assert_eq!(image.pixel(0, 0), Some([255, 255, 255, 255]));
+        assert_eq!(image.pixel(8, 0), None);
+    }
+
+    #[test]
+    fn clips_nest_and_pop() {
+        let image = SoftwareRenderer::render(
+            &DrawCommandList::from(vec![
+                DrawCommand::PushAxisAlignedClip {
+                    rect: rect(0.0, 0.0, 4.0, 8.0),
+                },
+                DrawCommand::FillRectangle {
+                    rect: rect(0.0, 0.0, 8.0, 4.0),
+                    color: RED,
+                },
+                DrawCommand::PopAxisAlignedClip,
+                DrawCommand::FillRectangle {
+                    rect: rect(0.0, 4.0, 8.0, 4.0),
+                    color: Color::BLACK,
+                },
+            ]),
+            8,
+            8,
+        );
+
+        assert_eq!(image.pixel(1, 1), Some([255, 0, 0, 255]));
+        assert_eq!(image.pixel(6, 1), Some([0, 0, 0, 0]));
+        assert_eq!(image.pixel(6, 6), Some([0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn layers_apply_opacity() {
+        let image = SoftwareRenderer::render(
+            &DrawCommandList::from(vec![
+                DrawCommand::PushLayer { opacity: 0.5 },
+                DrawCommand::FillRectangle {
+                    rect: rect(0.0, 0.0, 4.0, 4.0),
+                    color: RED,
+                },
+                DrawCommand::PopLayer,
+            ]),
+            4,
+            4,
+        );
+
+        let [r, _, _, a] = image.pixel(2, 2).unwrap();
+        assert_eq!(r, 255);
+        assert!((127..=128).contains(&a), "alpha was {a}");
+    }
+
+    #[test]
+    fn shadows_blur_beyond_the_shape() {
+        let image = SoftwareRenderer::render(
+            &DrawCommandList::from(vec![DrawCommand::DrawBlurredShadow {
+                rect: rect(8.0, 8.0, 16.0, 16.0),
+                shadow: DropShadow {
+                    blur_radius: 8.0,
+                    color: Color::BLACK,
+                    ..DropShadow::default()
+                },
+                border_radius: None,
+            }]),
+            32,
+            32,
+        );
+
+        assert!(image.pixel(16, 16).unwrap()[3] > 230);
+        let edge = image.pixel(6, 16).unwrap()[3];
+        assert!(edge > 0 && edge < 255, "alpha was {edge}");
+        assert_eq!(image.pixel(0, 0).unwrap()[3], 0);
+    }
+
+    #[test]
+    fn arcs_and_scaling() {
+        let mut renderer = SoftwareRenderer::new(20, 20).with_dip_scale(2.0);
+        renderer.execute_command(
+            &DrawCommand::DrawCircleArc {
+                center: PointDIP { x: 5.0, y: 5.0 },
+                radius: 4.0,
+                start_angle_deg: 0.0,
+                end_angle_deg: 360.0,
+                stroke_width: 1.0,
+                color: RED,
+            },
+            &ResourceTable::new(),
+        );
+        let image = renderer.into_image();
+
+        // A 2px ring 8px from the center, empty in the middle
+        assert_eq!(image.pixel(17, 10), Some([255, 0, 0, 255]));
+        assert_eq!(image.pixel(10, 10).unwrap()[3], 0);
+    }
+
+    #[test]
+    fn draws_path_and_bitmap_resources() {
+        let mut recorder = CommandRecorder::new();
+        recorder.fill_path_geometry(
+            &rect(0.0, 0.0, 4.0, 4.0),
+            SvgPathList {
+                paths: &[SvgPathCommands::Rect {
+                    x: 0.0,
+                    y: 0.0,
+                    width: 2.0,
+                    height: 2.0,
+                    rx: 0.0,
+                    ry: 0.0,
+                }],
+            },
+            RED,
+            2.0,
+            2.0,
+        );
+        recorder.draw_bitmap(
+            &rect(4.0, 4.0, 4.0, 4.0),
+            BitmapResource::new(1, 1, vec![0, 0, 255, 255]),
+            1.0,
+        );
+        let image = SoftwareRenderer::render(&recorder.take_commands(), 8, 8);
+
+        assert_eq!(image.pixel(3, 3), Some([255, 0, 0, 255]));
+        assert_eq!(image.pixel(6, 6), Some([0, 0, 255, 255]));
+        assert_eq!(image.pixel(6, 1).unwrap()[3], 0);
+    }
+}
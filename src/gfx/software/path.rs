@@ -0,0 +1,428 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use tiny_skia::{LineCap, LineJoin, Path, PathBuilder, Rect, Stroke, StrokeDash};
+
+use crate::gfx::RectDIP;
+use crate::layout::model::{BorderRadius, StrokeDashStyle, StrokeLineCap, StrokeLineJoin};
+use crate::{PathCommand, SvgPathCommands, SvgPathList};
+
+const DRAW_EPSILON: f32 = 1e-6;
+
+/// Shortest dash Direct2D still draws caps for; tiny-skia drops zero-length dashes.
+const MIN_DASH_LENGTH: f32 = 1e-3;
+
+pub fn rect_path(rect: &RectDIP) -> Option<Path> {
+    let rect = Rect::from_xywh(rect.x, rect.y, rect.width, rect.height)?;
+    Some(PathBuilder::from_rect(rect))
+}
+
+/// Builds a rounded rectangle path, clamping each radius to half the smaller side.
+pub fn rounded_rect_path(rect: &RectDIP, border_radius: &BorderRadius) -> Option<Path> {
+    if !border_radius.is_some() {
+        return rect_path(rect);
+    }
+
+    let mut pb = PathBuilder::new();
+    push_rounded_rect(&mut pb, rect, border_radius);
+    pb.finish()
+}
+
+pub fn shape_path(rect: &RectDIP, border_radius: Option<&BorderRadius>) -> Option<Path> {
+    match border_radius {
+        Some(border_radius) => rounded_rect_path(rect, border_radius),
+        None => rect_path(rect),
+    }
+}
+
+/// Appends a clockwise rounded rectangle figure to `pb`.
+pub fn push_rounded_rect(pb: &mut PathBuilder, rect: &RectDIP, border_radius: &BorderRadius) {
+    let max_radius = (rect.width / 2.0).min(rect.height / 2.0).max(0.0);
+    let clamp = |radius: f32| {
+        let radius = radius.clamp(0.0, max_radius);
+        (radius, radius)
+    };
+
+    push_rect_with_corners(
+        pb,
+        rect,
+        [
+            clamp(border_radius.top_left),
+            clamp(border_radius.top_right),
+            clamp(border_radius.bottom_right),
+            clamp(border_radius.bottom_left),
+        ],
+    );
+}
+
+/// Appends a clockwise rectangle whose corners are elliptical arcs, given as
+/// `(radius_x, radius_y)` from the top left corner clockwise.
+fn push_rect_with_corners(pb: &mut PathBuilder, rect: &RectDIP, corners: [(f32, f32); 4]) {
+    let [tl, tr, br, bl] = corners;
+    let left = rect.x;
+    let top = rect.y;
+    let right = rect.x + rect.width;
+    let bottom = rect.y + rect.height;
+
+    pb.move_to(left + tl.0, top);
+    pb.line_to(right - tr.0, top);
+    if tr.0 > 0.0 && tr.1 > 0.0 {
+        append_arc(
+            pb,
+            right - tr.0,
+            top + tr.1,
+            tr.0,
+            tr.1,
+            0.0,
+            -FRAC_PI_2,
+            FRAC_PI_2,
+        );
+    }
+    pb.line_to(right, bottom - br.1);
+    if br.0 > 0.0 && br.1 > 0.0 {
+        append_arc(
+            pb,
+            right - br.0,
+            bottom - br.1,
+            br.0,
+            br.1,
+            0.0,
+            0.0,
+            FRAC_PI_2,
+        );
+    }
+    pb.line_to(left + bl.0, bottom);
+    if bl.0 > 0.0 && bl.1 > 0.0 {
+        append_arc(
+            pb,
+            left + bl.0,
+            bottom - bl.1,
+            bl.0,
+            bl.1,
+            0.0,
+            FRAC_PI_2,
+            FRAC_PI_2,
+        );
+    }
+    pb.line_to(left, top + tl.1);
+    if tl.0 > 0.0 && tl.1 > 0.0 {
+        append_arc(pb, left + tl.0, top + tl.1, tl.0, tl.1, 0.0, PI, FRAC_PI_2);
+    }
+    pb.close();
+}
+
+/// Builds an open circle arc. Angles are in degrees, measured clockwise from the
+/// positive X axis, matching `gfx::circle_arc::CircleArc`.
+pub fn circle_arc_path(
+    center_x: f32,
+    center_y: f32,
+    radius: f32,
+    begin_deg: f32,
+    end_deg: f32,
+) -> Option<Path> {
+    // Don't emit anything for empty arcs
+    if begin_deg.abs() < DRAW_EPSILON && end_deg.abs() < DRAW_EPSILON {
+        return None;
+    }
+
+    // Normalize clockwise delta to [0, 360), where zero means a full circle
+    let mut cw_delta = (end_deg - begin_deg) % 360.0;
+    if cw_delta < 0.0 {
+        cw_delta += 360.0;
+    }
+    if cw_delta.abs() < DRAW_EPSILON {
+        cw_delta = 360.0;
+    }
+
+    let start = begin_deg.to_radians();
+    let mut pb = PathBuilder::new();
+    pb.move_to(
+        center_x + radius * start.cos(),
+        center_y + radius * start.sin(),
+    );
+    append_arc(
+        &mut pb,
+        center_x,
+        center_y,
+        radius,
+        radius,
+        0.0,
+        start,
+        cw_delta.to_radians(),
+    );
+    pb.finish()
+}
+
+/// Converts a [`SvgPathList`] into a path, following the figure rules of
+/// `SvgPathList::create_geometry`. Fill it with the winding rule.
+pub fn svg_path(list: &SvgPathList) -> Option<Path> {
+    let mut pb = PathBuilder::new();
+
+    for commands in list.paths {
+        match commands {
+            SvgPathCommands::Circle { cx, cy, r } => {
+                pb.push_circle(*cx, *cy, *r);
+            }
+            SvgPathCommands::Rect {
+                x,
+                y,
+                width,
+                height,
+                rx,
+                ry,
+            } => {
+                let rect = RectDIP {
+                    x: *x,
+                    y: *y,
+                    width: *width,
+                    height: *height,
+                };
+                // Clamp radii to prevent overlapping
+                let rx = rx.min(width / 2.0);
+                let ry = ry.min(height / 2.0);
+                if rx > 0.0 && ry > 0.0 {
+                    push_rect_with_corners(&mut pb, &rect, [(rx, ry); 4]);
+                } else if let Some(rect) = Rect::from_xywh(*x, *y, *width, *height) {
+                    pb.push_rect(rect);
+                }
+            }
+            SvgPathCommands::Path(commands) => {
+                let mut current = (0.0, 0.0);
+                let mut figure_start = (0.0, 0.0);
+
+                for command in *commands {
+                    match *command {
+                        PathCommand::MoveTo { x, y } => {
+                            pb.move_to(x, y);
+                            current = (x, y);
+                            figure_start = current;
+                        }
+                        PathCommand::LineTo { x, y } => {
+                            pb.line_to(x, y);
+                            current = (x, y);
+                        }
+                        PathCommand::Arc {
+                            radius_x,
+                            radius_y,
+                            rotation,
+                            large_arc,
+                            sweep,
+                            end_x,
+                            end_y,
+                        } => {
+                            append_svg_arc(
+                                &mut pb,
+                                current,
+                                radius_x,
+                                radius_y,
+                                rotation,
+                                large_arc,
+                                sweep,
+                                (end_x, end_y),
+                            );
+                            current = (end_x, end_y);
+                        }
+                        PathCommand::CubicBezier {
+                            cp1_x,
+                            cp1_y,
+                            cp2_x,
+                            cp2_y,
+                            end_x,
+                            end_y,
+                        } => {
+                            pb.cubic_to(cp1_x, cp1_y, cp2_x, cp2_y, end_x, end_y);
+                            current = (end_x, end_y);
+                        }
+                        PathCommand::QuadraticBezier {
+                            cp_x,
+                            cp_y,
+                            end_x,
+                            end_y,
+                        } => {
+                            pb.quad_to(cp_x, cp_y, end_x, end_y);
+                            current = (end_x, end_y);
+                        }
+                        PathCommand::ClosePath => {
+                            pb.close();
+                            current = figure_start;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pb.finish()
+}
+
+/// Appends an elliptical arc around a center as cubic segments of at most 90 degrees.
+/// Angles are in radians; a positive sweep runs clockwise on screen.
+#[allow(clippy::too_many_arguments)]
+fn append_arc(
+    pb: &mut PathBuilder,
+    center_x: f32,
+    center_y: f32,
+    radius_x: f32,
+    radius_y: f32,
+    rotation: f32,
+    start: f32,
+    sweep: f32,
+) {
+    let segments = (sweep.abs() / FRAC_PI_2).ceil().max(1.0) as usize;
+    let step = sweep / segments as f32;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    let (sin_r, cos_r) = rotation.sin_cos();
+
+    let point = |x: f32, y: f32| {
+        (
+            center_x + x * cos_r - y * sin_r,
+            center_y + x * sin_r + y * cos_r,
+        )
+    };
+
+    let mut angle = start;
+    for _ in 0..segments {
+        let next = angle + step;
+        let (sin_a, cos_a) = angle.sin_cos();
+        let (sin_b, cos_b) = next.sin_cos();
+
+        let cp1 = point(
+            radius_x * (cos_a - k * sin_a),
+            radius_y * (sin_a + k * cos_a),
+        );
+        let cp2 = point(
+            radius_x * (cos_b + k * sin_b),
+            radius_y * (sin_b - k * cos_b),
+        );
+        let end = point(radius_x * cos_b, radius_y * sin_b);
+        pb.cubic_to(cp1.0, cp1.1, cp2.0, cp2.1, end.0, end.1);
+
+        angle = next;
+    }
+}
+
+/// Appends an SVG endpoint-parameterized arc (SVG 1.1 implementation notes, F.6.5).
+#[allow(clippy::too_many_arguments)]
+fn append_svg_arc(
+    pb: &mut PathBuilder,
+    from: (f32, f32),
+    radius_x: f32,
+    radius_y: f32,
+    rotation_deg: f32,
+    large_arc: bool,
+    sweep: bool,
+    to: (f32, f32),
+) {
+    let mut rx = radius_x.abs();
+    let mut ry = radius_y.abs();
+    if rx < DRAW_EPSILON || ry < DRAW_EPSILON || from == to {
+        pb.line_to(to.0, to.1);
+        return;
+    }
+
+    let phi = rotation_deg.to_radians();
+    let (sin_phi, cos_phi) = phi.sin_cos();
+
+    let dx = (from.0 - to.0) / 2.0;
+    let dy = (from.1 - to.1) / 2.0;
+    let x1 = cos_phi * dx + sin_phi * dy;
+    let y1 = -sin_phi * dx + cos_phi * dy;
+
+    // Scale radii up if they cannot span the endpoints
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1.0 {
+        let s = lambda.sqrt();
+        rx *= s;
+        ry *= s;
+    }
+
+    let num = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let den = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let mut coef = (num / den).max(0.0).sqrt();
+    if large_arc == sweep {
+        coef = -coef;
+    }
+    let cx1 = coef * rx * y1 / ry;
+    let cy1 = -coef * ry * x1 / rx;
+
+    let center_x = cos_phi * cx1 - sin_phi * cy1 + (from.0 + to.0) / 2.0;
+    let center_y = sin_phi * cx1 + cos_phi * cy1 + (from.1 + to.1) / 2.0;
+
+    let start = ((y1 - cy1) / ry).atan2((x1 - cx1) / rx);
+    let end = ((-y1 - cy1) / ry).atan2((-x1 - cx1) / rx);
+    let mut delta = end - start;
+    if sweep && delta < 0.0 {
+        delta += 2.0 * PI;
+    } else if !sweep && delta > 0.0 {
+        delta -= 2.0 * PI;
+    }
+
+    append_arc(pb, center_x, center_y, rx, ry, phi, start, delta);
+}
+
+/// Builds a stroke matching the Direct2D stroke style the renderer would create.
+/// Dash lengths are in multiples of the stroke width, as in Direct2D.
+pub fn stroke(
+    width: f32,
+    dash_style: Option<&StrokeDashStyle>,
+    cap: StrokeLineCap,
+    join: StrokeLineJoin,
+) -> Stroke {
+    let line_cap = match cap {
+        StrokeLineCap::Flat => LineCap::Butt,
+        StrokeLineCap::Round => LineCap::Round,
+        StrokeLineCap::Square => LineCap::Square,
+        // tiny-skia has no triangle caps, round is the closest shape
+        StrokeLineCap::Triangle => LineCap::Round,
+    };
+
+    let line_join = match join {
+        StrokeLineJoin::Miter => LineJoin::MiterClip,
+        StrokeLineJoin::Bevel => LineJoin::Bevel,
+        StrokeLineJoin::Round => LineJoin::Round,
+        StrokeLineJoin::MiterOrBevel => LineJoin::Miter,
+    };
+
+    let (pattern, offset): (&[f32], f32) = match dash_style {
+        None | Some(StrokeDashStyle::Solid) => (&[], 0.0),
+        Some(StrokeDashStyle::Dash) => (&[2.0, 2.0], 0.0),
+        Some(StrokeDashStyle::Dot) => (&[0.0, 2.0], 0.0),
+        Some(StrokeDashStyle::DashDot) => (&[2.0, 2.0, 0.0, 2.0], 0.0),
+        Some(StrokeDashStyle::DashDotDot) => (&[2.0, 2.0, 0.0, 2.0, 0.0, 2.0], 0.0),
+        Some(StrokeDashStyle::Custom { dashes, offset }) => (dashes, *offset),
+    };
+
+    let dash = if pattern.is_empty() {
+        None
+    } else {
+        // An odd pattern repeats with dashes and gaps swapped
+        let repeat = if pattern.len() % 2 == 0 { 1 } else { 2 };
+        let intervals = pattern
+            .iter()
+            .cycle()
+            .take(pattern.len() * repeat)
+            .enumerate()
+            .map(|(i, len)| {
+                let len = len.max(0.0) * width;
+                if i % 2 == 0 {
+                    len.max(MIN_DASH_LENGTH)
+                } else {
+                    len
+                }
+            })
+            .collect();
+        StrokeDash::new(intervals, offset * width)
+    };
+
+    Stroke {
+        width,
+        miter_limit: 10.0,
+        line_cap,
+        line_join,
+        dash,
+    }
+}
+
+/// The stroke Direct2D uses when no stroke style is given.
+pub fn plain_stroke(width: f32) -> Stroke {
+    stroke(width, None, StrokeLineCap::Flat, StrokeLineJoin::Miter)
+}
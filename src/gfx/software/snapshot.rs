@@ -0,0 +1,338 @@
+//! Golden-image snapshots.
+//!
+//! Rendered [`RgbaImage`]s are compared against reference PNGs stored in the
+//! repository. When they differ by more than a per-channel tolerance, the
+//! actual image and a diff highlighting the mismatched pixels are written next
+//! to the golden file so the failure can be inspected.
+//!
+//! Set `RAXIS_UPDATE_SNAPSHOTS=1` to (re)write golden files instead of
+//! comparing against them.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use super::RgbaImage;
+
+/// Environment variable that makes [`compare_with_golden`] write golden files
+pub const UPDATE_SNAPSHOTS_ENV: &str = "RAXIS_UPDATE_SNAPSHOTS";
+
+/// Color of mismatched pixels in diff images
+const DIFF_COLOR: [u8; 4] = [255, 0, 255, 255];
+
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("I/O error on {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to encode PNG: {0}")]
+    Encode(#[from] png::EncodingError),
+
+    #[error("Failed to decode PNG: {0}")]
+    Decode(#[from] png::DecodingError),
+
+    #[error("Golden file {path} does not exist, set {UPDATE_SNAPSHOTS_ENV}=1 to create it")]
+    MissingGolden { path: PathBuf },
+
+    #[error("Snapshot is {actual:?} pixels but golden file {path} is {expected:?}")]
+    SizeMismatch {
+        path: PathBuf,
+        actual: (u32, u32),
+        expected: (u32, u32),
+    },
+
+    #[error(
+        "{mismatched_pixels} pixels differ from golden file {path} by more than {tolerance} \
+         (at most {max_difference}), see {diff_path}"
+    )]
+    Mismatch {
+        path: PathBuf,
+        diff_path: PathBuf,
+        mismatched_pixels: usize,
+        max_difference: u8,
+        tolerance: u8,
+    },
+}
+
+/// The result of comparing two images of the same size
+#[derive(Clone, Debug)]
+pub struct ImageDiff {
+    /// Pixels with a channel differing by more than the tolerance
+    pub mismatched_pixels: usize,
+    /// Largest difference of any channel of any pixel
+    pub max_difference: u8,
+    /// The expected image dimmed, with mismatched pixels highlighted
+    pub image: RgbaImage,
+}
+
+impl RgbaImage {
+    /// Encodes the image as an 8-bit RGBA PNG.
+    pub fn encode_png(&self) -> Result<Vec<u8>, SnapshotError> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.data)?;
+        writer.finish()?;
+
+        Ok(bytes)
+    }
+
+    /// Decodes a PNG of any color type into straight RGBA8.
+    pub fn decode_png(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        buffer.truncate(info.buffer_size());
+
+        let data = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => buffer
+                .chunks_exact(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buffer
+                .chunks_exact(2)
+                .flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]])
+                .collect(),
+            // Indexed images are expanded to RGB(A) by the transformations
+            png::ColorType::Grayscale | png::ColorType::Indexed => {
+                buffer.iter().flat_map(|&g| [g, g, g, 255]).collect()
+            }
+        };
+
+        Ok(Self {
+            width: info.width,
+            height: info.height,
+            data,
+        })
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        let path = path.as_ref();
+        let bytes = self.encode_png()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|source| io_error(parent, source))?;
+        }
+        fs::write(path, bytes).map_err(|source| io_error(path, source))
+    }
+
+    pub fn load_png(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(|source| io_error(path, source))?;
+        Self::decode_png(&bytes)
+    }
+
+    /// Compares this image with `expected` pixel by pixel.
+    ///
+    /// A pixel mismatches when any of its channels differs by more than
+    /// `tolerance`. Returns `None` when the sizes differ.
+    pub fn diff(&self, expected: &RgbaImage, tolerance: u8) -> Option<ImageDiff> {
+        if self.width != expected.width || self.height != expected.height {
+            return None;
+        }
+
+        let mut mismatched_pixels = 0;
+        let mut max_difference = 0;
+        let mut data = Vec::with_capacity(expected.data.len());
+
+        for (actual, expected) in self.data.chunks_exact(4).zip(expected.data.chunks_exact(4)) {
+            let difference = actual
+                .iter()
+                .zip(expected)
+                .map(|(a, e)| a.abs_diff(*e))
+                .max()
+                .unwrap_or(0);
+            max_difference = max_difference.max(difference);
+
+            if difference > tolerance {
+                mismatched_pixels += 1;
+                data.extend_from_slice(&DIFF_COLOR);
+            } else {
+                // Faded grayscale of the expected pixel for context
+                let luma = (expected[0] as u32 * 299
+                    + expected[1] as u32 * 587
+                    + expected[2] as u32 * 114)
+                    / 1000;
+                let faded = (255 - (255 - luma) * expected[3] as u32 / 255 / 4) as u8;
+                data.extend_from_slice(&[faded, faded, faded, 255]);
+            }
+        }
+
+        Some(ImageDiff {
+            mismatched_pixels,
+            max_difference,
+            image: RgbaImage {
+                width: self.width,
+                height: self.height,
+                data,
+            },
+        })
+    }
+}
+
+/// Compares `image` against the golden PNG at `golden_path`.
+///
+/// On failure `<name>.actual.png` is written next to the golden file, plus
+/// `<name>.diff.png` when the sizes match. Stale failure artifacts are removed
+/// when the comparison passes. With [`UPDATE_SNAPSHOTS_ENV`] set, the golden
+/// file is overwritten with `image` instead.
+pub fn compare_with_golden(
+    image: &RgbaImage,
+    golden_path: impl AsRef<Path>,
+    tolerance: u8,
+) -> Result<(), SnapshotError> {
+    let update = std::env::var_os(UPDATE_SNAPSHOTS_ENV).is_some_and(|value| value != "0");
+    compare(image, golden_path.as_ref(), tolerance, update)
+}
+
+/// Like [`compare_with_golden`], but panics with the error so it can be used
+/// directly in tests.
+#[track_caller]
+pub fn assert_golden(image: &RgbaImage, golden_path: impl AsRef<Path>, tolerance: u8) {
+    if let Err(error) = compare_with_golden(image, golden_path, tolerance) {
+        panic!("{error}");
+    }
+}
+
+fn compare(
+    image: &RgbaImage,
+    path: &Path,
+    tolerance: u8,
+    update: bool,
+) -> Result<(), SnapshotError> {
+    let actual_path = sibling_path(path, "actual");
+    let diff_path = sibling_path(path, "diff");
+
+    if update {
+        remove_if_exists(&actual_path)?;
+        remove_if_exists(&diff_path)?;
+        return image.save_png(path);
+    }
+
+    if !path.exists() {
+        image.save_png(&actual_path)?;
+        return Err(SnapshotError::MissingGolden {
+            path: path.to_path_buf(),
+        });
+    }
+
+    let expected = RgbaImage::load_png(path)?;
+    let Some(diff) = image.diff(&expected, tolerance) else {
+        image.save_png(&actual_path)?;
+        return Err(SnapshotError::SizeMismatch {
+            path: path.to_path_buf(),
+            actual: (image.width, image.height),
+            expected: (expected.width, expected.height),
+        });
+    };
+
+    if diff.mismatched_pixels > 0 {
+        image.save_png(&actual_path)?;
+        diff.image.save_png(&diff_path)?;
+        return Err(SnapshotError::Mismatch {
+            path: path.to_path_buf(),
+            diff_path,
+            mismatched_pixels: diff.mismatched_pixels,
+            max_difference: diff.max_difference,
+            tolerance,
+        });
+    }
+
+    remove_if_exists(&actual_path)?;
+    remove_if_exists(&diff_path)
+}
+
+/// `dir/name.png` -> `dir/name.<suffix>.png`
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{stem}.{suffix}.png"))
+}
+
+fn remove_if_exists(path: &Path) -> Result<(), SnapshotError> {
+    match fs::remove_file(path) {
+        Err(source) if source.kind() != std::io::ErrorKind::NotFound => Err(io_error(path, source)),
+        _ => Ok(()),
+    }
+}
+
+fn io_error(path: &Path, source: std::io::Error) -> SnapshotError {
+    SnapshotError::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(pixels: &[[u8; 4]], width: u32) -> RgbaImage {
+        RgbaImage {
+            width,
+            height: pixels.len() as u32 / width,
+            data: pixels.concat(),
+        }
+    }
+
+    #[test]
+    fn png_round_trips() {
+        let original = image(
+            &[
+                [255, 0, 0, 255],
+                [0, 255, 0, 128],
+                [0, 0, 255, 0],
+                [1, 2, 3, 4],
+            ],
+            2,
+        );
+
+        let decoded = RgbaImage::decode_png(&original.encode_png().unwrap()).unwrap();
+
+        assert_eq!(decoded, original);
+    }
+
+    #[test]
+    fn diff_respects_tolerance() {
+        let expected = image(&[[100, 100, 100, 255], [0, 0, 0, 255]], 2);
+        let actual = image(&[[102, 100, 100, 255], [0, 0, 90, 255]], 2);
+
+        let diff = actual.diff(&expected, 2).unwrap();
+        assert_eq!(diff.mismatched_pixels, 1);
+        assert_eq!(diff.max_difference, 90);
+        assert_eq!(diff.image.pixel(1, 0), Some(DIFF_COLOR));
+
+        assert!(actual.diff(&image(&[[0; 4]], 1), 2).is_none());
+    }
+
+    #[test]
+    fn mismatch_writes_actual_and_diff() {
+        let dir = std::env::temp_dir().join(format!("raxis-snapshot-{}", std::process::id()));
+        let golden = dir.join("square.png");
+        image(&[[0, 0, 0, 255]; 4], 2).save_png(&golden).unwrap();
+
+        assert!(compare(&image(&[[1, 1, 1, 255]; 4], 2), &golden, 1, false).is_ok());
+
+        let error = compare(&image(&[[9, 9, 9, 255]; 4], 2), &golden, 1, false).unwrap_err();
+        assert!(matches!(
+            error,
+            SnapshotError::Mismatch {
+                mismatched_pixels: 4,
+                ..
+            }
+        ));
+        assert!(dir.join("square.actual.png").exists());
+        assert!(dir.join("square.diff.png").exists());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,107 @@
+//! Text laid out by a [`TextEngine`] and turned into fillable glyph paths.
+
+use tiny_skia::{Path, PathBuilder};
+
+use crate::gfx::RectDIP;
+use crate::gfx::resources::TextResource;
+use crate::gfx::text_engine::{OutlineSink, TextEngine};
+
+/// Glyph outlines of a [`TextResource`], relative to its layout box
+pub(super) struct TextOutlines {
+    /// Byte position of the character each glyph was shaped from, with its path
+    pub glyphs: Vec<(usize, Path)>,
+    /// Underline rectangles, with the index of the run each belongs to
+    pub underlines: Vec<(usize, RectDIP)>,
+}
+
+impl TextOutlines {
+    /// Lays out `text` with `engine`. Returns `None` when the engine can't
+    /// provide glyph outlines.
+    pub fn new(engine: &dyn TextEngine, text: &TextResource) -> Option<Self> {
+        let layout = engine
+            .create_layout(&text.text, &text.style, text.max_width, text.max_height)
+            .ok()?;
+
+        let mut sink = PathSink::default();
+        if !layout.outline(&mut sink) {
+            return None;
+        }
+        sink.finish_glyph();
+
+        // Underlines sit a little below the baseline of each line they cross
+        let lines = layout.lines();
+        let thickness = (text.style.font_size / 14.0).max(1.0);
+        let mut underlines = Vec::new();
+        for (index, run) in text.runs.iter().enumerate() {
+            if !run.underline {
+                continue;
+            }
+            for rect in layout.range_rects(run.start..run.end) {
+                let Some(line) = lines.iter().find(|line| (line.top - rect.y).abs() < 0.5) else {
+                    continue;
+                };
+                underlines.push((
+                    index,
+                    RectDIP {
+                        x: rect.x,
+                        y: line.top + line.baseline + thickness,
+                        width: rect.width,
+                        height: thickness,
+                    },
+                ));
+            }
+        }
+
+        Some(Self {
+            glyphs: sink.glyphs,
+            underlines,
+        })
+    }
+}
+
+#[derive(Default)]
+struct PathSink {
+    glyphs: Vec<(usize, Path)>,
+    current: Option<(usize, PathBuilder)>,
+}
+
+impl PathSink {
+    fn finish_glyph(&mut self) {
+        if let Some((position, builder)) = self.current.take()
+            && let Some(path) = builder.finish()
+        {
+            self.glyphs.push((position, path));
+        }
+    }
+
+    fn builder(&mut self) -> &mut PathBuilder {
+        &mut self.current.get_or_insert_with(Default::default).1
+    }
+}
+
+impl OutlineSink for PathSink {
+    fn begin_glyph(&mut self, position: usize) {
+        self.finish_glyph();
+        self.current = Some((position, PathBuilder::new()));
+    }
+
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.builder().move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.builder().line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.builder().quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.builder().cubic_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.builder().close();
+    }
+}
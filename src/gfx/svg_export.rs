@@ -0,0 +1,1200 @@
+//! Serializes [`DrawCommandList`]s into standalone SVG documents.
+//!
+//! Commands are interpreted the way the software renderer interprets them, so an
+//! exported frame looks like its snapshot while staying editable: shapes stay
+//! shapes and text stays text. Clips become `clipPath`s, layers become groups
+//! with an opacity and shadows are blurred with SVG filters. Backdrop filters
+//! have no SVG equivalent and are exported as plain fills.
+
+use std::fmt::{self, Display, Write};
+use std::ops::Range;
+use std::rc::Rc;
+
+use crate::gfx::RectDIP;
+use crate::gfx::draw_commands::{DrawCommand, DrawCommandList};
+use crate::gfx::resources::{Resource, ResourceTable, SvgResource, TextResource};
+use crate::gfx::software::RgbaImage;
+use crate::gfx::text_engine::TextEngine;
+use crate::gfx::text_style::{FontStyle, FontWidth, TextAlignment};
+use crate::layout::model::{
+    Border, BorderPlacement, BorderRadius, Color, DropShadow, StrokeDashStyle, StrokeLineCap,
+    StrokeLineJoin, TextShadow,
+};
+use crate::{PathCommand, SvgPathCommands, SvgPathList};
+
+/// Line height, relative to the font size, used to place text without a text engine
+const FALLBACK_LINE_HEIGHT: f32 = 1.2;
+/// Distance from the top of a line to its baseline, relative to the font size
+const FALLBACK_BASELINE: f32 = 0.8;
+
+/// Writes command lists as SVG documents of a fixed size in DIPs.
+pub struct SvgExporter {
+    width: f32,
+    height: f32,
+    /// Places lines of text, which otherwise follow nominal font metrics
+    text_engine: Option<Rc<dyn TextEngine>>,
+}
+
+impl SvgExporter {
+    /// Creates an exporter for a `width` x `height` DIP viewport.
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            text_engine: None,
+        }
+    }
+
+    /// Lays out text with `text_engine`, so lines and runs end up exactly
+    /// where the renderer draws them.
+    pub fn with_text_engine(mut self, text_engine: Rc<dyn TextEngine>) -> Self {
+        self.text_engine = Some(text_engine);
+        self
+    }
+
+    /// Serializes `commands` into an SVG document.
+    pub fn export(&self, commands: &DrawCommandList) -> String {
+        let mut writer = SvgWriter {
+            resources: &commands.resources,
+            text_engine: self.text_engine.as_deref(),
+            defs: String::new(),
+            body: String::new(),
+            open_groups: 0,
+            next_id: 0,
+        };
+        for command in commands {
+            writer.command(command);
+        }
+        while writer.open_groups > 0 {
+            writer.close_group();
+        }
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" \
+             xmlns:xlink=\"http://www.w3.org/1999/xlink\" \
+             width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+            w = Num(self.width),
+            h = Num(self.height),
+        );
+        if !writer.defs.is_empty() {
+            svg.push_str("  <defs>\n");
+            svg.push_str(&writer.defs);
+            svg.push_str("  </defs>\n");
+        }
+        svg.push_str(&writer.body);
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+struct SvgWriter<'a> {
+    resources: &'a ResourceTable,
+    text_engine: Option<&'a dyn TextEngine>,
+    defs: String,
+    body: String,
+    /// Groups opened by clips, layers and transforms that haven't been popped yet
+    open_groups: usize,
+    next_id: usize,
+}
+
+impl SvgWriter<'_> {
+    fn command(&mut self, command: &DrawCommand) {
+        match command {
+            DrawCommand::Clear { color } => {
+                self.element(format!(
+                    "<rect width=\"100%\" height=\"100%\"{}/>",
+                    Paint::Fill(*color)
+                ));
+            }
+            DrawCommand::FillRectangle { rect, color } => {
+                self.element(format!("{}{}/>", shape(rect, None), Paint::Fill(*color)));
+            }
+            DrawCommand::FillRoundedRectangle {
+                rect,
+                border_radius,
+                color,
+            } => {
+                self.element(format!(
+                    "{}{}/>",
+                    shape(rect, Some(border_radius)),
+                    Paint::Fill(*color)
+                ));
+            }
+            // Only the fill survives, SVG can't filter what's behind an element
+            DrawCommand::FillRectangleWithBackdropFilter {
+                rect,
+                color,
+                border_radius,
+                ..
+            } => {
+                self.element(format!(
+                    "{}{}/>",
+                    shape(rect, border_radius.as_ref()),
+                    Paint::Fill(*color)
+                ));
+            }
+            DrawCommand::DrawBlurredShadow {
+                rect,
+                shadow,
+                border_radius,
+            } => self.shadow(rect, shadow, border_radius.as_ref()),
+            DrawCommand::DrawText {
+                rect,
+                text,
+                color,
+                text_shadows,
+            } => {
+                if let Some(Resource::Text(resource)) = self.resources.get(*text) {
+                    self.text(rect, resource, *color, text_shadows);
+                }
+            }
+            DrawCommand::DrawRectangleOutline {
+                rect,
+                color,
+                stroke_width,
+            } => {
+                self.element(format!(
+                    "{} fill=\"none\"{}{}/>",
+                    shape(rect, None),
+                    Paint::Stroke(*color),
+                    Stroke::plain(*stroke_width)
+                ));
+            }
+            DrawCommand::DrawRoundedRectangleOutline {
+                rect,
+                border_radius,
+                color,
+                stroke_width,
+            } => {
+                self.element(format!(
+                    "{} fill=\"none\"{}{}/>",
+                    shape(rect, Some(border_radius)),
+                    Paint::Stroke(*color),
+                    Stroke::plain(*stroke_width)
+                ));
+            }
+            DrawCommand::DrawBorder {
+                rect,
+                border_radius,
+                border,
+            } => self.border(rect, border_radius.as_ref(), border),
+            DrawCommand::DrawCircleArc {
+                center,
+                radius,
+                start_angle_deg,
+                end_angle_deg,
+                stroke_width,
+                color,
+            } => {
+                let Some(arc) = circle_arc(
+                    center.x,
+                    center.y,
+                    *radius,
+                    *start_angle_deg,
+                    *end_angle_deg,
+                ) else {
+                    return;
+                };
+                self.element(format!(
+                    "{arc} fill=\"none\"{}{}/>",
+                    Paint::Stroke(*color),
+                    Stroke::plain(*stroke_width)
+                ));
+            }
+            DrawCommand::PushAxisAlignedClip { rect } => self.push_clip(shape(rect, None)),
+            DrawCommand::PushRoundedClip {
+                rect,
+                border_radius,
+            } => self.push_clip(shape(rect, Some(border_radius))),
+            DrawCommand::PopAxisAlignedClip
+            | DrawCommand::PopRoundedClip
+            | DrawCommand::PopLayer
+            | DrawCommand::PopTransform => self.close_group(),
+            DrawCommand::PushLayer { opacity } => {
+                self.open_group(format!("<g opacity=\"{}\">", Num(opacity.clamp(0.0, 1.0))));
+            }
+            DrawCommand::PushTransform { transform } => {
+                self.open_group(format!(
+                    "<g transform=\"matrix({} {} {} {} {} {})\">",
+                    Num(transform.m11),
+                    Num(transform.m12),
+                    Num(transform.m21),
+                    Num(transform.m22),
+                    Num(transform.dx),
+                    Num(transform.dy)
+                ));
+            }
+            // Every command carries its own color
+            DrawCommand::SetBrushColor { .. } => {}
+            DrawCommand::DrawSvg { rect, svg } => {
+                if let Some(Resource::Svg(resource)) = self.resources.get(*svg) {
+                    self.svg(rect, resource);
+                }
+            }
+            DrawCommand::FillPathGeometry {
+                rect,
+                path,
+                color,
+                scale_x,
+                scale_y,
+            } => {
+                if let Some(Resource::Path(paths)) = self.resources.get(*path) {
+                    self.element(format!(
+                        "<path d=\"{}\" transform=\"translate({} {}) scale({} {})\"{}/>",
+                        path_data(paths),
+                        Num(rect.x),
+                        Num(rect.y),
+                        Num(*scale_x),
+                        Num(*scale_y),
+                        Paint::Fill(*color)
+                    ));
+                }
+            }
+            DrawCommand::StrokePathGeometry {
+                rect,
+                path,
+                color,
+                stroke_width,
+                scale_x,
+                scale_y,
+                stroke_cap,
+                stroke_join,
+            } => {
+                if let Some(Resource::Path(paths)) = self.resources.get(*path) {
+                    // For 1px stroke, offset by 0.5 to avoid antialiasing
+                    let (x, y) = if *stroke_width == 1.0 {
+                        (rect.x - 0.5, rect.y - 0.5)
+                    } else {
+                        (rect.x, rect.y)
+                    };
+                    let stroke = if stroke_cap.is_some() || stroke_join.is_some() {
+                        Stroke {
+                            width: *stroke_width,
+                            dash_style: None,
+                            cap: stroke_cap.unwrap_or(StrokeLineCap::Square),
+                            join: stroke_join.unwrap_or(StrokeLineJoin::Miter),
+                        }
+                    } else {
+                        Stroke::plain(*stroke_width)
+                    };
+                    self.element(format!(
+                        "<path d=\"{}\" transform=\"translate({} {}) scale({} {})\" \
+                         fill=\"none\"{}{stroke}/>",
+                        path_data(paths),
+                        Num(x),
+                        Num(y),
+                        Num(*scale_x),
+                        Num(*scale_y),
+                        Paint::Stroke(*color)
+                    ));
+                }
+            }
+            DrawCommand::DrawLine {
+                start_x,
+                start_y,
+                end_x,
+                end_y,
+                color,
+                stroke_width,
+                dash_style,
+                stroke_cap,
+            } => {
+                let stroke = if dash_style.is_some() || stroke_cap.is_some() {
+                    Stroke {
+                        width: *stroke_width,
+                        dash_style: *dash_style,
+                        cap: stroke_cap.unwrap_or(StrokeLineCap::Square),
+                        join: StrokeLineJoin::Miter,
+                    }
+                } else {
+                    Stroke::plain(*stroke_width)
+                };
+                self.element(format!(
+                    "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"{}{stroke}/>",
+                    Num(*start_x),
+                    Num(*start_y),
+                    Num(*end_x),
+                    Num(*end_y),
+                    Paint::Stroke(*color)
+                ));
+            }
+            DrawCommand::DrawBitmap {
+                rect,
+                bitmap,
+                opacity,
+            } => {
+                let Some(Resource::Bitmap(bitmap)) = self.resources.get(*bitmap) else {
+                    return;
+                };
+                let image = RgbaImage {
+                    width: bitmap.width,
+                    height: bitmap.height,
+                    data: bitmap.pixels.to_vec(),
+                };
+                let Ok(png) = image.encode_png() else {
+                    return;
+                };
+                let opacity = if *opacity < 1.0 {
+                    format!(" opacity=\"{}\"", Num(opacity.max(0.0)))
+                } else {
+                    String::new()
+                };
+                self.element(format!(
+                    "{} preserveAspectRatio=\"none\"{opacity} \
+                     xlink:href=\"data:image/png;base64,{}\"/>",
+                    image_element(rect),
+                    base64(&png)
+                ));
+            }
+        }
+    }
+
+    fn indent(&self) -> usize {
+        2 + self.open_groups * 2
+    }
+
+    fn element(&mut self, element: impl Display) {
+        let _ = writeln!(self.body, "{:indent$}{element}", "", indent = self.indent());
+    }
+
+    fn open_group(&mut self, group: String) {
+        self.element(group);
+        self.open_groups += 1;
+    }
+
+    fn close_group(&mut self) {
+        if self.open_groups > 0 {
+            self.open_groups -= 1;
+            self.element("</g>");
+        }
+    }
+
+    /// Adds a definition and returns the id it can be referenced by.
+    fn define(&mut self, prefix: &str, definition: impl FnOnce(&str) -> String) -> String {
+        let id = format!("{prefix}{}", self.next_id);
+        self.next_id += 1;
+        let _ = writeln!(self.defs, "    {}", definition(&id));
+        id
+    }
+
+    fn push_clip(&mut self, shape: String) {
+        let id = self.define("clip", |id| {
+            format!("<clipPath id=\"{id}\">{shape}/></clipPath>")
+        });
+        self.open_group(format!("<g clip-path=\"url(#{id})\">"));
+    }
+
+    /// Defines a gaussian blur of standard deviation `sigma` over `region`
+    fn blur_filter(&mut self, sigma: f32, region: Option<RectDIP>) -> String {
+        let region = match region {
+            Some(region) => format!(
+                " filterUnits=\"userSpaceOnUse\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"",
+                Num(region.x),
+                Num(region.y),
+                Num(region.width),
+                Num(region.height)
+            ),
+            None => " x=\"-50%\" y=\"-50%\" width=\"200%\" height=\"200%\"".to_string(),
+        };
+        self.define("blur", |id| {
+            format!(
+                "<filter id=\"{id}\"{region}><feGaussianBlur stdDeviation=\"{}\"/></filter>",
+                Num(sigma)
+            )
+        })
+    }
+
+    fn shadow(
+        &mut self,
+        rect: &RectDIP,
+        shadow: &DropShadow,
+        border_radius: Option<&BorderRadius>,
+    ) {
+        // The shadow shape: the element offset and expanded by the spread
+        let shadow_rect = RectDIP {
+            x: rect.x + shadow.offset_x - shadow.spread_radius,
+            y: rect.y + shadow.offset_y - shadow.spread_radius,
+            width: rect.width + shadow.spread_radius * 2.0,
+            height: rect.height + shadow.spread_radius * 2.0,
+        };
+        let sigma = shadow.blur_radius.max(0.0) / 2.0;
+        let filter = |writer: &mut Self, bounds: RectDIP| {
+            if sigma > 0.0 {
+                let id = writer.blur_filter(sigma, Some(expand(&bounds, sigma * 3.0)));
+                format!(" filter=\"url(#{id})\"")
+            } else {
+                String::new()
+            }
+        };
+
+        if !shadow.inset {
+            let filter = filter(self, shadow_rect);
+            self.element(format!(
+                "{}{}{filter}/>",
+                shape(&shadow_rect, border_radius),
+                Paint::Fill(shadow.color)
+            ));
+            return;
+        }
+
+        // Inset shadows fill a frame around the shadow shape, clipped to the element
+        let margin = shadow.blur_radius.max(0.0) * 3.0
+            + shadow.spread_radius.abs()
+            + shadow.offset_x.abs().max(shadow.offset_y.abs());
+        let outer = expand(rect, margin);
+        let hole = match border_radius {
+            Some(border_radius) if border_radius.is_some() => {
+                rounded_rect_data(&shadow_rect, border_radius)
+            }
+            _ => rect_data(&shadow_rect),
+        };
+
+        self.push_clip(shape(rect, border_radius));
+        let filter = filter(self, outer);
+        self.element(format!(
+            "<path d=\"{} {hole}\" fill-rule=\"evenodd\"{}{filter}/>",
+            rect_data(&outer),
+            Paint::Fill(shadow.color)
+        ));
+        self.close_group();
+    }
+
+    fn border(&mut self, rect: &RectDIP, border_radius: Option<&BorderRadius>, border: &Border) {
+        // Adjust rect and radius for placement
+        let half = border.width * 0.5;
+        let (adjusted, delta) = match border.placement {
+            BorderPlacement::Center => (*rect, 0.0),
+            BorderPlacement::Inset => (
+                RectDIP {
+                    x: rect.x + half,
+                    y: rect.y + half,
+                    width: (rect.width - border.width).max(0.0),
+                    height: (rect.height - border.width).max(0.0),
+                },
+                -half,
+            ),
+            BorderPlacement::Outset => (expand(rect, half), half),
+        };
+        let adjusted_radius = border_radius.map(|r| BorderRadius {
+            top_left: (r.top_left + delta).max(0.0),
+            top_right: (r.top_right + delta).max(0.0),
+            bottom_right: (r.bottom_right + delta).max(0.0),
+            bottom_left: (r.bottom_left + delta).max(0.0),
+        });
+
+        let stroke = Stroke {
+            width: border.width,
+            dash_style: border.dash_style,
+            cap: border.dash_cap,
+            join: border.stroke_join,
+        };
+        self.element(format!(
+            "{} fill=\"none\"{}{stroke}/>",
+            shape(&adjusted, adjusted_radius.as_ref()),
+            Paint::Stroke(border.color)
+        ));
+    }
+
+    fn svg(&mut self, rect: &RectDIP, resource: &SvgResource) {
+        // Recoloring floods everything the document covers with one color
+        let filter = match resource.recolor {
+            Some(color) => {
+                let id = self.define("recolor", |id| {
+                    format!(
+                        "<filter id=\"{id}\"><feFlood flood-color=\"{}\" flood-opacity=\"{}\"/>\
+                         <feComposite in2=\"SourceAlpha\" operator=\"in\"/></filter>",
+                        Hex(color),
+                        Num(color.a.clamp(0.0, 1.0))
+                    )
+                });
+                format!(" filter=\"url(#{id})\"")
+            }
+            None => String::new(),
+        };
+        self.element(format!(
+            "{}{filter} xlink:href=\"data:image/svg+xml;base64,{}\"/>",
+            image_element(rect),
+            base64(resource.content.as_bytes())
+        ));
+    }
+
+    fn text(&mut self, rect: &RectDIP, text: &TextResource, color: Color, shadows: &[TextShadow]) {
+        let lines = self.text_lines(rect, text);
+        if lines.is_empty() {
+            return;
+        }
+
+        for shadow in shadows {
+            let filter = if shadow.blur_radius > 0.0 {
+                let id = self.blur_filter(shadow.blur_radius, None);
+                format!(" filter=\"url(#{id})\"")
+            } else {
+                String::new()
+            };
+            self.open_group(format!(
+                "<g transform=\"translate({} {})\"{filter}>",
+                Num(shadow.offset_x),
+                Num(shadow.offset_y)
+            ));
+            self.element(text_element(text, &lines, shadow.color, false));
+            self.close_group();
+        }
+        self.element(text_element(text, &lines, color, true));
+    }
+
+    /// Places each line of `text` within `rect`
+    fn text_lines(&self, rect: &RectDIP, text: &TextResource) -> Vec<TextLine> {
+        let layout = self.text_engine.and_then(|engine| {
+            engine
+                .create_layout(&text.text, &text.style, text.max_width, text.max_height)
+                .ok()
+        });
+
+        if let Some(layout) = layout {
+            return layout
+                .lines()
+                .into_iter()
+                .filter_map(|line| {
+                    let range = trim_line_break(&text.text, line.range);
+                    let left = layout.range_rects(range.clone()).first()?.x;
+                    Some(TextLine {
+                        range,
+                        x: rect.x + left,
+                        y: rect.y + line.top + line.baseline,
+                        anchor: "start",
+                    })
+                })
+                .filter(|line| !line.range.is_empty())
+                .collect();
+        }
+
+        let font_size = text.style.font_size;
+        let (x, anchor) = match text.style.text_alignment {
+            _ if !text.max_width.is_finite() => (rect.x, "start"),
+            TextAlignment::Leading => (rect.x, "start"),
+            TextAlignment::Center => (rect.x + text.max_width / 2.0, "middle"),
+            TextAlignment::Trailing => (rect.x + text.max_width, "end"),
+        };
+        let mut start = 0;
+        let mut lines = Vec::new();
+        for (index, line) in text.text.split('\n').enumerate() {
+            let range = trim_line_break(&text.text, start..start + line.len());
+            start += line.len() + 1;
+            if range.is_empty() {
+                continue;
+            }
+            lines.push(TextLine {
+                range,
+                x,
+                y: rect.y + font_size * (index as f32 * FALLBACK_LINE_HEIGHT + FALLBACK_BASELINE),
+                anchor,
+            });
+        }
+        lines
+    }
+}
+
+/// A line of text, with the position of its baseline origin
+struct TextLine {
+    range: Range<usize>,
+    x: f32,
+    y: f32,
+    anchor: &'static str,
+}
+
+/// Writes a `<text>` element with one `<tspan>` per line. Runs get their own
+/// color and underline when `with_runs` is set, otherwise everything is `color`.
+fn text_element(text: &TextResource, lines: &[TextLine], color: Color, with_runs: bool) -> String {
+    let style = &text.style;
+    let mut element = format!(
+        "<text font-family=\"{}\" font-size=\"{}\"",
+        Escaped(style.font_id.family_name()),
+        Num(style.font_size)
+    );
+    let weight = style.font_axes.weight.value();
+    if weight != 400.0 {
+        let _ = write!(element, " font-weight=\"{}\"", Num(weight));
+    }
+    match style.font_axes.style {
+        FontStyle::Normal => {}
+        FontStyle::Italic => element.push_str(" font-style=\"italic\""),
+        FontStyle::Oblique(_) => element.push_str(" font-style=\"oblique\""),
+    }
+    if style.font_axes.width != FontWidth::Normal {
+        let _ = write!(
+            element,
+            " font-stretch=\"{}%\"",
+            Num(style.font_axes.width.value())
+        );
+    }
+    let _ = write!(element, " xml:space=\"preserve\"{}>", Paint::Fill(color));
+
+    for line in lines {
+        let _ = write!(
+            element,
+            "<tspan x=\"{}\" y=\"{}\"",
+            Num(line.x),
+            Num(line.y)
+        );
+        if line.anchor != "start" {
+            let _ = write!(element, " text-anchor=\"{}\"", line.anchor);
+        }
+        element.push('>');
+
+        if !with_runs || text.runs.is_empty() {
+            let _ = write!(element, "{}", Escaped(&text.text[line.range.clone()]));
+        } else {
+            for segment in run_segments(text, line.range.clone()) {
+                let content = Escaped(&text.text[segment.clone()]);
+                // The last run covering a position wins, as in the renderer
+                let run_color = text
+                    .runs
+                    .iter()
+                    .rev()
+                    .find(|run| run.start <= segment.start && segment.start < run.end)
+                    .and_then(|run| run.color);
+                let underline = text.runs.iter().any(|run| {
+                    run.underline && run.start <= segment.start && segment.start < run.end
+                });
+
+                if run_color.is_none() && !underline {
+                    let _ = write!(element, "{content}");
+                    continue;
+                }
+                element.push_str("<tspan");
+                if let Some(run_color) = run_color {
+                    let _ = write!(element, "{}", Paint::Fill(run_color));
+                }
+                if underline {
+                    element.push_str(" text-decoration=\"underline\"");
+                }
+                let _ = write!(element, ">{content}</tspan>");
+            }
+        }
+        element.push_str("</tspan>");
+    }
+
+    element.push_str("</text>");
+    element
+}
+
+/// Splits `range` wherever a run starts or ends
+fn run_segments(text: &TextResource, range: Range<usize>) -> Vec<Range<usize>> {
+    let mut bounds: Vec<usize> = text
+        .runs
+        .iter()
+        .flat_map(|run| [run.start, run.end])
+        .filter(|bound| range.contains(bound) && text.text.is_char_boundary(*bound))
+        .chain([range.start, range.end])
+        .collect();
+    bounds.sort_unstable();
+    bounds.dedup();
+    bounds.windows(2).map(|pair| pair[0]..pair[1]).collect()
+}
+
+/// Drops the line break at the end of a line's range
+fn trim_line_break(text: &str, range: Range<usize>) -> Range<usize> {
+    let line = text[range.clone()].trim_end_matches(['\r', '\n']);
+    range.start..range.start + line.len()
+}
+
+fn expand(rect: &RectDIP, amount: f32) -> RectDIP {
+    RectDIP {
+        x: rect.x - amount,
+        y: rect.y - amount,
+        width: rect.width + amount * 2.0,
+        height: rect.height + amount * 2.0,
+    }
+}
+
+/// An unterminated element for a rectangle, rounded when `border_radius` has
+/// any radius. Radii are clamped to half the smaller side.
+fn shape(rect: &RectDIP, border_radius: Option<&BorderRadius>) -> String {
+    let rect_element = format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"",
+        Num(rect.x),
+        Num(rect.y),
+        Num(rect.width.max(0.0)),
+        Num(rect.height.max(0.0))
+    );
+    match border_radius {
+        Some(radius) if radius.is_some() => {
+            let uniform = radius.top_left == radius.top_right
+                && radius.top_left == radius.bottom_right
+                && radius.top_left == radius.bottom_left;
+            if uniform {
+                let max_radius = (rect.width / 2.0).min(rect.height / 2.0).max(0.0);
+                format!(
+                    "{rect_element} rx=\"{}\"",
+                    Num(radius.top_left.clamp(0.0, max_radius))
+                )
+            } else {
+                format!("<path d=\"{}\"", rounded_rect_data(rect, radius))
+            }
+        }
+        _ => rect_element,
+    }
+}
+
+fn rect_data(rect: &RectDIP) -> String {
+    format!(
+        "M{} {}H{}V{}H{}Z",
+        Num(rect.x),
+        Num(rect.y),
+        Num(rect.x + rect.width),
+        Num(rect.y + rect.height),
+        Num(rect.x)
+    )
+}
+
+/// A clockwise rounded rectangle, like `software::path::push_rounded_rect`
+fn rounded_rect_data(rect: &RectDIP, border_radius: &BorderRadius) -> String {
+    let max_radius = (rect.width / 2.0).min(rect.height / 2.0).max(0.0);
+    let clamp = |radius: f32| radius.clamp(0.0, max_radius);
+    let (tl, tr, br, bl) = (
+        clamp(border_radius.top_left),
+        clamp(border_radius.top_right),
+        clamp(border_radius.bottom_right),
+        clamp(border_radius.bottom_left),
+    );
+    let left = rect.x;
+    let top = rect.y;
+    let right = rect.x + rect.width;
+    let bottom = rect.y + rect.height;
+
+    let mut data = format!("M{} {}H{}", Num(left + tl), Num(top), Num(right - tr));
+    let corner = |data: &mut String, radius: f32, x: f32, y: f32| {
+        if radius > 0.0 {
+            let _ = write!(
+                data,
+                "A{r} {r} 0 0 1 {} {}",
+                Num(x),
+                Num(y),
+                r = Num(radius)
+            );
+        }
+    };
+    corner(&mut data, tr, right, top + tr);
+    let _ = write!(data, "V{}", Num(bottom - br));
+    corner(&mut data, br, right - br, bottom);
+    let _ = write!(data, "H{}", Num(left + bl));
+    corner(&mut data, bl, left, bottom - bl);
+    let _ = write!(data, "V{}", Num(top + tl));
+    corner(&mut data, tl, left + tl, top);
+    data.push('Z');
+    data
+}
+
+/// An unterminated element for a circle arc. Angles are in degrees, measured
+/// clockwise from the positive X axis.
+fn circle_arc(
+    center_x: f32,
+    center_y: f32,
+    radius: f32,
+    begin_deg: f32,
+    end_deg: f32,
+) -> Option<String> {
+    const DRAW_EPSILON: f32 = 1e-6;
+
+    // Don't emit anything for empty arcs
+    if begin_deg.abs() < DRAW_EPSILON && end_deg.abs() < DRAW_EPSILON {
+        return None;
+    }
+
+    // Normalize clockwise delta to [0, 360), where zero means a full circle
+    let mut cw_delta = (end_deg - begin_deg) % 360.0;
+    if cw_delta < 0.0 {
+        cw_delta += 360.0;
+    }
+    if cw_delta.abs() < DRAW_EPSILON {
+        return Some(format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\"",
+            Num(center_x),
+            Num(center_y),
+            Num(radius)
+        ));
+    }
+
+    let point = |deg: f32| {
+        let (sin, cos) = deg.to_radians().sin_cos();
+        (center_x + radius * cos, center_y + radius * sin)
+    };
+    let start = point(begin_deg);
+    let end = point(begin_deg + cw_delta);
+    Some(format!(
+        "<path d=\"M{} {}A{r} {r} 0 {} 1 {} {}\"",
+        Num(start.0),
+        Num(start.1),
+        u8::from(cw_delta > 180.0),
+        Num(end.0),
+        Num(end.1),
+        r = Num(radius)
+    ))
+}
+
+/// Path data for a [`SvgPathList`]
+fn path_data(list: &SvgPathList) -> String {
+    let mut data = String::new();
+    for commands in list.paths {
+        match commands {
+            SvgPathCommands::Circle { cx, cy, r } => {
+                let _ = write!(
+                    data,
+                    "M{} {}A{r} {r} 0 1 1 {} {}A{r} {r} 0 1 1 {} {}Z",
+                    Num(cx - r),
+                    Num(*cy),
+                    Num(cx + r),
+                    Num(*cy),
+                    Num(cx - r),
+                    Num(*cy),
+                    r = Num(*r)
+                );
+            }
+            SvgPathCommands::Rect {
+                x,
+                y,
+                width,
+                height,
+                rx,
+                ry,
+            } => {
+                // Clamp radii to prevent overlapping
+                let rx = rx.min(width / 2.0);
+                let ry = ry.min(height / 2.0);
+                if rx > 0.0 && ry > 0.0 {
+                    let (right, bottom) = (x + width, y + height);
+                    let _ = write!(
+                        data,
+                        "M{} {y}H{}A{rx} {ry} 0 0 1 {right} {}V{}A{rx} {ry} 0 0 1 {} {bottom}\
+                         H{}A{rx} {ry} 0 0 1 {x} {}V{}A{rx} {ry} 0 0 1 {} {y}Z",
+                        Num(x + rx),
+                        Num(right - rx),
+                        Num(y + ry),
+                        Num(bottom - ry),
+                        Num(right - rx),
+                        Num(x + rx),
+                        Num(bottom - ry),
+                        Num(y + ry),
+                        Num(x + rx),
+                        x = Num(*x),
+                        y = Num(*y),
+                        right = Num(right),
+                        bottom = Num(bottom),
+                        rx = Num(rx),
+                        ry = Num(ry),
+                    );
+                } else {
+                    data.push_str(&rect_data(&RectDIP {
+                        x: *x,
+                        y: *y,
+                        width: *width,
+                        height: *height,
+                    }));
+                }
+            }
+            SvgPathCommands::Path(commands) => {
+                for command in *commands {
+                    let _ = match *command {
+                        PathCommand::MoveTo { x, y } => write!(data, "M{} {}", Num(x), Num(y)),
+                        PathCommand::LineTo { x, y } => write!(data, "L{} {}", Num(x), Num(y)),
+                        PathCommand::Arc {
+                            radius_x,
+                            radius_y,
+                            rotation,
+                            large_arc,
+                            sweep,
+                            end_x,
+                            end_y,
+                        } => write!(
+                            data,
+                            "A{} {} {} {} {} {} {}",
+                            Num(radius_x),
+                            Num(radius_y),
+                            Num(rotation),
+                            u8::from(large_arc),
+                            u8::from(sweep),
+                            Num(end_x),
+                            Num(end_y)
+                        ),
+                        PathCommand::CubicBezier {
+                            cp1_x,
+                            cp1_y,
+                            cp2_x,
+                            cp2_y,
+                            end_x,
+                            end_y,
+                        } => write!(
+                            data,
+                            "C{} {} {} {} {} {}",
+                            Num(cp1_x),
+                            Num(cp1_y),
+                            Num(cp2_x),
+                            Num(cp2_y),
+                            Num(end_x),
+                            Num(end_y)
+                        ),
+                        PathCommand::QuadraticBezier {
+                            cp_x,
+                            cp_y,
+                            end_x,
+                            end_y,
+                        } => write!(
+                            data,
+                            "Q{} {} {} {}",
+                            Num(cp_x),
+                            Num(cp_y),
+                            Num(end_x),
+                            Num(end_y)
+                        ),
+                        PathCommand::ClosePath => write!(data, "Z"),
+                    };
+                }
+            }
+        }
+    }
+    data
+}
+
+fn image_element(rect: &RectDIP) -> String {
+    format!(
+        "<image x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"",
+        Num(rect.x),
+        Num(rect.y),
+        Num(rect.width.max(0.0)),
+        Num(rect.height.max(0.0))
+    )
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = u32::from(b[0]) << 16 | u32::from(b[1]) << 8 | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// A number with at most three decimals and no trailing zeros
+struct Num(f32);
+
+impl Display for Num {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = (self.0 * 1000.0).round() / 1000.0;
+        // Avoid printing "-0"
+        let value = if value == 0.0 { 0.0 } else { value };
+        write!(f, "{value}")
+    }
+}
+
+/// A color as `#rrggbb`, ignoring alpha
+struct Hex(Color);
+
+impl Display for Hex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        write!(
+            f,
+            "#{:02x}{:02x}{:02x}",
+            channel(self.0.r),
+            channel(self.0.g),
+            channel(self.0.b)
+        )
+    }
+}
+
+/// Fill or stroke attributes for a color, with its alpha as an opacity
+enum Paint {
+    Fill(Color),
+    Stroke(Color),
+}
+
+impl Display for Paint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (attribute, color) = match self {
+            Paint::Fill(color) => ("fill", color),
+            Paint::Stroke(color) => ("stroke", color),
+        };
+        write!(f, " {attribute}=\"{}\"", Hex(*color))?;
+        if color.a < 1.0 {
+            write!(f, " {attribute}-opacity=\"{}\"", Num(color.a.max(0.0)))?;
+        }
+        Ok(())
+    }
+}
+
+/// Stroke attributes matching the stroke style the renderer would create
+struct Stroke {
+    width: f32,
+    dash_style: Option<StrokeDashStyle>,
+    cap: StrokeLineCap,
+    join: StrokeLineJoin,
+}
+
+impl Stroke {
+    /// The stroke Direct2D uses when no stroke style is given.
+    fn plain(width: f32) -> Self {
+        Self {
+            width,
+            dash_style: None,
+            cap: StrokeLineCap::Flat,
+            join: StrokeLineJoin::Miter,
+        }
+    }
+}
+
+impl Display for Stroke {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, " stroke-width=\"{}\"", Num(self.width))?;
+        match self.cap {
+            StrokeLineCap::Flat => {}
+            StrokeLineCap::Square => write!(f, " stroke-linecap=\"square\"")?,
+            // SVG has no triangle caps, round is the closest shape
+            StrokeLineCap::Round | StrokeLineCap::Triangle => {
+                write!(f, " stroke-linecap=\"round\"")?
+            }
+        }
+        match self.join {
+            StrokeLineJoin::Miter | StrokeLineJoin::MiterOrBevel => {}
+            StrokeLineJoin::Bevel => write!(f, " stroke-linejoin=\"bevel\"")?,
+            StrokeLineJoin::Round => write!(f, " stroke-linejoin=\"round\"")?,
+        }
+
+        // Dash lengths are in multiples of the stroke width, as in Direct2D
+        let (pattern, offset): (&[f32], f32) = match &self.dash_style {
+            None | Some(StrokeDashStyle::Solid) => (&[], 0.0),
+            Some(StrokeDashStyle::Dash) => (&[2.0, 2.0], 0.0),
+            Some(StrokeDashStyle::Dot) => (&[0.0, 2.0], 0.0),
+            Some(StrokeDashStyle::DashDot) => (&[2.0, 2.0, 0.0, 2.0], 0.0),
+            Some(StrokeDashStyle::DashDotDot) => (&[2.0, 2.0, 0.0, 2.0, 0.0, 2.0], 0.0),
+            Some(StrokeDashStyle::Custom { dashes, offset }) => (dashes, *offset),
+        };
+        if !pattern.is_empty() {
+            write!(f, " stroke-dasharray=\"")?;
+            for (i, length) in pattern.iter().enumerate() {
+                let separator = if i == 0 { "" } else { " " };
+                write!(f, "{separator}{}", Num(length.max(0.0) * self.width))?;
+            }
+            write!(f, "\"")?;
+            if offset != 0.0 {
+                write!(f, " stroke-dashoffset=\"{}\"", Num(offset * self.width))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Text with XML special characters escaped
+struct Escaped<'a>(&'a str);
+
+impl Display for Escaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ch in self.0.chars() {
+            match ch {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                '\'' => f.write_str("&apos;")?,
+                ch => f.write_char(ch)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gfx::text_style::{FontAxes, FontWeight, ParagraphAlignment, TextStyle};
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> RectDIP {
+        RectDIP {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn exports_shapes_clips_and_text() {
+        let mut commands = DrawCommandList::new();
+        let text = commands.add_resource(Resource::Text(TextResource {
+            text: "a < b".to_string(),
+            style: TextStyle {
+                font_id: "Segoe UI".into(),
+                font_size: 14.0,
+                font_axes: FontAxes::new().with_weight(FontWeight::Bold),
+                line_spacing: None,
+                text_alignment: TextAlignment::Leading,
+                paragraph_alignment: ParagraphAlignment::Top,
+                word_wrap: true,
+            },
+            max_width: 100.0,
+            max_height: 20.0,
+            runs: Vec::new(),
+        }));
+        commands.push(DrawCommand::PushLayer { opacity: 0.5 });
+        commands.push(DrawCommand::PushRoundedClip {
+            rect: rect(0.0, 0.0, 40.0, 20.0),
+            border_radius: BorderRadius::all(4.0),
+        });
+        commands.push(DrawCommand::DrawBlurredShadow {
+            rect: rect(0.0, 0.0, 40.0, 20.0),
+            shadow: DropShadow {
+                blur_radius: 8.0,
+                color: Color::BLACK,
+                ..DropShadow::default()
+            },
+            border_radius: None,
+        });
+        commands.push(DrawCommand::DrawBorder {
+            rect: rect(0.0, 0.0, 40.0, 20.0),
+            border_radius: None,
+            border: Border {
+                width: 2.0,
+                dash_style: Some(StrokeDashStyle::Dash),
+                ..Border::default()
+            },
+        });
+        commands.push(DrawCommand::DrawText {
+            rect: rect(2.0, 2.0, 36.0, 16.0),
+            text,
+            color: Color::WHITE,
+            text_shadows: Vec::new(),
+        });
+        commands.push(DrawCommand::PopRoundedClip);
+
+        let svg = SvgExporter::new(40.0, 20.0).export(&commands);
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains(
+            "<clipPath id=\"clip0\"><rect x=\"0\" y=\"0\" width=\"40\" height=\"20\" rx=\"4\"/>"
+        ));
+        assert!(svg.contains("<feGaussianBlur stdDeviation=\"4\"/>"));
+        assert!(svg.contains(
+            "<rect x=\"1\" y=\"1\" width=\"38\" height=\"18\" fill=\"none\" stroke=\"#000000\" \
+             stroke-width=\"2\" stroke-linecap=\"square\" stroke-dasharray=\"4 4\"/>"
+        ));
+        assert!(svg.contains(
+            "<text font-family=\"Segoe UI\" font-size=\"14\" font-weight=\"700\" \
+             xml:space=\"preserve\" fill=\"#ffffff\"><tspan x=\"2\" y=\"13.2\">a &lt; b</tspan></text>"
+        ));
+        // The layer is left open and closed at the end, after the clip
+        assert!(svg.ends_with("    </g>\n  </g>\n</svg>\n"));
+        assert!(svg.contains("<g opacity=\"0.5\">"));
+    }
+}
@@ -0,0 +1,244 @@
+//! DirectWrite implementation of [`TextEngine`].
+
+use std::ops::Range;
+use std::path::Path;
+
+use windows::Win32::Graphics::DirectWrite::{
+    DWRITE_HIT_TEST_METRICS, DWRITE_LINE_METRICS, DWRITE_PARAGRAPH_ALIGNMENT_CENTER,
+    DWRITE_PARAGRAPH_ALIGNMENT_FAR, DWRITE_PARAGRAPH_ALIGNMENT_NEAR, DWRITE_TEXT_ALIGNMENT_CENTER,
+    DWRITE_TEXT_ALIGNMENT_LEADING, DWRITE_TEXT_ALIGNMENT_TRAILING, DWRITE_TEXT_METRICS,
+    DWRITE_WORD_WRAPPING_NO_WRAP, DWRITE_WORD_WRAPPING_WRAP, IDWriteFactory6, IDWriteTextLayout,
+};
+
+use super::{
+    CaretPosition, HitTest, LineMetrics, Result, TextEngine, TextLayout, TextMetrics,
+    byte_to_utf16_index, utf16_to_byte_index,
+};
+use crate::gfx::RectDIP;
+use crate::gfx::text_style::{ParagraphAlignment, TextAlignment, TextStyle};
+use crate::runtime::font_manager::GlobalFontManager;
+
+/// Lays out text with DirectWrite, using fonts registered with the
+/// [`GlobalFontManager`].
+pub struct DirectWriteTextEngine {
+    dwrite_factory: IDWriteFactory6,
+}
+
+impl DirectWriteTextEngine {
+    pub fn new(dwrite_factory: IDWriteFactory6) -> Self {
+        Self { dwrite_factory }
+    }
+}
+
+impl TextEngine for DirectWriteTextEngine {
+    fn load_font(&self, data: &'static [u8]) -> Result<()> {
+        Ok(GlobalFontManager::load_font_from_memory(data)?)
+    }
+
+    fn load_font_file(&self, path: &Path) -> Result<()> {
+        Ok(GlobalFontManager::load_font_from_file(path)?)
+    }
+
+    fn create_layout(
+        &self,
+        text: &str,
+        style: &TextStyle,
+        max_width: f32,
+        max_height: f32,
+    ) -> Result<Box<dyn TextLayout>> {
+        let layout = create_text_layout(&self.dwrite_factory, text, style, max_width, max_height)?;
+        Ok(Box::new(DirectWriteTextLayout {
+            text: text.to_string(),
+            layout,
+        }))
+    }
+}
+
+/// Creates a DirectWrite layout for `text` with the format, alignment and
+/// wrapping described by `style`.
+pub fn create_text_layout(
+    dwrite_factory: &IDWriteFactory6,
+    text: &str,
+    style: &TextStyle,
+    max_width: f32,
+    max_height: f32,
+) -> windows::core::Result<IDWriteTextLayout> {
+    let text_format = GlobalFontManager::create_text_format(
+        &style.font_id,
+        style.font_size,
+        style.font_axes,
+        style.line_spacing,
+        "en-us",
+    )?;
+
+    unsafe {
+        text_format.SetTextAlignment(match style.text_alignment {
+            TextAlignment::Leading => DWRITE_TEXT_ALIGNMENT_LEADING,
+            TextAlignment::Center => DWRITE_TEXT_ALIGNMENT_CENTER,
+            TextAlignment::Trailing => DWRITE_TEXT_ALIGNMENT_TRAILING,
+        })?;
+        text_format.SetParagraphAlignment(match style.paragraph_alignment {
+            ParagraphAlignment::Top => DWRITE_PARAGRAPH_ALIGNMENT_NEAR,
+            ParagraphAlignment::Center => DWRITE_PARAGRAPH_ALIGNMENT_CENTER,
+            ParagraphAlignment::Bottom => DWRITE_PARAGRAPH_ALIGNMENT_FAR,
+        })?;
+        text_format.SetWordWrapping(if style.word_wrap {
+            DWRITE_WORD_WRAPPING_WRAP
+        } else {
+            DWRITE_WORD_WRAPPING_NO_WRAP
+        })?;
+
+        let wtext: Vec<u16> = text.encode_utf16().collect();
+        dwrite_factory.CreateTextLayout(&wtext, &text_format, max_width, max_height)
+    }
+}
+
+/// Wraps an [`IDWriteTextLayout`], translating between byte offsets and the
+/// UTF-16 positions DirectWrite works with.
+struct DirectWriteTextLayout {
+    text: String,
+    layout: IDWriteTextLayout,
+}
+
+impl DirectWriteTextLayout {
+    fn utf16(&self, position: usize) -> u32 {
+        byte_to_utf16_index(&self.text, position) as u32
+    }
+
+    fn byte(&self, position: u32) -> usize {
+        utf16_to_byte_index(&self.text, position as usize)
+    }
+}
+
+impl TextLayout for DirectWriteTextLayout {
+    fn text(&self) -> &str {
+        &self.text
+    }
+
+    fn max_size(&self) -> (f32, f32) {
+        unsafe { (self.layout.GetMaxWidth(), self.layout.GetMaxHeight()) }
+    }
+
+    fn set_max_size(&mut self, max_width: f32, max_height: f32) {
+        unsafe {
+            let _ = self.layout.SetMaxWidth(max_width);
+            let _ = self.layout.SetMaxHeight(max_height);
+        }
+    }
+
+    fn metrics(&self) -> TextMetrics {
+        let mut metrics = DWRITE_TEXT_METRICS::default();
+        let _ = unsafe { self.layout.GetMetrics(&mut metrics) };
+        TextMetrics {
+            left: metrics.left,
+            top: metrics.top,
+            width: metrics.width,
+            width_including_trailing_whitespace: metrics.widthIncludingTrailingWhitespace,
+            height: metrics.height,
+            line_count: metrics.lineCount as usize,
+        }
+    }
+
+    fn min_width(&self) -> f32 {
+        unsafe { self.layout.DetermineMinWidth().unwrap_or_default() }
+    }
+
+    fn lines(&self) -> Vec<LineMetrics> {
+        let mut count = 0;
+        let _ = unsafe { self.layout.GetLineMetrics(None, &mut count) };
+        let mut lines = vec![DWRITE_LINE_METRICS::default(); count as usize];
+        if unsafe { self.layout.GetLineMetrics(Some(&mut lines), &mut count) }.is_err() {
+            return Vec::new();
+        }
+
+        let mut start16 = 0;
+        let mut top = self.metrics().top;
+        lines
+            .iter()
+            .map(|line| {
+                let end16 = start16 + line.length;
+                let metrics = LineMetrics {
+                    range: self.byte(start16)..self.byte(end16),
+                    top,
+                    height: line.height,
+                    baseline: line.baseline,
+                };
+                start16 = end16;
+                top += line.height;
+                metrics
+            })
+            .collect()
+    }
+
+    fn hit_test_point(&self, x: f32, y: f32) -> HitTest {
+        let mut trailing = windows::core::BOOL(0);
+        let mut inside = windows::core::BOOL(0);
+        let mut metrics = DWRITE_HIT_TEST_METRICS::default();
+        let _ = unsafe {
+            self.layout
+                .HitTestPoint(x, y, &mut trailing, &mut inside, &mut metrics)
+        };
+
+        let position = self.byte(metrics.textPosition);
+        HitTest {
+            position,
+            length: self.byte(metrics.textPosition + metrics.length) - position,
+            trailing: trailing.as_bool(),
+            inside: inside.as_bool(),
+        }
+    }
+
+    fn caret_position(&self, position: usize) -> CaretPosition {
+        let mut x = 0.0;
+        let mut y = 0.0;
+        let mut metrics = DWRITE_HIT_TEST_METRICS::default();
+        let _ = unsafe {
+            self.layout.HitTestTextPosition(
+                self.utf16(position),
+                false,
+                &mut x,
+                &mut y,
+                &mut metrics,
+            )
+        };
+        CaretPosition {
+            x,
+            y: metrics.top,
+            height: metrics.height,
+        }
+    }
+
+    fn range_rects(&self, range: Range<usize>) -> Vec<RectDIP> {
+        let start = self.utf16(range.start);
+        let length = self.utf16(range.end).saturating_sub(start);
+        if length == 0 {
+            return Vec::new();
+        }
+
+        let mut count = 0;
+        let _ = unsafe {
+            self.layout
+                .HitTestTextRange(start, length, 0.0, 0.0, None, &mut count)
+        };
+        let mut rects = vec![DWRITE_HIT_TEST_METRICS::default(); count as usize];
+        if unsafe {
+            self.layout
+                .HitTestTextRange(start, length, 0.0, 0.0, Some(&mut rects), &mut count)
+        }
+        .is_err()
+        {
+            return Vec::new();
+        }
+
+        rects
+            .iter()
+            .take(count as usize)
+            .map(|metrics| RectDIP {
+                x: metrics.left,
+                y: metrics.top,
+                width: metrics.width,
+                height: metrics.height,
+            })
+            .collect()
+    }
+}
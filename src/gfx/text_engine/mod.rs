@@ -0,0 +1,192 @@
+//! Text shaping and measurement behind a backend-neutral interface.
+//!
+//! A [`TextEngine`] loads fonts and lays text out into a [`TextLayout`], which
+//! answers the questions widgets ask about text: how big it is, where its lines
+//! and baselines are, which character is under a point and where the caret for
+//! a position goes. Text positions are byte offsets into the laid out string.
+//!
+//! [`DirectWriteTextEngine`](direct_write::DirectWriteTextEngine) is used on
+//! Windows. [`TtfTextEngine`](ttf::TtfTextEngine) is pure Rust and gives the same
+//! results on every platform, which makes it the engine for headless runs.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::gfx::RectDIP;
+use crate::gfx::text_style::TextStyle;
+
+#[cfg(windows)]
+pub mod direct_write;
+pub mod ttf;
+
+#[derive(Debug, Error)]
+pub enum TextError {
+    #[error("Failed to read font file {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Font data could not be parsed")]
+    InvalidFont,
+
+    #[cfg(windows)]
+    #[error("Windows API error: {0}")]
+    WindowsApi(#[from] windows::core::Error),
+}
+
+pub type Result<T> = std::result::Result<T, TextError>;
+
+/// Size of laid out text, in DIPs relative to the layout box
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TextMetrics {
+    /// Left-most point of the text, after alignment
+    pub left: f32,
+    /// Top-most point of the text, after paragraph alignment
+    pub top: f32,
+    /// Width of the widest line, ignoring trailing whitespace
+    pub width: f32,
+    /// Width of the widest line, including trailing whitespace
+    pub width_including_trailing_whitespace: f32,
+    pub height: f32,
+    pub line_count: usize,
+}
+
+/// Placement of one line of a [`TextLayout`]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LineMetrics {
+    /// Byte range of the line, including its trailing whitespace and newline
+    pub range: Range<usize>,
+    /// Top of the line relative to the layout box
+    pub top: f32,
+    pub height: f32,
+    /// Distance from the top of the line to its baseline
+    pub baseline: f32,
+}
+
+/// Result of hit testing a point against a [`TextLayout`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HitTest {
+    /// Start of the cluster closest to the point
+    pub position: usize,
+    /// Length of that cluster in bytes
+    pub length: usize,
+    /// Whether the point is past the middle of the cluster
+    pub trailing: bool,
+    /// Whether the point is on the text rather than beside or below it
+    pub inside: bool,
+}
+
+impl HitTest {
+    /// The position a caret placed at the point should go to
+    pub fn caret_position(&self) -> usize {
+        if self.trailing {
+            self.position + self.length
+        } else {
+            self.position
+        }
+    }
+}
+
+/// Where a caret in front of a text position is drawn
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CaretPosition {
+    pub x: f32,
+    /// Top of the line the position is on
+    pub y: f32,
+    /// Height of that line
+    pub height: f32,
+}
+
+/// Receives glyph outlines in DIPs relative to the layout box.
+///
+/// Contours of a glyph follow a call to [`OutlineSink::begin_glyph`] with the
+/// byte position of the character the glyph was shaped from.
+pub trait OutlineSink {
+    fn begin_glyph(&mut self, position: usize);
+    fn move_to(&mut self, x: f32, y: f32);
+    fn line_to(&mut self, x: f32, y: f32);
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32);
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32);
+    fn close(&mut self);
+}
+
+/// Loads fonts and lays out text.
+pub trait TextEngine {
+    /// Makes a font available under the family names it declares.
+    fn load_font(&self, data: &'static [u8]) -> Result<()>;
+
+    fn load_font_file(&self, path: &Path) -> Result<()> {
+        let data = std::fs::read(path).map_err(|source| TextError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        self.load_font(Vec::leak(data))
+    }
+
+    /// Lays out `text` within `max_width` x `max_height`, either of which may
+    /// be infinite.
+    fn create_layout(
+        &self,
+        text: &str,
+        style: &TextStyle,
+        max_width: f32,
+        max_height: f32,
+    ) -> Result<Box<dyn TextLayout>>;
+}
+
+/// Text laid out by a [`TextEngine`].
+pub trait TextLayout {
+    /// The text this layout was created from
+    fn text(&self) -> &str;
+
+    fn max_size(&self) -> (f32, f32);
+
+    /// Lays the text out again for a new maximum size.
+    fn set_max_size(&mut self, max_width: f32, max_height: f32);
+
+    fn metrics(&self) -> TextMetrics;
+
+    /// Width of the widest unbreakable run, the narrowest the text can wrap to
+    fn min_width(&self) -> f32;
+
+    fn lines(&self) -> Vec<LineMetrics>;
+
+    /// Finds the text position under a point in DIPs relative to the layout box.
+    fn hit_test_point(&self, x: f32, y: f32) -> HitTest;
+
+    /// Where the caret in front of the character at `position` goes.
+    fn caret_position(&self, position: usize) -> CaretPosition;
+
+    /// Rectangles covering `range`, one per line it spans.
+    fn range_rects(&self, range: Range<usize>) -> Vec<RectDIP>;
+
+    /// Emits the outlines of every glyph. Returns false when the engine draws
+    /// text itself and can't provide outlines.
+    fn outline(&self, _sink: &mut dyn OutlineSink) -> bool {
+        false
+    }
+}
+
+/// Converts a UTF-16 code unit offset into `text` to a byte offset, clamped to
+/// the end of the text.
+pub fn utf16_to_byte_index(text: &str, utf16_index: usize) -> usize {
+    let mut units = 0;
+    for (index, ch) in text.char_indices() {
+        if units >= utf16_index {
+            return index;
+        }
+        units += ch.len_utf16();
+    }
+    text.len()
+}
+
+/// Converts a byte offset into `text` to a UTF-16 code unit offset.
+pub fn byte_to_utf16_index(text: &str, byte_index: usize) -> usize {
+    text.char_indices()
+        .take_while(|(index, _)| *index < byte_index)
+        .map(|(_, ch)| ch.len_utf16())
+        .sum()
+}
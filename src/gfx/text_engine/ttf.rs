@@ -0,0 +1,715 @@
+//! Pure-Rust text engine for TrueType and OpenType fonts.
+//!
+//! Glyphs are mapped one per character with `kern` table kerning; there is no
+//! complex shaping or bidi. Lines break at whitespace, or between characters
+//! when a single word doesn't fit. The results only depend on the loaded fonts,
+//! so sizes are the same on every platform.
+
+use std::cell::RefCell;
+use std::ops::Range;
+use std::rc::Rc;
+
+use ttf_parser::{Face, GlyphId, OutlineBuilder, Tag};
+
+use super::{
+    CaretPosition, HitTest, LineMetrics, OutlineSink, Result, TextEngine, TextError, TextLayout,
+    TextMetrics,
+};
+use crate::gfx::RectDIP;
+use crate::gfx::text_style::{FontIdentifier, ParagraphAlignment, TextAlignment, TextStyle};
+
+/// Slack allowed when checking whether a line fits, so text laid out at its own
+/// measured width doesn't wrap because of rounding
+const FIT_EPSILON: f32 = 1e-3;
+
+struct LoadedFont {
+    data: &'static [u8],
+    index: u32,
+    families: Vec<String>,
+    weight: f32,
+    italic: bool,
+}
+
+impl LoadedFont {
+    fn face(&self, style: &TextStyle) -> Face<'static> {
+        let mut face = Face::parse(self.data, self.index).expect("font was validated on load");
+        if face.is_variable() {
+            let axes = style.font_axes;
+            face.set_variation(Tag::from_bytes(b"wght"), axes.weight.value());
+            face.set_variation(Tag::from_bytes(b"wdth"), axes.width.value());
+            face.set_variation(Tag::from_bytes(b"ital"), axes.style.italic_value());
+            face.set_variation(Tag::from_bytes(b"slnt"), axes.style.slant_value());
+        }
+        face
+    }
+
+    fn has_family(&self, family: &str) -> bool {
+        self.families
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(family))
+    }
+}
+
+/// Lays out text with fonts loaded from TTF/OTF data.
+///
+/// Fonts are matched by family name, then by the closest weight and style.
+/// Text in a family that isn't loaded falls back to the first loaded font.
+#[derive(Default)]
+pub struct TtfTextEngine {
+    fonts: RefCell<Vec<Rc<LoadedFont>>>,
+}
+
+impl TtfTextEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Family names of the loaded fonts, in load order
+    pub fn families(&self) -> Vec<String> {
+        let mut families = Vec::new();
+        for font in self.fonts.borrow().iter() {
+            for family in &font.families {
+                if !families.contains(family) {
+                    families.push(family.clone());
+                }
+            }
+        }
+        families
+    }
+
+    fn select_font(&self, font_id: &FontIdentifier, style: &TextStyle) -> Option<Rc<LoadedFont>> {
+        let fonts = self.fonts.borrow();
+        let family = font_id.family_name();
+        let italic = style.font_axes.style.italic_value() > 0.0;
+        let weight = style.font_axes.weight.value();
+
+        let distance = |font: &&Rc<LoadedFont>| {
+            let style_mismatch = if font.italic == italic { 0.0 } else { 1000.0 };
+            style_mismatch + (font.weight - weight).abs()
+        };
+
+        fonts
+            .iter()
+            .filter(|font| font.has_family(family))
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .or_else(|| fonts.first())
+            .cloned()
+    }
+}
+
+impl TextEngine for TtfTextEngine {
+    fn load_font(&self, data: &'static [u8]) -> Result<()> {
+        let count = ttf_parser::fonts_in_collection(data).unwrap_or(1);
+        let mut fonts = self.fonts.borrow_mut();
+
+        for index in 0..count {
+            let face = Face::parse(data, index).map_err(|_| TextError::InvalidFont)?;
+
+            let mut families = Vec::new();
+            for name in face.names() {
+                if matches!(
+                    name.name_id,
+                    ttf_parser::name_id::FAMILY | ttf_parser::name_id::TYPOGRAPHIC_FAMILY
+                ) && let Some(family) = name.to_string()
+                    && !families.contains(&family)
+                {
+                    families.push(family);
+                }
+            }
+
+            fonts.push(Rc::new(LoadedFont {
+                data,
+                index,
+                families,
+                weight: face.weight().to_number() as f32,
+                italic: face.is_italic(),
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn create_layout(
+        &self,
+        text: &str,
+        style: &TextStyle,
+        max_width: f32,
+        max_height: f32,
+    ) -> Result<Box<dyn TextLayout>> {
+        let font = self
+            .select_font(&style.font_id, style)
+            .ok_or(TextError::InvalidFont)?;
+
+        let mut layout = TtfTextLayout {
+            font,
+            text: text.to_string(),
+            style: style.clone(),
+            max_width,
+            max_height,
+            glyphs: Vec::new(),
+            lines: Vec::new(),
+            scale: 1.0,
+            line_height: 0.0,
+            baseline: 0.0,
+            offset_y: 0.0,
+        };
+        layout.shape();
+        layout.break_lines();
+        Ok(Box::new(layout))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Glyph {
+    /// Byte offset of the character this glyph was mapped from
+    position: usize,
+    len: usize,
+    id: GlyphId,
+    /// Offset from the start of its line
+    x: f32,
+    /// Advance including kerning with the next glyph
+    advance: f32,
+    whitespace: bool,
+    newline: bool,
+}
+
+#[derive(Clone, Debug)]
+struct Line {
+    glyphs: Range<usize>,
+    range: Range<usize>,
+    /// Offset from the left edge of the layout box after alignment
+    left: f32,
+    top: f32,
+    /// Width without trailing whitespace
+    width: f32,
+    width_including_trailing_whitespace: f32,
+}
+
+struct TtfTextLayout {
+    font: Rc<LoadedFont>,
+    text: String,
+    style: TextStyle,
+    max_width: f32,
+    max_height: f32,
+
+    glyphs: Vec<Glyph>,
+    lines: Vec<Line>,
+    /// DIPs per font unit
+    scale: f32,
+    line_height: f32,
+    baseline: f32,
+    /// Offset of the first line from paragraph alignment
+    offset_y: f32,
+}
+
+impl TtfTextLayout {
+    fn shape(&mut self) {
+        let face = self.font.face(&self.style);
+        self.scale = self.style.font_size / face.units_per_em() as f32;
+
+        let ascent = face.ascender() as f32 * self.scale;
+        let descent = -face.descender() as f32 * self.scale;
+        let gap = face.line_gap() as f32 * self.scale;
+        let spacing = self.style.line_spacing.unwrap_or_default();
+        self.line_height = (ascent + descent + gap) * spacing.height;
+        self.baseline = ascent * spacing.baseline;
+
+        let space_advance = face
+            .glyph_index(' ')
+            .and_then(|id| face.glyph_hor_advance(id))
+            .unwrap_or(0) as f32
+            * self.scale;
+
+        self.glyphs.clear();
+        for (position, ch) in self.text.char_indices() {
+            let newline = ch == '\n';
+            let id = face.glyph_index(ch).unwrap_or_default();
+            let advance = match ch {
+                '\n' | '\r' => 0.0,
+                '\t' => space_advance * 4.0,
+                _ => face.glyph_hor_advance(id).unwrap_or(0) as f32 * self.scale,
+            };
+
+            if let Some(previous) = self.glyphs.last_mut()
+                && !previous.newline
+            {
+                previous.advance += kerning(&face, previous.id, id) * self.scale;
+            }
+
+            self.glyphs.push(Glyph {
+                position,
+                len: ch.len_utf8(),
+                id,
+                x: 0.0,
+                advance,
+                whitespace: ch.is_whitespace(),
+                newline,
+            });
+        }
+    }
+
+    fn break_lines(&mut self) {
+        let max_width = if self.style.word_wrap {
+            self.max_width
+        } else {
+            f32::INFINITY
+        };
+
+        self.lines.clear();
+        let count = self.glyphs.len();
+        let mut start = 0;
+
+        loop {
+            let mut x = 0.0;
+            let mut last_break = None;
+            let mut end = count;
+
+            for i in start..count {
+                let glyph = self.glyphs[i];
+                if glyph.newline {
+                    end = i + 1;
+                    break;
+                }
+                if glyph.whitespace {
+                    // Whitespace hangs past the edge instead of wrapping
+                    x += glyph.advance;
+                    continue;
+                }
+                if i > start && self.glyphs[i - 1].whitespace {
+                    last_break = Some(i);
+                }
+                if i > start && x + glyph.advance > max_width + FIT_EPSILON {
+                    end = last_break.unwrap_or(i);
+                    break;
+                }
+                x += glyph.advance;
+            }
+
+            self.push_line(start..end);
+
+            let ends_with_newline = end > start && self.glyphs[end - 1].newline;
+            if end >= count && !ends_with_newline {
+                break;
+            }
+            start = end;
+            if start == count {
+                // Text ending in a newline has an empty last line
+                self.push_line(start..start);
+                break;
+            }
+        }
+
+        self.align();
+    }
+
+    fn push_line(&mut self, glyphs: Range<usize>) {
+        let range = match (
+            self.glyphs.get(glyphs.start),
+            glyphs.end.checked_sub(1).and_then(|i| self.glyphs.get(i)),
+        ) {
+            (Some(first), Some(last)) if !glyphs.is_empty() => {
+                first.position..last.position + last.len
+            }
+            _ => self.text.len()..self.text.len(),
+        };
+
+        let mut x = 0.0;
+        let mut width = 0.0;
+        for glyph in &mut self.glyphs[glyphs.clone()] {
+            glyph.x = x;
+            x += glyph.advance;
+            if !glyph.whitespace {
+                width = x;
+            }
+        }
+
+        self.lines.push(Line {
+            glyphs,
+            range,
+            left: 0.0,
+            top: self.lines.len() as f32 * self.line_height,
+            width,
+            width_including_trailing_whitespace: x,
+        });
+    }
+
+    fn align(&mut self) {
+        let widest = self.lines.iter().map(|line| line.width).fold(0.0, f32::max);
+        let container = if self.max_width.is_finite() {
+            self.max_width
+        } else {
+            widest
+        };
+
+        for line in &mut self.lines {
+            line.left = match self.style.text_alignment {
+                TextAlignment::Leading => 0.0,
+                TextAlignment::Center => (container - line.width) / 2.0,
+                TextAlignment::Trailing => container - line.width,
+            };
+        }
+
+        let height = self.lines.len() as f32 * self.line_height;
+        self.offset_y = if self.max_height.is_finite() {
+            match self.style.paragraph_alignment {
+                ParagraphAlignment::Top => 0.0,
+                ParagraphAlignment::Center => (self.max_height - height) / 2.0,
+                ParagraphAlignment::Bottom => self.max_height - height,
+            }
+        } else {
+            0.0
+        };
+    }
+
+    fn line_index_at(&self, position: usize) -> usize {
+        self.lines
+            .iter()
+            .position(|line| position < line.range.end)
+            .unwrap_or(self.lines.len() - 1)
+    }
+
+    /// Offset of `position` from the left edge of the layout box
+    fn x_at(&self, line: &Line, position: usize) -> f32 {
+        let x = self.glyphs[line.glyphs.clone()]
+            .iter()
+            .find(|glyph| glyph.position >= position)
+            .map(|glyph| glyph.x)
+            .unwrap_or(line.width_including_trailing_whitespace);
+        line.left + x
+    }
+}
+
+impl TextLayout for TtfTextLayout {
+    fn text(&self) -> &str {
+        &self.text
+    }
+
+    fn max_size(&self) -> (f32, f32) {
+        (self.max_width, self.max_height)
+    }
+
+    fn set_max_size(&mut self, max_width: f32, max_height: f32) {
+        if (max_width, max_height) != (self.max_width, self.max_height) {
+            self.max_width = max_width;
+            self.max_height = max_height;
+            self.break_lines();
+        }
+    }
+
+    fn metrics(&self) -> TextMetrics {
+        TextMetrics {
+            left: self
+                .lines
+                .iter()
+                .map(|line| line.left)
+                .fold(f32::INFINITY, f32::min),
+            top: self.offset_y,
+            width: self.lines.iter().map(|line| line.width).fold(0.0, f32::max),
+            width_including_trailing_whitespace: self
+                .lines
+                .iter()
+                .map(|line| line.width_including_trailing_whitespace)
+                .fold(0.0, f32::max),
+            height: self.lines.len() as f32 * self.line_height,
+            line_count: self.lines.len(),
+        }
+    }
+
+    fn min_width(&self) -> f32 {
+        if !self.style.word_wrap {
+            return self.metrics().width;
+        }
+
+        let mut widest: f32 = 0.0;
+        let mut word = 0.0;
+        for glyph in &self.glyphs {
+            if glyph.whitespace {
+                word = 0.0;
+            } else {
+                word += glyph.advance;
+                widest = widest.max(word);
+            }
+        }
+        widest
+    }
+
+    fn lines(&self) -> Vec<LineMetrics> {
+        self.lines
+            .iter()
+            .map(|line| LineMetrics {
+                range: line.range.clone(),
+                top: self.offset_y + line.top,
+                height: self.line_height,
+                baseline: self.baseline,
+            })
+            .collect()
+    }
+
+    fn hit_test_point(&self, x: f32, y: f32) -> HitTest {
+        let y = y - self.offset_y;
+        let row = (y / self.line_height).floor();
+        let inside_y = row >= 0.0 && (row as usize) < self.lines.len();
+        let line = &self.lines[(row.max(0.0) as usize).min(self.lines.len() - 1)];
+
+        let glyphs = &self.glyphs[line.glyphs.clone()];
+        let glyphs = match glyphs.last() {
+            Some(last) if last.newline => &glyphs[..glyphs.len() - 1],
+            _ => glyphs,
+        };
+
+        let x = x - line.left;
+        let Some(first) = glyphs.first() else {
+            return HitTest {
+                position: line.range.start,
+                length: 0,
+                trailing: false,
+                inside: false,
+            };
+        };
+
+        if x < 0.0 {
+            return HitTest {
+                position: first.position,
+                length: first.len,
+                trailing: false,
+                inside: false,
+            };
+        }
+
+        for glyph in glyphs {
+            if x < glyph.x + glyph.advance {
+                return HitTest {
+                    position: glyph.position,
+                    length: glyph.len,
+                    trailing: x > glyph.x + glyph.advance / 2.0,
+                    inside: inside_y,
+                };
+            }
+        }
+
+        let last = glyphs[glyphs.len() - 1];
+        HitTest {
+            position: last.position,
+            length: last.len,
+            trailing: true,
+            inside: false,
+        }
+    }
+
+    fn caret_position(&self, position: usize) -> CaretPosition {
+        let line = &self.lines[self.line_index_at(position)];
+        CaretPosition {
+            x: self.x_at(line, position),
+            y: self.offset_y + line.top,
+            height: self.line_height,
+        }
+    }
+
+    fn range_rects(&self, range: Range<usize>) -> Vec<RectDIP> {
+        self.lines
+            .iter()
+            .filter(|line| range.start < line.range.end && line.range.start < range.end)
+            .map(|line| {
+                let start = self.x_at(line, range.start.max(line.range.start));
+                let end = self.x_at(line, range.end.min(line.range.end));
+                RectDIP {
+                    x: start,
+                    y: self.offset_y + line.top,
+                    width: end - start,
+                    height: self.line_height,
+                }
+            })
+            .collect()
+    }
+
+    fn outline(&self, sink: &mut dyn OutlineSink) -> bool {
+        let face = self.font.face(&self.style);
+
+        for line in &self.lines {
+            let baseline = self.offset_y + line.top + self.baseline;
+            for glyph in &self.glyphs[line.glyphs.clone()] {
+                if glyph.whitespace {
+                    continue;
+                }
+                sink.begin_glyph(glyph.position);
+                let mut builder = SinkBuilder {
+                    sink: &mut *sink,
+                    x: line.left + glyph.x,
+                    y: baseline,
+                    scale: self.scale,
+                };
+                face.outline_glyph(glyph.id, &mut builder);
+            }
+        }
+
+        true
+    }
+}
+
+fn kerning(face: &Face, left: GlyphId, right: GlyphId) -> f32 {
+    let Some(kern) = face.tables().kern else {
+        return 0.0;
+    };
+    kern.subtables
+        .into_iter()
+        .filter(|subtable| subtable.horizontal && !subtable.variable)
+        .find_map(|subtable| subtable.glyphs_kerning(left, right))
+        .unwrap_or(0) as f32
+}
+
+/// Maps font units with y up to DIPs with y down
+struct SinkBuilder<'a> {
+    sink: &'a mut dyn OutlineSink,
+    x: f32,
+    y: f32,
+    scale: f32,
+}
+
+impl SinkBuilder<'_> {
+    fn map(&self, x: f32, y: f32) -> (f32, f32) {
+        (self.x + x * self.scale, self.y - y * self.scale)
+    }
+}
+
+impl OutlineBuilder for SinkBuilder<'_> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.map(x, y);
+        self.sink.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.map(x, y);
+        self.sink.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x1, y1) = self.map(x1, y1);
+        let (x, y) = self.map(x, y);
+        self.sink.quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x1, y1) = self.map(x1, y1);
+        let (x2, y2) = self.map(x2, y2);
+        let (x, y) = self.map(x, y);
+        self.sink.curve_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.sink.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gfx::text_style::FontAxes;
+
+    const DEJAVU_SANS: &[u8] = include_bytes!("../../../fonts/DejaVuSans.ttf");
+
+    fn engine() -> TtfTextEngine {
+        let engine = TtfTextEngine::new();
+        engine.load_font(DEJAVU_SANS).unwrap();
+        engine
+    }
+
+    fn style() -> TextStyle {
+        TextStyle {
+            font_id: FontIdentifier::system("DejaVu Sans"),
+            font_size: 16.0,
+            font_axes: FontAxes::default(),
+            line_spacing: None,
+            text_alignment: TextAlignment::Leading,
+            paragraph_alignment: ParagraphAlignment::Top,
+            word_wrap: true,
+        }
+    }
+
+    fn layout(text: &str, max_width: f32) -> Box<dyn TextLayout> {
+        engine()
+            .create_layout(text, &style(), max_width, f32::INFINITY)
+            .unwrap()
+    }
+
+    #[test]
+    fn loads_family_names() {
+        assert_eq!(engine().families(), vec!["DejaVu Sans".to_string()]);
+
+        let icons = TtfTextEngine::new();
+        icons
+            .load_font(include_bytes!("../../../fonts/lucide.ttf"))
+            .unwrap();
+        assert_eq!(icons.families(), vec!["lucide".to_string()]);
+        assert!(matches!(
+            TtfTextEngine::new().load_font(b"not a font"),
+            Err(TextError::InvalidFont)
+        ));
+    }
+
+    #[test]
+    fn wraps_at_whitespace() {
+        let single = layout("hello world", f32::INFINITY);
+        let metrics = single.metrics();
+        assert_eq!(metrics.line_count, 1);
+
+        let world = layout("world", f32::INFINITY).metrics().width;
+        let wrapped = layout("hello world", world + 1.0);
+        let lines = wrapped.lines();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].range, 0..6);
+        assert_eq!(lines[1].range, 6..11);
+        assert_eq!(wrapped.metrics().height, metrics.height * 2.0);
+        assert_eq!(wrapped.min_width(), world);
+
+        // Laying out at the measured width keeps a single line
+        assert_eq!(layout("hello world", metrics.width).metrics().line_count, 1);
+    }
+
+    #[test]
+    fn breaks_long_words_and_newlines() {
+        let narrow = layout("abcdef", 20.0);
+        assert!(narrow.metrics().line_count > 1);
+        assert!(narrow.metrics().width <= 20.0);
+
+        let lines = layout("a\n\nb\n", f32::INFINITY).lines();
+        let ranges: Vec<_> = lines.iter().map(|line| line.range.clone()).collect();
+        assert_eq!(ranges, vec![0..2, 2..3, 3..5, 5..5]);
+    }
+
+    #[test]
+    fn hit_tests_and_places_carets() {
+        let layout = layout("héllo", f32::INFINITY);
+        let e = layout.caret_position(1);
+        let l = layout.caret_position(3);
+        assert!(l.x > e.x);
+        assert_eq!(e.height, layout.metrics().height);
+
+        let hit = layout.hit_test_point(e.x + 0.5, 2.0);
+        assert_eq!((hit.position, hit.length, hit.trailing), (1, 2, false));
+        assert!(hit.inside);
+        assert_eq!(layout.hit_test_point(l.x - 0.5, 2.0).caret_position(), 3);
+
+        let end = layout.hit_test_point(1000.0, 2.0);
+        assert_eq!(end.caret_position(), 6);
+        assert!(!end.inside);
+        assert_eq!(layout.caret_position(6).x, layout.metrics().width);
+
+        let rects = layout.range_rects(1..3);
+        assert_eq!(rects.len(), 1);
+        assert_eq!(rects[0].x, e.x);
+        assert_eq!(rects[0].width, l.x - e.x);
+    }
+
+    #[test]
+    fn aligns_within_bounds() {
+        let mut style = style();
+        style.text_alignment = TextAlignment::Center;
+        style.paragraph_alignment = ParagraphAlignment::Bottom;
+
+        let layout = engine().create_layout("hi", &style, 100.0, 50.0).unwrap();
+        let metrics = layout.metrics();
+        assert_eq!(metrics.left, (100.0 - metrics.width) / 2.0);
+        assert_eq!(metrics.top, 50.0 - metrics.height);
+        assert_eq!(layout.caret_position(0).x, metrics.left);
+    }
+}
@@ -0,0 +1,145 @@
+//! First baselines for [`Alignment::Baseline`] rows.
+//!
+//! Widgets report their baseline through [`Widget::baseline`](crate::widgets::Widget::baseline).
+//! Widgets without one, like icons, sit on the baseline with their bottom edge. Other elements
+//! take the baseline of their first child, as if it were placed at the top of their content box.
+
+use std::collections::HashMap;
+
+use slotmap::SlotMap;
+
+use crate::{
+    layout::{
+        UIArenas,
+        model::{Alignment, UIElement, UIKey},
+    },
+    widgets::Instance,
+};
+
+/// Distance from the top of the element's border box to its first baseline
+pub fn first_baseline<Message>(
+    slots: &SlotMap<UIKey, UIElement<Message>>,
+    widget_state: &mut HashMap<u64, Instance>,
+    arenas: &UIArenas,
+    key: UIKey,
+) -> f32 {
+    let element = &slots[key];
+
+    if let Some(widget) = element.content.as_ref() {
+        let content_width = element.computed_width - element.padding.left - element.padding.right;
+        let content_height = element.computed_height - element.padding.top - element.padding.bottom;
+        return element
+            .id
+            .and_then(|id| widget_state.get_mut(&id))
+            .and_then(|instance| widget.baseline(arenas, instance, content_width, content_height))
+            .map(|baseline| element.padding.top + baseline)
+            .unwrap_or(element.computed_height);
+    }
+
+    match element
+        .children
+        .iter()
+        .copied()
+        .find(|child| slots[*child].floating.is_none())
+    {
+        Some(child) => {
+            element.padding.top
+                + slots[child].margin.top
+                + first_baseline(slots, widget_state, arenas, child)
+        }
+        None => element.computed_height,
+    }
+}
+
+/// Baselines of the `children` of `key` aligned on them, from the top of their margin box
+pub fn child_baselines<Message>(
+    slots: &SlotMap<UIKey, UIElement<Message>>,
+    widget_state: &mut HashMap<u64, Instance>,
+    arenas: &UIArenas,
+    key: UIKey,
+    children: &[UIKey],
+) -> HashMap<UIKey, f32> {
+    children
+        .iter()
+        .copied()
+        .filter(|child| {
+            slots[*child]
+                .cross_align_self
+                .unwrap_or(slots[key].cross_align_items)
+                == Alignment::Baseline
+        })
+        .map(|child| {
+            let baseline =
+                slots[child].margin.top + first_baseline(slots, widget_state, arenas, child);
+            (child, baseline)
+        })
+        .collect()
+}
+
+/// Lowest baseline among `children`, which all baseline aligned children are placed on
+pub fn line_baseline(baselines: &HashMap<UIKey, f32>, children: &[UIKey]) -> f32 {
+    children
+        .iter()
+        .filter_map(|child| baselines.get(child))
+        .fold(0.0_f32, |acc, baseline| acc.max(*baseline))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::layout::OwnedUITree;
+    use crate::layout::model::{Alignment, Element, Sizing};
+    use crate::layout::testing::{bounds, lay_out};
+    use crate::runtime::DeviceResources;
+    use crate::widgets::text::Text;
+
+    #[test]
+    fn baseline_alignment_lines_up_text() {
+        let mut ui_tree = OwnedUITree::<()>::default();
+        lay_out(
+            &mut ui_tree,
+            Element {
+                width: Sizing::fixed(300.0),
+                cross_align_items: Alignment::Baseline,
+                children: vec![
+                    Element {
+                        id: Some(71),
+                        ..Text::new("Label").with_font_size(12.0).as_element()
+                    },
+                    Element {
+                        id: Some(72),
+                        ..Text::new("Title").with_font_size(24.0).as_element()
+                    },
+                    Element {
+                        id: Some(73),
+                        width: Sizing::fixed(10.0),
+                        height: Sizing::fixed(10.0),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+            320.0,
+            240.0,
+        );
+
+        let text_engine = DeviceResources::default().text_engine;
+        let baseline = |text: &str, font_size: f32| {
+            let style = Text::new(text.to_string())
+                .with_font_size(font_size)
+                .text_style();
+            let line = text_engine
+                .create_layout(text, &style, f32::INFINITY, f32::INFINITY)
+                .unwrap()
+                .lines()
+                .remove(0);
+            line.top + line.baseline
+        };
+        let (label, title) = (baseline("Label", 12.0), baseline("Title", 24.0));
+        assert!(title > label);
+
+        // The larger text sets the baseline, smaller text and icons move down onto it
+        assert_eq!(bounds(&ui_tree, 72).1, 0.0);
+        assert_eq!(bounds(&ui_tree, 71).1, title - label);
+        assert_eq!(bounds(&ui_tree, 73).1, title - 10.0);
+    }
+}
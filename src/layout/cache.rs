@@ -0,0 +1,599 @@
+//! Reuses the layout of subtrees that didn't change since the previous frame.
+//!
+//! Before laying out, the new tree is diffed against the previous one. Elements are matched by id,
+//! or by their index under a matched parent, and a subtree is unchanged when the layout properties
+//! of all of its elements and the [layout keys](crate::widgets::Widget::layout_key) of their
+//! widgets are. The children of the topmost unchanged elements are set aside, so the sizing and
+//! positioning passes treat them as leaves with the fit size of the previous frame. Once
+//! positioned, the subtree takes the sizes and positions of the previous frame, moved along with
+//! its root.
+//!
+//! When the parent gives a reused subtree another size than in the previous frame, its children
+//! are put back and the passes that already ran catch up on them.
+//!
+//! Of the previous frame, only the layout properties and the geometry of its elements are kept,
+//! its widgets are dropped with it.
+//!
+//! Scroll containers, floating and sticky elements and the anchors of floating elements depend on
+//! more than their subtree, so subtrees containing them are always laid out.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use slotmap::SlotMap;
+
+use crate::layout::{
+    BorrowedUITree, OwnedUITree,
+    model::{
+        Alignment, Axis, BoxAmount, ContentAlignment, Direction, GridConfig, GridPlacement,
+        LayoutDirection, Sizing, UIElement, UIKey,
+    },
+    resize_subtree,
+};
+
+/// Sizes of an element from the sizing passes, which reused elements are restored from
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Measured {
+    /// Size and minimum size after fitting the content, by axis
+    fit: [(f32, f32); 2],
+    /// Size given by the parent before snapping, by axis
+    settled: [f32; 2],
+}
+
+const fn index(axis: Axis) -> usize {
+    match axis {
+        Axis::X => 0,
+        Axis::Y => 1,
+    }
+}
+
+/// An unchanged subtree, whose children are set aside while its root is laid out
+struct Reused {
+    previous: UIKey,
+    children: Vec<UIKey>,
+}
+
+/// Properties an element is sized and positioned by
+#[derive(Clone, Debug, PartialEq)]
+struct LayoutInputs {
+    id: Option<u64>,
+    has_content: bool,
+    layout_key: Option<u64>,
+    direction: Direction,
+    axis_align_content: Alignment,
+    axis_align_items: Alignment,
+    cross_align_items: Alignment,
+    cross_align_content: ContentAlignment,
+    axis_align_self: Option<Alignment>,
+    cross_align_self: Option<Alignment>,
+    width: Sizing,
+    height: Sizing,
+    aspect_ratio: Option<f32>,
+    child_gap: f32,
+    cross_gap: Option<f32>,
+    margin: BoxAmount,
+    padding: BoxAmount,
+    layout_direction: Option<LayoutDirection>,
+    grid: Option<GridConfig>,
+    grid_placement: Option<GridPlacement>,
+    wrap: bool,
+    snap: bool,
+}
+
+impl LayoutInputs {
+    fn of<Message>(element: &UIElement<Message>) -> Self {
+        Self {
+            id: element.id,
+            has_content: element.content.is_some(),
+            layout_key: element.__layout_key,
+            direction: element.direction,
+            axis_align_content: element.axis_align_content,
+            axis_align_items: element.axis_align_items,
+            cross_align_items: element.cross_align_items,
+            cross_align_content: element.cross_align_content,
+            axis_align_self: element.axis_align_self,
+            cross_align_self: element.cross_align_self,
+            width: element.width,
+            height: element.height,
+            aspect_ratio: element.aspect_ratio,
+            child_gap: element.child_gap,
+            cross_gap: element.cross_gap,
+            margin: element.margin,
+            padding: element.padding,
+            layout_direction: element.layout_direction,
+            grid: element.grid.clone(),
+            grid_placement: element.grid_placement,
+            wrap: element.wrap,
+            snap: element.snap,
+        }
+    }
+}
+
+/// An element of the previous frame: its layout properties to diff against, and the geometry
+/// reused subtrees are grafted from
+struct Previous {
+    inputs: LayoutInputs,
+    children: Vec<UIKey>,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    content_width: f32,
+    content_height: f32,
+    min_width: f32,
+    min_height: f32,
+    wrap_breaks: Vec<usize>,
+    grid_columns: Vec<f32>,
+    grid_rows: Vec<f32>,
+    measured: Measured,
+}
+
+impl Previous {
+    fn of<Message>(mut element: UIElement<Message>) -> Self {
+        Self {
+            inputs: LayoutInputs::of(&element),
+            children: std::mem::take(&mut element.children),
+            x: element.x,
+            y: element.y,
+            width: element.computed_width,
+            height: element.computed_height,
+            content_width: element.computed_content_width,
+            content_height: element.computed_content_height,
+            min_width: element.min_width,
+            min_height: element.min_height,
+            wrap_breaks: std::mem::take(&mut element.wrap_breaks),
+            grid_columns: std::mem::take(&mut element.grid_columns),
+            grid_rows: std::mem::take(&mut element.grid_rows),
+            measured: element.__measured,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LayoutCache {
+    previous: HashMap<UIKey, Previous>,
+    /// Elements of the previous tree by id
+    previous_ids: HashMap<u64, UIKey>,
+    /// Scale the previous tree was laid out at, `None` if it wasn't
+    previous_dip_scale: Option<f32>,
+    /// Scale the current tree was laid out at
+    dip_scale: Option<f32>,
+    /// Reused subtrees of the current tree by their root
+    reused: HashMap<UIKey, Reused>,
+}
+
+impl LayoutCache {
+    /// Keeps what the next tree is diffed against and grafted from, leaving `slots` empty
+    pub(crate) fn retire<Message>(
+        &mut self,
+        slots: &mut SlotMap<UIKey, UIElement<Message>>,
+        root: UIKey,
+    ) {
+        self.previous_ids = slots
+            .get_mut(root)
+            .map(|root| std::mem::take(&mut root.id_map))
+            .unwrap_or_default();
+        self.previous.clear();
+        self.previous.extend(
+            slots
+                .drain()
+                .map(|(key, element)| (key, Previous::of(element))),
+        );
+        self.previous_dip_scale = self.dip_scale.take();
+        self.reused.clear();
+    }
+}
+
+/// Sets aside the children of the topmost subtrees of `root` that didn't change since the
+/// previous frame
+pub(crate) fn prune<Message>(ui_tree: BorrowedUITree<'_, Message>, root: UIKey, dip_scale: f32) {
+    let OwnedUITree {
+        slots,
+        widget_state,
+        arenas,
+        layout_cache: cache,
+        ..
+    } = ui_tree;
+
+    for element in slots.values_mut() {
+        element.__layout_key = match (&element.content, element.id) {
+            (Some(widget), Some(id)) => widget_state
+                .get(&id)
+                .and_then(|instance| widget.layout_key(arenas, instance)),
+            _ => None,
+        };
+    }
+
+    cache.dip_scale = Some(dip_scale);
+    if cache.previous_dip_scale != Some(dip_scale) {
+        return;
+    }
+    let previous = &cache.previous;
+    let previous_ids = &cache.previous_ids;
+
+    // Match elements top down, in breadth-first order
+    let mut order = Vec::new();
+    let mut matches: HashMap<UIKey, UIKey> = HashMap::new();
+    let mut queue = VecDeque::from([(root, 0)]);
+    while let Some((key, index)) = queue.pop_front() {
+        order.push(key);
+        let element = &slots[key];
+        let matched = match (element.id, element.parent) {
+            (Some(id), _) => previous_ids.get(&id).copied(),
+            (None, Some(parent)) => matches
+                .get(&parent)
+                .and_then(|previous_parent| previous[previous_parent].children.get(index))
+                .copied()
+                .filter(|previous_key| previous[previous_key].inputs.id.is_none()),
+            (None, None) => None,
+        };
+        if let Some(matched) = matched {
+            matches.insert(key, matched);
+        }
+        queue.extend(element.children.iter().copied().zip(0..));
+    }
+
+    // Anchors have to be positioned before the floating elements anchored to them
+    let anchors: HashSet<u64> = slots
+        .values()
+        .filter_map(|element| element.floating.as_ref()?.anchor_id)
+        .collect();
+
+    // Find unchanged subtrees bottom up
+    let mut unchanged = HashSet::new();
+    for &key in order.iter().rev() {
+        let Some(&previous_key) = matches.get(&key) else {
+            continue;
+        };
+        let element = &slots[key];
+        let previous_element = &previous[&previous_key];
+        let reusable = element.floating.is_none()
+            && element.sticky.is_none()
+            && element.scroll.is_none()
+            && element.id.is_none_or(|id| !anchors.contains(&id))
+            && (element.content.is_none() || element.__layout_key.is_some())
+            && LayoutInputs::of(element) == previous_element.inputs
+            && element.children.len() == previous_element.children.len()
+            && element.children.iter().zip(&previous_element.children).all(
+                |(child, previous_child)| {
+                    unchanged.contains(child) && matches.get(child) == Some(previous_child)
+                },
+            );
+        if reusable {
+            unchanged.insert(key);
+        }
+    }
+
+    // Set aside the children of the topmost unchanged subtrees. Baselines are measured through
+    // the first children of elements, which have to stay in place
+    let mut queue = VecDeque::from([(root, false)]);
+    while let Some((key, measures_baseline)) = queue.pop_front() {
+        if unchanged.contains(&key) && !measures_baseline {
+            let children = std::mem::take(&mut slots[key].children);
+            cache.reused.insert(
+                key,
+                Reused {
+                    previous: matches[&key],
+                    children,
+                },
+            );
+            continue;
+        }
+
+        let first_child = slots[key]
+            .children
+            .iter()
+            .copied()
+            .find(|child| slots[*child].floating.is_none());
+        for &child in &slots[key].children {
+            let baseline_aligned = slots[child]
+                .cross_align_self
+                .unwrap_or(slots[key].cross_align_items)
+                == Alignment::Baseline;
+            let measures_baseline =
+                baseline_aligned || (measures_baseline && Some(child) == first_child);
+            queue.push_back((child, measures_baseline));
+        }
+    }
+}
+
+/// Records the fit size of the element along `axis`
+pub(crate) fn record_fit<Message>(element: &mut UIElement<Message>, axis: Axis) {
+    element.__measured.fit[index(axis)] = match axis {
+        Axis::X => (element.computed_width, element.min_width),
+        Axis::Y => (element.computed_height, element.min_height),
+    };
+}
+
+/// Gives a reused element the fit size of the previous frame, as its children are set aside.
+/// Returns whether the element is reused
+pub(crate) fn restore_fit<Message>(
+    ui_tree: BorrowedUITree<'_, Message>,
+    key: UIKey,
+    axis: Axis,
+) -> bool {
+    let Some(reused) = ui_tree.layout_cache.reused.get(&key) else {
+        return false;
+    };
+
+    let (size, min_size) =
+        ui_tree.layout_cache.previous[&reused.previous].measured.fit[index(axis)];
+    let element = &mut ui_tree.slots[key];
+    match axis {
+        Axis::X => {
+            element.computed_width = size;
+            element.min_width = min_size;
+        }
+        Axis::Y => {
+            element.computed_height = size;
+            element.min_height = min_size;
+        }
+    }
+    record_fit(element, axis);
+    true
+}
+
+/// Records the size the parent gave the element along `axis`. A reused subtree given another size
+/// than in the previous frame is laid out after all, catching up on the passes that already ran
+pub(crate) fn settle<Message>(ui_tree: BorrowedUITree<'_, Message>, key: UIKey, axis: Axis) {
+    let element = &mut ui_tree.slots[key];
+    let size = match axis {
+        Axis::X => element.computed_width,
+        Axis::Y => element.computed_height,
+    };
+    element.__measured.settled[index(axis)] = size;
+
+    let cache = &mut ui_tree.layout_cache;
+    let Some(reused) = cache.reused.get(&key) else {
+        return;
+    };
+    if cache.previous[&reused.previous].measured.settled[index(axis)] == size {
+        return;
+    }
+
+    let reused = cache.reused.remove(&key).unwrap();
+    ui_tree.slots[key].children = reused.children;
+    resize_subtree(ui_tree, key, axis);
+}
+
+/// Puts the children of reused subtrees back, with the sizes and positions of the previous frame
+/// moved along with their root
+pub(crate) fn graft<Message>(ui_tree: BorrowedUITree<'_, Message>) {
+    let OwnedUITree {
+        slots,
+        layout_cache: cache,
+        ..
+    } = ui_tree;
+    let previous = &cache.previous;
+
+    for (key, reused) in cache.reused.drain() {
+        let offset = (
+            slots[key].x - previous[&reused.previous].x,
+            slots[key].y - previous[&reused.previous].y,
+        );
+        slots[key].children = reused.children;
+
+        let mut stack = vec![(key, reused.previous)];
+        while let Some((key, previous_key)) = stack.pop() {
+            let element = &mut slots[key];
+            let previous_element = &previous[&previous_key];
+            element.x = previous_element.x + offset.0;
+            element.y = previous_element.y + offset.1;
+            element.computed_width = previous_element.width;
+            element.computed_height = previous_element.height;
+            element.computed_content_width = previous_element.content_width;
+            element.computed_content_height = previous_element.content_height;
+            element.min_width = previous_element.min_width;
+            element.min_height = previous_element.min_height;
+            element
+                .wrap_breaks
+                .clone_from(&previous_element.wrap_breaks);
+            element
+                .grid_columns
+                .clone_from(&previous_element.grid_columns);
+            element.grid_rows.clone_from(&previous_element.grid_rows);
+            element.__measured = previous_element.measured;
+            element.__positioned = true;
+
+            stack.extend(
+                element
+                    .children
+                    .iter()
+                    .copied()
+                    .zip(previous_element.children.iter().copied()),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::time::Instant;
+
+    use super::*;
+    use crate::Shell;
+    use crate::gfx::command_recorder::CommandRecorder;
+    use crate::layout::model::{BoxAmount, Direction, Element, ElementStyle, Sizing};
+    use crate::layout::testing::{bounds, build, lay_out};
+    use crate::layout::{UIArenas, fit_along_axis, propagate_inherited_properties};
+    use crate::widgets::button::Button;
+    use crate::widgets::limit_response::SizingForX;
+    use crate::widgets::toggle::Toggle;
+    use crate::widgets::{Bounds, Event, Instance, Widget, widget};
+
+    thread_local! {
+        static MEASUREMENTS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// Widget of a fixed width that counts how often it is measured
+    #[derive(Debug)]
+    struct Measured(f32);
+
+    impl Widget<()> for Measured {
+        fn limits_x(&self, _arenas: &UIArenas, _instance: &mut Instance) -> SizingForX {
+            MEASUREMENTS.set(MEASUREMENTS.get() + 1);
+            SizingForX {
+                min_width: self.0,
+                preferred_width: self.0,
+            }
+        }
+
+        fn layout_key(&self, _arenas: &UIArenas, _instance: &Instance) -> Option<u64> {
+            Some(self.0.to_bits().into())
+        }
+
+        fn paint(
+            &mut self,
+            _arenas: &UIArenas,
+            _instance: &mut Instance,
+            _shell: &mut Shell<()>,
+            _recorder: &mut CommandRecorder,
+            _style: ElementStyle,
+            _bounds: Bounds,
+            _now: Instant,
+        ) {
+        }
+
+        fn update(
+            &mut self,
+            _arenas: &mut UIArenas,
+            _instance: &mut Instance,
+            _shell: &mut Shell<()>,
+            _event: &Event,
+            _bounds: Bounds,
+        ) {
+        }
+    }
+
+    /// A spacer of `spacer` height above a row with a measured widget of `measured` width and a
+    /// growing element, in a column of `width`
+    fn measured_row(spacer: f32, measured: f32, width: f32) -> Element<()> {
+        let spacer = Element {
+            width: Sizing::fixed(10.0),
+            height: Sizing::fixed(spacer),
+            ..Default::default()
+        };
+        let row = Element {
+            id: Some(102),
+            width: Sizing::grow(),
+            padding: BoxAmount::all(5.0),
+            children: vec![
+                Element {
+                    id: Some(100),
+                    content: widget(Measured(measured)),
+                    height: Sizing::fixed(20.0),
+                    ..Default::default()
+                },
+                Element {
+                    id: Some(101),
+                    width: Sizing::grow(),
+                    height: Sizing::fixed(10.0),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        Element {
+            direction: Direction::TopToBottom,
+            width: Sizing::fixed(width),
+            children: vec![spacer, row],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn prunes_unchanged_subtrees_and_settles_resized_ones() {
+        let mut ui_tree = OwnedUITree::default();
+        lay_out(&mut ui_tree, measured_row(10.0, 40.0, 100.0), 320.0, 240.0);
+
+        build(&mut ui_tree, measured_row(11.0, 40.0, 100.0), 320.0, 240.0);
+        let root = ui_tree.root;
+        propagate_inherited_properties(&mut ui_tree, root);
+        prune(&mut ui_tree, root, 1.0);
+
+        // Only the row is unchanged, its children are set aside
+        let row = ui_tree.slots[root].id_map[&102];
+        assert!(ui_tree.slots[row].children.is_empty());
+        assert_eq!(ui_tree.layout_cache.reused.len(), 1);
+
+        // Reused elements take the fit size of the previous frame
+        fit_along_axis(&mut ui_tree, root, Axis::X);
+        assert_eq!(ui_tree.slots[row].computed_width, 50.0);
+
+        // Given the same size, the row stays reused
+        ui_tree.slots[row].computed_width = 100.0;
+        settle(&mut ui_tree, row, Axis::X);
+        assert!(ui_tree.layout_cache.reused.contains_key(&row));
+
+        // Given another size, its children are put back and fitted again
+        ui_tree.slots[row].computed_width = 120.0;
+        settle(&mut ui_tree, row, Axis::X);
+        assert!(ui_tree.layout_cache.reused.is_empty());
+        assert_eq!(ui_tree.slots[row].children.len(), 2);
+        assert_eq!(ui_tree.slots[row].computed_width, 120.0);
+        let measured = ui_tree.slots[row].children[0];
+        assert_eq!(ui_tree.slots[measured].computed_width, 40.0);
+    }
+
+    #[test]
+    fn unchanged_subtrees_reuse_their_layout() {
+        let mut ui_tree = OwnedUITree::default();
+        lay_out(&mut ui_tree, measured_row(10.0, 40.0, 100.0), 320.0, 240.0);
+        let measurements = MEASUREMENTS.get();
+
+        // Moved along with its parent without being measured again
+        lay_out(&mut ui_tree, measured_row(11.0, 40.0, 100.0), 320.0, 240.0);
+        assert_eq!(MEASUREMENTS.get(), measurements);
+        assert_eq!(bounds(&ui_tree, 100), (5.0, 16.0, 40.0, 20.0));
+        assert_eq!(bounds(&ui_tree, 101).2, 50.0);
+
+        // Measured again once it changes
+        lay_out(&mut ui_tree, measured_row(12.0, 60.0, 100.0), 320.0, 240.0);
+        assert!(MEASUREMENTS.get() > measurements);
+        assert_eq!(bounds(&ui_tree, 100).2, 60.0);
+        assert_eq!(bounds(&ui_tree, 101).2, 30.0);
+
+        // Laid out again when its parent gives it another size
+        lay_out(&mut ui_tree, measured_row(13.0, 60.0, 200.0), 320.0, 240.0);
+        assert_eq!(bounds(&ui_tree, 101).2, 130.0);
+        assert_eq!(bounds(&ui_tree, 101).1, 18.0);
+    }
+
+    /// A button holding a toggle below a spacer of `spacer` height
+    fn toggle_button(spacer: f32) -> Element<()> {
+        Element {
+            direction: Direction::TopToBottom,
+            width: Sizing::fixed(100.0),
+            children: vec![
+                Element {
+                    width: Sizing::fixed(10.0),
+                    height: Sizing::fixed(spacer),
+                    ..Default::default()
+                },
+                Button::new().as_element(110, Toggle::new(false).as_element(111)),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn props_only_widgets_are_reused() {
+        let mut ui_tree = OwnedUITree::default();
+        lay_out(&mut ui_tree, toggle_button(10.0), 320.0, 240.0);
+        let (x, y, width, height) = bounds(&ui_tree, 111);
+
+        lay_out(&mut ui_tree, toggle_button(11.0), 320.0, 240.0);
+        assert_eq!(bounds(&ui_tree, 111), (x, y + 1.0, width, height));
+
+        build(&mut ui_tree, toggle_button(12.0), 320.0, 240.0);
+        let root = ui_tree.root;
+        propagate_inherited_properties(&mut ui_tree, root);
+        prune(&mut ui_tree, root, 1.0);
+
+        // The button and its toggle are keyed by their props, so the button is set aside
+        let button = ui_tree.slots[root].id_map[&110];
+        assert!(ui_tree.slots[button].children.is_empty());
+        assert!(ui_tree.layout_cache.reused.contains_key(&button));
+    }
+}
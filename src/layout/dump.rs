@@ -0,0 +1,323 @@
+//! Structured snapshots of a laid out tree, for regression tests and debugging.
+//!
+//! [`OwnedUITree::dump`] captures what layout decided for every element: its
+//! sizing, computed position and size, content box, scroll position and
+//! z-index. Dumps serialize to JSON, and [`LayoutDump::diff`] lists what
+//! changed between two of them.
+
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::gfx::RectDIP;
+use crate::layout::OwnedUITree;
+use crate::layout::model::{Sizing, UIElement, UIKey};
+use crate::runtime::scroll::{ScrollPosition, ScrollStateManager};
+
+/// Differences smaller than this, in DIPs, are not reported by [`LayoutDump::diff`]
+pub const DIFF_TOLERANCE: f32 = 0.01;
+
+/// A laid out tree, starting at its root
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayoutDump {
+    pub root: ElementDump,
+}
+
+/// The layout of a single element and its children
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElementDump {
+    pub id: Option<u64>,
+    /// Widget type without its module path, `Container` for plain elements
+    pub type_name: String,
+    pub width: Sizing,
+    pub height: Sizing,
+    pub x: f32,
+    pub y: f32,
+    pub computed_width: f32,
+    pub computed_height: f32,
+    pub min_width: f32,
+    pub min_height: f32,
+    pub content_width: f32,
+    pub content_height: f32,
+    pub content_box: RectDIP,
+    /// Scroll position, for scrollable elements with an id
+    pub scroll: Option<ScrollPosition>,
+    pub z_index: Option<i32>,
+    pub children: Vec<ElementDump>,
+}
+
+impl<Message> OwnedUITree<Message> {
+    /// Captures the current layout of the whole tree.
+    pub fn dump(&self, scroll_state_manager: &ScrollStateManager) -> LayoutDump {
+        LayoutDump {
+            root: dump_element(self, self.root, scroll_state_manager),
+        }
+    }
+}
+
+fn dump_element<Message>(
+    ui_tree: &OwnedUITree<Message>,
+    key: UIKey,
+    scroll_state_manager: &ScrollStateManager,
+) -> ElementDump {
+    let element = &ui_tree.slots[key];
+    let scroll = match (element.id, &element.scroll) {
+        (Some(id), Some(_)) => Some(scroll_state_manager.get_scroll_position(id)),
+        _ => None,
+    };
+
+    ElementDump {
+        id: element.id,
+        type_name: type_name(element).to_string(),
+        width: element.width,
+        height: element.height,
+        x: element.x,
+        y: element.y,
+        computed_width: element.computed_width,
+        computed_height: element.computed_height,
+        min_width: element.min_width,
+        min_height: element.min_height,
+        content_width: element.computed_content_width,
+        content_height: element.computed_content_height,
+        content_box: element.bounds().content_box,
+        scroll,
+        z_index: element.z_index,
+        children: element
+            .children
+            .iter()
+            .map(|child| dump_element(ui_tree, *child, scroll_state_manager))
+            .collect(),
+    }
+}
+
+fn type_name<Message>(element: &UIElement<Message>) -> &'static str {
+    match &element.content {
+        Some(content) => {
+            let type_name = content.type_name();
+            // Drop the module path and any generic arguments
+            let type_name = type_name.split('<').next().unwrap_or(type_name);
+            type_name.rsplit("::").next().unwrap_or(type_name)
+        }
+        None => "Container",
+    }
+}
+
+impl LayoutDump {
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Finds the element with the given id
+    pub fn find(&self, id: u64) -> Option<&ElementDump> {
+        fn find(element: &ElementDump, id: u64) -> Option<&ElementDump> {
+            if element.id == Some(id) {
+                return Some(element);
+            }
+            element.children.iter().find_map(|child| find(child, id))
+        }
+        find(&self.root, id)
+    }
+
+    /// Lists what changed from `self` to `other`, element by element.
+    ///
+    /// Children are matched by their position. When the number of children
+    /// differs, only the children both dumps have are compared.
+    pub fn diff(&self, other: &LayoutDump) -> Vec<LayoutChange> {
+        let mut changes = Vec::new();
+        diff_element("", &self.root, &other.root, &mut changes);
+        changes
+    }
+}
+
+/// A property that differs between two dumps
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutChange {
+    /// Child indices from the root, followed by the element's type and id
+    pub element: String,
+    pub property: &'static str,
+    pub before: String,
+    pub after: String,
+}
+
+impl Display for LayoutChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} {} -> {}",
+            self.element, self.property, self.before, self.after
+        )
+    }
+}
+
+fn diff_element(path: &str, a: &ElementDump, b: &ElementDump, changes: &mut Vec<LayoutChange>) {
+    let element = match b.id {
+        Some(id) => format!("/{} {} #{id}", path.trim_start_matches('/'), b.type_name),
+        None => format!("/{} {}", path.trim_start_matches('/'), b.type_name),
+    };
+    let mut change = |property: &'static str, before: String, after: String| {
+        changes.push(LayoutChange {
+            element: element.clone(),
+            property,
+            before,
+            after,
+        });
+    };
+
+    if a.type_name != b.type_name {
+        change("type_name", a.type_name.clone(), b.type_name.clone());
+    }
+    if a.id != b.id {
+        change("id", format!("{:?}", a.id), format!("{:?}", b.id));
+    }
+    if a.width != b.width {
+        change("width", format!("{:?}", a.width), format!("{:?}", b.width));
+    }
+    if a.height != b.height {
+        change(
+            "height",
+            format!("{:?}", a.height),
+            format!("{:?}", b.height),
+        );
+    }
+    for (property, before, after) in [
+        ("x", a.x, b.x),
+        ("y", a.y, b.y),
+        ("computed_width", a.computed_width, b.computed_width),
+        ("computed_height", a.computed_height, b.computed_height),
+        ("min_width", a.min_width, b.min_width),
+        ("min_height", a.min_height, b.min_height),
+        ("content_width", a.content_width, b.content_width),
+        ("content_height", a.content_height, b.content_height),
+    ] {
+        if differs(before, after) {
+            change(property, before.to_string(), after.to_string());
+        }
+    }
+    let (before, after) = (a.content_box, b.content_box);
+    if differs(before.x, after.x)
+        || differs(before.y, after.y)
+        || differs(before.width, after.width)
+        || differs(before.height, after.height)
+    {
+        change("content_box", format_rect(before), format_rect(after));
+    }
+    let scroll_differs = match (a.scroll, b.scroll) {
+        (Some(before), Some(after)) => differs(before.x, after.x) || differs(before.y, after.y),
+        (before, after) => before.is_some() != after.is_some(),
+    };
+    if scroll_differs {
+        change("scroll", format_scroll(a.scroll), format_scroll(b.scroll));
+    }
+    if a.z_index != b.z_index {
+        change(
+            "z_index",
+            format!("{:?}", a.z_index),
+            format!("{:?}", b.z_index),
+        );
+    }
+    if a.children.len() != b.children.len() {
+        change(
+            "children",
+            a.children.len().to_string(),
+            b.children.len().to_string(),
+        );
+    }
+
+    for (index, (a, b)) in a.children.iter().zip(&b.children).enumerate() {
+        diff_element(&format!("{path}/{index}"), a, b, changes);
+    }
+}
+
+fn differs(a: f32, b: f32) -> bool {
+    // Checking equality first keeps equal infinities, which subtract to NaN, apart
+    a != b && ((a - b).abs() > DIFF_TOLERANCE || (a - b).is_nan())
+}
+
+fn format_rect(rect: RectDIP) -> String {
+    format!("{},{} {}x{}", rect.x, rect.y, rect.width, rect.height)
+}
+
+fn format_scroll(scroll: Option<ScrollPosition>) -> String {
+    match scroll {
+        Some(position) => format!("{},{}", position.x, position.y),
+        None => "none".to_string(),
+    }
+}
+
+/// Serializes an unbounded maximum as `null`, which JSON uses for infinity
+pub(crate) mod unbounded {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &f32, serializer: S) -> Result<S::Ok, S::Error> {
+        if value.is_finite() {
+            serializer.serialize_some(value)
+        } else {
+            serializer.serialize_none()
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+        Ok(Option::<f32>::deserialize(deserializer)?.unwrap_or(f32::INFINITY))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(id: u64, width: f32, children: Vec<ElementDump>) -> ElementDump {
+        ElementDump {
+            id: Some(id),
+            type_name: "Container".to_string(),
+            width: Sizing::grow(),
+            height: Sizing::fit(),
+            x: 0.0,
+            y: 0.0,
+            computed_width: width,
+            computed_height: 10.0,
+            min_width: 0.0,
+            min_height: 0.0,
+            content_width: width,
+            content_height: 10.0,
+            content_box: RectDIP {
+                x: 0.0,
+                y: 0.0,
+                width,
+                height: 10.0,
+            },
+            scroll: None,
+            z_index: None,
+            children,
+        }
+    }
+
+    #[test]
+    fn diffs_round_tripped_dumps() {
+        let before = LayoutDump {
+            root: element(1, 100.0, vec![element(2, 50.0, Vec::new())]),
+        };
+        let after = LayoutDump {
+            root: element(1, 100.0, vec![element(2, 0.0, Vec::new())]),
+        };
+
+        let parsed = LayoutDump::from_json(&before.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, before);
+        assert!(parsed.diff(&before).is_empty());
+
+        let changes = before.diff(&after);
+        let changes: Vec<String> = changes.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            changes,
+            [
+                "/0 Container #2: computed_width 50 -> 0",
+                "/0 Container #2: content_width 50 -> 0",
+                "/0 Container #2: content_box 0,0 50x10 -> 0,0 0x10",
+            ]
+        );
+        assert_eq!(after.find(2).unwrap().computed_width, 0.0);
+    }
+}
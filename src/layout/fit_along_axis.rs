@@ -1,8 +1,8 @@
 use crate::{
     layout::{
-        BorrowedUITree,
+        BorrowedUITree, baseline, cache, grid,
         model::{Axis, Direction, Sizing, UIElement, UIKey},
-        visitors,
+        visitors, wrap,
     },
     widgets::limit_response,
 };
@@ -22,6 +22,10 @@ pub fn fit_along_axis<Message>(ui_tree: BorrowedUITree<'_, Message>, root: UIKey
     let x_axis = matches!(axis, Axis::X);
 
     visitors::visit_reverse_bfs(ui_tree, root, |ui_tree, key, _parent| {
+        if cache::restore_fit(ui_tree, key, axis) {
+            return;
+        }
+
         let element = &ui_tree.slots[key];
         let axis_padding = if x_axis {
             element.padding.left + element.padding.right
@@ -43,16 +47,7 @@ pub fn fit_along_axis<Message>(ui_tree: BorrowedUITree<'_, Message>, root: UIKey
         }
 
         // Container sizing
-        let axis_direction = if x_axis {
-            Direction::LeftToRight
-        } else {
-            Direction::TopToBottom
-        };
-        let off_axis_direction = if x_axis {
-            Direction::TopToBottom
-        } else {
-            Direction::LeftToRight
-        };
+        let cross_axis = if x_axis { Axis::Y } else { Axis::X };
 
         // Filter out floating children
         let non_floating_children: Vec<UIKey> = element!()
@@ -62,8 +57,23 @@ pub fn fit_along_axis<Message>(ui_tree: BorrowedUITree<'_, Message>, root: UIKey
             .filter(|child| ui_tree.slots[*child].floating.is_none())
             .collect();
 
+        // For Grid, the size of the tracks measured from their cells
+        if element!().direction == Direction::Grid {
+            let (tracks_size, tracks_min_size) = grid::fit(&ui_tree.slots, key, axis);
+
+            if x_axis {
+                element!().computed_width = tracks_size + axis_padding;
+                if !is_scroll_enabled(&element!(), Axis::X) {
+                    element!().min_width = tracks_min_size + axis_padding;
+                }
+            } else {
+                element!().computed_height = tracks_size + axis_padding;
+                if !is_scroll_enabled(&element!(), Axis::Y) {
+                    element!().min_height = tracks_min_size + axis_padding;
+                }
+            }
         // For ZStack, always use max of children (like cross-axis)
-        if element!().direction == Direction::ZStack {
+        } else if element!().direction == Direction::ZStack {
             // Cross-axis sizing: max of child sizes + padding
             let (children_max_size, children_max_min_size) = if non_floating_children.is_empty() {
                 (0.0_f32, 0.0_f32)
@@ -72,11 +82,10 @@ pub fn fit_along_axis<Message>(ui_tree: BorrowedUITree<'_, Message>, root: UIKey
                     .iter()
                     .fold((0.0_f32, 0.0_f32), |acc, child| {
                         let c = &ui_tree.slots[*child];
-                        if x_axis {
-                            (acc.0.max(c.computed_width), acc.1.max(c.min_width))
-                        } else {
-                            (acc.0.max(c.computed_height), acc.1.max(c.min_height))
-                        }
+                        (
+                            acc.0.max(c.outer_size(axis)),
+                            acc.1.max(c.outer_min_size(axis)),
+                        )
                     })
             };
 
@@ -91,130 +100,110 @@ pub fn fit_along_axis<Message>(ui_tree: BorrowedUITree<'_, Message>, root: UIKey
                     element!().min_height = children_max_min_size + axis_padding;
                 }
             }
-        } else if element!().direction == axis_direction {
-            // Check if wrapping is enabled for LeftToRight direction
-            let use_wrapping = x_axis && element!().wrap;
+        } else if element!().direction.main_axis() == Some(axis) {
+            // On-axis sizing: sum of child sizes + gaps + padding
+            let (children_size_sum, children_min_size_sum) = non_floating_children.iter().fold(
+                (0.0_f32, 0.0_f32),
+                |(acc_size, acc_min), child| {
+                    let c = &ui_tree.slots[*child];
+                    (
+                        acc_size + c.outer_size(axis),
+                        acc_min + c.outer_min_size(axis),
+                    )
+                },
+            );
 
-            if use_wrapping {
-                // For On-axis sizing with wrapping LeftToRight: use max child size (like TopToBottom)
-                let (children_max_size, children_max_min_size) = if non_floating_children.is_empty()
-                {
-                    (0.0_f32, 0.0_f32)
-                } else {
-                    non_floating_children
-                        .iter()
-                        .fold((0.0_f32, 0.0_f32), |acc, child| {
-                            let c = &ui_tree.slots[*child];
-                            (acc.0.max(c.computed_width), acc.1.max(c.min_width))
-                        })
-                };
+            let child_gap_sum =
+                (non_floating_children.len().saturating_sub(1)) as f32 * element!().child_gap;
 
-                element!().computed_width = children_max_size + axis_padding;
+            // A wrapping element prefers a single line, but can shrink down to its largest child
+            let children_min_size = if element!().wraps() {
+                non_floating_children.iter().fold(0.0_f32, |acc, child| {
+                    let c = &ui_tree.slots[*child];
+                    acc.max(c.outer_min_size(axis))
+                })
+            } else {
+                children_min_size_sum + child_gap_sum
+            };
+
+            if x_axis {
+                element!().computed_width = children_size_sum + axis_padding + child_gap_sum;
                 if !is_scroll_enabled(&element!(), Axis::X) {
-                    element!().min_width = children_max_min_size + axis_padding;
+                    element!().min_width = children_min_size + axis_padding;
                 }
             } else {
-                // On-axis sizing: sum of child sizes + gaps + padding
-                let (children_size_sum, children_min_size_sum) = non_floating_children.iter().fold(
-                    (0.0_f32, 0.0_f32),
-                    |(acc_size, acc_min), child| {
+                element!().computed_height = children_size_sum + axis_padding + child_gap_sum;
+                if !is_scroll_enabled(&element!(), Axis::Y) {
+                    element!().min_height = children_min_size + axis_padding;
+                }
+            }
+        } else if element!().direction.main_axis() == Some(cross_axis) {
+            // Wrapped lines stack along the cross axis. Columns are only broken in the Y pass, so
+            // they are a single line when measuring widths
+            if element!().wraps() {
+                let line_gap = element!().line_gap();
+                let breaks = element!().wrap_breaks.clone();
+                let mut line_sizes = Vec::new();
+                let mut line_min_sizes = Vec::new();
+                for line in wrap::lines(&non_floating_children, &breaks) {
+                    let (size, min_size) = line.iter().fold((0.0_f32, 0.0_f32), |acc, child| {
                         let c = &ui_tree.slots[*child];
-                        if x_axis {
-                            (acc_size + c.computed_width, acc_min + c.min_width)
-                        } else {
-                            (acc_size + c.computed_height, acc_min + c.min_height)
-                        }
-                    },
-                );
-
-                let child_gap_sum =
-                    (non_floating_children.len().saturating_sub(1)) as f32 * element!().child_gap;
+                        (
+                            acc.0.max(c.outer_size(axis)),
+                            acc.1.max(c.outer_min_size(axis)),
+                        )
+                    });
+                    line_sizes.push(size);
+                    line_min_sizes.push(min_size);
+                }
+                let lines_size = wrap::total_size(&line_sizes, line_gap);
+                let lines_min_size = wrap::total_size(&line_min_sizes, line_gap);
 
                 if x_axis {
-                    element!().computed_width = children_size_sum + axis_padding + child_gap_sum;
+                    element!().computed_width = lines_size + axis_padding;
                     if !is_scroll_enabled(&element!(), Axis::X) {
-                        element!().min_width = children_min_size_sum + axis_padding + child_gap_sum;
+                        element!().min_width = lines_min_size + axis_padding;
                     }
                 } else {
-                    element!().computed_height = children_size_sum + axis_padding + child_gap_sum;
+                    element!().computed_height = lines_size + axis_padding;
                     if !is_scroll_enabled(&element!(), Axis::Y) {
-                        element!().min_height =
-                            children_min_size_sum + axis_padding + child_gap_sum;
+                        element!().min_height = lines_min_size + axis_padding;
                     }
                 }
-            }
-        } else if element!().direction == off_axis_direction {
-            // Handle wrapping for LeftToRight when calculating Y-axis (height)
-            if !x_axis && element!().direction == Direction::LeftToRight && element!().wrap {
-                // For Cross-axis sizing with wrapping LeftToRight: calculate height as sum of row heights
-                // We need to determine how many rows and the max height of each row
-                let breaks = element!().wrap_breaks.clone();
-
-                let mut row_heights = Vec::new();
-                let mut start_idx = 0;
-
-                for &break_idx in breaks
-                    .iter()
-                    .chain(std::iter::once(&non_floating_children.len()))
-                {
-                    let row_children = &non_floating_children[start_idx..break_idx];
-                    if !row_children.is_empty() {
-                        let max_height = row_children.iter().fold(0.0_f32, |acc, &child| {
-                            acc.max(ui_tree.slots[child].computed_height)
-                        });
-                        row_heights.push(max_height);
-                    }
-                    start_idx = break_idx;
-                }
-
-                let total_height = row_heights.iter().sum::<f32>();
-                let row_gaps = (row_heights.len().saturating_sub(1)) as f32 * element!().child_gap;
-
-                element!().computed_height = total_height + axis_padding + row_gaps;
-
-                if !is_scroll_enabled(&element!(), Axis::Y) {
-                    // Min height is also the sum of row heights for wrapping
-                    let min_row_heights: Vec<f32> = {
-                        let mut min_heights = Vec::new();
-                        let mut start_idx = 0;
-                        for &break_idx in breaks
+            } else {
+                // Cross-axis sizing: max of child sizes + padding
+                let (mut children_max_size, mut children_max_min_size) =
+                    if non_floating_children.is_empty() {
+                        (0.0_f32, 0.0_f32)
+                    } else {
+                        non_floating_children
                             .iter()
-                            .chain(std::iter::once(&non_floating_children.len()))
-                        {
-                            let row_children = &non_floating_children[start_idx..break_idx];
-                            if !row_children.is_empty() {
-                                let max_min_height =
-                                    row_children.iter().fold(0.0_f32, |acc, &child| {
-                                        acc.max(ui_tree.slots[child].min_height)
-                                    });
-                                min_heights.push(max_min_height);
-                            }
-                            start_idx = break_idx;
-                        }
-                        min_heights
+                            .fold((0.0_f32, 0.0_f32), |acc, child| {
+                                let c = &ui_tree.slots[*child];
+                                (
+                                    acc.0.max(c.outer_size(axis)),
+                                    acc.1.max(c.outer_min_size(axis)),
+                                )
+                            })
                     };
-                    let total_min_height = min_row_heights.iter().sum::<f32>();
-                    let min_row_gaps =
-                        (min_row_heights.len().saturating_sub(1)) as f32 * element!().child_gap;
-                    element!().min_height = total_min_height + axis_padding + min_row_gaps;
+
+                // Children aligned on the baseline of a row can reach below its tallest child
+                if !x_axis {
+                    let baselines = baseline::child_baselines(
+                        &ui_tree.slots,
+                        &mut ui_tree.widget_state,
+                        &ui_tree.arenas,
+                        key,
+                        &non_floating_children,
+                    );
+                    let line_baseline = baseline::line_baseline(&baselines, &non_floating_children);
+                    for (child, child_baseline) in &baselines {
+                        let extent =
+                            line_baseline - child_baseline + ui_tree.slots[*child].outer_size(axis);
+                        children_max_size = children_max_size.max(extent);
+                        children_max_min_size = children_max_min_size.max(extent);
+                    }
                 }
-            } else {
-                // Cross-axis sizing: max of child sizes + padding
-                let (children_max_size, children_max_min_size) = if non_floating_children.is_empty()
-                {
-                    (0.0_f32, 0.0_f32)
-                } else {
-                    non_floating_children
-                        .iter()
-                        .fold((0.0_f32, 0.0_f32), |acc, child| {
-                            let c = &ui_tree.slots[*child];
-                            if x_axis {
-                                (acc.0.max(c.computed_width), acc.1.max(c.min_width))
-                            } else {
-                                (acc.0.max(c.computed_height), acc.1.max(c.min_height))
-                            }
-                        })
-                };
 
                 if x_axis {
                     element!().computed_width = children_max_size + axis_padding;
@@ -269,6 +258,23 @@ pub fn fit_along_axis<Message>(ui_tree: BorrowedUITree<'_, Message>, root: UIKey
             }
         }
 
+        // Derive the size from the other axis when it follows an aspect ratio. A width that follows
+        // a height set by the parent is derived once the parent has sized the height
+        if let Some(aspect_ratio) = element!().aspect_ratio.filter(|ratio| *ratio > 0.0) {
+            let width_follows_height = element!().width_follows_height();
+            match (axis, element!().height) {
+                (Axis::X, Sizing::Fixed { px }) if width_follows_height => {
+                    element!().computed_width = px * aspect_ratio;
+                    element!().min_width = element!().computed_width;
+                }
+                (Axis::Y, _) if !width_follows_height => {
+                    element!().computed_height = element!().computed_width / aspect_ratio;
+                    element!().min_height = element!().computed_height;
+                }
+                _ => {}
+            }
+        }
+
         // Clamp to sizing (unless Percent, which is deferred to grow/shrink)
         match (axis, element!().width, element!().height) {
             (Axis::X, Sizing::Percent { .. }, _) => {
@@ -290,5 +296,7 @@ pub fn fit_along_axis<Message>(ui_tree: BorrowedUITree<'_, Message>, root: UIKey
                 element!().min_height = element!().min_height.clamp(min, max);
             }
         }
+
+        cache::record_fit(&mut element!(), axis);
     });
 }
@@ -0,0 +1,559 @@
+//! Cell placement and track sizing for [`Direction::Grid`](crate::layout::model::Direction::Grid) containers.
+//!
+//! Children are placed into cells first: explicitly placed children where their
+//! [`GridPlacement`] says, then the rest in order into the next free cells, row
+//! by row. Tracks are sized once per axis in two steps that mirror the other
+//! directions. The fit pass measures every track from the cells it contains,
+//! so that the container fits its tracks. The grow pass resolves the tracks
+//! against the size the container ended up with: left over space goes to
+//! `MinMax` tracks and then to `Fraction` tracks, and missing space is taken
+//! from tracks above their minimum size. Children are then sized to their cells.
+
+use slotmap::SlotMap;
+
+use crate::layout::model::{Axis, GridConfig, GridPlacement, GridTrack, Sizing, UIElement, UIKey};
+
+/// The cells a child covers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GridArea {
+    pub column: usize,
+    pub row: usize,
+    pub column_span: usize,
+    pub row_span: usize,
+}
+
+impl GridArea {
+    pub fn start(&self, axis: Axis) -> usize {
+        match axis {
+            Axis::X => self.column,
+            Axis::Y => self.row,
+        }
+    }
+
+    pub fn span(&self, axis: Axis) -> usize {
+        match axis {
+            Axis::X => self.column_span,
+            Axis::Y => self.row_span,
+        }
+    }
+}
+
+/// Non-floating children of a grid with the cells they cover
+pub struct GridItems {
+    pub items: Vec<(UIKey, GridArea)>,
+    pub columns: usize,
+    pub rows: usize,
+}
+
+impl GridItems {
+    fn count(&self, axis: Axis) -> usize {
+        match axis {
+            Axis::X => self.columns,
+            Axis::Y => self.rows,
+        }
+    }
+}
+
+pub fn place_items<Message>(slots: &SlotMap<UIKey, UIElement<Message>>, key: UIKey) -> GridItems {
+    let element = &slots[key];
+    let defined_columns = element.grid.as_ref().map_or(0, |grid| grid.columns.len());
+    let defined_rows = element.grid.as_ref().map_or(0, |grid| grid.rows.len());
+
+    let children: Vec<(UIKey, GridPlacement)> = element
+        .children
+        .iter()
+        .filter(|child| slots[**child].floating.is_none())
+        .map(|child| (*child, slots[*child].grid_placement.unwrap_or_default()))
+        .collect();
+
+    // Explicit columns may add implicit columns, auto placement never does
+    let columns = children
+        .iter()
+        .filter_map(|(_, placement)| {
+            placement
+                .column
+                .map(|column| column + placement.column_span.max(1))
+        })
+        .fold(defined_columns.max(1), usize::max);
+
+    let mut occupied: Vec<Vec<bool>> = Vec::new();
+    let is_free = |occupied: &Vec<Vec<bool>>, area: &GridArea| {
+        (area.row..area.row + area.row_span).all(|row| {
+            (area.column..area.column + area.column_span)
+                .all(|column| !occupied.get(row).is_some_and(|cells| cells[column]))
+        })
+    };
+    let occupy = |occupied: &mut Vec<Vec<bool>>, area: &GridArea| {
+        if occupied.len() < area.row + area.row_span {
+            occupied.resize(area.row + area.row_span, vec![false; columns]);
+        }
+        for row in &mut occupied[area.row..area.row + area.row_span] {
+            row[area.column..area.column + area.column_span].fill(true);
+        }
+    };
+    let area = |placement: &GridPlacement, column: usize, row: usize| {
+        let column_span = placement.column_span.clamp(1, columns);
+        GridArea {
+            column: column.min(columns - column_span),
+            row,
+            column_span,
+            row_span: placement.row_span.max(1),
+        }
+    };
+
+    let mut areas: Vec<Option<GridArea>> = vec![None; children.len()];
+    for (index, (_, placement)) in children.iter().enumerate() {
+        if let (Some(column), Some(row)) = (placement.column, placement.row) {
+            let area = area(placement, column, row);
+            occupy(&mut occupied, &area);
+            areas[index] = Some(area);
+        }
+    }
+
+    let mut cursor = (0, 0);
+    for (index, (_, placement)) in children.iter().enumerate() {
+        if areas[index].is_some() {
+            continue;
+        }
+
+        let placed = match (placement.column, placement.row) {
+            (Some(column), _) => (0..)
+                .map(|row| area(placement, column, row))
+                .find(|area| is_free(&occupied, area))
+                .unwrap(),
+            (None, Some(row)) => (0..columns)
+                .map(|column| area(placement, column, row))
+                .find(|area| area.column_span + area.column <= columns && is_free(&occupied, area))
+                .unwrap_or_else(|| area(placement, 0, row)),
+            (None, None) => loop {
+                let (row, column) = cursor;
+                let candidate = area(placement, column, row);
+                if column + candidate.column_span > columns {
+                    cursor = (row + 1, 0);
+                } else if is_free(&occupied, &candidate) {
+                    cursor = (row, column + candidate.column_span);
+                    break candidate;
+                } else {
+                    cursor = (row, column + 1);
+                }
+            },
+        };
+        occupy(&mut occupied, &placed);
+        areas[index] = Some(placed);
+    }
+
+    let items: Vec<(UIKey, GridArea)> = children
+        .iter()
+        .zip(areas)
+        .map(|((child, _), area)| (*child, area.unwrap()))
+        .collect();
+    let rows = items
+        .iter()
+        .map(|(_, area)| area.row + area.row_span)
+        .fold(defined_rows, usize::max);
+
+    GridItems {
+        items,
+        columns,
+        rows,
+    }
+}
+
+pub fn gap(grid: Option<&GridConfig>, axis: Axis) -> f32 {
+    grid.map_or(0.0, |grid| match axis {
+        Axis::X => grid.column_gap,
+        Axis::Y => grid.row_gap,
+    })
+}
+
+/// Offset of every track from the start of the first one
+pub fn track_offsets(sizes: &[f32], gap: f32) -> Vec<f32> {
+    sizes
+        .iter()
+        .scan(0.0, |offset, size| {
+            let start = *offset;
+            *offset += size + gap;
+            Some(start)
+        })
+        .collect()
+}
+
+/// Size of `span` tracks starting at `start`, including the gaps between them
+pub fn span_size(sizes: &[f32], gap: f32, start: usize, span: usize) -> f32 {
+    let end = (start + span).min(sizes.len());
+    let start = start.min(end);
+    sizes[start..end].iter().sum::<f32>() + gap * (end - start).saturating_sub(1) as f32
+}
+
+fn tracks(grid: Option<&GridConfig>, axis: Axis, count: usize) -> Vec<GridTrack> {
+    let defined: &[GridTrack] = grid.map_or(&[], |grid| match axis {
+        Axis::X => &grid.columns,
+        Axis::Y => &grid.rows,
+    });
+    (0..count)
+        .map(|index| defined.get(index).copied().unwrap_or(GridTrack::Fit))
+        .collect()
+}
+
+/// Computed and minimum size of an element along `axis`, including its margins
+fn size<Message>(element: &UIElement<Message>, axis: Axis) -> (f32, f32) {
+    (element.outer_size(axis), element.outer_min_size(axis))
+}
+
+/// Upper limit a track may grow to while fitting its cells
+fn fit_limit(track: GridTrack) -> f32 {
+    match track {
+        GridTrack::Fixed(px) => px,
+        GridTrack::MinMax { min, max } => max.max(min),
+        GridTrack::Fit | GridTrack::Fraction(_) => f32::INFINITY,
+    }
+}
+
+/// Spreads `amount` evenly over the tracks at `indices` without taking any of
+/// them past its limit. A negative amount shrinks the tracks. Returns what
+/// could not be spread.
+fn spread(sizes: &mut [f32], limits: &[f32], mut indices: Vec<usize>, mut amount: f32) -> f32 {
+    let grows = amount > 0.0;
+    indices.retain(|&index| {
+        if grows {
+            limits[index] > sizes[index]
+        } else {
+            limits[index] < sizes[index]
+        }
+    });
+
+    while amount.abs() > 0.01 && !indices.is_empty() {
+        let share = amount / indices.len() as f32;
+        indices.retain(|&index| {
+            let room = limits[index] - sizes[index];
+            let step = if grows {
+                share.min(room)
+            } else {
+                share.max(room)
+            };
+            sizes[index] += step;
+            amount -= step;
+            (limits[index] - sizes[index]).abs() > 0.01
+        });
+    }
+    amount
+}
+
+/// Sizes and minimum sizes of the tracks along `axis`, measured from their cells
+fn measure_tracks<Message>(
+    slots: &SlotMap<UIKey, UIElement<Message>>,
+    items: &[(UIKey, GridArea)],
+    tracks: &[GridTrack],
+    axis: Axis,
+    gap: f32,
+) -> (Vec<f32>, Vec<f32>) {
+    let mut sizes: Vec<f32> = tracks
+        .iter()
+        .map(|track| match *track {
+            GridTrack::Fixed(px) => px,
+            GridTrack::MinMax { min, .. } => min,
+            GridTrack::Fit | GridTrack::Fraction(_) => 0.0,
+        })
+        .collect();
+    let mut min_sizes = sizes.clone();
+    let limits: Vec<f32> = tracks.iter().copied().map(fit_limit).collect();
+
+    // Single cells first, then spanning children by increasing span so they
+    // only grow tracks that are still too small for them
+    let mut items: Vec<&(UIKey, GridArea)> = items.iter().collect();
+    items.sort_by_key(|(_, area)| area.span(axis));
+
+    for (child, area) in items {
+        let (size, min_size) = size(&slots[*child], axis);
+        let start = area.start(axis);
+        let span = area.span(axis);
+        let indices: Vec<usize> = (start..start + span).collect();
+
+        // Fraction tracks are sized by the space left over, so only the
+        // minimum size of their cells counts towards their own size
+        let fit_indices: Vec<usize> = indices
+            .iter()
+            .copied()
+            .filter(|index| !matches!(tracks[*index], GridTrack::Fraction(_)))
+            .collect();
+        if fit_indices.is_empty() {
+            let missing = min_size - span_size(&sizes, gap, start, span);
+            spread(&mut sizes, &limits, indices.clone(), missing);
+        } else {
+            let missing = size - span_size(&sizes, gap, start, span);
+            spread(&mut sizes, &limits, fit_indices, missing);
+        }
+
+        let missing = min_size - span_size(&min_sizes, gap, start, span);
+        if missing > 0.0 {
+            spread(&mut min_sizes, &limits, indices, missing);
+        }
+    }
+
+    // Fitting a spanning child may take a track past its minimum on one side only
+    for (size, min_size) in sizes.iter_mut().zip(&min_sizes) {
+        *size = size.max(*min_size);
+    }
+
+    (sizes, min_sizes)
+}
+
+/// Size and minimum size of a grid's tracks along `axis`, including the gaps
+/// between them but not the container's padding
+pub fn fit<Message>(
+    slots: &SlotMap<UIKey, UIElement<Message>>,
+    key: UIKey,
+    axis: Axis,
+) -> (f32, f32) {
+    let grid_items = place_items(slots, key);
+    let grid = slots[key].grid.as_ref();
+    let tracks = tracks(grid, axis, grid_items.count(axis));
+    let gap = gap(grid, axis);
+
+    let (sizes, min_sizes) = measure_tracks(slots, &grid_items.items, &tracks, axis, gap);
+    let gaps = gap * tracks.len().saturating_sub(1) as f32;
+    (
+        sizes.iter().sum::<f32>() + gaps,
+        min_sizes.iter().sum::<f32>() + gaps,
+    )
+}
+
+/// Resolves a grid's tracks along `axis` against its computed size, stores
+/// them on the element and sizes the children to their cells
+pub fn grow<Message>(slots: &mut SlotMap<UIKey, UIElement<Message>>, key: UIKey, axis: Axis) {
+    let x_axis = axis == Axis::X;
+    let element = &slots[key];
+    let (available, padding) = if x_axis {
+        (
+            element.computed_width,
+            element.padding.left + element.padding.right,
+        )
+    } else {
+        (
+            element.computed_height,
+            element.padding.top + element.padding.bottom,
+        )
+    };
+    let available = (available - padding).max(0.0);
+    let scroll_enabled = element.scroll.as_ref().is_some_and(|scroll| {
+        if x_axis {
+            scroll.horizontal
+        } else {
+            scroll.vertical
+        }
+    });
+
+    let grid_items = place_items(slots, key);
+    let grid = element.grid.as_ref();
+    let tracks = tracks(grid, axis, grid_items.count(axis));
+    let gap = gap(grid, axis);
+    let gaps = gap * tracks.len().saturating_sub(1) as f32;
+
+    let (mut sizes, min_sizes) = measure_tracks(slots, &grid_items.items, &tracks, axis, gap);
+    let free = available - sizes.iter().sum::<f32>() - gaps;
+
+    if free > 0.0 {
+        // MinMax tracks grow towards their maximum first
+        let limits: Vec<f32> = tracks
+            .iter()
+            .zip(&sizes)
+            .map(|(track, size)| match track {
+                GridTrack::MinMax { max, .. } => max.max(*size),
+                _ => *size,
+            })
+            .collect();
+        let free = spread(&mut sizes, &limits, (0..tracks.len()).collect(), free);
+
+        // Fraction tracks split what remains, but never shrink below their
+        // size. Tracks that would are left as they are and the others split again
+        let mut flexible: Vec<usize> = (0..tracks.len())
+            .filter(
+                |index| matches!(tracks[*index], GridTrack::Fraction(fraction) if fraction > 0.0),
+            )
+            .collect();
+        let mut space = free + flexible.iter().map(|index| sizes[*index]).sum::<f32>();
+        loop {
+            let fractions: f32 = flexible
+                .iter()
+                .map(|index| match tracks[*index] {
+                    GridTrack::Fraction(fraction) => fraction,
+                    _ => 0.0,
+                })
+                .sum();
+            if fractions <= 0.0 {
+                break;
+            }
+            let per_fraction = space / fractions;
+            let share = |index: usize| match tracks[index] {
+                GridTrack::Fraction(fraction) => per_fraction * fraction,
+                _ => 0.0,
+            };
+
+            let too_large: Vec<usize> = flexible
+                .iter()
+                .copied()
+                .filter(|index| sizes[*index] > share(*index))
+                .collect();
+            if too_large.is_empty() {
+                for index in &flexible {
+                    sizes[*index] = share(*index);
+                }
+                break;
+            }
+            for index in too_large {
+                space -= sizes[index];
+                flexible.retain(|flexible| *flexible != index);
+            }
+        }
+    } else if free < 0.0 && !scroll_enabled {
+        spread(&mut sizes, &min_sizes, (0..tracks.len()).collect(), free);
+    }
+
+    let content_size = sizes.iter().sum::<f32>() + gaps + padding;
+    let element = &mut slots[key];
+    if x_axis {
+        element.computed_content_width = content_size;
+        element.grid_columns = sizes.clone();
+    } else {
+        element.computed_content_height = content_size;
+        element.grid_rows = sizes.clone();
+    }
+
+    for (child, area) in grid_items.items {
+        let child = &mut slots[child];
+        let cell =
+            span_size(&sizes, gap, area.start(axis), area.span(axis)) - child.margin_along(axis);
+        let follows_aspect_ratio = child.follows_aspect_ratio(axis);
+        let (sizing, size, min_size) = if x_axis {
+            (child.width, &mut child.computed_width, child.min_width)
+        } else {
+            (child.height, &mut child.computed_height, child.min_height)
+        };
+
+        match sizing {
+            _ if follows_aspect_ratio => {}
+            Sizing::Percent { percent } => *size = cell * percent,
+            Sizing::Grow { min, max, .. } => *size = cell.min(max).max(min),
+            _ if *size > cell => *size = cell.max(min_size),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::OwnedUITree;
+    use crate::layout::model::{Alignment, Element};
+    use crate::layout::testing::{bounds, lay_out};
+
+    /// A grid of one row with the given columns, with a child of the given width and minimum
+    /// width in every column
+    fn single_row(
+        columns: Vec<GridTrack>,
+        cells: &[(Sizing, f32, f32)],
+    ) -> (SlotMap<UIKey, UIElement<()>>, UIKey) {
+        let mut slots = SlotMap::with_key();
+        let grid = slots.insert(UIElement {
+            grid: Some(GridConfig::new().with_columns(columns).with_column_gap(10.0)),
+            ..Default::default()
+        });
+        for &(width, computed_width, min_width) in cells {
+            let cell = slots.insert(UIElement {
+                parent: Some(grid),
+                width,
+                computed_width,
+                min_width,
+                ..Default::default()
+            });
+            slots[grid].children.push(cell);
+        }
+        (slots, grid)
+    }
+
+    #[test]
+    fn tracks_fit_cells_and_share_free_space() {
+        let (mut slots, grid) = single_row(
+            vec![
+                GridTrack::fixed(50.0),
+                GridTrack::fit(),
+                GridTrack::fraction(1.0),
+                GridTrack::fraction(2.0),
+            ],
+            &[
+                (Sizing::fit(), 20.0, 20.0),
+                (Sizing::fit(), 30.0, 20.0),
+                (Sizing::grow(), 25.0, 10.0),
+                (Sizing::grow(), 40.0, 40.0),
+            ],
+        );
+
+        // Fraction tracks only hold the minimum size of their cells
+        assert_eq!(fit(&slots, grid, Axis::X), (160.0, 150.0));
+
+        slots[grid].computed_width = 290.0;
+        grow(&mut slots, grid, Axis::X);
+        assert_eq!(slots[grid].grid_columns, [50.0, 30.0, 60.0, 120.0]);
+        let cell = slots[grid].children[2];
+        assert_eq!(slots[cell].computed_width, 60.0);
+
+        // Missing space comes out of the tracks above their minimum size
+        slots[grid].computed_width = 120.0;
+        grow(&mut slots, grid, Axis::X);
+        assert_eq!(slots[grid].grid_columns, [50.0, 20.0, 10.0, 40.0]);
+    }
+
+    #[test]
+    fn fractions_keep_cells_that_dont_fit_their_share() {
+        let (mut slots, grid) = single_row(
+            vec![GridTrack::fraction(1.0), GridTrack::fraction(1.0)],
+            &[(Sizing::fit(), 100.0, 100.0), (Sizing::fit(), 10.0, 10.0)],
+        );
+
+        slots[grid].computed_width = 160.0;
+        grow(&mut slots, grid, Axis::X);
+        assert_eq!(slots[grid].grid_columns, [100.0, 50.0]);
+    }
+
+    fn dashboard() -> Element<()> {
+        let cell = |id: u64, width: Sizing, height: Sizing| Element {
+            id: Some(id),
+            width,
+            height,
+            ..Default::default()
+        };
+
+        Element {
+            width: Sizing::fixed(270.0),
+            children: vec![
+                cell(10, Sizing::fixed(20.0), Sizing::fixed(20.0)),
+                cell(11, Sizing::grow(), Sizing::fixed(30.0)),
+                cell(12, Sizing::grow(), Sizing::grow())
+                    .with_grid_placement(GridPlacement::new().with_column_span(2)),
+                cell(13, Sizing::fixed(10.0), Sizing::fixed(10.0))
+                    .with_grid_placement(GridPlacement::cell(2, 0))
+                    .with_axis_align_self(Alignment::Center)
+                    .with_cross_align_self(Alignment::Center),
+            ],
+            ..Default::default()
+        }
+        .with_grid(
+            GridConfig::new()
+                .with_columns([GridTrack::fixed(50.0), GridTrack::grow(), GridTrack::grow()])
+                .with_rows([GridTrack::fit(), GridTrack::fixed(25.0)])
+                .with_gap(10.0),
+        )
+    }
+
+    #[test]
+    fn sizes_tracks_and_places_cells() {
+        let mut ui_tree = OwnedUITree::default();
+        lay_out(&mut ui_tree, dashboard(), 320.0, 240.0);
+
+        // Columns are 50, 100 and 100 wide, rows 30 and 25 high
+        assert_eq!(bounds(&ui_tree, 10), (0.0, 0.0, 20.0, 20.0));
+        assert_eq!(bounds(&ui_tree, 11), (60.0, 0.0, 100.0, 30.0));
+        assert_eq!(bounds(&ui_tree, 12), (0.0, 40.0, 160.0, 25.0));
+        assert_eq!(bounds(&ui_tree, 13), (215.0, 10.0, 10.0, 10.0));
+    }
+}
@@ -1,40 +1,13 @@
+use slotmap::SlotMap;
+
 use crate::layout::{
-    BorrowedUITree,
+    BorrowedUITree, cache, fit_along_axis,
     float::EpsFloatCmp,
-    model::{Axis, Direction, Sizing, UIKey},
-    visitors,
+    grid,
+    model::{Axis, Direction, FloatingConfig, Sizing, UIElement, UIKey},
+    resize_subtree, visitors, wrap,
 };
 
-/// Calculate wrap breaks for LeftToRight wrapping layout
-/// Returns the indices where new rows should start
-fn calculate_wrap_breaks<Message>(
-    ui_tree: BorrowedUITree<'_, Message>,
-    children: &[UIKey],
-    available_width: f32,
-    child_gap: f32,
-) -> Vec<usize> {
-    let mut breaks = Vec::new();
-    let mut current_width = 0.0;
-    let mut is_first_in_row = true;
-
-    for (i, &child) in children.iter().enumerate() {
-        let child_width = ui_tree.slots[child].computed_width;
-        let gap_width = if is_first_in_row { 0.0 } else { child_gap };
-
-        if !is_first_in_row && current_width + gap_width + child_width > available_width {
-            // Start a new row
-            breaks.push(i);
-            current_width = child_width;
-        } else {
-            current_width += gap_width + child_width;
-        }
-
-        is_first_in_row = false;
-    }
-
-    breaks
-}
-
 /// Apply grow algorithm to a set of children
 fn apply_axis_grow_algorithm<Message>(
     ui_tree: BorrowedUITree<'_, Message>,
@@ -42,6 +15,14 @@ fn apply_axis_grow_algorithm<Message>(
     remaining_size: &mut f32,
     x_axis: bool,
 ) {
+    // Children of weight 0 keep their size, children of different weights split the free space
+    // by weight instead of evening out sizes
+    growable_children.retain(|ck| grow_weight(&ui_tree.slots, *ck, x_axis) > 0.0);
+    if weights_differ(&ui_tree.slots, growable_children, x_axis) {
+        apply_axis_weighted_grow_algorithm(ui_tree, growable_children, remaining_size, x_axis);
+        return;
+    }
+
     while remaining_size.gt_eps(&0.01) && !growable_children.is_empty() {
         // Find smallest group among growable children
         let mut smallest_size = f32::INFINITY;
@@ -137,6 +118,13 @@ fn apply_axis_shrink_algorithm<Message>(
     remaining_size: &mut f32,
     x_axis: bool,
 ) {
+    // Children of weight 0 keep their size here too
+    shrinkable_children.retain(|ck| grow_weight(&ui_tree.slots, *ck, x_axis) > 0.0);
+    if weights_differ(&ui_tree.slots, shrinkable_children, x_axis) {
+        apply_axis_weighted_shrink_algorithm(ui_tree, shrinkable_children, remaining_size, x_axis);
+        return;
+    }
+
     while remaining_size.lt_eps(&-0.01) && !shrinkable_children.is_empty() {
         let mut largest_size = -f32::INFINITY;
         let mut second_largest_size = -f32::INFINITY;
@@ -210,6 +198,319 @@ fn apply_axis_shrink_algorithm<Message>(
     }
 }
 
+/// Grow weight of a child along the axis, children that don't grow weigh 1
+fn grow_weight<Message>(
+    slots: &SlotMap<UIKey, UIElement<Message>>,
+    ck: UIKey,
+    x_axis: bool,
+) -> f32 {
+    let c = &slots[ck];
+    match if x_axis { c.width } else { c.height } {
+        Sizing::Grow { weight, .. } => weight.max(0.0),
+        _ => 1.0,
+    }
+}
+
+/// Whether the children don't all have the same grow weight
+fn weights_differ<Message>(
+    slots: &SlotMap<UIKey, UIElement<Message>>,
+    children: &[UIKey],
+    x_axis: bool,
+) -> bool {
+    let mut weights = children.iter().map(|ck| grow_weight(slots, *ck, x_axis));
+    weights
+        .next()
+        .is_some_and(|first| weights.any(|weight| weight != first))
+}
+
+/// Splits the free space between children in proportion to their grow weight, like CSS
+/// `flex-grow`. Children that would pass their max stop there and the rest is split again
+fn apply_axis_weighted_grow_algorithm<Message>(
+    ui_tree: BorrowedUITree<'_, Message>,
+    growable_children: &mut Vec<UIKey>,
+    remaining_size: &mut f32,
+    x_axis: bool,
+) {
+    while remaining_size.gt_eps(&0.01) && !growable_children.is_empty() {
+        let total_weight: f32 = growable_children
+            .iter()
+            .map(|ck| grow_weight(&ui_tree.slots, *ck, x_axis))
+            .sum();
+        let size_per_weight = *remaining_size / total_weight;
+
+        // Children that reach their max take their part first
+        let mut reached_max = false;
+        let mut i = 0;
+        while i < growable_children.len() {
+            let ck = growable_children[i];
+            let c = &ui_tree.slots[ck];
+            let (size, sizing) = if x_axis {
+                (c.computed_width, c.width)
+            } else {
+                (c.computed_height, c.height)
+            };
+            let max = match sizing {
+                Sizing::Grow { max, .. } => max,
+                _ => f32::INFINITY,
+            };
+            if size + size_per_weight * grow_weight(&ui_tree.slots, ck, x_axis) >= max {
+                if x_axis {
+                    ui_tree.slots[ck].computed_width = max;
+                } else {
+                    ui_tree.slots[ck].computed_height = max;
+                }
+                *remaining_size -= max - size;
+                growable_children.remove(i);
+                reached_max = true;
+                continue;
+            }
+            i += 1;
+        }
+        if reached_max {
+            continue;
+        }
+
+        for &ck in growable_children.iter() {
+            let size_to_add = size_per_weight * grow_weight(&ui_tree.slots, ck, x_axis);
+            if x_axis {
+                ui_tree.slots[ck].computed_width += size_to_add;
+            } else {
+                ui_tree.slots[ck].computed_height += size_to_add;
+            }
+        }
+        *remaining_size = 0.0;
+    }
+}
+
+/// Takes the missing space from children in proportion to their grow weight times their size,
+/// like CSS `flex-shrink`. Children that would pass their min stop there and the rest is taken
+/// again from the others
+fn apply_axis_weighted_shrink_algorithm<Message>(
+    ui_tree: BorrowedUITree<'_, Message>,
+    shrinkable_children: &mut Vec<UIKey>,
+    remaining_size: &mut f32,
+    x_axis: bool,
+) {
+    let sizing = |ui_tree: &BorrowedUITree<'_, Message>, ck: UIKey| {
+        let c = &ui_tree.slots[ck];
+        // Children that don't grow still shrink, like CSS `flex-shrink: 1`
+        let weight = grow_weight(&ui_tree.slots, ck, x_axis);
+        if x_axis {
+            (c.computed_width, c.min_width, weight * c.computed_width)
+        } else {
+            (c.computed_height, c.min_height, weight * c.computed_height)
+        }
+    };
+
+    while remaining_size.lt_eps(&-0.01) && !shrinkable_children.is_empty() {
+        let total_weight: f32 = shrinkable_children
+            .iter()
+            .map(|ck| sizing(&ui_tree, *ck).2)
+            .sum();
+        if total_weight <= 0.0 {
+            break;
+        }
+        let size_per_weight = -*remaining_size / total_weight;
+
+        // Children that reach their min give up their part first
+        let mut reached_min = false;
+        let mut i = 0;
+        while i < shrinkable_children.len() {
+            let ck = shrinkable_children[i];
+            let (size, min, weight) = sizing(&ui_tree, ck);
+            if size - size_per_weight * weight <= min {
+                if x_axis {
+                    ui_tree.slots[ck].computed_width = min;
+                } else {
+                    ui_tree.slots[ck].computed_height = min;
+                }
+                *remaining_size += size - min;
+                shrinkable_children.remove(i);
+                reached_min = true;
+                continue;
+            }
+            i += 1;
+        }
+        if reached_min {
+            continue;
+        }
+
+        for &ck in shrinkable_children.iter() {
+            let (size, _, weight) = sizing(&ui_tree, ck);
+            let new_size = size - size_per_weight * weight;
+            if x_axis {
+                ui_tree.slots[ck].computed_width = new_size;
+            } else {
+                ui_tree.slots[ck].computed_height = new_size;
+            }
+        }
+        *remaining_size = 0.0;
+    }
+}
+
+/// Adjusts the size the parent gave an element before its children are sized
+fn adjust_settled_size<Message>(
+    ui_tree: BorrowedUITree<'_, Message>,
+    key: UIKey,
+    parent: Option<UIKey>,
+    axis: Axis,
+) {
+    let x_axis = matches!(axis, Axis::X);
+
+    // A width that follows a height set by the parent is known once the parent sized the height.
+    // The children of a row share its width, so they are all sized again. Otherwise only the
+    // subtree of the element is
+    if !x_axis && let Some(width) = derived_width(&ui_tree.slots, key) {
+        match parent {
+            Some(parent)
+                if ui_tree.slots[parent].direction.main_axis() == Some(Axis::X)
+                    && !ui_tree.slots[parent].wraps() =>
+            {
+                resize_row(ui_tree, parent);
+            }
+            _ => {
+                let element = &mut ui_tree.slots[key];
+                element.computed_width = width;
+                element.min_width = width;
+                resize_subtree(ui_tree, key, Axis::Y);
+            }
+        }
+    }
+
+    // Floating elements take their anchor's width and their max size. An anchor that isn't an
+    // ancestor may not be sized yet, [`match_anchor_widths`] corrects those widths
+    let Some(floating) = ui_tree.slots[key].floating.clone() else {
+        return;
+    };
+    if x_axis && floating.match_anchor_width {
+        let anchor = anchor_of(ui_tree, &floating, parent);
+        if let Some(anchor) = anchor {
+            ui_tree.slots[key].computed_width = ui_tree.slots[anchor].computed_width;
+        }
+    }
+
+    let element = &mut ui_tree.slots[key];
+    let max_size = floating
+        .max_size
+        .and_then(|size| if x_axis { size.width } else { size.height });
+    if let Some(max_size) = max_size {
+        if x_axis {
+            element.computed_width = element.computed_width.min(max_size);
+        } else {
+            element.computed_height = element.computed_height.min(max_size);
+        }
+    }
+}
+
+/// Whether the width of an element follows the height its parent gives it, by its aspect ratio or
+/// as the columns of a wrapping column
+fn derives_width<Message>(element: &UIElement<Message>) -> bool {
+    element.follows_aspect_ratio(Axis::X)
+        || (element.wraps()
+            && element.direction.main_axis() == Some(Axis::Y)
+            && matches!(element.width, Sizing::Fit { .. } | Sizing::Grow { .. }))
+}
+
+/// Width derived from the height of an element that [`derives_width`], when it differs from the
+/// width it has. Columns only widen a growing element
+fn derived_width<Message>(
+    slots: &SlotMap<UIKey, UIElement<Message>>,
+    key: UIKey,
+) -> Option<f32> {
+    let element = &slots[key];
+    if !derives_width(element) {
+        return None;
+    }
+    let (min, max) = (element.width.get_min(), element.width.get_max());
+    let width = match element.aspect_ratio.filter(|ratio| *ratio > 0.0) {
+        Some(ratio) => element.computed_height * ratio,
+        None => wrap::columns_width(slots, key),
+    }
+    .clamp(min, max);
+
+    let delta = width - element.computed_width;
+    let grows = matches!(element.width, Sizing::Grow { .. }) && element.aspect_ratio.is_none();
+    (if grows { delta > 0.01 } else { delta.abs() > 0.01 }).then_some(width)
+}
+
+/// Sizes the children of a row again once heights derived the width of some of them. The
+/// others are fit and given the width left, then heights are fit and settled again
+fn resize_row<Message>(ui_tree: BorrowedUITree<'_, Message>, row: UIKey) {
+    let children: Vec<UIKey> = ui_tree.slots[row]
+        .children
+        .iter()
+        .copied()
+        .filter(|child| ui_tree.slots[*child].floating.is_none())
+        .collect();
+
+    for &child in &children {
+        if !ui_tree.slots[child].follows_aspect_ratio(Axis::X) {
+            fit_along_axis(ui_tree, child, Axis::X);
+        }
+        if let Some(width) = derived_width(&ui_tree.slots, child) {
+            let element = &mut ui_tree.slots[child];
+            element.computed_width = width;
+            element.min_width = width;
+        }
+    }
+    grow_and_shrink_along_axis(ui_tree, row, Axis::X);
+
+    // Heights that derived a width stay as the row settled them
+    for &child in &children {
+        let element = &ui_tree.slots[child];
+        let settled =
+            derives_width(element).then_some((element.computed_height, element.min_height));
+        fit_along_axis(ui_tree, child, Axis::Y);
+        if let Some((height, min_height)) = settled {
+            let element = &mut ui_tree.slots[child];
+            (element.computed_height, element.min_height) = (height, min_height);
+        }
+    }
+    grow_and_shrink_along_axis(ui_tree, row, Axis::Y);
+}
+
+/// Anchor of a floating element, its parent unless `anchor_id` is set
+fn anchor_of<Message>(
+    ui_tree: BorrowedUITree<'_, Message>,
+    floating: &FloatingConfig,
+    parent: Option<UIKey>,
+) -> Option<UIKey> {
+    floating
+        .anchor_id
+        .and_then(|id| ui_tree.slots[ui_tree.root].id_map.get(&id).copied())
+        .or(parent)
+}
+
+/// Gives floating elements the width of their anchor once every element is sized along X, as an
+/// anchor elsewhere in the tree isn't sized yet when the element is. The subtree of an element
+/// whose width changes is sized along X again
+pub fn match_anchor_widths<Message>(ui_tree: BorrowedUITree<'_, Message>, root: UIKey) {
+    let mut matching = Vec::new();
+    visitors::visit_bfs(ui_tree, root, |ui_tree, key, parent| {
+        if let Some(floating) = &ui_tree.slots[key].floating
+            && floating.match_anchor_width
+        {
+            matching.push((key, parent));
+        }
+    });
+
+    for (key, parent) in matching {
+        let floating = ui_tree.slots[key].floating.clone().unwrap_or_default();
+        let Some(anchor) = anchor_of(ui_tree, &floating, parent) else {
+            continue;
+        };
+        let max_width = floating
+            .max_size
+            .and_then(|size| size.width)
+            .unwrap_or(f32::INFINITY);
+        let width = ui_tree.slots[anchor].computed_width.min(max_width);
+        if (width - ui_tree.slots[key].computed_width).abs() > 0.01 {
+            ui_tree.slots[key].computed_width = width;
+            grow_and_shrink_along_axis(ui_tree, key, Axis::X);
+        }
+    }
+}
+
 pub fn grow_and_shrink_along_axis<Message>(
     ui_tree: BorrowedUITree<'_, Message>,
     root: UIKey,
@@ -217,7 +518,7 @@ pub fn grow_and_shrink_along_axis<Message>(
 ) {
     let x_axis = matches!(axis, Axis::X);
 
-    visitors::visit_bfs(ui_tree, root, |ui_tree, key, _parent| {
+    visitors::visit_bfs(ui_tree, root, |ui_tree, key, parent| {
         // Use a macro to safely obtain multiple mutable reads/writes separated by operations
         macro_rules! element {
             () => {
@@ -225,6 +526,14 @@ pub fn grow_and_shrink_along_axis<Message>(
             };
         }
 
+        // The element the pass starts from keeps the size it was given
+        if key != root {
+            adjust_settled_size(ui_tree, key, parent, axis);
+        }
+
+        // Unchanged subtrees are only laid out again when they are given another size
+        cache::settle(ui_tree, key, axis);
+
         let total_padding = if x_axis {
             element!().padding.left + element!().padding.right
         } else {
@@ -238,13 +547,6 @@ pub fn grow_and_shrink_along_axis<Message>(
             element!().computed_height
         } - total_padding;
 
-        // Determine axis directions
-        let axis_direction = if x_axis {
-            Direction::LeftToRight
-        } else {
-            Direction::TopToBottom
-        };
-
         // Non-floating children keys
         let non_floating_children: Vec<UIKey> = element!()
             .children
@@ -253,6 +555,12 @@ pub fn grow_and_shrink_along_axis<Message>(
             .filter(|c| ui_tree.slots[*c].floating.is_none())
             .collect();
 
+        // Grid tracks are resolved together, children take the size of their cells
+        if element!().direction == Direction::Grid {
+            grid::grow(&mut ui_tree.slots, key, axis);
+            return;
+        }
+
         // Handle ZStack specially - all children grow to container size (like cross-axis)
         if element!().direction == Direction::ZStack {
             let mut grow_to_size = remaining_size;
@@ -273,9 +581,9 @@ pub fn grow_and_shrink_along_axis<Message>(
             for child in &non_floating_children {
                 let c = &ui_tree.slots[*child];
                 if x_axis {
-                    inner_content_size = inner_content_size.max(c.computed_width + total_padding);
+                    inner_content_size = inner_content_size.max(c.outer_size(Axis::X) + total_padding);
                 } else {
-                    inner_content_size = inner_content_size.max(c.computed_height + total_padding);
+                    inner_content_size = inner_content_size.max(c.outer_size(Axis::Y) + total_padding);
                 }
             }
 
@@ -319,6 +627,7 @@ pub fn grow_and_shrink_along_axis<Message>(
                     };
                     !matches!(sizing, Sizing::Percent { .. })
                         && matches!(sizing, Sizing::Grow { .. })
+                        && !ui_tree.slots[*c].follows_aspect_ratio(axis)
                 })
                 .collect();
 
@@ -328,6 +637,7 @@ pub fn grow_and_shrink_along_axis<Message>(
                 } else {
                     ui_tree.slots[*ck].computed_height
                 };
+                let grow_to_size = grow_to_size - ui_tree.slots[*ck].margin_along(axis);
                 if size < grow_to_size {
                     let max_allowed = match if x_axis {
                         ui_tree.slots[*ck].width
@@ -358,6 +668,7 @@ pub fn grow_and_shrink_along_axis<Message>(
                             ui_tree.slots[*c].height
                         };
                         !matches!(sizing, Sizing::Percent { .. })
+                            && !ui_tree.slots[*c].follows_aspect_ratio(axis)
                     })
                     .filter(|c| {
                         let e = &ui_tree.slots[*c];
@@ -367,7 +678,7 @@ pub fn grow_and_shrink_along_axis<Message>(
                             e.computed_height
                         };
                         let min_allowed = if x_axis { e.min_width } else { e.min_height };
-                        size > min_allowed && size > remaining_size
+                        size > min_allowed && size > remaining_size - e.margin_along(axis)
                     })
                     .collect();
 
@@ -377,6 +688,7 @@ pub fn grow_and_shrink_along_axis<Message>(
                     } else {
                         ui_tree.slots[ck].computed_height
                     };
+                    let remaining_size = remaining_size - ui_tree.slots[ck].margin_along(axis);
                     if size > remaining_size {
                         let min_allowed = if x_axis {
                             ui_tree.slots[ck].min_width
@@ -411,7 +723,7 @@ pub fn grow_and_shrink_along_axis<Message>(
             .collect();
 
         // Child gap only applies when flowing along axis direction
-        let size_child_gap = if element!().direction == axis_direction {
+        let size_child_gap = if element!().direction.main_axis() == Some(axis) {
             element!().child_gap * (non_floating_children.len().saturating_sub(1)) as f32
         } else {
             0.0
@@ -426,16 +738,12 @@ pub fn grow_and_shrink_along_axis<Message>(
         // Precompute inner content size from resizable non-percent children
         for child in &resizable_children {
             let c = &ui_tree.slots[*child];
-            if element!().direction == axis_direction {
-                if x_axis {
-                    inner_content_size += c.computed_width;
-                } else {
-                    inner_content_size += c.computed_height;
-                }
+            if element!().direction.main_axis() == Some(axis) {
+                inner_content_size += c.outer_size(axis);
             } else if x_axis {
-                inner_content_size = inner_content_size.max(c.computed_width + total_padding);
+                inner_content_size = inner_content_size.max(c.outer_size(Axis::X) + total_padding);
             } else {
-                inner_content_size = inner_content_size.max(c.computed_height + total_padding);
+                inner_content_size = inner_content_size.max(c.outer_size(Axis::Y) + total_padding);
             }
         }
 
@@ -459,8 +767,9 @@ pub fn grow_and_shrink_along_axis<Message>(
 
                 // For non-floating children, subtract from remaining and add to content size
                 if ui_tree.slots[*child].floating.is_none() {
-                    remaining_size -= assign;
-                    inner_content_size += assign;
+                    let margin = ui_tree.slots[*child].margin_along(axis);
+                    remaining_size -= assign + margin;
+                    inner_content_size += assign + margin;
                 }
             }
         }
@@ -472,10 +781,11 @@ pub fn grow_and_shrink_along_axis<Message>(
             element!().computed_content_height = inner_content_size;
         }
 
-        // Growable children are resizable with Grow sizing
+        // Growable children are resizable with Grow sizing, and don't follow an aspect ratio
         let mut growable_children: Vec<UIKey> = resizable_children
             .iter()
             .copied()
+            .filter(|c| !ui_tree.slots[*c].follows_aspect_ratio(axis))
             .filter(|c| {
                 matches!(
                     if x_axis {
@@ -488,98 +798,95 @@ pub fn grow_and_shrink_along_axis<Message>(
             })
             .collect();
 
-        if element!().direction == axis_direction {
-            // Handle wrapping for LeftToRight direction
-            let use_wrapping = x_axis && element!().wrap;
-
-            if use_wrapping {
+        if element!().direction.main_axis() == Some(axis) {
+            // Wrapping rows break in the X pass, wrapping columns in the Y pass
+            if element!().wraps() {
                 // Calculate breaks and store them in the element
-                let available_width = full_available_size;
-                let breaks = calculate_wrap_breaks(
-                    ui_tree,
+                let available_size = full_available_size;
+                let breaks = wrap::calculate_breaks(
+                    &ui_tree.slots,
                     &non_floating_children,
-                    available_width,
+                    axis,
+                    available_size,
                     element!().child_gap,
                 );
                 element!().wrap_breaks = breaks.clone();
 
-                // Process each row individually
-                let mut start_idx = 0;
-                for &break_idx in breaks
-                    .iter()
-                    .chain(std::iter::once(&non_floating_children.len()))
-                {
-                    let row_children: Vec<UIKey> =
-                        non_floating_children[start_idx..break_idx].to_vec();
-                    if row_children.is_empty() {
-                        start_idx = break_idx;
-                        continue;
-                    }
+                let scroll_enabled = element!()
+                    .scroll
+                    .as_ref()
+                    .map(|s| {
+                        if x_axis {
+                            s.horizontal
+                        } else {
+                            s.vertical
+                        }
+                    })
+                    .unwrap_or(false);
 
-                    // Calculate row available width
-                    let row_current_width: f32 = row_children
+                // Process each line individually
+                for line_children in wrap::lines(&non_floating_children, &breaks) {
+                    // Calculate line available size
+                    let line_current_size: f32 = line_children
                         .iter()
-                        .fold(0.0, |acc, &child| acc + ui_tree.slots[child].computed_width);
-                    let row_gaps =
-                        (row_children.len().saturating_sub(1)) as f32 * element!().child_gap;
-                    let mut row_remaining_size = available_width - row_current_width - row_gaps;
+                        .fold(0.0, |acc, &child| acc + ui_tree.slots[child].outer_size(axis));
+                    let line_gaps =
+                        (line_children.len().saturating_sub(1)) as f32 * element!().child_gap;
+                    let mut line_remaining_size = available_size - line_current_size - line_gaps;
 
-                    // Get growable children in this row
-                    let mut row_growable: Vec<UIKey> = row_children
+                    // Get growable children in this line
+                    let mut line_growable: Vec<UIKey> = line_children
                         .iter()
                         .copied()
-                        .filter(|c| matches!(ui_tree.slots[*c].width, Sizing::Grow { .. }))
+                        .filter(|c| growable_children.contains(c))
                         .collect();
 
-                    // Row grow pass
+                    // Line grow pass
                     apply_axis_grow_algorithm(
                         ui_tree,
-                        &mut row_growable,
-                        &mut row_remaining_size,
-                        true,
+                        &mut line_growable,
+                        &mut line_remaining_size,
+                        x_axis,
                     );
 
-                    // Row shrink pass (if scroll not enabled)
-                    let scroll_enabled = element!()
-                        .scroll
-                        .as_ref()
-                        .map(|s| s.horizontal)
-                        .unwrap_or(false);
-
-                    if !scroll_enabled && row_remaining_size.lt_eps(&-0.01) {
-                        let mut row_shrinkable: Vec<UIKey> = row_children
+                    // Line shrink pass (if scroll not enabled)
+                    if !scroll_enabled && line_remaining_size.lt_eps(&-0.01) {
+                        let mut line_shrinkable: Vec<UIKey> = line_children
                             .iter()
                             .copied()
                             .filter(|c| {
                                 let e = &ui_tree.slots[*c];
-                                let size = e.computed_width;
-                                let min_allowed = e.min_width;
-                                size.gt_eps(&min_allowed)
+                                if e.follows_aspect_ratio(axis) {
+                                    return false;
+                                }
+                                if x_axis {
+                                    e.computed_width.gt_eps(&e.min_width)
+                                } else {
+                                    e.computed_height.gt_eps(&e.min_height)
+                                }
                             })
                             .collect();
 
-                        // Row shrink pass
+                        // Line shrink pass
                         apply_axis_shrink_algorithm(
                             ui_tree,
-                            &mut row_shrinkable,
-                            &mut row_remaining_size,
-                            true,
+                            &mut line_shrinkable,
+                            &mut line_remaining_size,
+                            x_axis,
                         );
                     }
+                }
 
-                    start_idx = break_idx;
+                // Widths were settled without knowing the columns, grow them to their column now
+                if !x_axis {
+                    wrap::grow_lines(&mut ui_tree.slots, key, Axis::X);
                 }
             } else {
                 // Non-wrapping behavior
                 // Subtract current sizes of resizable children
-                remaining_size -= resizable_children.iter().fold(0.0_f32, |acc, ckey| {
-                    let c = &ui_tree.slots[*ckey];
-                    if x_axis {
-                        acc + c.computed_width
-                    } else {
-                        acc + c.computed_height
-                    }
-                });
+                remaining_size -= resizable_children
+                    .iter()
+                    .fold(0.0_f32, |acc, ckey| acc + ui_tree.slots[*ckey].outer_size(axis));
 
                 // Grow pass
                 apply_axis_grow_algorithm(
@@ -609,6 +916,7 @@ pub fn grow_and_shrink_along_axis<Message>(
                 let mut shrinkable_children: Vec<UIKey> = resizable_children
                     .iter()
                     .copied()
+                    .filter(|c| !ui_tree.slots[*c].follows_aspect_ratio(axis))
                     .filter(|c| {
                         let e = &ui_tree.slots[*c];
                         let size = if x_axis {
@@ -630,58 +938,16 @@ pub fn grow_and_shrink_along_axis<Message>(
             }
         } else {
             // Cross-axis behavior
-            // Check if we need to handle wrapping breaks for off-axis
-            let has_breaks = !x_axis
-                && element!().direction == Direction::LeftToRight
-                && element!().wrap
-                && !element!().wrap_breaks.is_empty();
-
-            if has_breaks {
-                // For wrapping LeftToRight when calculating Y-axis (height),
-                // we need to process each row for off-axis grow/shrink
-                let breaks = element!().wrap_breaks.clone();
-                let mut start_idx = 0;
-
-                for &break_idx in breaks
-                    .iter()
-                    .chain(std::iter::once(&non_floating_children.len()))
-                {
-                    let row_children: Vec<UIKey> =
-                        non_floating_children[start_idx..break_idx].to_vec();
-                    if row_children.is_empty() {
-                        start_idx = break_idx;
-                        continue;
-                    }
-
-                    // Calculate the maximum height needed for this row
-                    let max_row_height = row_children.iter().fold(0.0_f32, |acc, &child| {
-                        acc.max(ui_tree.slots[child].computed_height)
-                    });
-
-                    // Get growable children in this row for Y-axis
-                    let row_growable: Vec<UIKey> = row_children
-                        .iter()
-                        .copied()
-                        .filter(|c| matches!(ui_tree.slots[*c].height, Sizing::Grow { .. }))
-                        .collect();
-
-                    // Grow all growable children in this row to the max height
-                    for ck in &row_growable {
-                        let current_height = ui_tree.slots[*ck].computed_height;
-                        if current_height.lt_eps(&max_row_height) {
-                            let max_allowed = match ui_tree.slots[*ck].height {
-                                Sizing::Grow { max, .. } => max,
-                                _ => f32::INFINITY,
-                            };
-                            let new_height = max_row_height.min(max_allowed);
-                            ui_tree.slots[*ck].computed_height = new_height;
-                        }
-                    }
-
-                    start_idx = break_idx;
-                }
+            // Rows were broken in the X pass, grow children to the height of their row
+            if !x_axis && element!().wraps() {
+                wrap::grow_lines(&mut ui_tree.slots, key, Axis::Y);
             } else {
-                // Original cross-axis behavior for non-wrapping layouts
+                // Original cross-axis behavior for non-wrapping layouts. Wrapping columns don't know
+                // their columns yet in the X pass, their children only shrink to fit until then
+                if element!().wraps() {
+                    growable_children.clear();
+                }
+
                 let mut grow_to_size = non_wrap_available_size;
                 let scroll_enabled = element!()
                     .scroll
@@ -705,6 +971,7 @@ pub fn grow_and_shrink_along_axis<Message>(
                     } else {
                         ui_tree.slots[*ck].computed_height
                     };
+                    let grow_to_size = grow_to_size - ui_tree.slots[*ck].margin_along(axis);
                     if size.lt_eps(&grow_to_size) {
                         // cap by max
                         let max_allowed = match if x_axis {
@@ -733,6 +1000,7 @@ pub fn grow_and_shrink_along_axis<Message>(
                 let shrinkable_children: Vec<UIKey> = resizable_children
                     .iter()
                     .copied()
+                    .filter(|c| !ui_tree.slots[*c].follows_aspect_ratio(axis))
                     .filter(|c| {
                         let e = &ui_tree.slots[*c];
                         let size = if x_axis {
@@ -741,7 +1009,8 @@ pub fn grow_and_shrink_along_axis<Message>(
                             e.computed_height
                         };
                         let min_allowed = if x_axis { e.min_width } else { e.min_height };
-                        size.gt_eps(&min_allowed) && size.gt_eps(&non_wrap_available_size)
+                        size.gt_eps(&min_allowed)
+                            && size.gt_eps(&(non_wrap_available_size - e.margin_along(axis)))
                     })
                     .collect();
 
@@ -751,13 +1020,14 @@ pub fn grow_and_shrink_along_axis<Message>(
                     } else {
                         ui_tree.slots[ck].computed_height
                     };
-                    if size.gt_eps(&non_wrap_available_size) {
+                    let available_size = non_wrap_available_size - ui_tree.slots[ck].margin_along(axis);
+                    if size.gt_eps(&available_size) {
                         let min_allowed = if x_axis {
                             ui_tree.slots[ck].min_width
                         } else {
                             ui_tree.slots[ck].min_height
                         };
-                        let new_size = non_wrap_available_size.max(min_allowed);
+                        let new_size = available_size.max(min_allowed);
                         if x_axis {
                             ui_tree.slots[ck].computed_width = new_size;
                         } else {
@@ -769,3 +1039,223 @@ pub fn grow_and_shrink_along_axis<Message>(
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::layout::OwnedUITree;
+    use crate::layout::model::{Direction, Element, Sizing};
+    use crate::layout::testing::{bounds, lay_out};
+
+    fn column(children: Vec<Element<()>>) -> Element<()> {
+        Element {
+            direction: Direction::TopToBottom,
+            width: Sizing::fixed(300.0),
+            children,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn aspect_ratio_derives_the_other_axis() {
+        let mut ui_tree = OwnedUITree::default();
+        lay_out(
+            &mut ui_tree,
+            column(vec![
+                Element {
+                    id: Some(20),
+                    width: Sizing::grow(),
+                    ..Default::default()
+                }
+                .with_aspect_ratio(2.0),
+                Element {
+                    id: Some(21),
+                    height: Sizing::fixed(40.0),
+                    ..Default::default()
+                }
+                .with_aspect_ratio(1.5),
+            ]),
+            320.0,
+            240.0,
+        );
+
+        assert_eq!(bounds(&ui_tree, 20), (0.0, 0.0, 300.0, 150.0));
+        assert_eq!(bounds(&ui_tree, 21), (0.0, 150.0, 60.0, 40.0));
+    }
+
+    #[test]
+    fn widths_that_follow_the_height_size_children_and_siblings() {
+        let mut ui_tree = OwnedUITree::<()>::default();
+        lay_out(
+            &mut ui_tree,
+            Element {
+                width: Sizing::fixed(300.0),
+                height: Sizing::fixed(100.0),
+                children: vec![
+                    Element {
+                        id: Some(22),
+                        height: Sizing::grow(),
+                        children: vec![Element {
+                            id: Some(23),
+                            width: Sizing::grow(),
+                            height: Sizing::fixed(10.0),
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    }
+                    .with_aspect_ratio(2.0),
+                    Element {
+                        id: Some(24),
+                        width: Sizing::grow(),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+            320.0,
+            240.0,
+        );
+
+        assert_eq!(bounds(&ui_tree, 22), (0.0, 0.0, 200.0, 100.0));
+        assert_eq!(bounds(&ui_tree, 23).2, 200.0);
+        assert_eq!(bounds(&ui_tree, 24), (200.0, 0.0, 100.0, 0.0));
+    }
+
+    #[test]
+    fn heights_that_follow_the_width_leave_the_rest_to_siblings() {
+        let mut ui_tree = OwnedUITree::default();
+        lay_out(
+            &mut ui_tree,
+            Element {
+                direction: Direction::TopToBottom,
+                width: Sizing::fixed(300.0),
+                height: Sizing::fixed(400.0),
+                children: vec![Element {
+                    id: Some(25),
+                    height: Sizing::grow(),
+                    ..column(vec![
+                        Element {
+                            id: Some(26),
+                            width: Sizing::grow(),
+                            height: Sizing::fixed(50.0),
+                            ..Default::default()
+                        },
+                        Element {
+                            id: Some(27),
+                            width: Sizing::grow(),
+                            height: Sizing::grow(),
+                            ..Default::default()
+                        }
+                        .with_aspect_ratio(2.0),
+                        Element {
+                            id: Some(28),
+                            width: Sizing::grow(),
+                            height: Sizing::grow(),
+                            ..Default::default()
+                        },
+                    ])
+                }],
+                ..Default::default()
+            },
+            320.0,
+            480.0,
+        );
+
+        assert_eq!(bounds(&ui_tree, 25), (0.0, 0.0, 300.0, 400.0));
+        assert_eq!(bounds(&ui_tree, 27), (0.0, 50.0, 300.0, 150.0));
+        assert_eq!(bounds(&ui_tree, 28), (0.0, 200.0, 300.0, 200.0));
+    }
+
+    #[test]
+    fn grow_weights_share_free_space() {
+        let pane = |id: u64, width: Sizing| Element {
+            id: Some(id),
+            width,
+            height: Sizing::fixed(10.0),
+            ..Default::default()
+        };
+        let row = |children| Element {
+            width: Sizing::grow(),
+            children,
+            ..Default::default()
+        };
+
+        let mut ui_tree = OwnedUITree::default();
+        lay_out(
+            &mut ui_tree,
+            column(vec![
+                row(vec![
+                    pane(30, Sizing::grow_weighted(2.0)),
+                    pane(31, Sizing::grow()),
+                ]),
+                row(vec![
+                    pane(32, Sizing::grow_weighted(2.0).max(150.0)),
+                    pane(33, Sizing::grow()),
+                ]),
+                row(vec![
+                    pane(34, Sizing::grow_weighted(3.0).min(100.0)),
+                    pane(35, Sizing::grow_weighted(3.0)),
+                ]),
+                row(vec![
+                    pane(36, Sizing::grow_weighted(0.0).min(50.0)),
+                    pane(37, Sizing::grow()),
+                ]),
+            ]),
+            320.0,
+            240.0,
+        );
+        let width = |id| bounds(&ui_tree, id).2;
+
+        assert_eq!((width(30), width(31)), (200.0, 100.0));
+        // The heavier pane stops at its max and the other takes the rest
+        assert_eq!((width(32), width(33)), (150.0, 150.0));
+        // Panes of the same weight even out, like panes without one
+        assert_eq!((width(34), width(35)), (150.0, 150.0));
+        // A pane of weight 0 keeps its size
+        assert_eq!((width(36), width(37)), (50.0, 250.0));
+    }
+
+    #[test]
+    fn grow_weights_split_free_space_on_top_of_content() {
+        // Panes of 20 wide tiles prefer a single line, but can wrap down to one tile
+        let pane = |id: u64, tiles: usize, weight: f32| {
+            Element {
+                id: Some(id),
+                width: Sizing::grow_weighted(weight),
+                children: (0..tiles)
+                    .map(|_| Element {
+                        width: Sizing::fixed(20.0),
+                        height: Sizing::fixed(10.0),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            }
+            .with_wrap(true)
+        };
+        let row = |width: f32, children| Element {
+            width: Sizing::fixed(width),
+            children,
+            ..Default::default()
+        };
+
+        let mut ui_tree = OwnedUITree::default();
+        lay_out(
+            &mut ui_tree,
+            column(vec![
+                row(300.0, vec![pane(34, 2, 2.0), pane(35, 4, 1.0)]),
+                row(90.0, vec![pane(36, 2, 2.0), pane(37, 4, 1.0)]),
+                row(90.0, vec![pane(38, 2, 0.0), pane(39, 4, 1.0)]),
+            ]),
+            320.0,
+            240.0,
+        );
+        let width = |id| bounds(&ui_tree, id).2;
+
+        // The 180 left over is split 2:1 on top of each pane's content
+        assert_eq!((width(34), width(35)), (160.0, 140.0));
+        // The 30 missing is taken 1:1, as the lighter pane is twice as wide
+        assert_eq!((width(36), width(37)), (25.0, 65.0));
+        // A pane of weight 0 doesn't shrink either, the other gives up all 30
+        assert_eq!((width(38), width(39)), (40.0, 50.0));
+    }
+}
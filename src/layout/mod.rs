@@ -1,32 +1,42 @@
-use std::{cell::RefCell, collections::HashMap, time::Instant};
+use std::{cell::RefCell, collections::HashMap};
 
 use slotmap::SlotMap;
 use string_interner::{StringInterner, backend::StringBackend};
 
 use crate::{
     HookState, Shell,
-    gfx::{RectDIP, command_recorder::CommandRecorder, draw_commands::DrawCommandList},
+    gfx::{
+        Matrix2D, PointDIP, RectDIP, command_recorder::CommandRecorder,
+        draw_commands::DrawCommandList,
+    },
     layout::{
         model::{Axis, ElementStyle, UIElement, UIKey},
         positioning::position_elements,
-        visitors::VisitFrame,
     },
     runtime::scroll::{ScrollStateManager, ScrollbarGeom, compute_scrollbar_geom},
     widgets::{Instance, PaintOwnership},
 };
 
+pub mod dump;
 pub mod helpers;
 pub mod model;
 
 mod float;
 pub mod visitors;
 
+mod baseline;
+mod cache;
 mod fit_along_axis;
+mod grid;
 mod grow_and_shrink_along_axis;
 mod positioning;
+#[cfg(test)]
+mod testing;
+mod wrap;
 
+pub use cache::LayoutCache;
 use fit_along_axis::fit_along_axis;
-use grow_and_shrink_along_axis::grow_and_shrink_along_axis;
+use grow_and_shrink_along_axis::{grow_and_shrink_along_axis, match_anchor_widths};
 
 pub struct UIArenas {
     pub strings: StringInterner<StringBackend>,
@@ -38,6 +48,7 @@ pub struct OwnedUITree<Message> {
     pub widget_state: HashMap<u64, Instance>,
     pub hook_state: HashMap<u64, HookState>,
     pub arenas: UIArenas,
+    pub layout_cache: LayoutCache,
 }
 pub type BorrowedUITree<'a, Message> = &'a mut OwnedUITree<Message>;
 
@@ -51,13 +62,13 @@ impl<Message> Default for OwnedUITree<Message> {
             arenas: UIArenas {
                 strings: StringInterner::new(),
             },
+            layout_cache: LayoutCache::default(),
         }
     }
 }
 
-#[allow(dead_code)]
 fn propagate_inherited_properties<Message>(ui_tree: BorrowedUITree<'_, Message>, root: UIKey) {
-    visitors::visit_bfs(ui_tree, root, |ui_tree, key, _parent| {
+    visitors::visit_bfs(ui_tree, root, |ui_tree, key, parent| {
         // if let Some(parent_key) = parent {
         //     if ui_tree.slots[key].color.is_none() && ui_tree.slots[parent_key].color.is_some() {
         //         ui_tree.slots[key].color = ui_tree.slots[parent_key].color;
@@ -68,10 +79,63 @@ fn propagate_inherited_properties<Message>(ui_tree: BorrowedUITree<'_, Message>,
             ui_tree.slots[root].id_map.insert(id, key);
         }
 
+        if let Some(parent_key) = parent
+            && ui_tree.slots[key].layout_direction.is_none()
+        {
+            ui_tree.slots[key].layout_direction = ui_tree.slots[parent_key].layout_direction;
+        }
+
+        if let Some(parent_key) = parent {
+            ui_tree.slots[key].inert |= ui_tree.slots[parent_key].inert;
+        }
+
         // TODO: propagate Font
     });
 }
 
+fn size_elements<Message>(ui_tree: BorrowedUITree<'_, Message>, root: UIKey) {
+    fit_along_axis(ui_tree, root, Axis::X);
+    grow_and_shrink_along_axis(ui_tree, root, Axis::X);
+    match_anchor_widths(ui_tree, root);
+
+    fit_along_axis(ui_tree, root, Axis::Y);
+    grow_and_shrink_along_axis(ui_tree, root, Axis::Y);
+}
+
+/// Sizes the subtree of an element again in the passes that ran before growing along `axis`,
+/// after its parent gave it another size. Fitting the element itself would undo that size, so it
+/// keeps it
+pub(crate) fn resize_subtree<Message>(
+    ui_tree: BorrowedUITree<'_, Message>,
+    key: UIKey,
+    axis: Axis,
+) {
+    let element = &ui_tree.slots[key];
+    let sizes = (
+        element.computed_width,
+        element.min_width,
+        element.computed_height,
+        element.min_height,
+    );
+    let keep_sizes = |ui_tree: BorrowedUITree<'_, Message>| {
+        let element = &mut ui_tree.slots[key];
+        (
+            element.computed_width,
+            element.min_width,
+            element.computed_height,
+            element.min_height,
+        ) = sizes;
+    };
+
+    fit_along_axis(ui_tree, key, Axis::X);
+    keep_sizes(ui_tree);
+    if axis == Axis::Y {
+        grow_and_shrink_along_axis(ui_tree, key, Axis::X);
+        fit_along_axis(ui_tree, key, Axis::Y);
+        keep_sizes(ui_tree);
+    }
+}
+
 pub fn layout<Message>(
     ui_tree: BorrowedUITree<'_, Message>,
     root: UIKey,
@@ -79,14 +143,41 @@ pub fn layout<Message>(
     dip_scale: f32,
 ) {
     propagate_inherited_properties(ui_tree, root);
+    cache::prune(ui_tree, root, dip_scale);
 
-    fit_along_axis(ui_tree, root, Axis::X);
-    grow_and_shrink_along_axis(ui_tree, root, Axis::X);
+    size_elements(ui_tree, root);
+    position_elements(ui_tree, root, scroll_state_manager, dip_scale);
+    cache::graft(ui_tree);
+}
 
-    fit_along_axis(ui_tree, root, Axis::Y);
-    grow_and_shrink_along_axis(ui_tree, root, Axis::Y);
+/// Transform from the coordinates an element is laid out in to the screen, combining the
+/// transforms of the element and its ancestors. `None` when none of them is transformed
+pub(crate) fn screen_transform<Message>(
+    slots: &SlotMap<UIKey, UIElement<Message>>,
+    key: UIKey,
+) -> Option<Matrix2D> {
+    let mut transform: Option<Matrix2D> = None;
+    let mut current_key = Some(key);
+    while let Some(key) = current_key {
+        if let Some(matrix) = slots[key].transform_matrix() {
+            transform = Some(transform.map_or(matrix, |transform| transform.then(&matrix)));
+        }
+        current_key = slots[key].parent;
+    }
+    transform
+}
 
-    position_elements(ui_tree, root, scroll_state_manager, dip_scale);
+/// Maps a point on the screen into the coordinates an element is laid out in. `None` when the
+/// transforms of the element collapse it, so no point lands on it
+pub(crate) fn point_to_local<Message>(
+    slots: &SlotMap<UIKey, UIElement<Message>>,
+    key: UIKey,
+    point: PointDIP,
+) -> Option<PointDIP> {
+    match screen_transform(slots, key) {
+        Some(transform) => Some(transform.invert()?.transform_point(point)),
+        None => Some(point),
+    }
 }
 
 pub fn paint<Message>(
@@ -104,7 +195,7 @@ pub fn generate_paint_commands<Message>(
     root: UIKey,
 ) -> DrawCommandList {
     let recorder = CommandRecorder::new();
-    let now = Instant::now();
+    let now = shell.now();
 
     // TODO: Modify visitor to allow handing this around
     // rather than unnecessarily creating a RefCell<>
@@ -112,18 +203,18 @@ pub fn generate_paint_commands<Message>(
     let shell = RefCell::new(shell);
 
     {
-        // Track current z-index for deferred rendering
-        let current_z_index = RefCell::new(0i32);
-
-        visitors::visit_deferring_dfs(
+        // Elements that pushed a transform, innermost last. Deferred elements are painted after
+        // their ancestors popped theirs, so they push the transforms of their ancestors as well
+        let transformed = RefCell::new(Vec::new());
+        let has_transforms = ui_tree
+            .slots
+            .values()
+            .any(|element| element.transform.is_some());
+
+        // Elements with a higher z-index are painted once their ancestors have popped their clips
+        visitors::visit_paint_order_dfs(
             ui_tree,
             root,
-            |ui_tree, key, _parent| {
-                // Defer if this element's z-index is greater than current z-index
-                let element_z_index = ui_tree.slots[key].z_index.unwrap_or(0);
-                let current = *current_z_index.borrow();
-                element_z_index > current
-            },
             |ui_tree, key, parent| {
                 let mut recorder = recorder.borrow_mut();
 
@@ -133,16 +224,25 @@ pub fn generate_paint_commands<Message>(
                     None
                 };
 
+                if has_transforms {
+                    let mut transformed = transformed.borrow_mut();
+                    let transform = if transformed.is_empty() {
+                        screen_transform(&ui_tree.slots, key)
+                    } else {
+                        ui_tree.slots[key].transform_matrix()
+                    };
+                    if let Some(transform) = transform {
+                        recorder.push_transform(&transform);
+                        transformed.push(key);
+                    }
+                }
+
                 let element = &mut ui_tree.slots[key];
                 let x = element.x;
                 let y = element.y;
                 let width = element.computed_width;
                 let height = element.computed_height;
 
-                let no_overflow = element.scroll.as_ref().map(|s| s.overflow == false).unwrap_or(false);
-                let has_scroll_x = element.scroll.as_ref().map(|s| s.horizontal).unwrap_or(false);
-                let has_scroll_y = element.scroll.as_ref().map(|s| s.vertical).unwrap_or(false);
-
                 let bounds = element.bounds();
                 let mut style = ElementStyle::from(&*element);
                 if let Some(color) = inherited_color {
@@ -248,15 +348,8 @@ pub fn generate_paint_commands<Message>(
                     }
                 }
 
-                if has_scroll_x || has_scroll_y || no_overflow {
-                    let clip_rect = RectDIP {
-                        x,
-                        y,
-                        width,
-                        height,
-                    };
-
-                    if let Some(border_radius) = &element.border_radius {
+                if let Some((clip_rect, border_radius)) = element.child_clip() {
+                    if let Some(border_radius) = &border_radius {
                         // Use layer with rounded rectangle geometry for clipping
                         recorder.push_rounded_clip(&clip_rect, border_radius);
                     } else {
@@ -283,7 +376,7 @@ pub fn generate_paint_commands<Message>(
                     let mut recorder = recorder.borrow_mut();
                     let element = &slots[key];
 
-                    let no_overflow = element.scroll.as_ref().map(|s| s.overflow == false).unwrap_or(false);
+                    let no_overflow = element.scroll.as_ref().map(|s| !s.overflow).unwrap_or(false);
                     let has_scroll_x = element.scroll.as_ref().map(|s| s.horizontal).unwrap_or(false);
                     let has_scroll_y = element.scroll.as_ref().map(|s| s.vertical).unwrap_or(false);
 
@@ -355,7 +448,10 @@ pub fn generate_paint_commands<Message>(
                             }
                         }
 
-                        if element.border_radius.is_some() {
+                    }
+
+                    if let Some((_, border_radius)) = element.child_clip() {
+                        if border_radius.is_some() {
                             // Use layer with rounded rectangle geometry for clipping
                             recorder.pop_rounded_clip();
                         } else {
@@ -369,20 +465,11 @@ pub fn generate_paint_commands<Message>(
                     {
                         recorder.pop_layer();
                     }
-                },
-            ),
-            Some(
-                |ui_tree: BorrowedUITree<'_, Message>, deferred_frames: &[VisitFrame]| {
-                    // After each pass, find the next lowest z-index and update current_z_index
-                    if !deferred_frames.is_empty() {
-                        let mut next_z_index = i32::MAX;
-                        for frame in deferred_frames {
-                            let element_z_index = ui_tree.slots[frame.element].z_index.unwrap_or(0);
-                            if element_z_index < next_z_index {
-                                next_z_index = element_z_index;
-                            }
-                        }
-                        *current_z_index.borrow_mut() = next_z_index;
+
+                    let mut transformed = transformed.borrow_mut();
+                    if transformed.last() == Some(&key) {
+                        transformed.pop();
+                        recorder.pop_transform();
                     }
                 },
             ),
@@ -3,10 +3,15 @@
 
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
+    HookManager, ViewFn,
+    gfx::{Matrix2D, RectDIP},
     impl_numeric,
-    layout::OwnedUITree,
+    layout::{OwnedUITree, cache::Measured},
     runtime::DeviceResources,
+    w_id,
     widgets::{Instance, Widget},
 };
 
@@ -112,7 +117,42 @@ pub enum Direction {
     #[default]
     LeftToRight,
     TopToBottom,
+    /// A row starting from the right, `Alignment::Start` is the right edge
+    RightToLeft,
+    /// A column starting from the bottom, `Alignment::Start` is the bottom edge
+    BottomToTop,
     ZStack,
+    /// Children are placed into the cells of a [`GridConfig`]. Within its cells a
+    /// child is aligned horizontally by `axis_align_self`/`axis_align_items` and
+    /// vertically by `cross_align_self`/`cross_align_items`.
+    Grid,
+}
+
+impl Direction {
+    /// Axis children are stacked along, `None` for `ZStack` and `Grid`
+    pub fn main_axis(self) -> Option<Axis> {
+        match self {
+            Direction::LeftToRight | Direction::RightToLeft => Some(Axis::X),
+            Direction::TopToBottom | Direction::BottomToTop => Some(Axis::Y),
+            Direction::ZStack | Direction::Grid => None,
+        }
+    }
+
+    /// Whether children are stacked from the right or the bottom
+    pub fn is_reversed(self) -> bool {
+        matches!(self, Direction::RightToLeft | Direction::BottomToTop)
+    }
+}
+
+/// Reading direction of a subtree. In `RightToLeft` rows run from the right,
+/// horizontal `Alignment::Start`/`End`, left/right padding and margins, grid
+/// columns and floating anchors are mirrored, and vertical scrollbars sit on
+/// the left.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LayoutDirection {
+    #[default]
+    LeftToRight,
+    RightToLeft,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
@@ -121,18 +161,59 @@ pub enum Alignment {
     Start,
     Center,
     End,
+    /// Aligns the first baselines of the children of a row, see [`Widget::baseline`]. Same as
+    /// `Start` anywhere else.
+    ///
+    /// [`Widget::baseline`]: crate::widgets::Widget::baseline
+    Baseline,
+}
+
+/// How wrapped lines are distributed along the cross axis
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ContentAlignment {
+    #[default]
+    Start,
+    Center,
+    End,
+    /// First and last line at the edges, free space evenly between lines
+    SpaceBetween,
+    /// Free space is shared evenly between lines, which `Grow` children then fill
+    Stretch,
+}
+
+impl From<Alignment> for ContentAlignment {
+    fn from(value: Alignment) -> Self {
+        match value {
+            Alignment::Start | Alignment::Baseline => ContentAlignment::Start,
+            Alignment::Center => ContentAlignment::Center,
+            Alignment::End => ContentAlignment::End,
+        }
+    }
 }
 
 // ---------- Sizing ----------
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Sizing {
     /// Fixed pixel size. Equivalent to min=max=px in TS "Fixed".
     Fixed { px: f32 },
-    /// Grow between [min, max].
-    Grow { min: f32, max: f32 },
+    /// Grow between [min, max]. Growing siblings of the same weight even out their sizes. When
+    /// their weights differ, free space is split by weight on top of their content size like CSS
+    /// `flex-grow`, and missing space is taken back by weight times size like `flex-shrink`.
+    /// A weight of 0 keeps the content size, it neither grows nor shrinks next to siblings.
+    Grow {
+        min: f32,
+        #[serde(with = "crate::layout::dump::unbounded")]
+        max: f32,
+        #[serde(default = "default_grow_weight")]
+        weight: f32,
+    },
     /// Fit content between [min, max].
-    Fit { min: f32, max: f32 },
+    Fit {
+        min: f32,
+        #[serde(with = "crate::layout::dump::unbounded")]
+        max: f32,
+    },
     /// Percentage of parent size (0..=1 or 0..=100 based on convention).
     Percent { percent: f32 },
 }
@@ -159,7 +240,7 @@ impl Sizing {
     pub fn min(self, min: f32) -> Self {
         match self {
             Sizing::Fixed { px } => Sizing::Fixed { px },
-            Sizing::Grow { max, .. } => Sizing::Grow { min, max },
+            Sizing::Grow { max, weight, .. } => Sizing::Grow { min, max, weight },
             Sizing::Fit { max, .. } => Sizing::Fit { min, max },
             Sizing::Percent { .. } => Sizing::Percent { percent: 1.0 },
         }
@@ -168,7 +249,7 @@ impl Sizing {
     pub fn max(self, max: f32) -> Self {
         match self {
             Sizing::Fixed { px } => Sizing::Fixed { px },
-            Sizing::Grow { min, .. } => Sizing::Grow { min, max },
+            Sizing::Grow { min, weight, .. } => Sizing::Grow { min, max, weight },
             Sizing::Fit { min, .. } => Sizing::Fit { min, max },
             Sizing::Percent { .. } => Sizing::Percent { percent: 1.0 },
         }
@@ -185,6 +266,20 @@ impl Sizing {
         Sizing::Grow {
             min: 0.0,
             max: f32::INFINITY,
+            weight: 1.0,
+        }
+    }
+
+    /// Grows with the given weight, e.g. `2.0` next to a `1.0` sibling takes two thirds of the space
+    pub fn grow_weighted(weight: f32) -> Self {
+        Sizing::grow().weight(weight)
+    }
+
+    /// Sets the weight of a `Grow` sizing, other sizings are returned unchanged
+    pub fn weight(self, weight: f32) -> Self {
+        match self {
+            Sizing::Grow { min, max, .. } => Sizing::Grow { min, max, weight },
+            sizing => sizing,
         }
     }
 
@@ -197,6 +292,10 @@ impl Sizing {
     }
 }
 
+fn default_grow_weight() -> f32 {
+    1.0
+}
+
 impl Default for Sizing {
     fn default() -> Self {
         Sizing::fit()
@@ -217,6 +316,12 @@ pub struct Offset2D {
     pub y: Option<f32>,
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Size2D {
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+}
+
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub struct Alignment2D<A, B> {
     pub x: Option<A>,
@@ -231,6 +336,134 @@ pub struct FloatingConfig {
     pub anchor_id: Option<u64>,
     pub anchor: Option<Alignment2D<Alignment, Alignment>>,
     pub align: Option<Alignment2D<Alignment, Alignment>>,
+
+    /// Flips `anchor`, `align` and `offset` to the opposite side of the anchor along an axis
+    /// when the element overflows the viewport and overflows less on the other side
+    pub flip: bool,
+    /// Moves the element back inside the viewport when it still overflows
+    pub shift: bool,
+    /// Gives the element the width of its anchor
+    pub match_anchor_width: bool,
+    /// Shrinks the element to the space left in the viewport, but not below this size, so
+    /// scrolling content stays reachable
+    pub min_size: Option<Size2D>,
+    /// Largest size of the element
+    pub max_size: Option<Size2D>,
+}
+
+// ---------- Sticky ----------
+
+/// Keeps an element at an edge of the viewport of its nearest scroll container while its parent
+/// is scrolled past, until the end of the parent or the next sticky sibling pushes it off.
+/// Sticky elements paint and hit test above their other siblings
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StickyConfig {
+    /// Distance kept from the top edge of the viewport, doesn't stick vertically when None
+    pub top: Option<f32>,
+    /// Distance kept from the left edge of the viewport, doesn't stick horizontally when None
+    pub left: Option<f32>,
+}
+
+impl StickyConfig {
+    pub(crate) fn inset(&self, axis: Axis) -> Option<f32> {
+        match axis {
+            Axis::X => self.left,
+            Axis::Y => self.top,
+        }
+    }
+}
+
+// ---------- Transform ----------
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransformOperation {
+    Translate {
+        x: f32,
+        y: f32,
+    },
+    Scale {
+        x: f32,
+        y: f32,
+    },
+    /// Clockwise rotation in degrees
+    Rotate(f32),
+    /// Skew in degrees along each axis
+    Skew {
+        x: f32,
+        y: f32,
+    },
+}
+
+/// Moves, scales, rotates or skews how an element and its subtree are painted and hit tested,
+/// without affecting layout, like CSS `transform`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transform {
+    /// Applied like CSS transform functions, the last one to the element first
+    pub operations: Vec<TransformOperation>,
+    /// Point the operations are applied around, as a fraction of the border box
+    pub origin: (f32, f32),
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            operations: Vec::new(),
+            origin: (0.5, 0.5),
+        }
+    }
+}
+
+impl Transform {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn translate(mut self, x: f32, y: f32) -> Self {
+        self.operations.push(TransformOperation::Translate { x, y });
+        self
+    }
+
+    pub fn scale(mut self, x: f32, y: f32) -> Self {
+        self.operations.push(TransformOperation::Scale { x, y });
+        self
+    }
+
+    pub fn rotate(mut self, degrees: f32) -> Self {
+        self.operations.push(TransformOperation::Rotate(degrees));
+        self
+    }
+
+    pub fn skew(mut self, x_degrees: f32, y_degrees: f32) -> Self {
+        self.operations.push(TransformOperation::Skew {
+            x: x_degrees,
+            y: y_degrees,
+        });
+        self
+    }
+
+    pub fn origin(self, x: f32, y: f32) -> Self {
+        Self {
+            origin: (x, y),
+            ..self
+        }
+    }
+
+    /// The transform as a matrix, for an element with the border box `rect`
+    pub fn matrix(&self, rect: RectDIP) -> Matrix2D {
+        let origin_x = rect.x + rect.width * self.origin.0;
+        let origin_y = rect.y + rect.height * self.origin.1;
+        let mut matrix = Matrix2D::translation(-origin_x, -origin_y);
+        for operation in self.operations.iter().rev() {
+            let step = match *operation {
+                TransformOperation::Translate { x, y } => Matrix2D::translation(x, y),
+                TransformOperation::Scale { x, y } => Matrix2D::scale(x, y),
+                TransformOperation::Rotate(degrees) => Matrix2D::rotation(degrees),
+                TransformOperation::Skew { x, y } => Matrix2D::skew(x, y),
+            };
+            matrix = matrix.then(&step);
+        }
+        matrix.then(&Matrix2D::translation(origin_x, origin_y))
+    }
 }
 
 // ---------- Border Radius ----------
@@ -259,6 +492,16 @@ impl BorderRadius {
             && self.bottom_left <= other.bottom_left
     }
 
+    /// Radii of the edge `width` inside a rounded rectangle with these radii
+    pub fn inner(&self, width: f32) -> Self {
+        Self {
+            top_left: (self.top_left - width).max(0.0),
+            top_right: (self.top_right - width).max(0.0),
+            bottom_right: (self.bottom_right - width).max(0.0),
+            bottom_left: (self.bottom_left - width).max(0.0),
+        }
+    }
+
     pub const fn all(radius: f32) -> Self {
         Self {
             top_left: radius,
@@ -502,6 +745,7 @@ pub enum BackdropFilter {
     Blur { radius: f32 },
 
     /// Apply any custom pixel shader effect
+    #[cfg(windows)]
     Custom(std::sync::Arc<dyn crate::gfx::effects::DynPixelShaderEffect>),
 }
 
@@ -509,6 +753,7 @@ impl std::fmt::Debug for BackdropFilter {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Blur { radius } => f.debug_struct("Blur").field("radius", radius).finish(),
+            #[cfg(windows)]
             Self::Custom(effect) => f
                 .debug_struct("Custom")
                 .field("clsid", &effect.clsid())
@@ -521,7 +766,9 @@ impl PartialEq for BackdropFilter {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::Blur { radius: a }, Self::Blur { radius: b }) => a == b,
+            #[cfg(windows)]
             (Self::Custom(a), Self::Custom(b)) => std::sync::Arc::ptr_eq(a, b),
+            #[cfg(windows)]
             _ => false,
         }
     }
@@ -539,6 +786,7 @@ impl BackdropFilter {
     /// ```ignore
     /// BackdropFilter::custom(MyEffect { intensity: 0.5 })
     /// ```
+    #[cfg(windows)]
     pub fn custom(effect: impl crate::gfx::effects::PixelShaderEffect) -> Self {
         Self::Custom(std::sync::Arc::new(effect))
     }
@@ -648,6 +896,18 @@ impl Border {
     }
 }
 
+// ---------- Overflow ----------
+
+/// How the children of an element are shown where they overflow it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Overflow {
+    #[default]
+    Visible,
+    /// Clips painting and hit testing to the padding box, inside the border and following
+    /// `border_radius`, without scrolling
+    Clip,
+}
+
 // ---------- Scrolling ----------
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -748,6 +1008,144 @@ pub struct ScrollConfig {
     pub scrollbar_style: Option<ScrollbarStyle>,
 }
 
+// ---------- Grid ----------
+
+/// Size of a grid column or row
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GridTrack {
+    /// Fixed pixel size
+    Fixed(f32),
+    /// As large as the largest cell in the track
+    Fit,
+    /// Share of the space left over by the other tracks, in proportion to its
+    /// fraction. Never smaller than the minimum size of its cells.
+    Fraction(f32),
+    /// Fits its cells between [min, max], then grows towards max when there is
+    /// space left over
+    MinMax { min: f32, max: f32 },
+}
+
+impl GridTrack {
+    pub fn fixed(px: f32) -> Self {
+        GridTrack::Fixed(px)
+    }
+
+    pub fn fit() -> Self {
+        GridTrack::Fit
+    }
+
+    pub fn grow() -> Self {
+        GridTrack::Fraction(1.0)
+    }
+
+    pub fn fraction(fraction: f32) -> Self {
+        GridTrack::Fraction(fraction)
+    }
+
+    pub fn minmax(min: f32, max: f32) -> Self {
+        GridTrack::MinMax { min, max }
+    }
+}
+
+/// Tracks and gaps of a [`Direction::Grid`] container.
+///
+/// Children placed outside of the defined tracks get implicit [`GridTrack::Fit`] tracks.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GridConfig {
+    pub columns: Vec<GridTrack>,
+    pub rows: Vec<GridTrack>,
+    pub column_gap: f32,
+    pub row_gap: f32,
+}
+
+impl GridConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_columns(mut self, columns: impl Into<Vec<GridTrack>>) -> Self {
+        self.columns = columns.into();
+        self
+    }
+
+    pub fn with_rows(mut self, rows: impl Into<Vec<GridTrack>>) -> Self {
+        self.rows = rows.into();
+        self
+    }
+
+    pub fn with_column_gap(mut self, gap: f32) -> Self {
+        self.column_gap = gap;
+        self
+    }
+
+    pub fn with_row_gap(mut self, gap: f32) -> Self {
+        self.row_gap = gap;
+        self
+    }
+
+    pub fn with_gap(self, gap: f32) -> Self {
+        self.with_column_gap(gap).with_row_gap(gap)
+    }
+}
+
+/// Where a child goes in its parent's grid.
+///
+/// Children without a column or row are placed into the next free cells,
+/// row by row, after the explicitly placed children.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GridPlacement {
+    pub column: Option<usize>,
+    pub row: Option<usize>,
+    pub column_span: usize,
+    pub row_span: usize,
+}
+
+impl Default for GridPlacement {
+    fn default() -> Self {
+        Self {
+            column: None,
+            row: None,
+            column_span: 1,
+            row_span: 1,
+        }
+    }
+}
+
+impl GridPlacement {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Places the child at a zero-based column and row
+    pub fn cell(column: usize, row: usize) -> Self {
+        Self {
+            column: Some(column),
+            row: Some(row),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_column(mut self, column: usize) -> Self {
+        self.column = Some(column);
+        self
+    }
+
+    pub fn with_row(mut self, row: usize) -> Self {
+        self.row = Some(row);
+        self
+    }
+
+    pub fn with_column_span(mut self, span: usize) -> Self {
+        self.column_span = span;
+        self
+    }
+
+    pub fn with_row_span(mut self, span: usize) -> Self {
+        self.row_span = span;
+        self
+    }
+}
+
 // ---------- Element tree ----------
 
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -795,7 +1193,7 @@ pub struct UIElement<Message> {
     /// How children are distributed along the main axis (container property)
     pub axis_align_content: Alignment,
     /// How wrapped rows/columns are distributed along the cross axis (container property, wrapping layouts only)
-    pub cross_align_content: Alignment,
+    pub cross_align_content: ContentAlignment,
     /// Default main-axis alignment for all children (container property, ZStack only)
     pub axis_align_items: Alignment,
     /// Default cross-axis alignment for all children (container property)
@@ -807,10 +1205,20 @@ pub struct UIElement<Message> {
 
     pub width: Sizing,
     pub height: Sizing,
+    /// Width divided by height. The height is derived from the width, unless
+    /// the height is fixed or the width only fits content while the height is
+    /// set by the parent, in which case the width is derived from the height.
+    pub aspect_ratio: Option<f32>,
 
     pub child_gap: f32,
+    /// Gap between wrapped rows/columns, `child_gap` when unset
+    pub cross_gap: Option<f32>,
 
     pub __positioned: bool,
+    /// Sizes from the sizing passes, kept to reuse the layout of unchanged subtrees
+    pub(crate) __measured: Measured,
+    /// Layout key of the widget, see [`Widget::layout_key`]
+    pub(crate) __layout_key: Option<u64>,
     pub computed_width: f32,
     pub computed_height: f32,
     pub computed_content_width: f32,
@@ -820,8 +1228,22 @@ pub struct UIElement<Message> {
     pub x: f32,
     pub y: f32,
 
+    /// Space around the element, outside of its size
+    pub margin: BoxAmount,
+
+    /// Reading direction, inherited from the parent when `None`
+    pub layout_direction: Option<LayoutDirection>,
+
     pub floating: Option<FloatingConfig>,
+    pub sticky: Option<StickyConfig>,
     pub scroll: Option<ScrollConfig>,
+    pub overflow: Overflow,
+
+    // Grid support
+    pub grid: Option<GridConfig>,
+    pub grid_placement: Option<GridPlacement>,
+    pub grid_columns: Vec<f32>, // Resolved column sizes
+    pub grid_rows: Vec<f32>,    // Resolved row sizes
 
     pub background_color: Option<Color>,
     pub color: Option<Color>,
@@ -832,12 +1254,24 @@ pub struct UIElement<Message> {
     pub border: Option<Border>,
     pub backdrop_filter: Option<BackdropFilter>,
     pub z_index: Option<i32>,
+    /// Paints and hit tests the subtree above everything else, outside the clips of its
+    /// ancestors. Events still bubble through its parents
+    pub overlay: bool,
+    /// Whether the pointer hits the element's own box. Decorative overlays and badges set it to
+    /// `false` to let the pointer through to what is below them. Children are hit tested on their
+    /// own, so a badge can still hold a button
+    pub hit_test: bool,
+    /// Makes the subtree non-interactive, for example behind a modal: the pointer passes through
+    /// it and its widgets neither receive keyboard input nor keep focus
+    pub inert: bool,
     pub opacity: Option<f32>,
+    /// Applied when painting and hit testing, doesn't affect layout
+    pub transform: Option<Transform>,
     pub snap: bool,
 
     // Wrapping support
     pub wrap: bool,
-    pub wrap_breaks: Vec<usize>, // Indices where rows break (child indices)
+    pub wrap_breaks: Vec<usize>, // Indices where rows/columns break (child indices)
 
     pub id: Option<u64>,
     pub id_map: HashMap<u64, UIKey>,
@@ -853,13 +1287,17 @@ impl<Message> Default for UIElement<Message> {
             axis_align_content: Alignment::Start,
             axis_align_items: Alignment::Start,
             cross_align_items: Alignment::Start,
-            cross_align_content: Alignment::Start,
+            cross_align_content: ContentAlignment::Start,
             axis_align_self: None,
             cross_align_self: None,
             width: Sizing::default(),
             height: Sizing::default(),
+            aspect_ratio: None,
             child_gap: 0.0,
+            cross_gap: None,
             __positioned: false,
+            __measured: Measured::default(),
+            __layout_key: None,
             computed_width: 0.0,
             computed_height: 0.0,
             computed_content_width: 0.0,
@@ -868,8 +1306,16 @@ impl<Message> Default for UIElement<Message> {
             min_height: 0.0,
             x: 0.0,
             y: 0.0,
+            margin: BoxAmount::default(),
+            layout_direction: None,
             floating: None,
+            sticky: None,
             scroll: None,
+            overflow: Overflow::Visible,
+            grid: None,
+            grid_placement: None,
+            grid_columns: Vec::new(),
+            grid_rows: Vec::new(),
             background_color: None,
             color: None,
             padding: BoxAmount::default(),
@@ -879,7 +1325,11 @@ impl<Message> Default for UIElement<Message> {
             border: None,
             backdrop_filter: None,
             z_index: None,
+            overlay: false,
+            hit_test: true,
+            inert: false,
             opacity: None,
+            transform: None,
             #[cfg(feature = "snap")]
             snap: true,
             #[cfg(not(feature = "snap"))]
@@ -892,6 +1342,118 @@ impl<Message> Default for UIElement<Message> {
     }
 }
 
+impl<Message> UIElement<Message> {
+    /// Whether children wrap into rows or columns
+    pub(crate) fn wraps(&self) -> bool {
+        self.wrap && self.direction.main_axis().is_some()
+    }
+
+    /// Z-index the element is painted at, overlays are painted after everything else
+    pub(crate) fn paint_z_index(&self) -> i32 {
+        if self.overlay {
+            i32::MAX
+        } else {
+            self.z_index.unwrap_or(0)
+        }
+    }
+
+    /// The transform of the element as a matrix, `None` if it has none
+    pub(crate) fn transform_matrix(&self) -> Option<Matrix2D> {
+        let rect = RectDIP {
+            x: self.x,
+            y: self.y,
+            width: self.computed_width,
+            height: self.computed_height,
+        };
+        Some(self.transform.as_ref()?.matrix(rect))
+    }
+
+    /// Rectangle and corner radii the children of the element are clipped to, if any. Scroll
+    /// containers clip to their border box
+    pub(crate) fn child_clip(&self) -> Option<(RectDIP, Option<BorderRadius>)> {
+        let border_box = RectDIP {
+            x: self.x,
+            y: self.y,
+            width: self.computed_width,
+            height: self.computed_height,
+        };
+        if self.overflow == Overflow::Clip {
+            let border_width = match &self.border {
+                Some(border) => match border.placement {
+                    BorderPlacement::Inset => border.width,
+                    BorderPlacement::Center => border.width / 2.0,
+                    BorderPlacement::Outset => 0.0,
+                },
+                None => 0.0,
+            };
+            let padding_box = RectDIP {
+                x: border_box.x + border_width,
+                y: border_box.y + border_width,
+                width: (border_box.width - border_width * 2.0).max(0.0),
+                height: (border_box.height - border_width * 2.0).max(0.0),
+            };
+            let border_radius = self.border_radius.map(|radius| radius.inner(border_width));
+            return Some((padding_box, border_radius));
+        }
+
+        let scroll = self.scroll.as_ref()?;
+        (scroll.horizontal || scroll.vertical || !scroll.overflow)
+            .then_some((border_box, self.border_radius))
+    }
+
+    /// Whether this element lays out right-to-left, once inherited from its ancestors
+    pub(crate) fn is_rtl(&self) -> bool {
+        self.layout_direction == Some(LayoutDirection::RightToLeft)
+    }
+
+    /// Gap between wrapped rows/columns
+    pub(crate) fn line_gap(&self) -> f32 {
+        self.cross_gap.unwrap_or(self.child_gap)
+    }
+
+    /// Margins on both sides along `axis`
+    pub(crate) fn margin_along(&self, axis: Axis) -> f32 {
+        match axis {
+            Axis::X => self.margin.left + self.margin.right,
+            Axis::Y => self.margin.top + self.margin.bottom,
+        }
+    }
+
+    /// Computed size including margins, the space the element takes up in its parent
+    pub(crate) fn outer_size(&self, axis: Axis) -> f32 {
+        match axis {
+            Axis::X => self.computed_width + self.margin_along(axis),
+            Axis::Y => self.computed_height + self.margin_along(axis),
+        }
+    }
+
+    /// Minimum size including margins
+    pub(crate) fn outer_min_size(&self, axis: Axis) -> f32 {
+        match axis {
+            Axis::X => self.min_width + self.margin_along(axis),
+            Axis::Y => self.min_height + self.margin_along(axis),
+        }
+    }
+
+    /// Whether `aspect_ratio` derives the width from the height rather than
+    /// the other way around
+    pub(crate) fn width_follows_height(&self) -> bool {
+        match (self.width, self.height) {
+            (Sizing::Fixed { .. }, _) => false,
+            (_, Sizing::Fixed { .. }) => true,
+            (Sizing::Fit { .. }, Sizing::Grow { .. } | Sizing::Percent { .. }) => true,
+            _ => false,
+        }
+    }
+
+    /// Whether the size along `axis` is derived from the other axis by `aspect_ratio`, so the
+    /// parent doesn't grow or shrink it
+    pub(crate) fn follows_aspect_ratio(&self, axis: Axis) -> bool {
+        self.aspect_ratio.is_some_and(|ratio| ratio > 0.0)
+            && (axis == Axis::X) == self.width_follows_height()
+    }
+}
+
 // impl UIElement {
 //     pub fn is_text_element(&self) -> bool {
 //         self.content.is_some()
@@ -941,7 +1503,7 @@ pub struct Element<Message> {
     /// How children are distributed along the main axis (container property)
     pub axis_align_content: Alignment,
     /// How wrapped rows/columns are distributed along the cross axis (container property, wrapping layouts only)
-    pub cross_align_content: Alignment,
+    pub cross_align_content: ContentAlignment,
     /// Default main-axis alignment for all children (container property, ZStack only)
     pub axis_align_items: Alignment,
     /// Default cross-axis alignment for all children (container property)
@@ -953,11 +1515,28 @@ pub struct Element<Message> {
 
     pub width: Sizing,
     pub height: Sizing,
+    /// Width divided by height. The height is derived from the width, unless
+    /// the height is fixed or the width only fits content while the height is
+    /// set by the parent, in which case the width is derived from the height.
+    pub aspect_ratio: Option<f32>,
 
     pub child_gap: f32,
+    /// Gap between wrapped rows/columns, `child_gap` when unset
+    pub cross_gap: Option<f32>,
+
+    /// Space around the element, outside of its size
+    pub margin: BoxAmount,
+
+    /// Reading direction, inherited from the parent when `None`
+    pub layout_direction: Option<LayoutDirection>,
 
     pub floating: Option<FloatingConfig>,
+    pub sticky: Option<StickyConfig>,
     pub scroll: Option<ScrollConfig>,
+    pub overflow: Overflow,
+
+    pub grid: Option<GridConfig>,
+    pub grid_placement: Option<GridPlacement>,
 
     pub background_color: Option<Color>,
     pub color: Option<Color>,
@@ -968,7 +1547,19 @@ pub struct Element<Message> {
     pub border: Option<Border>,
     pub backdrop_filter: Option<BackdropFilter>,
     pub z_index: Option<i32>,
+    /// Paints and hit tests the subtree above everything else, outside the clips of its
+    /// ancestors. Events still bubble through its parents
+    pub overlay: bool,
+    /// Whether the pointer hits the element's own box. Decorative overlays and badges set it to
+    /// `false` to let the pointer through to what is below them. Children are hit tested on their
+    /// own, so a badge can still hold a button
+    pub hit_test: bool,
+    /// Makes the subtree non-interactive, for example behind a modal: the pointer passes through
+    /// it and its widgets neither receive keyboard input nor keep focus
+    pub inert: bool,
     pub opacity: Option<f32>,
+    /// Applied when painting and hit testing, doesn't affect layout
+    pub transform: Option<Transform>,
     pub snap: bool,
 
     // Wrapping support
@@ -1018,9 +1609,9 @@ impl<Message> Element<Message> {
         }
     }
 
-    pub fn with_cross_align_content(self, align: Alignment) -> Self {
+    pub fn with_cross_align_content(self, align: impl Into<ContentAlignment>) -> Self {
         Self {
-            cross_align_content: align,
+            cross_align_content: align.into(),
             ..self
         }
     }
@@ -1046,8 +1637,11 @@ impl<Message> Element<Message> {
     )]
     pub fn with_horizontal_alignment(self, align: Alignment) -> Self {
         match self.direction {
-            Direction::LeftToRight | Direction::ZStack => self.with_axis_align_content(align),
-            Direction::TopToBottom => self.with_cross_align_self(align),
+            Direction::LeftToRight
+            | Direction::RightToLeft
+            | Direction::ZStack
+            | Direction::Grid => self.with_axis_align_content(align),
+            Direction::TopToBottom | Direction::BottomToTop => self.with_cross_align_self(align),
         }
     }
 
@@ -1057,8 +1651,11 @@ impl<Message> Element<Message> {
     )]
     pub fn with_vertical_alignment(self, align: Alignment) -> Self {
         match self.direction {
-            Direction::LeftToRight | Direction::ZStack => self.with_cross_align_self(align),
-            Direction::TopToBottom => self.with_axis_align_content(align),
+            Direction::LeftToRight
+            | Direction::RightToLeft
+            | Direction::ZStack
+            | Direction::Grid => self.with_cross_align_self(align),
+            Direction::TopToBottom | Direction::BottomToTop => self.with_axis_align_content(align),
         }
     }
 
@@ -1070,6 +1667,13 @@ impl<Message> Element<Message> {
         Self { height, ..self }
     }
 
+    pub fn with_aspect_ratio(self, aspect_ratio: f32) -> Self {
+        Self {
+            aspect_ratio: Some(aspect_ratio),
+            ..self
+        }
+    }
+
     pub fn with_child_gap(self, gap: f32) -> Self {
         Self {
             child_gap: gap,
@@ -1077,6 +1681,27 @@ impl<Message> Element<Message> {
         }
     }
 
+    pub fn with_cross_gap(self, gap: f32) -> Self {
+        Self {
+            cross_gap: Some(gap),
+            ..self
+        }
+    }
+
+    pub fn with_margin(self, margin: impl Into<BoxAmount>) -> Self {
+        Self {
+            margin: margin.into(),
+            ..self
+        }
+    }
+
+    pub fn with_layout_direction(self, layout_direction: LayoutDirection) -> Self {
+        Self {
+            layout_direction: Some(layout_direction),
+            ..self
+        }
+    }
+
     pub fn with_floating(self, floating: impl Into<FloatingConfig>) -> Self {
         Self {
             floating: Some(floating.into()),
@@ -1084,6 +1709,13 @@ impl<Message> Element<Message> {
         }
     }
 
+    pub fn with_sticky(self, sticky: StickyConfig) -> Self {
+        Self {
+            sticky: Some(sticky),
+            ..self
+        }
+    }
+
     pub fn with_scroll(self, scroll: impl Into<ScrollConfig>) -> Self {
         Self {
             scroll: Some(scroll.into()),
@@ -1091,6 +1723,26 @@ impl<Message> Element<Message> {
         }
     }
 
+    pub fn with_overflow(self, overflow: Overflow) -> Self {
+        Self { overflow, ..self }
+    }
+
+    /// Lays children out in a grid with the given tracks
+    pub fn with_grid(self, grid: impl Into<GridConfig>) -> Self {
+        Self {
+            direction: Direction::Grid,
+            grid: Some(grid.into()),
+            ..self
+        }
+    }
+
+    pub fn with_grid_placement(self, placement: impl Into<GridPlacement>) -> Self {
+        Self {
+            grid_placement: Some(placement.into()),
+            ..self
+        }
+    }
+
     pub fn with_background_color(self, color: impl Into<Color>) -> Self {
         Self {
             background_color: Some(color.into()),
@@ -1168,6 +1820,18 @@ impl<Message> Element<Message> {
         }
     }
 
+    pub fn with_overlay(self, overlay: bool) -> Self {
+        Self { overlay, ..self }
+    }
+
+    pub fn with_hit_test(self, hit_test: bool) -> Self {
+        Self { hit_test, ..self }
+    }
+
+    pub fn with_inert(self, inert: bool) -> Self {
+        Self { inert, ..self }
+    }
+
     pub fn with_opacity(self, opacity: f32) -> Self {
         Self {
             opacity: Some(opacity),
@@ -1175,6 +1839,13 @@ impl<Message> Element<Message> {
         }
     }
 
+    pub fn with_transform(self, transform: Transform) -> Self {
+        Self {
+            transform: Some(transform),
+            ..self
+        }
+    }
+
     pub fn with_snap(self, snap: bool) -> Self {
         Self { snap, ..self }
     }
@@ -1210,14 +1881,22 @@ impl<Message> Default for Element<Message> {
             axis_align_content: Alignment::Start,
             axis_align_items: Alignment::Start,
             cross_align_items: Alignment::Start,
-            cross_align_content: Alignment::Start,
+            cross_align_content: ContentAlignment::Start,
             axis_align_self: None,
             cross_align_self: None,
             width: Sizing::default(),
             height: Sizing::default(),
+            aspect_ratio: None,
             child_gap: 0.0,
+            cross_gap: None,
+            margin: BoxAmount::default(),
+            layout_direction: None,
             floating: None,
+            sticky: None,
             scroll: None,
+            overflow: Overflow::Visible,
+            grid: None,
+            grid_placement: None,
             background_color: None,
             color: None,
             padding: BoxAmount::default(),
@@ -1227,7 +1906,11 @@ impl<Message> Default for Element<Message> {
             border: None,
             backdrop_filter: None,
             z_index: None,
+            overlay: false,
+            hit_test: true,
+            inert: false,
             opacity: None,
+            transform: None,
             #[cfg(feature = "snap")]
             snap: true,
             #[cfg(not(feature = "snap"))]
@@ -1253,9 +1936,17 @@ fn to_shell<Message>(element: Element<Message>) -> (UIElement<Message>, Vec<Elem
             cross_align_self: element.cross_align_self,
             width: element.width,
             height: element.height,
+            aspect_ratio: element.aspect_ratio,
             child_gap: element.child_gap,
+            cross_gap: element.cross_gap,
+            margin: element.margin,
+            layout_direction: element.layout_direction,
             floating: element.floating,
+            sticky: element.sticky,
             scroll: element.scroll,
+            overflow: element.overflow,
+            grid: element.grid,
+            grid_placement: element.grid_placement,
             background_color: element.background_color,
             color: element.color,
             padding: element.padding,
@@ -1265,7 +1956,11 @@ fn to_shell<Message>(element: Element<Message>) -> (UIElement<Message>, Vec<Elem
             border: element.border,
             backdrop_filter: element.backdrop_filter,
             z_index: element.z_index,
+            overlay: element.overlay,
+            hit_test: element.hit_test,
+            inert: element.inert,
             opacity: element.opacity,
+            transform: element.transform,
             snap: element.snap,
             wrap: element.wrap,
             wrap_breaks: Vec::new(),
@@ -1284,7 +1979,8 @@ pub fn create_tree<Message>(
     let mut queue = vec![(root, None)];
     let mut root_key = None;
 
-    tree.slots.clear();
+    let previous_root = tree.root;
+    tree.layout_cache.retire(&mut tree.slots, previous_root);
 
     #[cfg(debug_assertions)]
     let mut id_set = std::collections::HashSet::new();
@@ -1328,3 +2024,32 @@ pub fn create_tree<Message>(
 
     tree.root = root_key.expect("no root found");
 }
+
+/// Builds the tree for `view_fn`, wrapped in the root and sentinel stacks the runtime expects.
+pub(crate) fn create_tree_root<State: 'static, Message>(
+    state: &State,
+    view_fn: ViewFn<State, Message>,
+    device_resources: &DeviceResources,
+    hook_manager: &mut HookManager<Message>,
+) {
+    let children = view_fn(state, hook_manager);
+    create_tree(
+        device_resources,
+        hook_manager.ui_tree,
+        Element {
+            id: Some(w_id!()),
+            direction: Direction::ZStack,
+            children: vec![Element {
+                id: Some(w_id!()),
+                direction: Direction::ZStack,
+                children: vec![children],
+                width: Sizing::grow(),
+                height: Sizing::grow(),
+
+                ..Default::default()
+            }],
+
+            ..Default::default()
+        },
+    )
+}
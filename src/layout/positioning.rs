@@ -1,8 +1,15 @@
+use std::collections::HashMap;
+
+use slotmap::SlotMap;
+
 use crate::{
     layout::{
-        BorrowedUITree, ScrollStateManager,
-        model::{Alignment, Direction, UIKey},
-        visitors,
+        BorrowedUITree, ScrollStateManager, baseline,
+        fit_along_axis::fit_along_axis,
+        grid,
+        grow_and_shrink_along_axis::grow_and_shrink_along_axis,
+        model::{Alignment, Axis, Direction, FloatingConfig, UIElement, UIKey},
+        visitors, wrap,
     },
     runtime::scroll::ScrollPosition,
 };
@@ -62,64 +69,65 @@ pub fn position_elements<Message>(
                 }
 
                 if let Some(anchor_key) = anchor {
-                    let anchor_x = slots[anchor_key].x;
-                    let anchor_y = slots[anchor_key].y;
-                    let anchor_w = slots[anchor_key].computed_width;
-                    let anchor_h = slots[anchor_key].computed_height;
-
-                    let mut anchor_point_x = anchor_x;
-                    let mut anchor_point_y = anchor_y;
-
-                    if let Some(anchor_align) = floating.anchor.as_ref() {
-                        if let Some(ax) = anchor_align.x {
-                            match ax {
-                                Alignment::Start => {}
-                                Alignment::Center => anchor_point_x = anchor_x + anchor_w / 2.0,
-                                Alignment::End => anchor_point_x = anchor_x + anchor_w,
-                            }
-                        }
-                        if let Some(ay) = anchor_align.y {
-                            match ay {
-                                Alignment::Start => {}
-                                Alignment::Center => anchor_point_y = anchor_y + anchor_h / 2.0,
-                                Alignment::End => anchor_point_y = anchor_y + anchor_h,
-                            }
-                        }
-                    }
+                    let element = &slots[key];
+                    let anchor = &slots[anchor_key];
+                    let x_placement = FloatingPlacement {
+                        anchor: (anchor.x, anchor.computed_width),
+                        anchor_align: floating.anchor.and_then(|a| a.x).unwrap_or_default(),
+                        align: floating.align.and_then(|a| a.x).unwrap_or_default(),
+                        offset: floating.offset.and_then(|o| o.x).unwrap_or(0.0),
+                        margins: (element.margin.left, element.margin.right),
+                        // Right-to-left floating elements are mirrored around their anchor
+                        mirrored: element.is_rtl(),
+                    };
+                    let y_placement = FloatingPlacement {
+                        anchor: (anchor.y, anchor.computed_height),
+                        anchor_align: floating.anchor.and_then(|a| a.y).unwrap_or_default(),
+                        align: floating.align.and_then(|a| a.y).unwrap_or_default(),
+                        offset: floating.offset.and_then(|o| o.y).unwrap_or(0.0),
+                        margins: (element.margin.top, element.margin.bottom),
+                        mirrored: false,
+                    };
 
-                    let element_w = slots[key].computed_width;
-                    let element_h = slots[key].computed_height;
+                    // The root bounds are the viewport floating elements are kept inside of
+                    let (x, width) = x_placement.resolve(
+                        &floating,
+                        (slots[root].x, slots[root].computed_width),
+                        element.computed_width,
+                        floating.min_size.and_then(|size| size.width),
+                    );
+                    let (y, height) = y_placement.resolve(
+                        &floating,
+                        (slots[root].y, slots[root].computed_height),
+                        element.computed_height,
+                        floating.min_size.and_then(|size| size.height),
+                    );
 
-                    let mut align_offset_x = 0.0;
-                    let mut align_offset_y = 0.0;
+                    let resized_x = width != element.computed_width;
+                    let resized_y = height != element.computed_height;
+                    slots[key].x = x;
+                    slots[key].y = y;
+                    slots[key].computed_width = width;
+                    slots[key].computed_height = height;
 
-                    if let Some(align) = floating.align.as_ref() {
-                        if let Some(ax) = align.x {
-                            match ax {
-                                Alignment::Start => {}
-                                Alignment::Center => align_offset_x = -element_w / 2.0,
-                                Alignment::End => align_offset_x = -element_w,
-                            }
-                        }
-                        if let Some(ay) = align.y {
-                            match ay {
-                                Alignment::Start => {}
-                                Alignment::Center => align_offset_y = -element_h / 2.0,
-                                Alignment::End => align_offset_y = -element_h,
-                            }
-                        }
+                    // Children were sized against the size before shrinking into the viewport
+                    if resized_x {
+                        grow_and_shrink_along_axis(ui_tree, key, Axis::X);
+                        let min_height = ui_tree.slots[key].min_height;
+                        fit_along_axis(ui_tree, key, Axis::Y);
+                        ui_tree.slots[key].computed_height = height;
+                        ui_tree.slots[key].min_height = min_height;
+                    }
+                    if resized_x || resized_y {
+                        grow_and_shrink_along_axis(ui_tree, key, Axis::Y);
                     }
-
-                    let offset_x = floating.offset.as_ref().and_then(|o| o.x).unwrap_or(0.0);
-                    let offset_y = floating.offset.as_ref().and_then(|o| o.y).unwrap_or(0.0);
-
-                    slots[key].x = anchor_point_x + align_offset_x + offset_x;
-                    slots[key].y = anchor_point_y + align_offset_y + offset_y;
                 } else {
                     // No anchor present; nothing to do
                 }
             }
 
+            let slots = &mut ui_tree.slots;
+
             // First, snap if snap is enabled
             if slots[key].snap {
                 // Round to nearest dip_scale
@@ -244,26 +252,79 @@ pub fn position_elements<Message>(
             let available_height =
                 slots[key].computed_height - slots[key].padding.top - slots[key].padding.bottom;
 
+            // Only rows line their children up on baselines
+            let baselines = if slots[key].direction.main_axis() == Some(Axis::X) {
+                baseline::child_baselines(
+                    slots,
+                    &mut ui_tree.widget_state,
+                    &ui_tree.arenas,
+                    key,
+                    &non_floating,
+                )
+            } else {
+                HashMap::new()
+            };
+
             match slots[key].direction {
+                Direction::Grid => {
+                    // Children are aligned within their cells like in a ZStack
+                    let grid_config = slots[key].grid.as_ref();
+                    let column_gap = grid::gap(grid_config, Axis::X);
+                    let row_gap = grid::gap(grid_config, Axis::Y);
+                    let columns = slots[key].grid_columns.clone();
+                    let rows = slots[key].grid_rows.clone();
+                    let column_offsets = grid::track_offsets(&columns, column_gap);
+                    let row_offsets = grid::track_offsets(&rows, row_gap);
+
+                    for (c, area) in grid::place_items(slots, key).items {
+                        let cell_x = content_start_x
+                            + column_offsets.get(area.column).copied().unwrap_or(0.0);
+                        let cell_y =
+                            content_start_y + row_offsets.get(area.row).copied().unwrap_or(0.0);
+                        let cell_width =
+                            grid::span_size(&columns, column_gap, area.column, area.column_span);
+                        let cell_height = grid::span_size(&rows, row_gap, area.row, area.row_span);
+
+                        let horizontal_align = slots[c]
+                            .axis_align_self
+                            .unwrap_or(slots[key].axis_align_items);
+                        let free_width = (cell_width - slots[c].outer_size(Axis::X)).max(0.0);
+                        slots[c].x = slots[c].margin.left + match horizontal_align {
+                            Alignment::Start | Alignment::Baseline => cell_x,
+                            Alignment::Center => cell_x + free_width / 2.0,
+                            Alignment::End => cell_x + free_width,
+                        };
+
+                        let vertical_align = slots[c]
+                            .cross_align_self
+                            .unwrap_or(slots[key].cross_align_items);
+                        let free_height = (cell_height - slots[c].outer_size(Axis::Y)).max(0.0);
+                        slots[c].y = slots[c].margin.top + match vertical_align {
+                            Alignment::Start | Alignment::Baseline => cell_y,
+                            Alignment::Center => cell_y + free_height / 2.0,
+                            Alignment::End => cell_y + free_height,
+                        };
+                    }
+                }
                 Direction::ZStack => {
                     // All children are stacked at the same position, respecting individual alignment
                     // ZStack: axis_align_self for horizontal, cross_align_self for vertical
-                    for c in non_floating {
+                    for &c in &non_floating {
                         // Horizontal position: use child's axis_align_self, fallback to parent's axis_align_items
                         let horizontal_align = slots[c]
                             .axis_align_self
                             .unwrap_or(slots[key].axis_align_items);
                         match horizontal_align {
-                            Alignment::Start => {
+                            Alignment::Start | Alignment::Baseline => {
                                 slots[c].x = content_start_x;
                             }
                             Alignment::Center => {
                                 slots[c].x = content_start_x
-                                    + (available_width - slots[c].computed_width).max(0.0) / 2.0;
+                                    + (available_width - slots[c].outer_size(Axis::X)).max(0.0) / 2.0;
                             }
                             Alignment::End => {
                                 slots[c].x = content_start_x
-                                    + (available_width - slots[c].computed_width).max(0.0);
+                                    + (available_width - slots[c].outer_size(Axis::X)).max(0.0);
                             }
                         }
 
@@ -272,181 +333,96 @@ pub fn position_elements<Message>(
                             .cross_align_self
                             .unwrap_or(slots[key].cross_align_items);
                         match vertical_align {
-                            Alignment::Start => {
+                            Alignment::Start | Alignment::Baseline => {
                                 slots[c].y = content_start_y;
                             }
                             Alignment::Center => {
                                 slots[c].y = content_start_y
-                                    + (available_height - slots[c].computed_height).max(0.0) / 2.0;
+                                    + (available_height - slots[c].outer_size(Axis::Y)).max(0.0)
+                                        / 2.0;
                             }
                             Alignment::End => {
                                 slots[c].y = content_start_y
-                                    + (available_height - slots[c].computed_height).max(0.0);
+                                    + (available_height - slots[c].outer_size(Axis::Y)).max(0.0);
                             }
                         }
+
+                        slots[c].x += slots[c].margin.left;
+                        slots[c].y += slots[c].margin.top;
                     }
                 }
-                Direction::LeftToRight => {
-                    // Check if this is a wrapping layout with breaks
-                    if slots[key].wrap {
-                        // Handle wrapping layout with multiple rows
-                        let breaks = slots[key].wrap_breaks.clone();
-
-                        // First pass: calculate total height of all rows
-                        let mut total_rows_height = 0.0;
-                        let mut row_count = 0;
-                        let mut start_idx = 0;
-                        for &break_idx in breaks.iter().chain(std::iter::once(&non_floating.len()))
-                        {
-                            let row_children: Vec<UIKey> =
-                                non_floating[start_idx..break_idx].to_vec();
-                            if !row_children.is_empty() {
-                                let row_height = row_children
-                                    .iter()
-                                    .fold(0.0_f32, |acc, &c| acc.max(slots[c].computed_height));
-                                total_rows_height += row_height;
-                                row_count += 1;
-                            }
-                            start_idx = break_idx;
-                        }
-                        // Add gaps between rows
-                        if row_count > 1 {
-                            total_rows_height += slots[key].child_gap * (row_count as f32 - 1.0);
+                Direction::LeftToRight
+                | Direction::RightToLeft
+                | Direction::TopToBottom
+                | Direction::BottomToTop
+                    if slots[key].wraps() =>
+                {
+                    position_wrapped_lines(
+                        slots,
+                        key,
+                        &non_floating,
+                        (content_start_x, content_start_y),
+                        (available_width, available_height),
+                        &baselines,
+                    );
+                }
+                Direction::LeftToRight | Direction::RightToLeft => {
+                    // Original single-row LeftToRight behavior, reversed rows are mirrored below
+                    let mut total_children_width = 0.0;
+                    if !non_floating.is_empty() {
+                        for c in &non_floating {
+                            total_children_width += slots[*c].outer_size(Axis::X);
                         }
+                        total_children_width +=
+                            slots[key].child_gap * (non_floating.len() as f32 - 1.0);
+                    }
 
-                        // Apply cross_align_content to determine starting Y position
-                        let remaining_height = available_height - total_rows_height;
-                        let mut current_y = content_start_y;
-                        if remaining_height > 0.0 {
-                            match slots[key].cross_align_content {
-                                Alignment::Start => {}
-                                Alignment::Center => current_y += remaining_height / 2.0,
-                                Alignment::End => current_y += remaining_height,
-                            }
+                    let remaining_width = available_width - total_children_width;
+                    let mut start_x = content_start_x;
+                    if remaining_width > 0.0 {
+                        match slots[key].axis_align_content {
+                            Alignment::Start | Alignment::Baseline => {}
+                            Alignment::Center => start_x += remaining_width / 2.0,
+                            Alignment::End => start_x += remaining_width,
                         }
+                    }
 
-                        // Second pass: position rows
-                        start_idx = 0;
-                        for &break_idx in breaks.iter().chain(std::iter::once(&non_floating.len()))
-                        {
-                            let row_children: Vec<UIKey> =
-                                non_floating[start_idx..break_idx].to_vec();
-                            if row_children.is_empty() {
-                                start_idx = break_idx;
-                                continue;
-                            }
-
-                            // Calculate total width for this row
-                            let mut total_row_width = 0.0;
-                            for &c in &row_children {
-                                total_row_width += slots[c].computed_width;
-                            }
-                            total_row_width +=
-                                slots[key].child_gap * (row_children.len() as f32 - 1.0);
-
-                            // Calculate row height (max of children in this row)
-                            let row_height = row_children
-                                .iter()
-                                .fold(0.0_f32, |acc, &c| acc.max(slots[c].computed_height));
-
-                            // Calculate starting X position for this row based on axis_align_content
-                            let remaining_width = available_width - total_row_width;
-                            let mut start_x = content_start_x;
-                            if remaining_width > 0.0 {
-                                match slots[key].axis_align_content {
-                                    Alignment::Start => {}
-                                    Alignment::Center => start_x += remaining_width / 2.0,
-                                    Alignment::End => start_x += remaining_width,
-                                }
-                            }
-
-                            // Position children in this row
-                            let mut current_x = start_x;
-                            for &c in &row_children {
-                                slots[c].x = current_x;
-
-                                // Vertical alignment: use child's cross_align_self, fallback to parent's cross_align_items
-                                let vertical_align = slots[c]
-                                    .cross_align_self
-                                    .unwrap_or(slots[key].cross_align_items);
-                                match vertical_align {
-                                    Alignment::Start => {
-                                        slots[c].y = current_y;
-                                    }
-                                    Alignment::Center => {
-                                        slots[c].y = current_y
-                                            + (row_height - slots[c].computed_height).max(0.0)
-                                                / 2.0;
-                                    }
-                                    Alignment::End => {
-                                        slots[c].y = current_y
-                                            + (row_height - slots[c].computed_height).max(0.0);
-                                    }
-                                }
-
-                                current_x += slots[c].computed_width + slots[key].child_gap;
+                    let line_baseline = baseline::line_baseline(&baselines, &non_floating);
+                    let mut current_x = start_x;
+                    for &c in &non_floating {
+                        slots[c].x = current_x;
+                        // Vertical alignment: use child's cross_align_self, fallback to parent's cross_align_items
+                        let vertical_align = slots[c]
+                            .cross_align_self
+                            .unwrap_or(slots[key].cross_align_items);
+                        match vertical_align {
+                            Alignment::Start => {
+                                slots[c].y = content_start_y;
                             }
-
-                            // Move to next row
-                            current_y += row_height;
-                            if break_idx < non_floating.len() {
-                                current_y += slots[key].child_gap; // Row gap
+                            Alignment::Center => {
+                                slots[c].y = content_start_y
+                                    + (available_height - slots[c].outer_size(Axis::Y)).max(0.0)
+                                        / 2.0;
                             }
-
-                            start_idx = break_idx;
-                        }
-                    } else {
-                        // Original single-row LeftToRight behavior
-                        let mut total_children_width = 0.0;
-                        if !non_floating.is_empty() {
-                            for c in &non_floating {
-                                total_children_width += slots[*c].computed_width;
+                            Alignment::End => {
+                                slots[c].y = content_start_y
+                                    + (available_height - slots[c].outer_size(Axis::Y)).max(0.0);
                             }
-                            total_children_width +=
-                                slots[key].child_gap * (non_floating.len() as f32 - 1.0);
-                        }
-
-                        let remaining_width = available_width - total_children_width;
-                        let mut start_x = content_start_x;
-                        if remaining_width > 0.0 {
-                            match slots[key].axis_align_content {
-                                Alignment::Start => {}
-                                Alignment::Center => start_x += remaining_width / 2.0,
-                                Alignment::End => start_x += remaining_width,
+                            Alignment::Baseline => {
+                                slots[c].y = content_start_y + line_baseline - baselines[&c];
                             }
                         }
 
-                        let mut current_x = start_x;
-                        for c in non_floating {
-                            slots[c].x = current_x;
-                            // Vertical alignment: use child's cross_align_self, fallback to parent's cross_align_items
-                            let vertical_align = slots[c]
-                                .cross_align_self
-                                .unwrap_or(slots[key].cross_align_items);
-                            match vertical_align {
-                                Alignment::Start => {
-                                    slots[c].y = content_start_y;
-                                }
-                                Alignment::Center => {
-                                    slots[c].y = content_start_y
-                                        + (available_height - slots[c].computed_height).max(0.0)
-                                            / 2.0;
-                                }
-                                Alignment::End => {
-                                    slots[c].y = content_start_y
-                                        + (available_height - slots[c].computed_height).max(0.0);
-                                }
-                            }
-
-                            current_x += slots[c].computed_width + slots[key].child_gap;
-                        }
+                        slots[c].x += slots[c].margin.left;
+                        slots[c].y += slots[c].margin.top;
+                        current_x += slots[c].outer_size(Axis::X) + slots[key].child_gap;
                     }
                 }
-                Direction::TopToBottom => {
+                Direction::TopToBottom | Direction::BottomToTop => {
                     let mut total_children_height = 0.0;
                     if !non_floating.is_empty() {
                         for c in &non_floating {
-                            total_children_height += slots[*c].computed_height;
+                            total_children_height += slots[*c].outer_size(Axis::Y);
                         }
                         total_children_height +=
                             slots[key].child_gap * (non_floating.len() as f32 - 1.0);
@@ -456,40 +432,652 @@ pub fn position_elements<Message>(
                     let mut start_y = content_start_y;
                     if remaining_height > 0.0 {
                         match slots[key].axis_align_content {
-                            Alignment::Start => {}
+                            Alignment::Start | Alignment::Baseline => {}
                             Alignment::Center => start_y += remaining_height / 2.0,
                             Alignment::End => start_y += remaining_height,
                         }
                     }
 
                     let mut current_y = start_y;
-                    for c in non_floating {
+                    for &c in &non_floating {
                         // Horizontal alignment: use child's cross_align_self, fallback to parent's cross_align_items
                         let horizontal_align = slots[c]
                             .cross_align_self
                             .unwrap_or(slots[key].cross_align_items);
                         match horizontal_align {
-                            Alignment::Start => {
+                            Alignment::Start | Alignment::Baseline => {
                                 slots[c].x = content_start_x;
                             }
                             Alignment::Center => {
                                 slots[c].x = content_start_x
-                                    + (available_width - slots[c].computed_width).max(0.0) / 2.0;
+                                    + (available_width - slots[c].outer_size(Axis::X)).max(0.0) / 2.0;
                             }
                             Alignment::End => {
                                 slots[c].x = content_start_x
-                                    + (available_width - slots[c].computed_width).max(0.0);
+                                    + (available_width - slots[c].outer_size(Axis::X)).max(0.0);
                             }
                         }
 
-                        slots[c].y = current_y;
-                        current_y += slots[c].computed_height + slots[key].child_gap;
+                        slots[c].x += slots[c].margin.left;
+                        slots[c].y = current_y + slots[c].margin.top;
+                        current_y += slots[c].outer_size(Axis::Y) + slots[key].child_gap;
                     }
                 }
             }
 
+            // Reversed directions mirror their children within the content box
+            let direction = slots[key].direction;
+            if direction.is_reversed() {
+                let content_x = slots[key].x + slots[key].padding.left;
+                let content_y = slots[key].y + slots[key].padding.top;
+                for &c in &non_floating {
+                    let child = &mut slots[c];
+                    if direction == Direction::RightToLeft {
+                        child.x = 2.0 * content_x + available_width
+                            - child.x
+                            - child.computed_width
+                            + child.margin.left
+                            - child.margin.right;
+                    } else {
+                        child.y = 2.0 * content_y + available_height
+                            - child.y
+                            - child.computed_height
+                            + child.margin.top
+                            - child.margin.bottom;
+                    }
+                }
+            }
+
+            // Right-to-left layouts mirror their children within the whole element, so padding and
+            // margins swap sides as well
+            if slots[key].is_rtl() {
+                let x = slots[key].x;
+                let width = slots[key].computed_width;
+                for &c in &non_floating {
+                    slots[c].x = 2.0 * x + width - slots[c].x - slots[c].computed_width;
+                }
+            }
+
+            // Sticky children stay inside the viewport of their nearest scroll container
+            stick_children(slots, key, &non_floating, Axis::X);
+            stick_children(slots, key, &non_floating, Axis::Y);
+
             // Mark as positioned
             slots[key].__positioned = true;
         },
     );
 }
+
+/// Positions wrapped rows (as `LeftToRight`) or columns (as `TopToBottom`), distributing the lines
+/// along the cross axis by `cross_align_content` and aligning children within their line
+fn position_wrapped_lines<Message>(
+    slots: &mut SlotMap<UIKey, UIElement<Message>>,
+    key: UIKey,
+    non_floating: &[UIKey],
+    content_start: (f32, f32),
+    available: (f32, f32),
+    baselines: &HashMap<UIKey, f32>,
+) {
+    let rows = slots[key].direction.main_axis() == Some(Axis::X);
+    let cross_axis = if rows { Axis::Y } else { Axis::X };
+    // Picks the main or cross component of an (x, y) pair
+    let main = |(x, y): (f32, f32)| if rows { x } else { y };
+    let cross = |(x, y): (f32, f32)| if rows { y } else { x };
+    let size =
+        |element: &UIElement<Message>| (element.outer_size(Axis::X), element.outer_size(Axis::Y));
+
+    let breaks = slots[key].wrap_breaks.clone();
+    let child_gap = slots[key].child_gap;
+    let line_gap = slots[key].line_gap();
+    let line_sizes = wrap::line_sizes(slots, key, cross_axis);
+    let (line_offset, line_spacing) = wrap::distribute(
+        slots[key].cross_align_content,
+        cross(available) - wrap::total_size(&line_sizes, line_gap),
+        line_sizes.len(),
+    );
+
+    let mut line_start = cross(content_start) + line_offset;
+    for (line, line_size) in wrap::lines(non_floating, &breaks).zip(line_sizes) {
+        let line_baseline = baseline::line_baseline(baselines, line);
+
+        // Calculate starting position for this line based on axis_align_content
+        let line_length = line.iter().map(|c| main(size(&slots[*c]))).sum::<f32>()
+            + child_gap * (line.len() as f32 - 1.0);
+        let remaining = main(available) - line_length;
+        let mut position = main(content_start);
+        if remaining > 0.0 {
+            match slots[key].axis_align_content {
+                Alignment::Start | Alignment::Baseline => {}
+                Alignment::Center => position += remaining / 2.0,
+                Alignment::End => position += remaining,
+            }
+        }
+
+        for &c in line {
+            // Cross alignment within the line: use child's cross_align_self, fallback to parent's cross_align_items
+            let align = slots[c]
+                .cross_align_self
+                .unwrap_or(slots[key].cross_align_items);
+            let free = (line_size - cross(size(&slots[c]))).max(0.0);
+            let cross_position = line_start
+                + match align {
+                    Alignment::Start => 0.0,
+                    Alignment::Center => free / 2.0,
+                    Alignment::End => free,
+                    Alignment::Baseline => baselines.get(&c).map_or(0.0, |b| line_baseline - b),
+                };
+
+            let (x, y) = if rows {
+                (position, cross_position)
+            } else {
+                (cross_position, position)
+            };
+            slots[c].x = x + slots[c].margin.left;
+            slots[c].y = y + slots[c].margin.top;
+            position += main(size(&slots[c])) + child_gap;
+        }
+
+        line_start += line_size + line_gap + line_spacing;
+    }
+}
+
+/// Moves the sticky `children` of `key` along `axis` to stay inside the viewport of their nearest
+/// scroll container, without leaving the content box of `key` or overlapping the next sticky child
+fn stick_children<Message>(
+    slots: &mut SlotMap<UIKey, UIElement<Message>>,
+    key: UIKey,
+    children: &[UIKey],
+    axis: Axis,
+) {
+    let Some(viewport_start) = scroll_viewport_start(slots, key, axis) else {
+        return;
+    };
+
+    let sticky_inset = |element: &UIElement<Message>| element.sticky.and_then(|s| s.inset(axis));
+    let start = |element: &UIElement<Message>| match axis {
+        Axis::X => element.x - element.margin.left,
+        Axis::Y => element.y - element.margin.top,
+    };
+    let end = |element: &UIElement<Message>| match axis {
+        Axis::X => element.x + element.computed_width + element.margin.right,
+        Axis::Y => element.y + element.computed_height + element.margin.bottom,
+    };
+    let content_end = match axis {
+        Axis::X => slots[key].x + slots[key].computed_width - slots[key].padding.right,
+        Axis::Y => slots[key].y + slots[key].computed_height - slots[key].padding.bottom,
+    };
+
+    for (i, &c) in children.iter().enumerate() {
+        let Some(inset) = sticky_inset(&slots[c]) else {
+            continue;
+        };
+
+        // Later sticky children haven't moved yet, so they are still where the flow put them
+        let limit = children[i + 1..]
+            .iter()
+            .find(|next| sticky_inset(&slots[**next]).is_some())
+            .map_or(content_end, |next| start(&slots[*next]));
+        let room = limit - end(&slots[c]);
+        let child = &mut slots[c];
+        let position = match axis {
+            Axis::X => &mut child.x,
+            Axis::Y => &mut child.y,
+        };
+        // The inset applies to the border box, the limit to the margin box
+        *position += (viewport_start + inset - *position).min(room).max(0.0);
+    }
+}
+
+/// Start of the viewport of the nearest scroll container at or above `key` scrolling along `axis`
+fn scroll_viewport_start<Message>(
+    slots: &SlotMap<UIKey, UIElement<Message>>,
+    key: UIKey,
+    axis: Axis,
+) -> Option<f32> {
+    let mut current = Some(key);
+    while let Some(k) = current {
+        let element = &slots[k];
+        if let Some(scroll) = &element.scroll {
+            match axis {
+                Axis::X if scroll.horizontal => return Some(element.x + element.padding.left),
+                Axis::Y if scroll.vertical => return Some(element.y + element.padding.top),
+                _ => {}
+            }
+        }
+        current = element.parent;
+    }
+    None
+}
+
+/// Placement of a floating element along one axis
+struct FloatingPlacement {
+    /// Start and size of the anchor
+    anchor: (f32, f32),
+    anchor_align: Alignment,
+    align: Alignment,
+    offset: f32,
+    /// Margins before and after the element
+    margins: (f32, f32),
+    mirrored: bool,
+}
+
+impl FloatingPlacement {
+    /// Start of an element of `size`, on the mirrored side of the anchor when `mirrored`
+    fn position(&self, size: f32, mirrored: bool) -> f32 {
+        let mirror = |alignment: Alignment| match alignment {
+            Alignment::Start | Alignment::Baseline => Alignment::End,
+            Alignment::Center => Alignment::Center,
+            Alignment::End => Alignment::Start,
+        };
+        let (anchor_align, align, offset, (margin_start, margin_end)) = if mirrored {
+            (
+                mirror(self.anchor_align),
+                mirror(self.align),
+                -self.offset,
+                (self.margins.1, self.margins.0),
+            )
+        } else {
+            (self.anchor_align, self.align, self.offset, self.margins)
+        };
+
+        // Floating elements are aligned by their margin box
+        let (anchor_start, anchor_size) = self.anchor;
+        let outer_size = size + margin_start + margin_end;
+        let anchor_point = anchor_start
+            + match anchor_align {
+                Alignment::Start | Alignment::Baseline => 0.0,
+                Alignment::Center => anchor_size / 2.0,
+                Alignment::End => anchor_size,
+            };
+        let align_offset = match align {
+            Alignment::Start | Alignment::Baseline => 0.0,
+            Alignment::Center => -outer_size / 2.0,
+            Alignment::End => -outer_size,
+        };
+
+        anchor_point + align_offset + offset + margin_start
+    }
+
+    /// Position and size of the element after flipping, shrinking and shifting it into the
+    /// `viewport` as `floating` allows
+    fn resolve(
+        &self,
+        floating: &FloatingConfig,
+        viewport: (f32, f32),
+        size: f32,
+        min_size: Option<f32>,
+    ) -> (f32, f32) {
+        let (viewport_start, viewport_size) = viewport;
+        let viewport_end = viewport_start + viewport_size;
+        let overflow = |position: f32, size: f32| {
+            (viewport_start - position).max(0.0) + (position + size - viewport_end).max(0.0)
+        };
+
+        let mut mirrored = self.mirrored;
+        let mut position = self.position(size, mirrored);
+
+        if floating.flip && overflow(position, size) > 0.0 {
+            let flipped = self.position(size, !mirrored);
+            if overflow(flipped, size) < overflow(position, size) {
+                mirrored = !mirrored;
+                position = flipped;
+            }
+        }
+
+        let mut size = size;
+        if let Some(min_size) = min_size
+            && overflow(position, size) > 0.0
+        {
+            let visible = (position + size).min(viewport_end) - position.max(viewport_start);
+            size = visible.max(min_size).min(size);
+            position = self.position(size, mirrored);
+        }
+
+        if floating.shift {
+            position = position.min(viewport_end - size).max(viewport_start);
+        }
+
+        (position, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::layout::OwnedUITree;
+    use crate::layout::model::{
+        Alignment, Alignment2D, BoxAmount, Direction, Element, FloatingConfig, LayoutDirection,
+        Offset2D, ScrollConfig, Size2D, Sizing,
+    };
+    use crate::layout::testing::{bounds, lay_out};
+
+    fn chip(id: u64, width: f32, height: f32) -> Element<()> {
+        Element {
+            id: Some(id),
+            width: Sizing::fixed(width),
+            height: Sizing::fixed(height),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn margins_space_out_children() {
+        let mut ui_tree = OwnedUITree::default();
+        lay_out(
+            &mut ui_tree,
+            Element {
+                direction: Direction::TopToBottom,
+                width: Sizing::fixed(300.0),
+                children: vec![
+                    Element {
+                        width: Sizing::grow(),
+                        children: vec![
+                            chip(50, 100.0, 20.0).with_margin(BoxAmount::all(10.0)),
+                            Element {
+                                id: Some(51),
+                                width: Sizing::grow(),
+                                height: Sizing::fixed(20.0),
+                                ..Default::default()
+                            }
+                            .with_margin(BoxAmount::horizontal(5.0)),
+                        ],
+                        ..Default::default()
+                    },
+                    Element {
+                        width: Sizing::grow(),
+                        children: vec![
+                            chip(52, 100.0, 20.0).with_margin(BoxAmount::left(10.0)),
+                            chip(53, 100.0, 20.0).with_margin(BoxAmount::left(10.0)),
+                            chip(54, 100.0, 20.0).with_margin(BoxAmount::left(10.0)),
+                        ],
+                        ..Default::default()
+                    }
+                    .with_wrap(true),
+                    chip(55, 100.0, 20.0)
+                        .with_floating(FloatingConfig::default())
+                        .with_margin(BoxAmount::all(4.0)),
+                ],
+                ..Default::default()
+            },
+            320.0,
+            240.0,
+        );
+        let bounds = |id| {
+            let (x, y, width, _) = bounds(&ui_tree, id);
+            (x, y, width)
+        };
+
+        // Margins offset the child and push its siblings away
+        assert_eq!(bounds(50), (10.0, 10.0, 100.0));
+        // A growing child fills the space left after its own margins
+        assert_eq!(bounds(51), (125.0, 0.0, 170.0));
+        // Lines break on the margin box
+        assert_eq!(bounds(52), (10.0, 40.0, 100.0));
+        assert_eq!(bounds(53), (120.0, 40.0, 100.0));
+        assert_eq!(bounds(54), (10.0, 60.0, 100.0));
+        // Floating elements are offset by their margins too
+        assert_eq!(bounds(55), (4.0, 4.0, 100.0));
+    }
+
+    #[test]
+    fn right_to_left_mirrors_layout() {
+        let chip = |id| chip(id, 50.0, 20.0);
+
+        let mut ui_tree = OwnedUITree::default();
+        lay_out(
+            &mut ui_tree,
+            Element {
+                direction: Direction::TopToBottom,
+                width: Sizing::fixed(300.0),
+                children: vec![
+                    Element {
+                        width: Sizing::grow(),
+                        children: vec![chip(61).with_margin(BoxAmount::left(5.0)), chip(62)],
+                        ..Default::default()
+                    }
+                    .with_padding(BoxAmount::left(10.0)),
+                    Element {
+                        direction: Direction::RightToLeft,
+                        width: Sizing::grow(),
+                        children: vec![chip(64), chip(65)],
+                        ..Default::default()
+                    }
+                    .with_child_gap(10.0)
+                    .with_layout_direction(LayoutDirection::LeftToRight),
+                    Element {
+                        direction: Direction::BottomToTop,
+                        height: Sizing::fixed(100.0),
+                        children: vec![chip(67), chip(68)],
+                        ..Default::default()
+                    },
+                    chip(69).with_floating(FloatingConfig {
+                        offset: Some(Offset2D {
+                            x: Some(10.0),
+                            y: None,
+                        }),
+                        ..Default::default()
+                    }),
+                ],
+                ..Default::default()
+            }
+            .with_layout_direction(LayoutDirection::RightToLeft),
+            320.0,
+            240.0,
+        );
+        let position = |id| {
+            let (x, y, _, _) = bounds(&ui_tree, id);
+            (x, y)
+        };
+
+        // Rows start at the right, after the mirrored padding and margins
+        assert_eq!(position(61), (235.0, 0.0));
+        assert_eq!(position(62), (185.0, 0.0));
+        // Reversed rows start at the right even in left-to-right layouts
+        assert_eq!(position(64), (250.0, 20.0));
+        assert_eq!(position(65), (190.0, 20.0));
+        // Reversed columns start at the bottom
+        assert_eq!(position(67), (250.0, 120.0));
+        assert_eq!(position(68), (250.0, 100.0));
+        // Floating offsets are mirrored around the anchor
+        assert_eq!(position(69), (240.0, 0.0));
+    }
+
+    #[test]
+    fn floating_elements_stay_in_the_viewport() {
+        let popover = |id: u64, width: f32, height: f32, floating: FloatingConfig| Element {
+            floating: Some(floating),
+            ..chip(id, width, height)
+        };
+
+        let mut ui_tree = OwnedUITree::default();
+        lay_out(
+            &mut ui_tree,
+            Element {
+                direction: Direction::ZStack,
+                width: Sizing::grow(),
+                height: Sizing::grow(),
+                axis_align_items: Alignment::End,
+                cross_align_items: Alignment::End,
+                children: vec![Element {
+                    children: vec![
+                        // A dropdown below the anchor flips above it
+                        popover(
+                            81,
+                            10.0,
+                            50.0,
+                            FloatingConfig {
+                                anchor: Some(Alignment2D {
+                                    x: None,
+                                    y: Some(Alignment::End),
+                                }),
+                                flip: true,
+                                match_anchor_width: true,
+                                ..Default::default()
+                            },
+                        ),
+                        // A wide popover shifts back into the viewport
+                        popover(
+                            82,
+                            150.0,
+                            20.0,
+                            FloatingConfig {
+                                shift: true,
+                                ..Default::default()
+                            },
+                        ),
+                        // A tall list above the anchor shrinks to the space left
+                        popover(
+                            83,
+                            200.0,
+                            300.0,
+                            FloatingConfig {
+                                align: Some(Alignment2D {
+                                    x: None,
+                                    y: Some(Alignment::End),
+                                }),
+                                min_size: Some(Size2D {
+                                    width: None,
+                                    height: Some(50.0),
+                                }),
+                                max_size: Some(Size2D {
+                                    width: Some(120.0),
+                                    height: None,
+                                }),
+                                ..Default::default()
+                            },
+                        ),
+                    ],
+                    ..chip(80, 100.0, 20.0)
+                }],
+                ..Default::default()
+            },
+            320.0,
+            240.0,
+        );
+
+        assert_eq!(bounds(&ui_tree, 80), (220.0, 220.0, 100.0, 20.0));
+        assert_eq!(bounds(&ui_tree, 81), (220.0, 170.0, 100.0, 50.0));
+        assert_eq!(bounds(&ui_tree, 82), (170.0, 220.0, 150.0, 20.0));
+        assert_eq!(bounds(&ui_tree, 83), (220.0, 0.0, 120.0, 220.0));
+    }
+
+    #[test]
+    fn floating_elements_match_anchors_anywhere_in_the_tree() {
+        let row = |children| Element {
+            width: Sizing::grow(),
+            children,
+            ..Default::default()
+        };
+
+        let mut ui_tree = OwnedUITree::<()>::default();
+        lay_out(
+            &mut ui_tree,
+            Element {
+                direction: Direction::TopToBottom,
+                width: Sizing::fixed(300.0),
+                children: vec![
+                    // The field is sized after the popover, which is higher up in the tree
+                    row(vec![row(vec![Element {
+                        id: Some(84),
+                        width: Sizing::grow(),
+                        height: Sizing::fixed(20.0),
+                        ..Default::default()
+                    }])]),
+                    row(vec![Element {
+                        id: Some(85),
+                        floating: Some(FloatingConfig {
+                            anchor_id: Some(84),
+                            match_anchor_width: true,
+                            ..Default::default()
+                        }),
+                        children: vec![Element {
+                            id: Some(86),
+                            width: Sizing::grow(),
+                            height: Sizing::fixed(10.0),
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    }]),
+                ],
+                ..Default::default()
+            },
+            320.0,
+            240.0,
+        );
+
+        assert_eq!(bounds(&ui_tree, 84), (0.0, 0.0, 300.0, 20.0));
+        assert_eq!(bounds(&ui_tree, 85), (0.0, 0.0, 300.0, 10.0));
+        assert_eq!(bounds(&ui_tree, 86), (0.0, 0.0, 300.0, 10.0));
+    }
+
+    #[test]
+    fn floating_children_follow_the_viewport_shrink() {
+        let shrinking = |width: Option<f32>, height: Option<f32>| FloatingConfig {
+            align: Some(Alignment2D {
+                x: None,
+                y: height.map(|_| Alignment::End),
+            }),
+            min_size: Some(Size2D { width, height }),
+            ..Default::default()
+        };
+
+        let mut ui_tree = OwnedUITree::default();
+        lay_out(
+            &mut ui_tree,
+            Element {
+                direction: Direction::ZStack,
+                width: Sizing::grow(),
+                height: Sizing::grow(),
+                axis_align_items: Alignment::End,
+                cross_align_items: Alignment::End,
+                children: vec![Element {
+                    children: vec![
+                        // A tall list above the anchor shrinks, and its scroll area with it
+                        Element {
+                            id: Some(87),
+                            direction: Direction::TopToBottom,
+                            width: Sizing::fixed(100.0),
+                            floating: Some(shrinking(None, Some(50.0))),
+                            children: vec![Element {
+                                id: Some(88),
+                                width: Sizing::grow(),
+                                height: Sizing::grow(),
+                                scroll: Some(ScrollConfig {
+                                    vertical: true,
+                                    ..Default::default()
+                                }),
+                                children: vec![chip(89, 100.0, 300.0)],
+                                ..Default::default()
+                            }],
+                            ..Default::default()
+                        },
+                        // A wide menu shrinks, and its growing items with it
+                        Element {
+                            id: Some(90),
+                            direction: Direction::TopToBottom,
+                            floating: Some(shrinking(Some(50.0), None)),
+                            children: vec![
+                                Element {
+                                    id: Some(91),
+                                    width: Sizing::grow(),
+                                    height: Sizing::fixed(10.0),
+                                    ..Default::default()
+                                },
+                                chip(92, 400.0, 10.0),
+                            ],
+                            ..Default::default()
+                        },
+                    ],
+                    ..chip(80, 100.0, 20.0)
+                }],
+                ..Default::default()
+            },
+            320.0,
+            240.0,
+        );
+
+        assert_eq!(bounds(&ui_tree, 87), (220.0, 0.0, 100.0, 220.0));
+        assert_eq!(bounds(&ui_tree, 88), (220.0, 0.0, 100.0, 220.0));
+        assert_eq!(bounds(&ui_tree, 90), (220.0, 220.0, 100.0, 20.0));
+        assert_eq!(bounds(&ui_tree, 91).2, 100.0);
+    }
+}
@@ -0,0 +1,54 @@
+//! Lays out element trees for the tests of the layout passes, without running an app.
+
+use crate::{
+    layout::{
+        self, OwnedUITree,
+        model::{Direction, Element, Sizing, create_tree},
+    },
+    runtime::{DeviceResources, scroll::ScrollStateManager},
+};
+
+/// Builds the tree for `element` in a viewport of `width` by `height`, in the root stack the
+/// runtime puts views in. The previous tree is kept to reuse the subtrees that didn't change
+pub(crate) fn build<Message>(
+    ui_tree: &mut OwnedUITree<Message>,
+    element: Element<Message>,
+    width: f32,
+    height: f32,
+) {
+    create_tree(
+        &DeviceResources::default(),
+        ui_tree,
+        Element {
+            direction: Direction::ZStack,
+            width: Sizing::fixed(width),
+            height: Sizing::fixed(height),
+            children: vec![element],
+            ..Default::default()
+        },
+    );
+}
+
+/// Builds and lays out the tree for `element`, see [`build`]
+pub(crate) fn lay_out<Message>(
+    ui_tree: &mut OwnedUITree<Message>,
+    element: Element<Message>,
+    width: f32,
+    height: f32,
+) {
+    build(ui_tree, element, width, height);
+    let root = ui_tree.root;
+    layout::layout(ui_tree, root, &mut ScrollStateManager::default(), 1.0);
+}
+
+/// Border box of the element with the given ID as `(x, y, width, height)`
+pub(crate) fn bounds<Message>(ui_tree: &OwnedUITree<Message>, id: u64) -> (f32, f32, f32, f32) {
+    let key = ui_tree.slots[ui_tree.root].id_map[&id];
+    let element = &ui_tree.slots[key];
+    (
+        element.x,
+        element.y,
+        element.computed_width,
+        element.computed_height,
+    )
+}
@@ -1,6 +1,6 @@
-use std::collections::VecDeque;
+use std::{cell::Cell, collections::VecDeque};
 
-use crate::layout::{BorrowedUITree, model::UIKey};
+use crate::layout::{BorrowedUITree, OwnedUITree, model::UIKey};
 
 pub enum VisitAction {
     Continue,
@@ -29,6 +29,21 @@ impl From<()> for VisitAction {
     }
 }
 
+/// Children of `element` in the order they are painted, sticky children paint above the
+/// siblings scrolling under them
+fn paint_order<'a, Message>(
+    ui_tree: &'a OwnedUITree<Message>,
+    element: UIKey,
+) -> impl DoubleEndedIterator<Item = UIKey> + 'a {
+    let children = &ui_tree.slots[element].children;
+    let sticky = |child: &&UIKey| ui_tree.slots[**child].sticky.is_some();
+    children
+        .iter()
+        .filter(move |child| !sticky(child))
+        .chain(children.iter().filter(sticky))
+        .copied()
+}
+
 /// Breadth-first traversal that visits nodes from leaves back to the root.
 pub fn visit_reverse_bfs<Message, F, R>(
     ui_tree: BorrowedUITree<'_, Message>,
@@ -70,7 +85,7 @@ pub fn visit_reverse_dfs<Message, F, R>(
 
     while let Some((current, parent)) = queue.pop() {
         stack.push((current, parent));
-        for &child in ui_tree.slots[current].children.iter().rev() {
+        for child in paint_order(ui_tree, current).rev() {
             queue.push((child, Some(current)));
         }
     }
@@ -196,8 +211,8 @@ pub fn visit_deferring_dfs<Message, S, F, E, A, R>(
                     exit: true,
                 });
 
-                // Push children in reverse so they are processed in original order
-                for &child in ui_tree.slots[frame.element].children.iter().rev() {
+                // Push children in reverse so they are processed in paint order
+                for child in paint_order(ui_tree, frame.element).rev() {
                     stack.push(VisitFrame {
                         element: child,
                         parent: Some(frame.element),
@@ -216,8 +231,69 @@ pub fn visit_deferring_dfs<Message, S, F, E, A, R>(
             f(ui_tree, &deferred);
         }
 
-        // Next pass processes previously deferred items
+        // Next pass processes previously deferred items, in the order they were deferred
         stack = std::mem::take(&mut deferred);
+        stack.reverse();
+    }
+}
+
+/// Depth-first traversal in the order elements are painted. Elements with a higher
+/// [paint z-index](crate::layout::model::UIElement::paint_z_index) than the current pass are
+/// deferred along with their subtrees, and visited in later passes by increasing z-index
+pub fn visit_paint_order_dfs<Message, F, E, R>(
+    ui_tree: BorrowedUITree<'_, Message>,
+    element: UIKey,
+    visitor: F,
+    exit_children_visitor: Option<E>,
+) where
+    F: FnMut(BorrowedUITree<'_, Message>, UIKey, Option<UIKey>) -> R,
+    E: FnMut(BorrowedUITree<'_, Message>, UIKey, Option<UIKey>),
+    R: Into<VisitAction>,
+{
+    let current_z_index = Cell::new(0);
+
+    visit_deferring_dfs(
+        ui_tree,
+        element,
+        |ui_tree, key, _parent| ui_tree.slots[key].paint_z_index() > current_z_index.get(),
+        visitor,
+        exit_children_visitor,
+        Some(
+            |ui_tree: BorrowedUITree<'_, Message>, deferred_frames: &[VisitFrame]| {
+                // The next pass paints the lowest z-index that was deferred
+                if let Some(next_z_index) = deferred_frames
+                    .iter()
+                    .map(|frame| ui_tree.slots[frame.element].paint_z_index())
+                    .min()
+                {
+                    current_z_index.set(next_z_index);
+                }
+            },
+        ),
+    );
+}
+
+/// Visits elements from the last painted to the first, the order they are hit by the pointer in
+pub fn visit_reverse_paint_order<Message, F, R>(
+    ui_tree: BorrowedUITree<'_, Message>,
+    element: UIKey,
+    mut visitor: F,
+) where
+    F: FnMut(BorrowedUITree<'_, Message>, UIKey, Option<UIKey>) -> R,
+    R: Into<VisitAction>,
+{
+    let mut order = Vec::new();
+    visit_paint_order_dfs(
+        ui_tree,
+        element,
+        |_, key, parent| order.push((key, parent)),
+        None::<fn(BorrowedUITree<'_, Message>, UIKey, Option<UIKey>)>,
+    );
+
+    while let Some((current, parent)) = order.pop() {
+        if visitor(ui_tree, current, parent).into().is_exit() {
+            break;
+        }
     }
 }
 
@@ -0,0 +1,360 @@
+//! Line breaking and line sizing for wrapping `LeftToRight` rows and `TopToBottom` columns.
+//!
+//! Breaks are found in the grow pass of the main axis, once the available size
+//! is known, and stored in `UIElement::wrap_breaks`. Along the cross axis each
+//! line is as large as its largest child, [`ContentAlignment::Stretch`] shares
+//! the free space between lines, and `Grow` children fill their line.
+//!
+//! Columns are only known once the parent has given a wrapping column its
+//! height, after widths are settled. A column whose width fits or grows is
+//! widened to its columns then, each as wide as its widest child, and the
+//! parent row shares its width again. Ancestors further up keep their size.
+
+use slotmap::SlotMap;
+
+use crate::layout::model::{Axis, ContentAlignment, Sizing, UIElement, UIKey};
+
+fn size<Message>(element: &UIElement<Message>, axis: Axis) -> f32 {
+    match axis {
+        Axis::X => element.computed_width,
+        Axis::Y => element.computed_height,
+    }
+}
+
+pub fn non_floating_children<Message>(
+    slots: &SlotMap<UIKey, UIElement<Message>>,
+    key: UIKey,
+) -> Vec<UIKey> {
+    slots[key]
+        .children
+        .iter()
+        .copied()
+        .filter(|child| slots[*child].floating.is_none())
+        .collect()
+}
+
+/// Indices where new lines start, breaking before a child that would overflow `available`
+pub fn calculate_breaks<Message>(
+    slots: &SlotMap<UIKey, UIElement<Message>>,
+    children: &[UIKey],
+    axis: Axis,
+    available: f32,
+    gap: f32,
+) -> Vec<usize> {
+    let mut breaks = Vec::new();
+    let mut current_size = 0.0;
+
+    for (i, &child) in children.iter().enumerate() {
+        let child_size = slots[child].outer_size(axis);
+
+        if i > 0 && current_size + gap + child_size > available {
+            breaks.push(i);
+            current_size = child_size;
+        } else if i > 0 {
+            current_size += gap + child_size;
+        } else {
+            current_size = child_size;
+        }
+    }
+
+    breaks
+}
+
+/// Splits children into lines at `breaks`, skipping empty lines
+pub fn lines<'a>(children: &'a [UIKey], breaks: &'a [usize]) -> impl Iterator<Item = &'a [UIKey]> {
+    let mut start = 0;
+    breaks
+        .iter()
+        .copied()
+        .chain(std::iter::once(children.len()))
+        .filter_map(move |end| {
+            let end = end.clamp(start, children.len());
+            let line = &children[start..end];
+            start = end;
+            (!line.is_empty()).then_some(line)
+        })
+}
+
+/// Size of every line along `cross_axis`, stretched when the element stretches its lines
+pub fn line_sizes<Message>(
+    slots: &SlotMap<UIKey, UIElement<Message>>,
+    key: UIKey,
+    cross_axis: Axis,
+) -> Vec<f32> {
+    let element = &slots[key];
+    let children = non_floating_children(slots, key);
+    let mut sizes: Vec<f32> = lines(&children, &element.wrap_breaks)
+        .map(|line| {
+            line.iter().fold(0.0_f32, |acc, child| {
+                acc.max(slots[*child].outer_size(cross_axis))
+            })
+        })
+        .collect();
+
+    if element.cross_align_content == ContentAlignment::Stretch && !sizes.is_empty() {
+        let padding = match cross_axis {
+            Axis::X => element.padding.left + element.padding.right,
+            Axis::Y => element.padding.top + element.padding.bottom,
+        };
+        let free = size(element, cross_axis) - padding - total_size(&sizes, element.line_gap());
+        if free > 0.0 {
+            let share = free / sizes.len() as f32;
+            sizes.iter_mut().for_each(|size| *size += share);
+        }
+    }
+
+    sizes
+}
+
+/// Width of the columns of a wrapping `TopToBottom` element, padding included, breaking its
+/// children by the height its parent gave it
+pub fn columns_width<Message>(slots: &SlotMap<UIKey, UIElement<Message>>, key: UIKey) -> f32 {
+    let element = &slots[key];
+    let children = non_floating_children(slots, key);
+    let available = element.computed_height - element.padding.top - element.padding.bottom;
+    let breaks = calculate_breaks(slots, &children, Axis::Y, available, element.child_gap);
+    let widths: Vec<f32> = lines(&children, &breaks)
+        .map(|line| {
+            line.iter().fold(0.0_f32, |acc, child| {
+                acc.max(slots[*child].outer_size(Axis::X))
+            })
+        })
+        .collect();
+    total_size(&widths, element.line_gap()) + element.padding.left + element.padding.right
+}
+
+/// Sum of line sizes and the gaps between them
+pub fn total_size(line_sizes: &[f32], gap: f32) -> f32 {
+    line_sizes.iter().sum::<f32>() + gap * line_sizes.len().saturating_sub(1) as f32
+}
+
+/// Offset of the first line and extra space after every line for `alignment`
+pub fn distribute(alignment: ContentAlignment, free: f32, line_count: usize) -> (f32, f32) {
+    if free <= 0.0 {
+        return (0.0, 0.0);
+    }
+    match alignment {
+        ContentAlignment::Start | ContentAlignment::Stretch => (0.0, 0.0),
+        ContentAlignment::Center => (free / 2.0, 0.0),
+        ContentAlignment::End => (free, 0.0),
+        ContentAlignment::SpaceBetween if line_count > 1 => (0.0, free / (line_count - 1) as f32),
+        ContentAlignment::SpaceBetween => (0.0, 0.0),
+    }
+}
+
+/// Grows `Grow` children along `cross_axis` to the size of their line, and
+/// records the size of all lines as the element's content size
+pub fn grow_lines<Message>(
+    slots: &mut SlotMap<UIKey, UIElement<Message>>,
+    key: UIKey,
+    cross_axis: Axis,
+) {
+    let sizes = line_sizes(slots, key, cross_axis);
+    let children = non_floating_children(slots, key);
+    let breaks = slots[key].wrap_breaks.clone();
+
+    for (line, line_size) in lines(&children, &breaks).zip(&sizes) {
+        for child in line {
+            let child = &mut slots[*child];
+            let line_size = line_size - child.margin_along(cross_axis);
+            let (sizing, size) = match cross_axis {
+                Axis::X => (child.width, &mut child.computed_width),
+                Axis::Y => (child.height, &mut child.computed_height),
+            };
+            match sizing {
+                Sizing::Grow { max, .. } if *size < line_size => *size = line_size.min(max),
+                _ => {}
+            }
+        }
+    }
+
+    let element = &mut slots[key];
+    let content_size = total_size(&sizes, element.line_gap());
+    match cross_axis {
+        Axis::X => {
+            element.computed_content_width =
+                content_size + element.padding.left + element.padding.right
+        }
+        Axis::Y => {
+            element.computed_content_height =
+                content_size + element.padding.top + element.padding.bottom
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::OwnedUITree;
+    use crate::layout::model::{BoxAmount, Direction, Element};
+    use crate::layout::testing::{bounds, lay_out};
+
+    #[test]
+    fn breaks_before_the_child_that_overflows() {
+        let mut slots: SlotMap<UIKey, UIElement<()>> = SlotMap::with_key();
+        let mut chip = |width: f32, margin: BoxAmount| {
+            slots.insert(UIElement {
+                computed_width: width,
+                margin,
+                ..Default::default()
+            })
+        };
+        let children = [
+            chip(120.0, BoxAmount::default()),
+            chip(120.0, BoxAmount::default()),
+            chip(120.0, BoxAmount::default()),
+            chip(100.0, BoxAmount::left(30.0)),
+            chip(400.0, BoxAmount::default()),
+        ];
+
+        // Lines break on the margin box, and a child larger than the line gets a line of its own
+        let breaks = calculate_breaks(&slots, &children, Axis::X, 300.0, 10.0);
+        assert_eq!(breaks, [2, 4]);
+        let lines: Vec<usize> = lines(&children, &breaks).map(<[UIKey]>::len).collect();
+        assert_eq!(lines, [2, 2, 1]);
+    }
+
+    #[test]
+    fn distributes_free_space_between_lines() {
+        assert_eq!(distribute(ContentAlignment::Start, 40.0, 3), (0.0, 0.0));
+        assert_eq!(distribute(ContentAlignment::Center, 40.0, 3), (20.0, 0.0));
+        assert_eq!(distribute(ContentAlignment::End, 40.0, 3), (40.0, 0.0));
+        assert_eq!(distribute(ContentAlignment::SpaceBetween, 40.0, 3), (0.0, 20.0));
+        assert_eq!(distribute(ContentAlignment::SpaceBetween, 40.0, 1), (0.0, 0.0));
+        // Lines that overflow start at the start
+        assert_eq!(distribute(ContentAlignment::End, -40.0, 3), (0.0, 0.0));
+    }
+
+    fn chips() -> Element<()> {
+        let chip = |id: u64, width: Sizing| Element {
+            id: Some(id),
+            width,
+            height: Sizing::fixed(20.0),
+            ..Default::default()
+        };
+        let fixed = |id: u64, width: f32| chip(id, Sizing::fixed(width));
+
+        Element {
+            direction: Direction::TopToBottom,
+            width: Sizing::fixed(300.0),
+            children: vec![
+                Element {
+                    width: Sizing::grow(),
+                    height: Sizing::fixed(100.0),
+                    children: vec![
+                        fixed(40, 120.0),
+                        fixed(41, 120.0),
+                        fixed(42, 120.0),
+                        fixed(43, 120.0),
+                    ],
+                    ..Default::default()
+                }
+                .with_wrap(true)
+                .with_child_gap(10.0)
+                .with_cross_gap(20.0)
+                .with_cross_align_content(ContentAlignment::SpaceBetween),
+                Element {
+                    width: Sizing::grow(),
+                    children: vec![
+                        fixed(44, 150.0),
+                        chip(45, Sizing::grow().min(100.0)),
+                        fixed(46, 250.0),
+                    ],
+                    ..Default::default()
+                }
+                .with_wrap(true)
+                .with_child_gap(10.0),
+                Element {
+                    direction: Direction::TopToBottom,
+                    width: Sizing::fixed(300.0),
+                    height: Sizing::fixed(70.0),
+                    children: vec![fixed(47, 100.0), chip(48, Sizing::grow()), fixed(49, 100.0)],
+                    ..Default::default()
+                }
+                .with_wrap(true)
+                .with_child_gap(10.0),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn wrapped_lines_distribute_and_grow() {
+        let mut ui_tree = OwnedUITree::default();
+        lay_out(&mut ui_tree, chips(), 320.0, 240.0);
+        let bounds = |id| {
+            let (x, y, width, _) = bounds(&ui_tree, id);
+            (x, y, width)
+        };
+
+        // Rows are spread out over the fixed height, past the cross gap
+        assert_eq!(bounds(41), (130.0, 0.0, 120.0));
+        assert_eq!(bounds(42), (0.0, 80.0, 120.0));
+        // A growing chip takes what's left of its own row
+        assert_eq!(bounds(45), (160.0, 100.0, 140.0));
+        assert_eq!(bounds(46), (0.0, 130.0, 250.0));
+        // Columns break by height and growing children fill their column
+        assert_eq!(bounds(47), (0.0, 150.0, 100.0));
+        assert_eq!(bounds(48), (0.0, 180.0, 100.0));
+        assert_eq!(bounds(49), (110.0, 150.0, 100.0));
+    }
+
+    #[test]
+    fn columns_take_the_width_of_their_widest_child() {
+        let cell = |id: u64, width: Sizing| Element {
+            id: Some(id),
+            width,
+            height: Sizing::fixed(30.0),
+            ..Default::default()
+        };
+        let fixed = |id: u64, width: f32| cell(id, Sizing::fixed(width));
+
+        let mut ui_tree = OwnedUITree::<()>::default();
+        lay_out(
+            &mut ui_tree,
+            Element {
+                width: Sizing::fixed(400.0),
+                height: Sizing::fixed(70.0),
+                children: vec![
+                    Element {
+                        id: Some(50),
+                        direction: Direction::TopToBottom,
+                        height: Sizing::grow(),
+                        children: vec![
+                            fixed(51, 100.0),
+                            cell(52, Sizing::grow()),
+                            fixed(53, 150.0),
+                            cell(54, Sizing::grow()),
+                            fixed(55, 40.0),
+                        ],
+                        ..Default::default()
+                    }
+                    .with_wrap(true)
+                    .with_child_gap(10.0)
+                    .with_cross_gap(20.0),
+                    Element {
+                        id: Some(56),
+                        width: Sizing::grow(),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+            480.0,
+            240.0,
+        );
+        let bounds = |id| {
+            let (x, y, width, _) = bounds(&ui_tree, id);
+            (x, y, width)
+        };
+
+        // The column is as wide as its columns, and its sibling takes the rest of the row
+        assert_eq!(bounds(50), (0.0, 0.0, 330.0));
+        assert_eq!(bounds(56), (330.0, 0.0, 70.0));
+        // Every column is as wide as its widest child, which growing children fill
+        assert_eq!(bounds(52), (0.0, 40.0, 100.0));
+        assert_eq!(bounds(53), (120.0, 0.0, 150.0));
+        assert_eq!(bounds(54), (120.0, 40.0, 150.0));
+        assert_eq!(bounds(55), (290.0, 0.0, 40.0));
+    }
+}
@@ -3,14 +3,6 @@ use std::{
 };
 
 use log::{debug, trace};
-use windows::Win32::{
-    Foundation::HWND,
-    Graphics::Gdi::InvalidateRect,
-    UI::{
-        HiDpi::GetDpiForWindow,
-        WindowsAndMessaging::{KillTimer, SetTimer},
-    },
-};
 
 extern crate self as raxis;
 
@@ -18,21 +10,25 @@ use crate::{
     gfx::{PointDIP, RectDIP},
     layout::{
         BorrowedUITree,
-        model::{Element, ScrollbarStyle, UIKey},
+        model::{Element, Overflow, ScrollbarStyle, UIKey},
         visitors::{self, VisitAction},
     },
     math::easing::Easing,
     runtime::{
+        clock::{Clock, SystemClock},
         focus::FocusManager,
         scroll::{ScrollPosition, ScrollStateManager},
         task::Task,
+        trace::{Trace, TraceRecord, TraceRecorder},
     },
     widgets::{DragData, DragEvent, DropResult, Event, Operation, dispatch_operation},
 };
 
+pub mod demos;
 pub mod gfx;
 pub mod layout;
 pub mod math;
+pub mod platform;
 pub mod proc;
 pub mod runtime;
 pub mod util;
@@ -59,6 +55,7 @@ pub struct HookManager<'a, Message> {
 
     pub scroll_state_manager: &'a ScrollStateManager,
     pub focus_manager: &'a FocusManager,
+    pub clock: &'a dyn Clock,
 
     layout_invalidated: bool,
     requested_animation: bool,
@@ -70,9 +67,15 @@ pub struct HookManager<'a, Message> {
 pub struct HookInstance<'a> {
     state: &'a mut HookState,
     position: usize,
+    now: Instant,
 }
 
 impl<'a> HookInstance<'a> {
+    /// The time of the frame being built
+    pub fn now(&self) -> Instant {
+        self.now
+    }
+
     pub fn use_hook<T: 'static>(&mut self, initializer: impl FnOnce() -> T) -> &mut T {
         if self.position >= self.state.hooks.len() {
             self.state.hooks.push(RefCell::new(Box::new(initializer())));
@@ -98,7 +101,16 @@ impl<Message> HookManager<'_, Message> {
             HookState::default()
         });
 
-        HookInstance { state, position: 0 }
+        HookInstance {
+            state,
+            position: 0,
+            now: self.clock.now(),
+        }
+    }
+
+    /// The time of the frame being built, as told by the shell's clock
+    pub fn now(&self) -> Instant {
+        self.clock.now()
     }
 
     pub fn invalidate_layout(&mut self) {
@@ -176,10 +188,10 @@ impl Interpolate for layout::model::Color {
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Animation<S: Clone + Copy + PartialEq> {
-    active: bool,
     target: S,
     last_target: S,
-    origin_ts: Instant,
+    /// When the transition to `target` started, `None` until the first one
+    origin_ts: Option<Instant>,
     duration: Duration,
     easing: Easing,
 }
@@ -187,21 +199,20 @@ pub struct Animation<S: Clone + Copy + PartialEq> {
 impl<S: Clone + Copy + PartialEq> Animation<S> {
     pub fn new(initial: S) -> Self {
         Self {
-            active: false,
             target: initial,
             last_target: initial,
-            origin_ts: Instant::now(),
+            origin_ts: None,
             duration: Duration::from_millis(100),
             easing: Easing::EaseOut,
         }
     }
 
-    pub fn update(&mut self, state: S) {
+    /// Starts animating towards `state` from `now` if it differs from the current target.
+    pub fn update(&mut self, state: S, now: Instant) {
         if state != self.target {
-            self.active = true;
             self.last_target = self.target;
             self.target = state;
-            self.origin_ts = Instant::now();
+            self.origin_ts = Some(now);
         }
     }
 
@@ -221,9 +232,10 @@ pub fn use_animation<S: Clone + Copy + PartialEq + 'static>(
     state: S,
 ) -> Animation<S> {
     // let mut instance = hook.instance(id);
+    let now = hook.now();
     let animation = hook.use_hook(|| Animation::new(state));
 
-    animation.update(state);
+    animation.update(state, now);
 
     animation.clone()
 }
@@ -243,11 +255,11 @@ impl<S: Clone + Copy + PartialEq> Animation<S> {
         f: impl Fn(S) -> I,
         at: Instant,
     ) -> I {
-        if !self.active {
+        let Some(origin_ts) = self.origin_ts else {
             return f(self.target);
-        }
+        };
 
-        let alpha = (at.duration_since(self.origin_ts)).as_secs_f32() / self.duration.as_secs_f32();
+        let alpha = (at.duration_since(origin_ts)).as_secs_f32() / self.duration.as_secs_f32();
         let alpha = alpha.clamp(0.0, 1.0);
         if alpha < 1.0 {
             hook.request_animation();
@@ -297,17 +309,23 @@ pub type UpdateFn<State, Message> =
     fn(&mut State, Message) -> Option<crate::runtime::task::Task<Message>>;
 pub type EventMapperFn<Message> = fn(Event, Option<u64>) -> Option<Message>;
 
+#[cfg(windows)]
 pub use gfx::effects::{
     DynPixelShaderEffect, EffectInstance, EffectMetadata, EffectProperty, EffectRegistry,
     PixelShaderEffect,
 };
+#[cfg(windows)]
+pub use platform::{current_dpi, dips_scale};
+#[cfg(windows)]
 pub use runtime::Application;
+pub use runtime::HeadlessApp;
 pub use runtime::context_menu::{ContextMenu, ContextMenuItem};
 pub use runtime::syscommand::{SystemCommand, SystemCommandResponse};
 pub use runtime::task::{
     close_window, minimize_window,
     show_context_menu, toggle_maximize_window,
 };
+#[cfg(windows)]
 pub use runtime::tray::{TrayEvent, TrayIconConfig};
 
 pub enum DeferredControl {
@@ -343,6 +361,8 @@ pub struct Shell<Message> {
     // redraw_timers: Vec<usize>,
     // next_timer_id: usize,
     redraw_request: RedrawRequest,
+    /// Set when `redraw_request` changed and the platform layer has yet to act on it
+    redraw_request_changed: bool,
 
     deferred_controls: Vec<DeferredControl>,
 
@@ -355,6 +375,9 @@ pub struct Shell<Message> {
 
     task_dispatcher: mpsc::Sender<Task<Message>>,
 
+    clock: Rc<dyn Clock>,
+    trace_recorder: Option<TraceRecorder>,
+
     pub scrollbar_style: ScrollbarStyle,
 }
 
@@ -392,16 +415,55 @@ impl<Message> Shell<Message> {
             previous_mouse_ancestry: Vec::new(),
             operation_queue: Vec::new(),
             redraw_request: RedrawRequest::Wait,
+            redraw_request_changed: false,
             deferred_controls: Vec::new(),
             pending_scroll_into_view: None,
             message_sender,
             pending_messages: false,
             task_dispatcher,
             event_mapper,
+            clock: Rc::new(SystemClock),
+            trace_recorder: None,
             scrollbar_style,
         }
     }
 
+    /// The current time, which animations and [`RedrawRequest::At`] deadlines are measured against
+    pub fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
+    pub fn clock(&self) -> &Rc<dyn Clock> {
+        &self.clock
+    }
+
+    /// Replaces the time source, e.g. with a [`ManualClock`](runtime::clock::ManualClock) in tests.
+    pub fn set_clock(&mut self, clock: Rc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Starts recording every dispatched event into a new [`Trace`], discarding
+    /// any recording in progress.
+    pub fn start_recording(&mut self) {
+        self.trace_recorder = Some(TraceRecorder::new(self.clock.now()));
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.trace_recorder.is_some()
+    }
+
+    /// Stops recording and returns what was recorded, if recording.
+    pub fn stop_recording(&mut self) -> Option<Trace> {
+        self.trace_recorder.take().map(TraceRecorder::finish)
+    }
+
+    /// Notes a viewport size change in the trace being recorded.
+    pub fn record_viewport(&mut self, width: f32, height: f32) {
+        if let Some(recorder) = &mut self.trace_recorder {
+            recorder.record(self.clock.now(), TraceRecord::Viewport { width, height });
+        }
+    }
+
     pub fn dispatch_task(&mut self, task: Task<Message>) {
         self.task_dispatcher.send(task).unwrap();
     }
@@ -439,33 +501,27 @@ impl<Message> Shell<Message> {
         self.redraw_request = request;
     }
 
-    pub fn request_redraw(&mut self, hwnd: HWND, request: RedrawRequest) {
+    pub fn request_redraw(&mut self, request: RedrawRequest) {
         if request < self.redraw_request {
             self.redraw_request = request;
-
-            if matches!(request, RedrawRequest::Immediate) {
-                unsafe {
-                    let _ = InvalidateRect(Some(hwnd), None, false);
-                }
-            } else {
-                let uelapse = match request {
-                    RedrawRequest::Wait => return,
-                    RedrawRequest::At(instant) => {
-                        instant.duration_since(Instant::now()).as_millis() as u32
-                    }
-                    _ => 0,
-                };
-                unsafe { SetTimer(Some(hwnd), REDRAW_TIMER_ID, uelapse, None) };
-            }
+            self.redraw_request_changed = true;
         }
     }
 
-    pub fn kill_redraw_timer(&mut self, hwnd: HWND, timer_id: usize) {
+    /// Returns the redraw request if it changed since the last call.
+    /// The platform layer uses this to invalidate the window or arm its redraw timer.
+    pub fn take_redraw_request(&mut self) -> Option<RedrawRequest> {
+        std::mem::take(&mut self.redraw_request_changed).then_some(self.redraw_request)
+    }
+
+    /// Resets the pending request once the redraw timer has fired.
+    /// Returns true if `timer_id` was the redraw timer.
+    pub fn kill_redraw_timer(&mut self, timer_id: usize) -> bool {
         if timer_id == REDRAW_TIMER_ID {
             self.redraw_request = RedrawRequest::Wait;
-            unsafe {
-                let _ = KillTimer(Some(hwnd), timer_id);
-            }
+            true
+        } else {
+            false
         }
     }
 
@@ -497,18 +553,12 @@ impl<Message> Shell<Message> {
         let point = gfx::PointDIP { x, y };
         let mut innermost_id = None;
 
-        // Use reverse DFS to find the innermost element (last leaf that contains the point)
-        visitors::visit_reverse_dfs(ui_tree, ui_tree.root, |ui_tree, key, _| {
-            let element = &ui_tree.slots[key];
-            let bounds = element.bounds();
-
-            // Check if point is within the border box (the full element including padding)
-            if point.within(bounds.border_box) {
-                // Additionally check if the point is within all scrollable ancestor viewports
-                if Self::is_point_visible_in_scroll_ancestors(ui_tree, key, point) {
-                    innermost_id = Some(key);
-                    return VisitAction::Exit;
-                }
+        // The innermost element is the last painted one that contains the point, so elements with
+        // a higher z-index, overlays and sticky elements are hit before what they cover
+        visitors::visit_reverse_paint_order(ui_tree, ui_tree.root, |ui_tree, key, _| {
+            if Self::is_point_over_element(ui_tree, key, point) {
+                innermost_id = Some(key);
+                return VisitAction::Exit;
             }
 
             VisitAction::Continue
@@ -517,7 +567,47 @@ impl<Message> Shell<Message> {
         innermost_id
     }
 
-    /// Check if a point is visible within all scrollable ancestor viewports
+    /// Whether a point lands on the element: within its border box (the full element including
+    /// padding), once mapped through its transforms, and within all scrollable ancestor viewports.
+    /// Elements that aren't hit tested or are inert let the point through
+    fn is_point_over_element(
+        ui_tree: BorrowedUITree<Message>,
+        element_key: UIKey,
+        point: gfx::PointDIP,
+    ) -> bool {
+        let element = &ui_tree.slots[element_key];
+        if !element.hit_test || element.inert {
+            return false;
+        }
+
+        let bounds = element.bounds();
+        layout::point_to_local(&ui_tree.slots, element_key, point)
+            .is_some_and(|point| point.within(bounds.border_box))
+            && Self::is_point_visible_in_scroll_ancestors(ui_tree, element_key, point)
+    }
+
+    /// The event with its mouse position mapped into the coordinates the element is laid out in,
+    /// `None` when they are the same as on the screen
+    fn event_in_element(
+        ui_tree: BorrowedUITree<Message>,
+        element_key: UIKey,
+        event: &Event,
+    ) -> Option<Event> {
+        let (x, y) = event.mouse_position()?;
+        let transform = layout::screen_transform(&ui_tree.slots, element_key)?;
+        let point = match transform.invert() {
+            Some(inverse) => inverse.transform_point(gfx::PointDIP { x, y }),
+            // Nothing lands on a collapsed element
+            None => gfx::PointDIP {
+                x: f32::NAN,
+                y: f32::NAN,
+            },
+        };
+        Some(event.with_mouse_position(point.x, point.y))
+    }
+
+    /// Check if a point is visible within all scrollable ancestor viewports and the clips of
+    /// ancestors with [`Overflow::Clip`]
     fn is_point_visible_in_scroll_ancestors(
         ui_tree: BorrowedUITree<Message>,
         element_key: UIKey,
@@ -525,17 +615,36 @@ impl<Message> Shell<Message> {
     ) -> bool {
         let mut current_key = element_key;
 
-        // Walk up the parent chain
+        // Walk up the parent chain, overlays aren't clipped by their ancestors
         loop {
             let element = &ui_tree.slots[current_key];
 
+            if element.overlay {
+                break;
+            }
+
             if let Some(parent_key) = element.parent {
                 let parent = &ui_tree.slots[parent_key];
 
                 // If parent is scrollable, check if point is within its content box (viewport)
                 if parent.scroll.is_some() {
                     let parent_bounds = parent.bounds();
-                    if !point.within(parent_bounds.content_box) {
+                    let within = layout::point_to_local(&ui_tree.slots, parent_key, point)
+                        .is_some_and(|point| point.within(parent_bounds.content_box));
+                    if !within {
+                        return false;
+                    }
+                }
+
+                if parent.overflow == Overflow::Clip
+                    && let Some((clip_rect, border_radius)) = parent.child_clip()
+                {
+                    let within = layout::point_to_local(&ui_tree.slots, parent_key, point)
+                        .is_some_and(|point| match &border_radius {
+                            Some(border_radius) => point.within_rounded(clip_rect, border_radius),
+                            None => point.within(clip_rect),
+                        });
+                    if !within {
                         return false;
                     }
                 }
@@ -609,7 +718,32 @@ impl<Message> Shell<Message> {
         shared_ancestry
     }
 
-    pub fn dispatch_event(&mut self, hwnd: HWND, ui_tree: BorrowedUITree<Message>, event: Event) {
+    /// Releases the focus and the mouse capture of elements that became inert, so they stop
+    /// receiving input and painting as focused. Runtimes call this after every layout
+    pub fn release_inert_elements(&mut self, ui_tree: BorrowedUITree<Message>) {
+        let is_inert = |id: u64| {
+            ui_tree
+                .slots
+                .get(ui_tree.root)
+                .and_then(|root| root.id_map.get(&id))
+                .is_some_and(|&key| ui_tree.slots[key].inert)
+        };
+
+        if let Some(id) = self.focus_manager.focused_widget
+            && is_inert(id)
+        {
+            self.focus_manager.release_focus(id);
+        }
+        if self.active_element_id.is_some_and(is_inert) {
+            self.active_element_id = None;
+        }
+    }
+
+    pub fn dispatch_event(&mut self, ui_tree: BorrowedUITree<Message>, event: Event) {
+        if let Some(recorder) = &mut self.trace_recorder {
+            recorder.record(self.clock.now(), TraceRecord::Event(event.clone()));
+        }
+
         self.event_captured_by = None;
 
         // For mouse events, use targeted dispatching
@@ -619,8 +753,10 @@ impl<Message> Shell<Message> {
             // Determine target element ID
             let innermost_key = Self::find_innermost_element_at(ui_tree, x, y);
             let target_key = if let Some(active_id) = self.active_element_id {
-                // If there's an active element, use it
-                Self::find_key_by_id(ui_tree, active_id).or(innermost_key)
+                // If there's an active element, use it unless it became inert
+                Self::find_key_by_id(ui_tree, active_id)
+                    .filter(|&key| !ui_tree.slots[key].inert)
+                    .or(innermost_key)
             } else {
                 // Otherwise, find the innermost element at the mouse position
                 innermost_key
@@ -656,30 +792,21 @@ impl<Message> Shell<Message> {
                     // TODO: I don't like cloning here...
                     for &prev_id in &self.previous_mouse_ancestry.clone() {
                         if !current_ancestry_ids.contains(&prev_id) {
-                            self.dispatch_event_to(
-                                hwnd,
-                                ui_tree,
-                                Event::MouseLeave { x, y },
-                                prev_id,
-                            );
+                            self.dispatch_event_to(ui_tree, Event::MouseLeave { x, y }, prev_id);
                         }
                     }
 
                     // Find elements that were entered (in current but not in previous)
                     for &curr_id in &current_ancestry_ids {
                         if !self.previous_mouse_ancestry.contains(&curr_id) {
-                            self.dispatch_event_to(
-                                hwnd,
-                                ui_tree,
-                                Event::MouseEnter { x, y },
-                                curr_id,
-                            );
+                            self.dispatch_event_to(ui_tree, Event::MouseEnter { x, y }, curr_id);
                         }
                     }
                 }
 
                 // Dispatch the main event from innermost to outermost
                 for key in ancestry_keys {
+                    let local_event = Self::event_in_element(ui_tree, key, &event);
                     let element = &mut ui_tree.slots[key];
                     let bounds = element.bounds();
 
@@ -687,7 +814,8 @@ impl<Message> Shell<Message> {
                         && let Some(id) = element.id
                     {
                         let instance = ui_tree.widget_state.get_mut(&id).unwrap();
-                        widget.update(&mut ui_tree.arenas, instance, hwnd, self, &event, bounds);
+                        let event = local_event.as_ref().unwrap_or(&event);
+                        widget.update(&mut ui_tree.arenas, instance, self, event, bounds);
 
                         if self.event_captured_by.is_some() {
                             break;
@@ -703,7 +831,7 @@ impl<Message> Shell<Message> {
                 // Mouse is outside all elements - send leave events to all previously hovered elements
                 // TODO: I don't like cloning here...
                 for &prev_id in &self.previous_mouse_ancestry.clone() {
-                    self.dispatch_event_to(hwnd, ui_tree, Event::MouseLeave { x, y }, prev_id);
+                    self.dispatch_event_to(ui_tree, Event::MouseLeave { x, y }, prev_id);
                 }
                 self.previous_mouse_ancestry.clear();
             }
@@ -728,11 +856,15 @@ impl<Message> Shell<Message> {
         // For non-mouse events, use the original broadcast behavior
         visitors::visit_reverse_bfs(ui_tree, ui_tree.root, |ui_tree, key, _| {
             let element = &mut ui_tree.slots[key];
+            if element.inert && event.is_keyboard_event() {
+                return VisitAction::Continue;
+            }
+
             let bounds = element.bounds();
             if let Some(ref mut widget) = element.content {
                 if let Some(id) = element.id {
                     let instance = ui_tree.widget_state.get_mut(&id).unwrap();
-                    widget.update(&mut ui_tree.arenas, instance, hwnd, self, &event, bounds);
+                    widget.update(&mut ui_tree.arenas, instance, self, &event, bounds);
                 }
 
                 if self.event_captured_by.is_some() {
@@ -753,7 +885,6 @@ impl<Message> Shell<Message> {
 
     pub fn dispatch_event_to(
         &mut self,
-        hwnd: HWND,
         ui_tree: BorrowedUITree<Message>,
         event: Event,
         target_id: u64,
@@ -762,11 +893,12 @@ impl<Message> Shell<Message> {
 
         // Find the element and dispatch directly
         if let Some(key) = Self::find_key_by_id(ui_tree, target_id) {
+            let event = Self::event_in_element(ui_tree, key, &event).unwrap_or(event);
             let element = &mut ui_tree.slots[key];
             let bounds = element.bounds();
             if let Some(ref mut widget) = element.content {
                 let instance = ui_tree.widget_state.get_mut(&target_id).unwrap();
-                widget.update(&mut ui_tree.arenas, instance, hwnd, self, &event, bounds);
+                widget.update(&mut ui_tree.arenas, instance, self, &event, bounds);
             }
         }
     }
@@ -963,11 +1095,8 @@ impl<Message> Shell<Message> {
             let mut found_new_widget = false;
 
             // Quick check to see if we're still over the same widget or moved to a new one
-            visitors::visit_reverse_bfs(ui_tree, ui_tree.root, |ui_tree, key, _| {
-                let bounds = ui_tree.slots[key].bounds();
-
-                if position.within(bounds.border_box)
-                    && Shell::is_point_visible_in_scroll_ancestors(ui_tree, key, position)
+            visitors::visit_reverse_paint_order(ui_tree, ui_tree.root, |ui_tree, key, _| {
+                if Shell::is_point_over_element(ui_tree, key, position)
                     && let element = &mut ui_tree.slots[key]
                     && element.content.is_some()
                 {
@@ -996,12 +1125,11 @@ impl<Message> Shell<Message> {
         }
 
         // Now find the widget under the current position and handle the event
-        visitors::visit_reverse_bfs(ui_tree, ui_tree.root, |ui_tree, key, _| {
+        visitors::visit_reverse_paint_order(ui_tree, ui_tree.root, |ui_tree, key, _| {
             let bounds = ui_tree.slots[key].bounds();
 
             // Check if point is within widget bounds (except for DragLeave, which should be handled by all)
-            if (position.within(bounds.border_box)
-                && Shell::is_point_visible_in_scroll_ancestors(ui_tree, key, position))
+            if Shell::is_point_over_element(ui_tree, key, position)
                 || matches!(event, DragEvent::DragLeave)
             {
                 let element = &mut ui_tree.slots[key];
@@ -1106,14 +1234,6 @@ impl<Message> Shell<Message> {
     }
 }
 
-pub fn current_dpi(hwnd: HWND) -> u32 {
-    unsafe { GetDpiForWindow(hwnd) }
-}
-
-pub fn dips_scale(hwnd: HWND) -> f32 {
-    dips_scale_for_dpi(current_dpi(hwnd))
-}
-
 pub fn dips_scale_for_dpi(dpi: u32) -> f32 {
     96.0f32 / (dpi as f32).max(1.0)
 }
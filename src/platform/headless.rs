@@ -0,0 +1,11 @@
+//! Fallback used on targets without a native backend. Nothing is drawn to a window here, so
+//! every call is a no-op.
+
+use crate::widgets::Cursor;
+
+pub fn set_cursor(_cursor: Cursor) {}
+
+/// There is no system clipboard to read from.
+pub fn clipboard_text() -> Option<String> {
+    None
+}
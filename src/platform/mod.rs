@@ -0,0 +1,15 @@
+//! Host platform integration.
+//!
+//! Layout, widgets and the [`Shell`](crate::Shell) never talk to the OS directly. The few
+//! places where they need to (cursor changes, DPI queries, scheduling repaints) go through
+//! this module so the rest of the crate builds on any target.
+
+#[cfg(windows)]
+mod win32;
+#[cfg(windows)]
+pub use win32::*;
+
+#[cfg(not(windows))]
+mod headless;
+#[cfg(not(windows))]
+pub use headless::*;
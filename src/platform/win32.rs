@@ -0,0 +1,104 @@
+use std::time::Instant;
+
+use windows::Win32::{
+    Foundation::{HWND, RECT},
+    Graphics::Gdi::InvalidateRect,
+    System::Ole::DROPEFFECT,
+    UI::{
+        HiDpi::GetDpiForWindow,
+        WindowsAndMessaging::{
+            IDC_APPSTARTING, IDC_ARROW, IDC_CROSS, IDC_HAND, IDC_HELP, IDC_IBEAM, IDC_NO,
+            IDC_SIZEALL, IDC_SIZENESW, IDC_SIZENS, IDC_SIZENWSE, IDC_SIZEWE, IDC_UPARROW, IDC_WAIT,
+            KillTimer, LoadCursorW, SetCursor, SetTimer,
+        },
+    },
+};
+
+use crate::{
+    REDRAW_TIMER_ID, RedrawRequest,
+    gfx::RectDIP,
+    widgets::{Cursor, DropEffect},
+};
+
+pub fn set_cursor(cursor: Cursor) {
+    let id = match cursor {
+        Cursor::Arrow => IDC_ARROW,
+        Cursor::IBeam => IDC_IBEAM,
+        Cursor::Pointer => IDC_HAND,
+        Cursor::Cross => IDC_CROSS,
+        Cursor::Hand => IDC_HAND,
+        Cursor::Help => IDC_HELP,
+        Cursor::No => IDC_NO,
+        Cursor::SizeNS => IDC_SIZENS,
+        Cursor::SizeEW => IDC_SIZEWE,
+        Cursor::SizeNWSE => IDC_SIZENWSE,
+        Cursor::SizeNESW => IDC_SIZENESW,
+        Cursor::SizeAll => IDC_SIZEALL,
+        Cursor::UpArrow => IDC_UPARROW,
+        Cursor::Wait => IDC_WAIT,
+        Cursor::AppStarting => IDC_APPSTARTING,
+    };
+
+    unsafe {
+        let _ = SetCursor(Some(LoadCursorW(None, id).unwrap()));
+    }
+}
+
+pub fn current_dpi(hwnd: HWND) -> u32 {
+    unsafe { GetDpiForWindow(hwnd) }
+}
+
+pub fn dips_scale(hwnd: HWND) -> f32 {
+    crate::dips_scale_for_dpi(current_dpi(hwnd))
+}
+
+/// Applies a redraw request taken from the shell: invalidates the window for
+/// [`RedrawRequest::Immediate`], or arms the redraw timer for [`RedrawRequest::At`].
+/// Deadlines are measured from `now`, the shell's current time.
+pub fn schedule_redraw(hwnd: HWND, request: RedrawRequest, now: Instant) {
+    match request {
+        RedrawRequest::Immediate => unsafe {
+            let _ = InvalidateRect(Some(hwnd), None, false);
+        },
+        RedrawRequest::At(instant) => {
+            let uelapse = instant.saturating_duration_since(now).as_millis() as u32;
+            unsafe { SetTimer(Some(hwnd), REDRAW_TIMER_ID, uelapse, None) };
+        }
+        RedrawRequest::Wait => {}
+    }
+}
+
+pub fn kill_redraw_timer(hwnd: HWND) {
+    unsafe {
+        let _ = KillTimer(Some(hwnd), REDRAW_TIMER_ID);
+    }
+}
+
+impl RectDIP {
+    pub fn from(hwnd: HWND, rc: RECT) -> Self {
+        let to_dip = dips_scale(hwnd);
+
+        Self {
+            x: rc.left as f32 * to_dip,
+            y: rc.top as f32 * to_dip,
+            width: (rc.right - rc.left) as f32 * to_dip,
+            height: (rc.bottom - rc.top) as f32 * to_dip,
+        }
+    }
+}
+
+impl From<DROPEFFECT> for DropEffect {
+    fn from(effect: DROPEFFECT) -> Self {
+        Self(effect.0)
+    }
+}
+
+impl From<DropEffect> for DROPEFFECT {
+    fn from(effect: DropEffect) -> Self {
+        Self(effect.0)
+    }
+}
+
+pub fn clipboard_text() -> Option<String> {
+    crate::runtime::clipboard::get_clipboard_text(None)
+}
@@ -1,12 +1,15 @@
 use crate::dips_scale;
 use crate::gfx::draw_commands::DrawCommandList;
+use crate::gfx::text_engine::direct_write::DirectWriteTextEngine;
 use crate::gfx::{RectDIP, command_recorder::CommandRecorder};
 use crate::layout::model::{
-    Color, Direction, Element, ScrollbarStyle, Sizing, StrokeLineCap, create_tree,
+    Color, ScrollbarStyle, Sizing, StrokeLineCap, create_tree_root,
 };
 use crate::layout::{self, OwnedUITree};
-use crate::runtime::device::DeviceResources;
+use crate::runtime::DeviceResources;
+use crate::runtime::device::GpuResources;
 use crate::runtime::focus::FocusManager;
+use crate::runtime::clock::SystemClock;
 use crate::runtime::font_manager;
 use crate::runtime::input::{MiddleMouseScrollState, MouseState, ScrollbarDragState};
 use crate::runtime::scroll::{ScrollPosition, ScrollStateManager};
@@ -21,11 +24,12 @@ use crate::runtime::tray::{TrayEvent, TrayIcon, TrayIconConfig};
 use crate::widgets::Event;
 use crate::{
     HookManager, MAGIC_ID_TITLEBAR_CLOSE, MAGIC_ID_TITLEBAR_MAXIMIZE, MAGIC_ID_TITLEBAR_MINIMIZE,
-    RedrawRequest, Shell, UpdateFn, ViewFn, w_id,
+    RedrawRequest, Shell, UpdateFn, ViewFn,
 };
-use raxis_core::{self as raxis, svg};
+use raxis_core::{self as raxis, SvgPathList, svg};
 use raxis_proc_macro::svg_path;
 use std::cell::RefCell;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
@@ -34,7 +38,7 @@ use thiserror::Error;
 use windows::Win32::Foundation::{HMODULE, HWND, POINT};
 use windows::Win32::Graphics::Direct2D::{
     D2D1_DEBUG_LEVEL_NONE, D2D1_DEVICE_CONTEXT_OPTIONS_NONE, D2D1_FACTORY_OPTIONS,
-    D2D1_FACTORY_TYPE_SINGLE_THREADED, D2D1CreateFactory, ID2D1Factory7, ID2D1PathGeometry,
+    D2D1_FACTORY_TYPE_SINGLE_THREADED, D2D1CreateFactory, ID2D1Factory7,
 };
 use windows::Win32::Graphics::Direct3D::{
     D3D_DRIVER_TYPE_HARDWARE, D3D_DRIVER_TYPE_UNKNOWN, D3D_FEATURE_LEVEL_9_1, D3D_FEATURE_LEVEL_9_2, D3D_FEATURE_LEVEL_9_3, D3D_FEATURE_LEVEL_10_0, D3D_FEATURE_LEVEL_10_1, D3D_FEATURE_LEVEL_11_0, D3D_FEATURE_LEVEL_11_1
@@ -111,7 +115,9 @@ pub type Result<T> = std::result::Result<T, RuntimeError>;
 
 /// Main application state container managing the UI tree, rendering, input, and async tasks
 pub struct ApplicationHandle<State, Message> {
-    pub(crate) device_resources: Rc<RefCell<DeviceResources>>,
+    pub(crate) device_resources: Rc<RefCell<GpuResources>>,
+    /// Handed to widgets when they create their state
+    pub(crate) widget_resources: DeviceResources,
 
     pub(crate) clock: f64,
     pub(crate) last_frame_time: Instant,
@@ -139,10 +145,10 @@ pub struct ApplicationHandle<State, Message> {
     // Middle mouse scroll state (if any)
     pub(crate) middle_mouse_scroll: Option<MiddleMouseScrollState>,
 
-    // Cached geometries for middle mouse scroll indicators
-    pub(crate) scroll_icon_all: Option<ID2D1PathGeometry>,
-    pub(crate) scroll_icon_horizontal: Option<ID2D1PathGeometry>,
-    pub(crate) scroll_icon_vertical: Option<ID2D1PathGeometry>,
+    // Paths for middle mouse scroll indicators
+    pub(crate) scroll_icon_all: SvgPathList,
+    pub(crate) scroll_icon_horizontal: SvgPathList,
+    pub(crate) scroll_icon_vertical: SvgPathList,
 
     // Async task executor
     pub(crate) task_sender: mpsc::Sender<Task<Message>>,
@@ -162,6 +168,9 @@ pub struct ApplicationHandle<State, Message> {
 
     // Fallback background color for Windows 10 (where Mica/Acrylic aren't supported)
     pub(crate) fallback_background_color: Option<Color>,
+
+    // Where the event trace is saved when the window is destroyed, if recording
+    pub(crate) trace_file: Option<PathBuf>,
 }
 
 impl<State: 'static, Message: 'static + Send + Clone> ApplicationHandle<State, Message> {
@@ -281,7 +290,13 @@ impl<State: 'static, Message: 'static + Send + Clone> ApplicationHandle<State, M
                 .CreateTargetForHwnd(hwnd, true)
                 .map_err(RuntimeError::DcompTargetCreationFailed)?;
 
-            let device_resources = DeviceResources {
+            let resource_cache = RefCell::new(crate::gfx::resource_cache::ResourceCache::new(
+                dwrite_factory.clone(),
+            ));
+
+            let text_engine = Rc::new(DirectWriteTextEngine::new(dwrite_factory.clone()));
+
+            let device_resources = GpuResources {
                 d3d_device,
                 d3d_context,
                 d2d_factory,
@@ -299,6 +314,8 @@ impl<State: 'static, Message: 'static + Send + Clone> ApplicationHandle<State, M
                 dcomp_target,
                 dcomp_visual: None,
                 shadow_cache: RefCell::new(crate::widgets::renderer::ShadowCache::default()),
+                resource_cache,
+                text_engine,
                 effect_registry: RefCell::new(crate::gfx::effects::EffectRegistry::new()),
             };
 
@@ -316,14 +333,20 @@ impl<State: 'static, Message: 'static + Send + Clone> ApplicationHandle<State, M
             let mut scroll_state_manager = ScrollStateManager::default();
             let mut focus_manager = FocusManager::default();
 
+            let widget_resources = DeviceResources {
+                text_engine: device_resources.text_engine.clone(),
+                d2d_device_context: Some(device_resources.d2d_device_context.clone()),
+            };
+
             create_tree_root(
                 &user_state,
                 view_fn,
-                &device_resources,
+                &widget_resources,
                 &mut HookManager {
                     ui_tree: &mut ui_tree,
                     scroll_state_manager: &mut scroll_state_manager,
                     focus_manager: &mut focus_manager,
+                    clock: &SystemClock,
                     layout_invalidated: false,
                     requested_animation: false,
                     window_active: GetForegroundWindow() == hwnd,
@@ -348,6 +371,8 @@ impl<State: 'static, Message: 'static + Send + Clone> ApplicationHandle<State, M
                 event_mapper_fn,
                 scrollbar_style,
             );
+            let smooth_scroll_manager =
+                SmoothScrollManager::new().with_clock(shell.clock().clone());
 
             // Spawn executor thread with selected async runtime
             let task_executor_thread = {
@@ -364,7 +389,7 @@ impl<State: 'static, Message: 'static + Send + Clone> ApplicationHandle<State, M
                 let _ = tray.add(); // Ignore errors during initialization
             }
 
-            // Create cached geometries for middle mouse scroll icons
+            // Paths for middle mouse scroll icons
             let scroll_icon_all = svg![
                 svg_path!("M12 2v20"),
                 svg_path!("m15 19-3 3-3-3"),
@@ -372,37 +397,32 @@ impl<State: 'static, Message: 'static + Send + Clone> ApplicationHandle<State, M
                 svg_path!("M2 12h20"),
                 svg_path!("m5 9-3 3 3 3"),
                 svg_path!("m9 5 3-3 3 3"),
-            ]
-            .create_geometry(&device_resources.d2d_factory)
-            .ok();
+            ];
 
             let scroll_icon_horizontal = svg![
                 svg_path!("m18 8 4 4-4 4"),
                 svg_path!("M2 12h20"),
                 svg_path!("m6 8-4 4 4 4"),
-            ]
-            .create_geometry(&device_resources.d2d_factory)
-            .ok();
+            ];
 
             let scroll_icon_vertical = svg![
                 svg_path!("M12 2v20"),
                 svg_path!("m8 18 4 4 4-4"),
                 svg_path!("m8 6 4-4 4 4"),
-            ]
-            .create_geometry(&device_resources.d2d_factory)
-            .ok();
+            ];
 
             Ok(Self {
                 device_resources: Rc::new(RefCell::new(device_resources)),
+                widget_resources,
                 clock: 0.0,
-                last_frame_time: Instant::now(),
+                last_frame_time: shell.now(),
                 ui_tree,
                 view_fn,
                 update_fn,
                 _event_mapper_fn: event_mapper_fn,
                 user_state,
                 shell,
-                smooth_scroll_manager: SmoothScrollManager::new(),
+                smooth_scroll_manager,
                 drop_target: None,
                 pending_high_surrogate: None,
                 mouse_state: MouseState::default(),
@@ -419,12 +439,13 @@ impl<State: 'static, Message: 'static + Send + Clone> ApplicationHandle<State, M
                 syscommand_handler,
                 active_mouse_tracking_flags: TRACKMOUSEEVENT_FLAGS(0),
                 fallback_background_color,
+                trace_file: None,
             })
         }
     }
 
     pub fn on_paint(&mut self, hwnd: HWND) -> Result<DrawCommandList> {
-        let now = Instant::now();
+        let now = self.shell.now();
         let dt = now.duration_since(self.last_frame_time).as_secs_f64();
         self.last_frame_time = now;
 
@@ -453,7 +474,6 @@ impl<State: 'static, Message: 'static + Send + Clone> ApplicationHandle<State, M
 
             // Dispatch mouse move event
             self.shell.dispatch_event(
-                hwnd,
                 &mut self.ui_tree,
                 Event::MouseMove { x: x_dip, y: y_dip },
             );
@@ -465,6 +485,7 @@ impl<State: 'static, Message: 'static + Send + Clone> ApplicationHandle<State, M
                 ui_tree: &mut self.ui_tree,
                 scroll_state_manager: &mut self.shell.scroll_state_manager,
                 focus_manager: &mut self.shell.focus_manager,
+                clock: &*self.shell.clock,
                 layout_invalidated: false,
                 requested_animation: false,
                 window_active,
@@ -474,17 +495,17 @@ impl<State: 'static, Message: 'static + Send + Clone> ApplicationHandle<State, M
             create_tree_root(
                 &self.user_state,
                 self.view_fn,
-                &self.device_resources.borrow(),
+                &self.widget_resources,
                 &mut hook,
             );
             let invalidated = hook.layout_invalidated;
             if hook.requested_animation {
-                self.shell.request_redraw(hwnd, RedrawRequest::Immediate);
+                self.shell.request_redraw(RedrawRequest::Immediate);
             }
 
             let root = self.ui_tree.root;
 
-            let rc = crate::runtime::client_rect(hwnd).unwrap();
+            let rc = crate::runtime::util::client_rect(hwnd).unwrap();
             let rc_dip = RectDIP::from(hwnd, rc);
             self.ui_tree.slots[root].width = Sizing::fixed(rc_dip.width);
             self.ui_tree.slots[root].height = Sizing::fixed(rc_dip.height);
@@ -502,6 +523,7 @@ impl<State: 'static, Message: 'static + Send + Clone> ApplicationHandle<State, M
                 break;
             }
         }
+        self.shell.release_inert_elements(&mut self.ui_tree);
 
         // Update non-client hit test regions for custom titlebar buttons.
         // We capture the final computed border-box rects after layout.
@@ -582,9 +604,9 @@ impl<State: 'static, Message: 'static + Send + Clone> ApplicationHandle<State, M
             };
 
             let geometry = match scroll_config {
-                (true, true) => self.scroll_icon_all.as_ref(),
-                (true, false) => self.scroll_icon_horizontal.as_ref(),
-                (false, true) => self.scroll_icon_vertical.as_ref(),
+                (true, true) => Some(self.scroll_icon_all),
+                (true, false) => Some(self.scroll_icon_horizontal),
+                (false, true) => Some(self.scroll_icon_vertical),
                 _ => None,
             };
 
@@ -618,7 +640,7 @@ impl<State: 'static, Message: 'static + Send + Clone> ApplicationHandle<State, M
 
             // Append the icon commands to the main command list
             let icon_commands = recorder.take_commands();
-            commands.extend(icon_commands);
+            commands.append(icon_commands);
         }
 
         self.clock += dt;
@@ -626,7 +648,7 @@ impl<State: 'static, Message: 'static + Send + Clone> ApplicationHandle<State, M
         // Request continuous redraws if middle mouse scrolling is active
         if self.middle_mouse_scroll.is_some() {
             self.shell
-                .request_redraw(hwnd, crate::RedrawRequest::Immediate);
+                .request_redraw(crate::RedrawRequest::Immediate);
         }
 
         Ok(commands)
@@ -639,6 +661,16 @@ impl<State: 'static, Message: 'static + Send + Clone> ApplicationHandle<State, M
         Ok(())
     }
 
+    /// Stops recording and writes the trace to the configured trace file.
+    pub fn save_trace(&mut self) {
+        if let Some(path) = self.trace_file.take()
+            && let Some(trace) = self.shell.stop_recording()
+            && let Err(e) = trace.save(&path)
+        {
+            log::error!("Failed to save event trace: {e}");
+        }
+    }
+
     pub fn hit_test_scrollbar_thumb(
         &mut self,
         x: f32,
@@ -692,12 +724,13 @@ impl<State: 'static, Message: 'static + Send + Clone> ApplicationHandle<State, M
 
     fn update_smooth_scroll_animations(&mut self) -> bool {
         self.smooth_scroll_manager.update_animations();
+        let now = self.smooth_scroll_manager.now();
 
         let mut any_scrolling = false;
 
         // Apply current animated positions to the scroll state manager
         for (&element_id, animation) in self.smooth_scroll_manager.get_active_animations() {
-            let current_pos = animation.current_position(std::time::Instant::now());
+            let current_pos = animation.current_position(now);
             let prev_pos = self
                 .shell
                 .scroll_state_manager
@@ -789,31 +822,3 @@ impl<State: 'static, Message: 'static + Send + Clone> ApplicationHandle<State, M
         let _ = self.task_sender.send(task);
     }
 }
-
-fn create_tree_root<State: 'static, Message>(
-    state: &State,
-    view_fn: ViewFn<State, Message>,
-    device_resources: &DeviceResources,
-    hook_manager: &mut HookManager<Message>,
-) {
-    let children = view_fn(state, hook_manager);
-    create_tree(
-        device_resources,
-        hook_manager.ui_tree,
-        Element {
-            id: Some(w_id!()),
-            direction: Direction::ZStack,
-            children: vec![Element {
-                id: Some(w_id!()),
-                direction: Direction::ZStack,
-                children: vec![children],
-                width: Sizing::grow(),
-                height: Sizing::grow(),
-
-                ..Default::default()
-            }],
-
-            ..Default::default()
-        },
-    )
-}
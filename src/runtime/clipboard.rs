@@ -43,10 +43,10 @@ pub fn set_clipboard_text(hwnd: HWND, s: &str) -> Result<()> {
     Ok(())
 }
 
-pub fn get_clipboard_text(hwnd: HWND) -> Option<String> {
+pub fn get_clipboard_text(hwnd: Option<HWND>) -> Option<String> {
     unsafe {
         if IsClipboardFormatAvailable(CF_UNICODETEXT.0.into()).is_ok()
-            && OpenClipboard(Some(hwnd)).is_ok()
+            && OpenClipboard(hwnd).is_ok()
         {
             let h = GetClipboardData(CF_UNICODETEXT.0.into());
             if let Ok(h) = h {
@@ -0,0 +1,66 @@
+//! Time source for animations and redraw scheduling.
+//!
+//! Everything that moves over time asks the [`Shell`](crate::Shell) for the
+//! current instant instead of calling [`Instant::now`], so tests can swap in a
+//! [`ManualClock`] and step through an animation frame by frame.
+
+use std::cell::Cell;
+use std::fmt::Debug;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+pub trait Clock: Debug {
+    fn now(&self) -> Instant;
+}
+
+/// Reads the system's monotonic clock
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when told to.
+///
+/// Clones share the same time, so a test can keep one handle and advance it
+/// while the application reads from another.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    now: Rc<Cell<Instant>>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ManualClock {
+    /// Starts the clock at the current system time.
+    pub fn new() -> Self {
+        Self::starting_at(Instant::now())
+    }
+
+    pub fn starting_at(now: Instant) -> Self {
+        Self {
+            now: Rc::new(Cell::new(now)),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+
+    pub fn set(&self, now: Instant) {
+        self.now.set(now);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+}
@@ -1,4 +1,5 @@
 use futures::channel::oneshot;
+#[cfg(windows)]
 use windows::Win32::{
     Foundation::{HWND, LPARAM, POINT, WPARAM},
     UI::WindowsAndMessaging::{
@@ -8,11 +9,14 @@ use windows::Win32::{
         TrackPopupMenuEx, WM_USER,
     },
 };
+#[cfg(windows)]
 use windows_core::PCWSTR;
 
+#[cfg(windows)]
 use crate::runtime::UncheckedHWND;
 
 /// Custom window message for showing context menus on the UI thread
+#[cfg(windows)]
 pub const WM_SHOW_CONTEXT_MENU: u32 = WM_USER + 200;
 
 /// A context menu that can be displayed to the user
@@ -46,7 +50,10 @@ impl ContextMenu {
     pub fn new(items: Vec<ContextMenuItem>) -> Self {
         Self { items }
     }
+}
 
+#[cfg(windows)]
+impl ContextMenu {
     /// Posts a request to show the context menu on the UI thread and returns the selected index
     ///
     /// This function sends the menu request to the UI thread via a window message,
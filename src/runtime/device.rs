@@ -1,7 +1,10 @@
 use crate::gfx::effects::EffectRegistry;
+use crate::gfx::resource_cache::ResourceCache;
+use crate::gfx::text_engine::TextEngine;
 use crate::widgets::renderer::ShadowCache;
 use std::cell::RefCell;
 use std::mem::ManuallyDrop;
+use std::rc::Rc;
 use thiserror::Error;
 use windows::Win32::Foundation::HWND;
 use windows::Win32::Graphics::Direct2D::Common::{
@@ -50,7 +53,7 @@ pub type Result<T> = std::result::Result<T, DeviceError>;
 
 /// Manages all Direct3D, Direct2D, DXGI, and DirectComposition device resources
 /// for the rendering pipeline.
-pub struct DeviceResources {
+pub struct GpuResources {
     // Rendering resources (recreated on resize/DPI change)
     pub solid_brush: Option<ID2D1SolidColorBrush>,
     pub d2d_target_bitmap: Option<ID2D1Bitmap1>,
@@ -76,11 +79,17 @@ pub struct DeviceResources {
     // Shadow rendering cache
     pub shadow_cache: RefCell<ShadowCache>,
 
+    // Direct2D realizations of draw command resources
+    pub resource_cache: RefCell<ResourceCache>,
+
+    // Text layout and measurement for widgets
+    pub text_engine: Rc<dyn TextEngine>,
+
     // Custom effects registry
     pub effect_registry: RefCell<EffectRegistry>,
 }
 
-impl DeviceResources {
+impl GpuResources {
     /// Creates rendering resources (swap chain, render target, brush) for the given window dimensions.
     /// This is called during initialization and after window resize/DPI changes.
     pub fn create_device_resources(&mut self, hwnd: HWND, width: u32, height: u32) -> Result<()> {
@@ -203,6 +212,7 @@ impl DeviceResources {
         self.back_buffer = None;
         self.d2d_target_bitmap = None;
         self.shadow_cache.borrow_mut().clear();
+        self.resource_cache.borrow_mut().clear();
 
         unsafe {
             self.d2d_device_context.SetTarget(None);
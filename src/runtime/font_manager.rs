@@ -9,46 +9,9 @@ use windows::Win32::Graphics::DirectWrite::{
 };
 use windows::core::{PCWSTR, Result};
 
-/// Identifies a font that can be either a system font or a custom loaded font
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub enum FontIdentifier {
-    /// System font identified by family name (e.g., "Segoe UI", "Arial")
-    System(String),
-    /// Custom font identified by a unique name
-    Custom(String),
-}
-
-impl FontIdentifier {
-    /// Create a system font identifier
-    pub fn system(family_name: impl Into<String>) -> Self {
-        Self::System(family_name.into())
-    }
-
-    /// Create a custom font identifier
-    pub fn custom(name: impl Into<String>) -> Self {
-        Self::Custom(name.into())
-    }
-
-    /// Get the family name for font creation
-    pub fn family_name(&self) -> &str {
-        match self {
-            FontIdentifier::System(name) => name,
-            FontIdentifier::Custom(name) => name,
-        }
-    }
-}
-
-impl From<&str> for FontIdentifier {
-    fn from(family_name: &str) -> Self {
-        Self::System(family_name.to_string())
-    }
-}
-
-impl From<String> for FontIdentifier {
-    fn from(family_name: String) -> Self {
-        Self::System(family_name)
-    }
-}
+pub use crate::gfx::text_style::{
+    FontAxes, FontIdentifier, FontStyle, FontWeight, FontWidth, LineSpacing,
+};
 
 /// Manages custom font loading and provides unified font access
 pub struct FontManager {
@@ -58,189 +21,6 @@ pub struct FontManager {
     memory_font_loader: Option<IDWriteInMemoryFontFileLoader>,
 }
 
-/// Font weight values (100-900, where 400 is normal, 700 is bold)
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
-pub enum FontWeight {
-    Thin,       // 100
-    ExtraLight, // 200
-    Light,      // 300
-    #[default]
-    Normal, // 400
-    Medium,     // 500
-    SemiBold,   // 600
-    Bold,       // 700
-    ExtraBold,  // 800
-    Black,      // 900
-    Custom(f32), // Custom weight value
-}
-
-impl FontWeight {
-    pub fn value(&self) -> f32 {
-        match self {
-            FontWeight::Thin => 100.0,
-            FontWeight::ExtraLight => 200.0,
-            FontWeight::Light => 300.0,
-            FontWeight::Normal => 400.0,
-            FontWeight::Medium => 500.0,
-            FontWeight::SemiBold => 600.0,
-            FontWeight::Bold => 700.0,
-            FontWeight::ExtraBold => 800.0,
-            FontWeight::Black => 900.0,
-            FontWeight::Custom(value) => *value,
-        }
-    }
-}
-
-impl std::hash::Hash for FontWeight {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.value().to_bits().hash(state);
-    }
-}
-
-/// Font style (italic/oblique)
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
-pub enum FontStyle {
-    #[default]
-    Normal,
-    Italic,
-    Oblique(f32), // Custom oblique angle
-}
-
-impl FontStyle {
-    pub fn italic_value(&self) -> f32 {
-        match self {
-            FontStyle::Normal => 0.0,
-            FontStyle::Italic => 1.0,
-            FontStyle::Oblique(_) => 1.0,
-        }
-    }
-
-    pub fn slant_value(&self) -> f32 {
-        match self {
-            FontStyle::Normal => 0.0,
-            FontStyle::Italic => -20.0, // Standard italic slant
-            FontStyle::Oblique(angle) => *angle,
-        }
-    }
-}
-
-impl std::hash::Hash for FontStyle {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.italic_value().to_bits().hash(state);
-        self.slant_value().to_bits().hash(state);
-    }
-}
-
-/// Font width/stretch values
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
-pub enum FontWidth {
-    UltraCondensed, // 50%
-    ExtraCondensed, // 62.5%
-    Condensed,      // 75%
-    SemiCondensed,  // 87.5%
-    #[default]
-    Normal, // 100%
-    SemiExpanded,   // 112.5%
-    Expanded,       // 125%
-    ExtraExpanded,  // 150%
-    UltraExpanded,  // 200%
-    Custom(f32),    // Custom width percentage
-}
-
-impl FontWidth {
-    pub fn value(&self) -> f32 {
-        match self {
-            FontWidth::UltraCondensed => 50.0,
-            FontWidth::ExtraCondensed => 62.5,
-            FontWidth::Condensed => 75.0,
-            FontWidth::SemiCondensed => 87.5,
-            FontWidth::Normal => 100.0,
-            FontWidth::SemiExpanded => 112.5,
-            FontWidth::Expanded => 125.0,
-            FontWidth::ExtraExpanded => 150.0,
-            FontWidth::UltraExpanded => 200.0,
-            FontWidth::Custom(value) => *value,
-        }
-    }
-}
-
-impl std::hash::Hash for FontWidth {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.value().to_bits().hash(state);
-    }
-}
-
-/// Collection of font axes for variable font support
-#[derive(Debug, Clone, Copy, PartialEq, Default, Hash)]
-pub struct FontAxes {
-    pub weight: FontWeight,
-    pub style: FontStyle,
-    pub width: FontWidth,
-}
-
-impl FontAxes {
-    pub fn new() -> Self {
-        Self::default()
-    }
-
-    pub fn with_weight(mut self, weight: FontWeight) -> Self {
-        self.weight = weight;
-        self
-    }
-
-    pub fn with_style(mut self, style: FontStyle) -> Self {
-        self.style = style;
-        self
-    }
-
-    pub fn with_width(mut self, width: FontWidth) -> Self {
-        self.width = width;
-        self
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct LineSpacing {
-    pub height: f32,
-    pub baseline: f32,
-}
-
-impl std::hash::Hash for LineSpacing {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.height.to_bits().hash(state);
-        self.baseline.to_bits().hash(state);
-    }
-}
-
-impl LineSpacing {
-    pub fn new(height: f32, baseline: f32) -> Self {
-        Self { height, baseline }
-    }
-
-    pub fn of_height(height: f32) -> Self {
-        Self {
-            height,
-            baseline: 1.0,
-        }
-    }
-
-    pub fn of_baseline(baseline: f32) -> Self {
-        Self {
-            height: 1.0,
-            baseline,
-        }
-    }
-}
-
-impl Default for LineSpacing {
-    fn default() -> Self {
-        Self {
-            height: 1.0,
-            baseline: 1.0,
-        }
-    }
-}
-
 impl FontManager {
     /// Create a new FontManager
     pub fn new(dwrite_factory: IDWriteFactory6) -> Result<Self> {
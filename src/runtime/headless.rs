@@ -0,0 +1,1487 @@
+//! Windowless application driver.
+//!
+//! [`HeadlessApp`] runs an application's view/update loop against an in-memory
+//! viewport so widget behavior can be exercised from tests: inject [`Event`]s,
+//! pump tasks until idle, then inspect the published messages and resulting state.
+
+use std::rc::Rc;
+use std::sync::mpsc;
+
+use futures::StreamExt;
+use futures::executor::LocalPool;
+use futures::task::LocalSpawnExt;
+use smol_str::SmolStr;
+
+use crate::gfx::RectDIP;
+use crate::gfx::draw_commands::DrawCommandList;
+use crate::gfx::software::{RgbaImage, SoftwareRenderer};
+use crate::gfx::svg_export::SvgExporter;
+use crate::gfx::text_engine::TextEngine;
+use crate::layout::dump::LayoutDump;
+use crate::layout::model::{Axis, ScrollbarStyle, Sizing, create_tree_root};
+use crate::layout::{self, OwnedUITree};
+use crate::runtime::clock::{Clock, ManualClock};
+use crate::runtime::focus::FocusManager;
+use crate::runtime::scroll::{
+    LINE_HEIGHT, ScrollDirection, ScrollPosition, ScrollStateManager, can_scroll_further,
+};
+use crate::runtime::task::{
+    Action, ClipboardAction, ContextMenuAction, Task, WindowAction, WindowMode, into_stream,
+};
+use crate::runtime::trace::{Trace, TraceRecord};
+use crate::runtime::vkey::VKey;
+use crate::widgets::{DropEffect, Event, Modifiers};
+use crate::{
+    ContextMenuItem, DeferredControl, EventMapperFn, HookManager, RedrawRequest, Shell, UpdateFn,
+    ViewFn,
+};
+
+use super::DeviceResources;
+
+/// Allow at most this many layout passes per frame, otherwise assume an infinite loop
+const MAX_LAYOUT_PASSES: usize = 5;
+
+/// Lines scrolled per wheel notch, matching the Windows default
+const WHEEL_SCROLL_LINES: f32 = 3.0;
+
+type ContextMenuHandler = Box<dyn FnMut(&[ContextMenuItem]) -> Option<usize>>;
+
+/// Drives an application without a window.
+///
+/// Tasks run on a single-threaded executor owned by the app and only make
+/// progress inside [`HeadlessApp::pump_tasks`], so tests stay deterministic.
+/// Platform actions are simulated: the clipboard is an in-memory string, window
+/// actions update [`HeadlessApp::window_mode`] and context menus are answered
+/// by the handler set with [`HeadlessApp::with_context_menu_handler`].
+pub struct HeadlessApp<State, Message> {
+    view_fn: ViewFn<State, Message>,
+    update_fn: UpdateFn<State, Message>,
+    user_state: State,
+
+    device_resources: DeviceResources,
+    ui_tree: OwnedUITree<Message>,
+    shell: Shell<Message>,
+
+    width: f32,
+    height: f32,
+    /// Pixels per DIP
+    dpi_scale: f32,
+    commands: DrawCommandList,
+
+    executor: LocalPool,
+    task_receiver: mpsc::Receiver<Task<Message>>,
+    message_sender: mpsc::Sender<Message>,
+    message_receiver: mpsc::Receiver<Message>,
+    action_sender: mpsc::Sender<Action<Message>>,
+    action_receiver: mpsc::Receiver<Action<Message>>,
+
+    published: Vec<Message>,
+
+    clipboard: Option<String>,
+    window_mode: WindowMode,
+    window_active: bool,
+    window_zoomed: bool,
+    exit_requested: bool,
+    opened_urls: Vec<String>,
+    context_menu_handler: Option<ContextMenuHandler>,
+}
+
+impl<State: 'static, Message: 'static + Send + Clone> HeadlessApp<State, Message> {
+    /// Creates the app, runs `boot_fn` and renders the first frame at 800x600.
+    ///
+    /// The boot task is queued but not run until [`HeadlessApp::pump_tasks`].
+    pub fn new(
+        state: State,
+        view_fn: ViewFn<State, Message>,
+        update_fn: UpdateFn<State, Message>,
+        boot_fn: impl Fn(&State) -> Option<Task<Message>>,
+    ) -> Self {
+        let (task_sender, task_receiver) = mpsc::channel::<Task<Message>>();
+        let (message_sender, message_receiver) = mpsc::channel::<Message>();
+        let (action_sender, action_receiver) = mpsc::channel::<Action<Message>>();
+
+        if let Some(boot_task) = boot_fn(&state) {
+            let _ = task_sender.send(boot_task);
+        }
+
+        let shell = Shell::new(
+            message_sender.clone(),
+            task_sender,
+            ScrollStateManager::default(),
+            FocusManager::default(),
+            |_, _| None,
+            ScrollbarStyle::default(),
+        );
+
+        let mut app = Self {
+            view_fn,
+            update_fn,
+            user_state: state,
+            device_resources: DeviceResources::default(),
+            ui_tree: OwnedUITree::default(),
+            shell,
+            width: 800.0,
+            height: 600.0,
+            dpi_scale: 1.0,
+            commands: DrawCommandList::new(),
+            executor: LocalPool::new(),
+            task_receiver,
+            message_sender,
+            message_receiver,
+            action_sender,
+            action_receiver,
+            published: Vec::new(),
+            clipboard: None,
+            window_mode: WindowMode::Windowed,
+            window_active: true,
+            window_zoomed: false,
+            exit_requested: false,
+            opened_urls: Vec::new(),
+            context_menu_handler: None,
+        };
+
+        app.render();
+        app
+    }
+
+    pub fn with_viewport_size(mut self, width: f32, height: f32) -> Self {
+        self.set_viewport_size(width, height);
+        self
+    }
+
+    /// Lays out and snapshots as if on a display with `dpi_scale` pixels per DIP
+    /// (1.0 at 96 DPI, 1.5 at 144 DPI, ...).
+    pub fn with_dpi_scale(mut self, dpi_scale: f32) -> Self {
+        self.dpi_scale = dpi_scale;
+        self.render();
+        self
+    }
+
+    /// Measures and draws text with `text_engine` instead of the default
+    /// [`TtfTextEngine`](crate::gfx::text_engine::ttf::TtfTextEngine) with DejaVu
+    /// Sans. Widget state is rebuilt so it picks up the engine.
+    pub fn with_text_engine(mut self, text_engine: Rc<dyn TextEngine>) -> Self {
+        self.device_resources.text_engine = text_engine;
+        self.ui_tree = OwnedUITree::default();
+        self.render();
+        self
+    }
+
+    /// Reads time from `clock` instead of the system clock.
+    ///
+    /// With a [`ManualClock`](crate::runtime::clock::ManualClock) animations only
+    /// progress when the test advances it; see [`HeadlessApp::redraw_if_due`].
+    pub fn with_clock(mut self, clock: Rc<dyn Clock>) -> Self {
+        self.shell.set_clock(clock);
+        self.render();
+        self
+    }
+
+    pub fn with_event_mapper(mut self, event_mapper_fn: EventMapperFn<Message>) -> Self {
+        self.shell.event_mapper = event_mapper_fn;
+        self
+    }
+
+    pub fn with_scrollbar_style(mut self, scrollbar_style: ScrollbarStyle) -> Self {
+        self.shell.scrollbar_style = scrollbar_style;
+        self.render();
+        self
+    }
+
+    /// Answers context menu requests with the index of the chosen item, or `None` to cancel.
+    ///
+    /// Without a handler every context menu is cancelled.
+    pub fn with_context_menu_handler(
+        mut self,
+        handler: impl FnMut(&[ContextMenuItem]) -> Option<usize> + 'static,
+    ) -> Self {
+        self.context_menu_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Resizes the viewport (in DIPs) and lays the tree out again.
+    pub fn set_viewport_size(&mut self, width: f32, height: f32) {
+        self.width = width;
+        self.height = height;
+        self.shell.record_viewport(width, height);
+        self.render();
+    }
+
+    pub fn viewport_size(&self) -> (f32, f32) {
+        (self.width, self.height)
+    }
+
+    pub fn dpi_scale(&self) -> f32 {
+        self.dpi_scale
+    }
+
+    pub fn state(&self) -> &State {
+        &self.user_state
+    }
+
+    /// Mutates the state directly and renders the result, bypassing `update_fn`.
+    pub fn update_state(&mut self, f: impl FnOnce(&mut State)) {
+        f(&mut self.user_state);
+        self.render();
+    }
+
+    pub fn ui_tree(&self) -> &OwnedUITree<Message> {
+        &self.ui_tree
+    }
+
+    pub fn shell(&self) -> &Shell<Message> {
+        &self.shell
+    }
+
+    /// The redraw the most recent frame asked for.
+    pub fn redraw_request(&self) -> RedrawRequest {
+        self.shell.redraw_request
+    }
+
+    /// Renders a frame if the last one asked to be redrawn by now, as told by
+    /// the shell's clock. Returns whether a frame was rendered.
+    pub fn redraw_if_due(&mut self) -> bool {
+        let due = match self.shell.redraw_request {
+            RedrawRequest::Immediate => true,
+            RedrawRequest::At(deadline) => deadline <= self.shell.now(),
+            RedrawRequest::Wait => false,
+        };
+        if due {
+            self.render();
+        }
+        due
+    }
+
+    /// The draw commands produced by the most recent frame.
+    pub fn commands(&self) -> &DrawCommandList {
+        &self.commands
+    }
+
+    /// Every message passed to `update_fn` so far, in order.
+    pub fn messages(&self) -> &[Message] {
+        &self.published
+    }
+
+    pub fn take_messages(&mut self) -> Vec<Message> {
+        std::mem::take(&mut self.published)
+    }
+
+    pub fn clipboard(&self) -> Option<&str> {
+        self.clipboard.as_deref()
+    }
+
+    pub fn set_clipboard(&mut self, text: impl Into<String>) {
+        self.clipboard = Some(text.into());
+    }
+
+    pub fn window_mode(&self) -> WindowMode {
+        self.window_mode
+    }
+
+    pub fn is_window_zoomed(&self) -> bool {
+        self.window_zoomed
+    }
+
+    /// Whether a task asked to close the window or exit the application.
+    pub fn exit_requested(&self) -> bool {
+        self.exit_requested
+    }
+
+    pub fn opened_urls(&self) -> &[String] {
+        &self.opened_urls
+    }
+
+    /// Returns the border box of the element with the given ID as of the last frame.
+    pub fn element_bounds(&mut self, id: u64) -> Option<RectDIP> {
+        let key = Shell::find_key_by_id(&mut self.ui_tree, id)?;
+        Some(self.ui_tree.slots[key].bounds().border_box)
+    }
+
+    /// Runs the view function, lays out the tree and paints it.
+    pub fn render(&mut self) -> &DrawCommandList {
+        self.shell.replace_redraw_request(RedrawRequest::Wait);
+
+        if !self.ui_tree.slots.is_empty() {
+            let now = self.shell.now();
+            self.shell
+                .dispatch_event(&mut self.ui_tree, Event::Redraw { now });
+        }
+
+        for _ in 0..MAX_LAYOUT_PASSES {
+            let mut hook = HookManager {
+                ui_tree: &mut self.ui_tree,
+                scroll_state_manager: &mut self.shell.scroll_state_manager,
+                focus_manager: &mut self.shell.focus_manager,
+                clock: &*self.shell.clock,
+                layout_invalidated: false,
+                requested_animation: false,
+                window_active: self.window_active,
+                window_zoomed: self.window_zoomed,
+            };
+
+            create_tree_root(
+                &self.user_state,
+                self.view_fn,
+                &self.device_resources,
+                &mut hook,
+            );
+            let invalidated = hook.layout_invalidated;
+            if hook.requested_animation {
+                self.shell.request_redraw(RedrawRequest::Immediate);
+            }
+
+            let root = self.ui_tree.root;
+            self.ui_tree.slots[root].width = Sizing::fixed(self.width);
+            self.ui_tree.slots[root].height = Sizing::fixed(self.height);
+
+            layout::layout(
+                &mut self.ui_tree,
+                root,
+                &mut self.shell.scroll_state_manager,
+                1.0 / self.dpi_scale,
+            );
+
+            if !invalidated {
+                break;
+            }
+        }
+        self.shell.release_inert_elements(&mut self.ui_tree);
+
+        let root = self.ui_tree.root;
+        self.commands = layout::paint(&mut self.shell, &mut self.ui_tree, root);
+        self.shell.take_redraw_request();
+
+        &self.commands
+    }
+
+    /// Rasterizes the most recent frame on the CPU at the app's DPI scale.
+    ///
+    /// The image covers the whole viewport, rounded up to whole pixels.
+    pub fn snapshot(&self) -> RgbaImage {
+        let mut renderer = SoftwareRenderer::new(
+            (self.width * self.dpi_scale).ceil() as u32,
+            (self.height * self.dpi_scale).ceil() as u32,
+        )
+        .with_dip_scale(self.dpi_scale)
+        .with_text_engine(self.device_resources.text_engine.clone());
+        renderer.execute_commands(&self.commands);
+        renderer.into_image()
+    }
+
+    /// Captures the layout of the most recent frame.
+    pub fn layout_dump(&self) -> LayoutDump {
+        self.ui_tree.dump(&self.shell.scroll_state_manager)
+    }
+
+    /// Exports the most recent frame as an SVG document covering the viewport.
+    pub fn to_svg(&self) -> String {
+        SvgExporter::new(self.width, self.height)
+            .with_text_engine(self.device_resources.text_engine.clone())
+            .export(&self.commands)
+    }
+
+    /// Starts recording dispatched events and viewport changes into a [`Trace`].
+    pub fn start_recording(&mut self) {
+        self.shell.start_recording();
+        self.shell.record_viewport(self.width, self.height);
+    }
+
+    /// Stops recording and returns the trace, if recording.
+    pub fn stop_recording(&mut self) -> Option<Trace> {
+        self.shell.stop_recording()
+    }
+
+    /// Feeds a recorded session back through the app.
+    ///
+    /// The app switches to a [`ManualClock`] that is set to each entry's
+    /// timestamp before it is applied. Tasks are pumped after every event, and
+    /// frames are rendered where the recording redrew, so widgets see the same
+    /// layout they did when the trace was taken. Drag and drop sessions end
+    /// without a target, as they always do headless.
+    pub fn replay(&mut self, trace: &Trace) {
+        let clock = ManualClock::new();
+        let start = clock.now();
+        self.shell.set_clock(Rc::new(clock.clone()));
+
+        for entry in &trace.entries {
+            clock.set(start + entry.at);
+            match &entry.record {
+                TraceRecord::Viewport { width, height } => {
+                    self.width = *width;
+                    self.height = *height;
+                    self.shell.record_viewport(*width, *height);
+                }
+                TraceRecord::Event(Event::Redraw { .. }) => {
+                    self.render();
+                }
+                TraceRecord::Event(Event::MouseWheel {
+                    x,
+                    y,
+                    wheel_delta,
+                    modifiers,
+                }) => {
+                    self.dispatch_wheel(*x, *y, *wheel_delta, *modifiers);
+                    self.settle();
+                }
+                TraceRecord::Event(event) => {
+                    self.shell.dispatch_event(&mut self.ui_tree, event.clone());
+                    self.settle();
+                }
+            }
+        }
+
+        self.render();
+    }
+
+    /// Dispatches an event, pumps tasks until idle and renders the next frame.
+    pub fn dispatch_event(&mut self, event: Event) {
+        self.shell.dispatch_event(&mut self.ui_tree, event);
+        self.after_dispatch();
+    }
+
+    pub fn mouse_move(&mut self, x: f32, y: f32) {
+        self.dispatch_event(Event::MouseMove { x, y });
+    }
+
+    pub fn mouse_down(&mut self, x: f32, y: f32) {
+        self.dispatch_event(Event::MouseButtonDown {
+            x,
+            y,
+            click_count: 1,
+            modifiers: Modifiers::default(),
+        });
+    }
+
+    pub fn mouse_up(&mut self, x: f32, y: f32) {
+        self.dispatch_event(Event::MouseButtonUp {
+            x,
+            y,
+            click_count: 1,
+            modifiers: Modifiers::default(),
+        });
+    }
+
+    /// Moves the mouse to the given point and presses and releases the left button there.
+    pub fn click(&mut self, x: f32, y: f32) {
+        self.mouse_move(x, y);
+        self.mouse_down(x, y);
+        self.mouse_up(x, y);
+    }
+
+    /// Clicks the center of the element with the given ID.
+    ///
+    /// Returns false if no such element exists.
+    pub fn click_element(&mut self, id: u64) -> bool {
+        let Some(bounds) = self.element_bounds(id) else {
+            return false;
+        };
+
+        self.click(
+            bounds.x + bounds.width / 2.0,
+            bounds.y + bounds.height / 2.0,
+        );
+        true
+    }
+
+    /// Scrolls by `wheel_delta` notches at the given point; positive values scroll down.
+    ///
+    /// Scrolling is applied immediately rather than animated.
+    pub fn mouse_wheel(&mut self, x: f32, y: f32, wheel_delta: f32) {
+        self.wheel(x, y, wheel_delta, Modifiers::default());
+    }
+
+    /// Like [`HeadlessApp::mouse_wheel`], but scrolls horizontally.
+    pub fn mouse_wheel_horizontal(&mut self, x: f32, y: f32, wheel_delta: f32) {
+        self.wheel(
+            x,
+            y,
+            wheel_delta,
+            Modifiers {
+                shift: true,
+                ..Default::default()
+            },
+        );
+    }
+
+    pub fn key_down(&mut self, key: VKey, modifiers: Modifiers) {
+        self.dispatch_event(Event::KeyDown { key, modifiers });
+    }
+
+    pub fn key_up(&mut self, key: VKey, modifiers: Modifiers) {
+        self.dispatch_event(Event::KeyUp { key, modifiers });
+    }
+
+    pub fn key_press(&mut self, key: VKey, modifiers: Modifiers) {
+        self.key_down(key, modifiers);
+        self.key_up(key, modifiers);
+    }
+
+    /// Sends one [`Event::Char`] per character of `text`.
+    pub fn type_text(&mut self, text: &str) {
+        for ch in text.chars() {
+            self.dispatch_event(Event::Char {
+                text: SmolStr::new(ch.encode_utf8(&mut [0; 4])),
+            });
+        }
+    }
+
+    /// Sends a message to `update_fn` as if a widget had published it, then renders.
+    pub fn publish(&mut self, message: Message) {
+        let _ = self.message_sender.send(message);
+        self.pump_tasks();
+        self.render();
+    }
+
+    /// Runs queued tasks and feeds their output through `update_fn` until nothing is left to do.
+    ///
+    /// Tasks waiting on something other than the app itself (timers, I/O) are left pending.
+    pub fn pump_tasks(&mut self) {
+        loop {
+            let mut progressed = false;
+
+            while let Ok(task) = self.task_receiver.try_recv() {
+                self.spawn_task(task);
+                progressed = true;
+            }
+
+            self.executor.run_until_stalled();
+
+            while let Ok(action) = self.action_receiver.try_recv() {
+                self.perform_action(action);
+                progressed = true;
+            }
+
+            while let Ok(message) = self.message_receiver.try_recv() {
+                self.published.push(message.clone());
+                if let Some(task) = (self.update_fn)(&mut self.user_state, message) {
+                    self.spawn_task(task);
+                }
+                progressed = true;
+            }
+
+            if !progressed {
+                break;
+            }
+        }
+    }
+
+    fn wheel(&mut self, x: f32, y: f32, wheel_delta: f32, modifiers: Modifiers) {
+        self.dispatch_wheel(x, y, wheel_delta, modifiers);
+        self.after_dispatch();
+    }
+
+    /// Dispatches a wheel event and scrolls the nearest scrollable ancestor if
+    /// no widget captured it.
+    fn dispatch_wheel(&mut self, x: f32, y: f32, wheel_delta: f32, modifiers: Modifiers) {
+        self.shell.dispatch_event(
+            &mut self.ui_tree,
+            Event::MouseWheel {
+                x,
+                y,
+                wheel_delta,
+                modifiers,
+            },
+        );
+
+        if self.shell.capture_event(0)
+            && let Some(innermost_key) = Shell::find_innermost_element_at(&mut self.ui_tree, x, y)
+        {
+            let axis = if modifiers.shift { Axis::X } else { Axis::Y };
+            let direction = if wheel_delta > 0.0 {
+                ScrollDirection::Positive
+            } else {
+                ScrollDirection::Negative
+            };
+
+            for key in Shell::collect_ancestry(&mut self.ui_tree, innermost_key) {
+                let element = &self.ui_tree.slots[key];
+                if element.scroll.is_none()
+                    || !can_scroll_further(
+                        element,
+                        axis,
+                        direction,
+                        &self.shell.scroll_state_manager,
+                    )
+                {
+                    continue;
+                }
+
+                let Some(element_id) = element.id else {
+                    continue;
+                };
+
+                let delta = wheel_delta * LINE_HEIGHT as f32 * WHEEL_SCROLL_LINES;
+                let current = self
+                    .shell
+                    .scroll_state_manager
+                    .get_scroll_position(element_id);
+                let position = match axis {
+                    Axis::X => ScrollPosition {
+                        x: current.x + delta,
+                        ..current
+                    },
+                    Axis::Y => ScrollPosition {
+                        y: current.y + delta,
+                        ..current
+                    },
+                };
+                self.shell
+                    .scroll_state_manager
+                    .set_scroll_position(element_id, position);
+                break;
+            }
+        }
+    }
+
+    fn after_dispatch(&mut self) {
+        self.settle();
+        self.render();
+    }
+
+    /// Applies what dispatching an event left behind: queued operations,
+    /// deferred platform controls and tasks.
+    fn settle(&mut self) {
+        self.shell.dispatch_operations(&mut self.ui_tree);
+
+        if let Some(controls) = self.shell.drain_deferred_controls() {
+            for control in controls {
+                match control {
+                    // There is no drag and drop session to hand the data to, so
+                    // the drag ends immediately without a target.
+                    DeferredControl::StartDrag { src_id, .. } => {
+                        self.shell.dispatch_event_to(
+                            &mut self.ui_tree,
+                            Event::DragFinish {
+                                effect: DropEffect::NONE,
+                            },
+                            src_id,
+                        );
+                    }
+                    DeferredControl::SetIMEPosition { .. } | DeferredControl::DisableIME => {}
+                    DeferredControl::SetClipboardText(text) => self.clipboard = Some(text),
+                    DeferredControl::OpenUrl(url) => self.opened_urls.push(url),
+                }
+            }
+        }
+
+        self.shell.pending_messages = false;
+        self.pump_tasks();
+    }
+
+    fn spawn_task(&mut self, task: Task<Message>) {
+        if let Some(mut stream) = into_stream(task) {
+            let action_sender = self.action_sender.clone();
+            let _ = self.executor.spawner().spawn_local(async move {
+                while let Some(action) = stream.next().await {
+                    if action_sender.send(action).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    }
+
+    fn perform_action(&mut self, action: Action<Message>) {
+        match action {
+            Action::Output(message) => {
+                let _ = self.message_sender.send(message);
+            }
+            Action::Clipboard(ClipboardAction::Set(text)) => self.clipboard = Some(text),
+            Action::Clipboard(ClipboardAction::Get(sender)) => {
+                let _ = sender.send(self.clipboard.clone());
+            }
+            Action::Window(action) => match action {
+                WindowAction::Activate => self.window_active = true,
+                WindowAction::GetMode(sender) => {
+                    let _ = sender.send(self.window_mode);
+                }
+                WindowAction::SetMode(mode) => self.window_mode = mode,
+                WindowAction::Minimize => self.window_mode = WindowMode::Minimized,
+                WindowAction::Restore => {
+                    self.window_mode = WindowMode::Windowed;
+                    self.window_zoomed = false;
+                }
+                WindowAction::ToggleMaximizeRestore => {
+                    self.window_mode = WindowMode::Windowed;
+                    self.window_zoomed = !self.window_zoomed;
+                }
+                WindowAction::Close => self.exit_requested = true,
+            },
+            Action::ContextMenu(ContextMenuAction::Show { items, sender, .. }) => {
+                let choice = self
+                    .context_menu_handler
+                    .as_mut()
+                    .and_then(|handler| handler(&items));
+                let _ = sender.send(choice);
+            }
+            Action::Exit => self.exit_requested = true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::demos;
+    use crate::gfx::software::snapshot::assert_golden;
+    use crate::layout::model::{
+        Border, BorderRadius, BoxAmount, Color, Direction, DropShadow, Element, FloatingConfig,
+        Offset2D, Overflow, ScrollConfig, StickyConfig, Transform,
+    };
+    use crate::math::easing::Easing;
+    use crate::runtime::clock::ManualClock;
+    use crate::runtime::task::{exit_application, get_window_mode, minimize_window};
+    use crate::widgets::button::Button;
+    use crate::widgets::text::Text;
+    use crate::widgets::text_input::TextInput;
+    use crate::widgets::widget;
+
+    const BUTTON_ID: u64 = 1;
+    const TEXT_ID: u64 = 2;
+    const ANIMATED_ID: u64 = 3;
+    const INPUT_ID: u64 = 4;
+
+    #[derive(Default)]
+    struct Counter {
+        count: u32,
+        mode: Option<WindowMode>,
+        text: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Message {
+        Increment,
+        Incremented(u32),
+        Minimize,
+        ModeChanged(WindowMode),
+        TextChanged(String),
+        Exit,
+    }
+
+    fn view(_state: &Counter, _hook: &mut HookManager<Message>) -> Element<Message> {
+        Button::new()
+            .with_click_handler(|_, shell| shell.publish(Message::Increment))
+            .as_element(
+                BUTTON_ID,
+                Element {
+                    width: Sizing::fixed(100.0),
+                    height: Sizing::fixed(40.0),
+                    ..Default::default()
+                },
+            )
+    }
+
+    fn label(_state: &Counter, _hook: &mut HookManager<Message>) -> Element<Message> {
+        Element {
+            width: Sizing::fixed(300.0),
+            children: vec![Element {
+                id: Some(TEXT_ID),
+                ..Text::new("Hello, world").with_font_size(16.0).as_element()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn animated(state: &Counter, hook: &mut HookManager<Message>) -> Element<Message> {
+        let mut instance = hook.instance(ANIMATED_ID);
+        let expanded = crate::use_animation(&mut instance, state.count > 0)
+            .duration(Duration::from_millis(100))
+            .easing(Easing::Linear);
+        let now = hook.now();
+        let width = expanded.interpolate(hook, 50.0, 150.0, now);
+
+        Element {
+            id: Some(ANIMATED_ID),
+            width: Sizing::fixed(width),
+            height: Sizing::fixed(10.0),
+            ..Default::default()
+        }
+    }
+
+    fn dropdown(_state: &Counter, _hook: &mut HookManager<Message>) -> Element<Message> {
+        let menu = Element {
+            id: Some(BUTTON_ID),
+            content: widget(
+                Button::new().with_click_handler(|_, shell| shell.publish(Message::Increment)),
+            ),
+            width: Sizing::fixed(80.0),
+            height: Sizing::fixed(60.0),
+            floating: Some(FloatingConfig {
+                offset: Some(Offset2D {
+                    x: None,
+                    y: Some(50.0),
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+        .with_overlay(true);
+
+        Element {
+            width: Sizing::fixed(100.0),
+            height: Sizing::fixed(40.0),
+            scroll: Some(ScrollConfig {
+                vertical: true,
+                ..Default::default()
+            }),
+            children: vec![menu],
+            ..Default::default()
+        }
+    }
+
+    fn sticky_list(_state: &Counter, _hook: &mut HookManager<Message>) -> Element<Message> {
+        let header = |element: Element<Message>| Element {
+            width: Sizing::grow(),
+            height: Sizing::fixed(30.0),
+            sticky: Some(StickyConfig {
+                top: Some(0.0),
+                left: None,
+            }),
+            ..element
+        };
+        let item = || Element {
+            width: Sizing::grow(),
+            height: Sizing::fixed(150.0),
+            background_color: Some(Color::BLACK),
+            ..Default::default()
+        };
+
+        Element {
+            id: Some(90),
+            direction: Direction::TopToBottom,
+            width: Sizing::fixed(100.0),
+            height: Sizing::fixed(100.0),
+            scroll: Some(ScrollConfig {
+                vertical: true,
+                ..Default::default()
+            }),
+            children: vec![
+                header(Element {
+                    id: Some(BUTTON_ID),
+                    content: widget(
+                        Button::new()
+                            .with_click_handler(|_, shell| shell.publish(Message::Increment)),
+                    ),
+                    ..Default::default()
+                }),
+                item(),
+                header(Element {
+                    id: Some(91),
+                    ..Default::default()
+                }),
+                item(),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn transformed_button(_state: &Counter, _hook: &mut HookManager<Message>) -> Element<Message> {
+        Element {
+            padding: BoxAmount::all(40.0),
+            children: vec![
+                Element {
+                    id: Some(BUTTON_ID),
+                    content: widget(
+                        Button::new()
+                            .with_click_handler(|_, shell| shell.publish(Message::Increment)),
+                    ),
+                    width: Sizing::fixed(40.0),
+                    height: Sizing::fixed(40.0),
+                    ..Default::default()
+                }
+                .with_transform(Transform::new().translate(50.0, 0.0).scale(2.0, 2.0)),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn avatar(_state: &Counter, _hook: &mut HookManager<Message>) -> Element<Message> {
+        Element {
+            width: Sizing::fixed(40.0),
+            height: Sizing::fixed(40.0),
+            border_radius: Some(BorderRadius::all(20.0)),
+            overflow: Overflow::Clip,
+            children: vec![Element {
+                id: Some(BUTTON_ID),
+                content: widget(
+                    Button::new().with_click_handler(|_, shell| shell.publish(Message::Increment)),
+                ),
+                width: Sizing::fixed(100.0),
+                height: Sizing::fixed(100.0),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn raised_button(_state: &Counter, _hook: &mut HookManager<Message>) -> Element<Message> {
+        Element {
+            children: vec![
+                Element {
+                    id: Some(BUTTON_ID),
+                    content: widget(
+                        Button::new()
+                            .with_click_handler(|_, shell| shell.publish(Message::Increment)),
+                    ),
+                    width: Sizing::fixed(60.0),
+                    height: Sizing::fixed(60.0),
+                    floating: Some(FloatingConfig {
+                        offset: Some(Offset2D {
+                            x: Some(30.0),
+                            y: None,
+                        }),
+                        ..Default::default()
+                    }),
+                    z_index: Some(1),
+                    ..Default::default()
+                },
+                Element {
+                    id: Some(92),
+                    content: widget(Button::new().with_click_handler(|_, shell| {
+                        shell.publish(Message::TextChanged("covered".into()))
+                    })),
+                    width: Sizing::fixed(60.0),
+                    height: Sizing::fixed(60.0),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn badge_and_inert(state: &Counter, hook: &mut HookManager<Message>) -> Element<Message> {
+        Element {
+            direction: Direction::LeftToRight,
+            children: vec![
+                Element {
+                    id: Some(BUTTON_ID),
+                    content: widget(
+                        Button::new()
+                            .with_click_handler(|_, shell| shell.publish(Message::Increment)),
+                    ),
+                    width: Sizing::fixed(60.0),
+                    height: Sizing::fixed(60.0),
+                    ..Default::default()
+                },
+                Element {
+                    width: Sizing::fixed(60.0),
+                    height: Sizing::fixed(60.0),
+                    background_color: Some(Color::BLACK),
+                    floating: Some(FloatingConfig::default()),
+                    z_index: Some(1),
+                    hit_test: false,
+                    children: vec![Element {
+                        id: Some(92),
+                        content: widget(Button::new().with_click_handler(|_, shell| {
+                            shell.publish(Message::TextChanged("badge".into()))
+                        })),
+                        width: Sizing::fixed(30.0),
+                        height: Sizing::fixed(30.0),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                Element {
+                    inert: true,
+                    children: vec![Element {
+                        id: Some(93),
+                        content: widget(Button::new().with_click_handler(|_, shell| {
+                            shell.publish(Message::TextChanged("inert".into()))
+                        })),
+                        width: Sizing::fixed(60.0),
+                        height: Sizing::fixed(60.0),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                Element {
+                    inert: state.count > 1,
+                    ..editor(state, hook)
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn editor(_state: &Counter, _hook: &mut HookManager<Message>) -> Element<Message> {
+        Element {
+            width: Sizing::fixed(200.0),
+            height: Sizing::fixed(30.0),
+            ..TextInput::new()
+                .with_text_changed_handler(|text, shell| {
+                    shell.publish(Message::TextChanged(text.to_string()))
+                })
+                .as_element(INPUT_ID)
+        }
+    }
+
+    fn swatch(_state: &Counter, _hook: &mut HookManager<Message>) -> Element<Message> {
+        Element {
+            width: Sizing::fixed(10.0),
+            height: Sizing::fixed(10.0),
+            background_color: Some(Color::BLACK),
+            ..Default::default()
+        }
+    }
+
+    fn swatch_row(swatches: Vec<Element<Message>>) -> Element<Message> {
+        Element {
+            direction: Direction::LeftToRight,
+            width: Sizing::grow(),
+            height: Sizing::grow(),
+            padding: BoxAmount::all(16.0),
+            child_gap: 16.0,
+            background_color: Some(Color::WHITE),
+            children: swatches,
+            ..Default::default()
+        }
+    }
+
+    fn swatch_box(border: Option<Border>, radius: Option<BorderRadius>) -> Element<Message> {
+        Element {
+            width: Sizing::fixed(72.0),
+            height: Sizing::fixed(56.0),
+            background_color: Some(Color::from(0xFAFAFAFF)),
+            border,
+            border_radius: radius,
+            ..Default::default()
+        }
+    }
+
+    fn border_demos(_state: &Counter, _hook: &mut HookManager<Message>) -> Element<Message> {
+        demos::border_demos()
+    }
+
+    fn toggle_demos(_state: &Counter, hook: &mut HookManager<Message>) -> Element<Message> {
+        demos::toggle_demos(hook)
+    }
+
+    /// The backdrop demo over stripes, so there is something to filter
+    fn backdrop_filter_demo(
+        _state: &Counter,
+        _hook: &mut HookManager<Message>,
+    ) -> Element<Message> {
+        let stripe = |i: usize| Element {
+            width: Sizing::fixed(24.0),
+            height: Sizing::grow(),
+            background_color: Some(Color::from([0x1976D2FF, 0xFB8C00FF][i % 2])),
+            ..Default::default()
+        };
+
+        Element {
+            direction: Direction::LeftToRight,
+            width: Sizing::grow(),
+            height: Sizing::grow(),
+            children: (0..16)
+                .map(stripe)
+                .chain([Element {
+                    floating: Some(FloatingConfig::default()),
+                    ..demos::backdrop_filter_demo()
+                }])
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn shadows(_state: &Counter, _hook: &mut HookManager<Message>) -> Element<Message> {
+        let shadowed = |drop_shadows| Element {
+            drop_shadows,
+            ..swatch_box(None, Some(BorderRadius::all(10.0)))
+        };
+
+        swatch_row(vec![
+            shadowed(vec![DropShadow::simple(3.0, 3.0).blur_radius(6.0)]),
+            shadowed(vec![
+                DropShadow::simple(0.0, 0.0)
+                    .blur_radius(8.0)
+                    .spread_radius(4.0)
+                    .color(0x3B82F680),
+            ]),
+            shadowed(vec![
+                DropShadow::simple(2.0, 4.0).blur_radius(6.0).inset(true),
+            ]),
+            shadowed(vec![
+                DropShadow::simple(0.0, 12.0)
+                    .blur_radius(6.0)
+                    .spread_radius(4.0)
+                    .inset(true),
+                DropShadow::simple(0.0, 12.0)
+                    .blur_radius(6.0)
+                    .spread_radius(-4.0)
+                    .inset(false),
+            ]),
+        ])
+    }
+
+    fn golden(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("snapshots")
+            .join(format!("{name}.png"))
+    }
+
+    /// Compares `view` at 1x and 1.5x scale against the goldens `name` and `name@1.5x`
+    fn assert_matches_goldens(
+        view: ViewFn<Counter, Message>,
+        (width, height): (f32, f32),
+        name: &str,
+    ) {
+        for (dpi_scale, name) in [(1.0, name.to_string()), (1.5, format!("{name}@1.5x"))] {
+            let image = HeadlessApp::new(Counter::default(), view, update, |_| None)
+                .with_viewport_size(width, height)
+                .with_dpi_scale(dpi_scale)
+                .snapshot();
+            assert_golden(&image, golden(&name), 2);
+        }
+    }
+
+    fn update(state: &mut Counter, message: Message) -> Option<Task<Message>> {
+        match message {
+            Message::Increment => {
+                state.count += 1;
+                Some(Task::done(Message::Incremented(state.count)))
+            }
+            Message::Incremented(_) => None,
+            Message::Minimize => Some(
+                minimize_window::<Message>().chain(get_window_mode().map(Message::ModeChanged)),
+            ),
+            Message::ModeChanged(mode) => {
+                state.mode = Some(mode);
+                None
+            }
+            Message::TextChanged(text) => {
+                state.text = text;
+                None
+            }
+            Message::Exit => Some(exit_application()),
+        }
+    }
+
+    fn app(view: ViewFn<Counter, Message>) -> HeadlessApp<Counter, Message> {
+        HeadlessApp::new(Counter::default(), view, update, |_| None)
+            .with_viewport_size(320.0, 240.0)
+    }
+
+    #[test]
+    fn click_publishes_and_updates() {
+        let mut app = app(view);
+
+        assert!(app.click_element(BUTTON_ID));
+
+        assert_eq!(app.state().count, 1);
+        assert_eq!(
+            app.messages(),
+            &[Message::Increment, Message::Incremented(1)]
+        );
+    }
+
+    #[test]
+    fn click_outside_does_nothing() {
+        let mut app = app(view);
+
+        app.click(300.0, 200.0);
+
+        assert_eq!(app.state().count, 0);
+        assert!(app.messages().is_empty());
+    }
+
+    #[test]
+    fn boot_task_runs_on_pump() {
+        let mut app = HeadlessApp::new(Counter::default(), view, update, |_| {
+            Some(Task::done(Message::Increment))
+        });
+        assert_eq!(app.state().count, 0);
+
+        app.pump_tasks();
+
+        assert_eq!(app.state().count, 1);
+    }
+
+    #[test]
+    fn window_actions_are_simulated() {
+        let mut app = app(view);
+
+        app.publish(Message::Minimize);
+        assert_eq!(app.state().mode, Some(WindowMode::Minimized));
+
+        app.publish(Message::Exit);
+        assert!(app.exit_requested());
+    }
+
+    #[test]
+    fn snapshot_scales_with_dpi() {
+        let app = app(swatch)
+            .with_viewport_size(20.0, 20.0)
+            .with_dpi_scale(2.0);
+
+        let image = app.snapshot();
+
+        assert_eq!((image.width, image.height), (40, 40));
+        assert_eq!(image.pixel(19, 19), Some([0, 0, 0, 255]));
+        assert_eq!(image.pixel(21, 21), Some([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn text_is_measured_headless() {
+        let mut app = app(label);
+        let bounds = app.element_bounds(TEXT_ID).unwrap();
+
+        let style = Text::new("Hello, world").with_font_size(16.0).text_style();
+        let metrics = app
+            .device_resources
+            .text_engine
+            .create_layout("Hello, world", &style, f32::INFINITY, f32::INFINITY)
+            .unwrap()
+            .metrics();
+        assert!(bounds.width > 0.0);
+        assert_eq!(bounds.width, metrics.width_including_trailing_whitespace);
+        assert_eq!(bounds.height, metrics.height);
+    }
+
+    #[test]
+    fn layout_dumps_show_resizes() {
+        let mut app = app(label);
+        let before = app.layout_dump();
+
+        app.set_viewport_size(200.0, 240.0);
+        let changes = before.diff(&app.layout_dump());
+
+        assert_eq!(before.root.type_name, "Container");
+        assert!(before.find(TEXT_ID).unwrap().computed_width > 0.0);
+        assert!(
+            changes
+                .iter()
+                .any(|change| change.property == "computed_width" && change.after == "200")
+        );
+    }
+
+    #[test]
+    fn frames_export_as_svg() {
+        let app = app(label);
+
+        let svg = app.to_svg();
+
+        assert!(svg.contains("width=\"320\" height=\"240\" viewBox=\"0 0 320 240\""));
+        assert!(svg.contains("font-size=\"16\""));
+        assert!(svg.contains(">Hello, world</tspan></text>"));
+    }
+
+    #[test]
+    fn overlays_escape_clips() {
+        let mut app = app(dropdown).with_viewport_size(200.0, 200.0);
+
+        // The menu hangs out of its scroll container, but is neither clipped nor hidden from the
+        // pointer by it
+        let alpha =
+            |app: &HeadlessApp<Counter, Message>, x, y| app.snapshot().pixel(x, y).unwrap()[3];
+        assert_eq!(alpha(&app, 10, 80), 255);
+        app.click(10.0, 80.0);
+        assert_eq!(app.state().count, 1);
+    }
+
+    #[test]
+    fn sticky_headers_stay_in_view() {
+        let mut app = app(sticky_list).with_viewport_size(200.0, 200.0);
+        let top = |app: &mut HeadlessApp<Counter, Message>, id| app.element_bounds(id).unwrap().y;
+
+        // The first header sticks to the top while its items scroll under it, and is hit first
+        app.mouse_wheel(50.0, 50.0, 1.0);
+        assert_eq!(top(&mut app, BUTTON_ID), 0.0);
+        assert_eq!(top(&mut app, 91), 84.0);
+        app.click(50.0, 10.0);
+        assert_eq!(app.state().count, 1);
+
+        // The next header pushes it off
+        app.mouse_wheel(50.0, 50.0, 0.75);
+        assert_eq!(top(&mut app, BUTTON_ID), -18.0);
+        assert_eq!(top(&mut app, 91), 12.0);
+
+        app.mouse_wheel(50.0, 50.0, 1.0);
+        assert_eq!(top(&mut app, 91), 0.0);
+    }
+
+    #[test]
+    fn transforms_move_painting_and_hit_testing() {
+        let mut app = app(transformed_button).with_viewport_size(200.0, 200.0);
+        let alpha =
+            |app: &HeadlessApp<Counter, Message>, x, y| app.snapshot().pixel(x, y).unwrap()[3];
+
+        // Laid out at 40..80, scaled around its center and moved right when painted
+        let bounds = app.element_bounds(BUTTON_ID).unwrap();
+        assert_eq!((bounds.x, bounds.width), (40.0, 40.0));
+        assert_eq!(alpha(&app, 50, 50), 0);
+        assert_eq!(alpha(&app, 140, 90), 255);
+
+        app.click(50.0, 50.0);
+        assert_eq!(app.state().count, 0);
+        app.click(140.0, 90.0);
+        assert_eq!(app.state().count, 1);
+    }
+
+    #[test]
+    fn overflow_clip_crops_without_scrolling() {
+        let mut app = app(avatar).with_viewport_size(200.0, 200.0);
+        let alpha =
+            |app: &HeadlessApp<Counter, Message>, x, y| app.snapshot().pixel(x, y).unwrap()[3];
+
+        // Cropped to the rounded box of its parent
+        assert_eq!(alpha(&app, 20, 20), 255);
+        assert_eq!(alpha(&app, 60, 20), 0);
+        assert_eq!(alpha(&app, 1, 1), 0);
+        app.click(60.0, 20.0);
+        app.click(2.0, 2.0);
+        assert_eq!(app.state().count, 0);
+        app.click(20.0, 20.0);
+        assert_eq!(app.state().count, 1);
+
+        app.mouse_wheel(20.0, 20.0, 1.0);
+        assert_eq!(app.element_bounds(BUTTON_ID).unwrap().y, 0.0);
+    }
+
+    #[test]
+    fn raised_elements_are_hit_first() {
+        let mut app = app(raised_button).with_viewport_size(200.0, 200.0);
+
+        // Comes first in the tree but is painted above its sibling
+        app.click(45.0, 30.0);
+        assert_eq!(app.state().count, 1);
+        assert_eq!(app.state().text, "");
+
+        app.click(10.0, 30.0);
+        assert_eq!(app.state().text, "covered");
+    }
+
+    #[test]
+    fn hit_test_and_inert_let_the_pointer_through() {
+        let mut app = app(badge_and_inert).with_viewport_size(400.0, 200.0);
+
+        // The badge is painted above the button but not hit, unlike the button it holds
+        app.click(45.0, 45.0);
+        assert_eq!(app.state().count, 1);
+        assert_eq!(app.state().text, "");
+
+        app.click(15.0, 15.0);
+        assert_eq!(app.state().count, 1);
+        assert_eq!(app.state().text, "badge");
+
+        app.click(90.0, 30.0);
+        assert_eq!(app.state().text, "badge");
+
+        app.click_element(INPUT_ID);
+        app.type_text("a");
+        assert_eq!(app.state().text, "a");
+
+        // Becoming inert takes the focus and the mouse away as soon as it is laid out
+        let input = app.element_bounds(INPUT_ID).unwrap();
+        app.mouse_down(input.x + 5.0, input.y + 5.0);
+        assert!(app.shell().active_element_id.is_some());
+        app.update_state(|state| state.count = 2);
+        assert_eq!(app.shell().focus_manager.focused_widget, None);
+        assert_eq!(app.shell().active_element_id, None);
+
+        app.type_text("b");
+        assert_eq!(app.state().text, "a");
+    }
+
+    #[test]
+    fn animations_follow_the_clock() {
+        let clock = ManualClock::new();
+        let mut app = HeadlessApp::new(Counter::default(), animated, update, |_| None)
+            .with_clock(Rc::new(clock.clone()));
+        let width = |app: &mut HeadlessApp<Counter, Message>| {
+            app.element_bounds(ANIMATED_ID).unwrap().width
+        };
+
+        app.update_state(|state| state.count = 1);
+        assert_eq!(width(&mut app), 50.0);
+        assert_eq!(app.redraw_request(), RedrawRequest::Immediate);
+
+        clock.advance(Duration::from_millis(25));
+        assert!(app.redraw_if_due());
+        assert_eq!(width(&mut app), 75.0);
+
+        clock.advance(Duration::from_millis(50));
+        assert!(app.redraw_if_due());
+        assert_eq!(width(&mut app), 125.0);
+
+        clock.advance(Duration::from_millis(25));
+        assert!(app.redraw_if_due());
+        assert_eq!(width(&mut app), 150.0);
+        assert_eq!(app.redraw_request(), RedrawRequest::Wait);
+        assert!(!app.redraw_if_due());
+    }
+
+    #[test]
+    fn undo_coalesces_typing_by_the_clock() {
+        let clock = ManualClock::new();
+        let mut app = app(editor).with_clock(Rc::new(clock.clone()));
+        let ctrl = Modifiers {
+            ctrl: true,
+            ..Default::default()
+        };
+
+        // Keystrokes less than a second apart are undone together
+        app.click_element(INPUT_ID);
+        app.type_text("ab");
+        clock.advance(Duration::from_millis(900));
+        app.type_text("c");
+        clock.advance(Duration::from_millis(1000));
+        app.type_text("d");
+        assert_eq!(app.state().text, "abcd");
+
+        app.key_press(VKey::Z, ctrl);
+        assert_eq!(app.state().text, "abc");
+        app.key_press(VKey::Z, ctrl);
+        assert_eq!(app.state().text, "");
+    }
+
+    #[test]
+    fn replaying_a_trace_reproduces_the_session() {
+        let editor_app = || app(editor);
+        let shift = Modifiers {
+            shift: true,
+            ..Default::default()
+        };
+
+        let mut app = editor_app();
+        app.start_recording();
+        app.click_element(INPUT_ID);
+        app.type_text("hello");
+        app.key_press(VKey::LEFT, shift);
+        app.key_press(VKey::LEFT, shift);
+        app.type_text("p!");
+        app.set_viewport_size(300.0, 200.0);
+        let trace = app.stop_recording().unwrap();
+        assert_eq!(app.state().text, "help!");
+
+        let trace = Trace::from_json(&trace.to_json().unwrap()).unwrap();
+        let mut replayed = editor_app();
+        replayed.replay(&trace);
+
+        assert_eq!(replayed.messages(), app.messages());
+        assert_eq!(replayed.state().text, "help!");
+        assert_eq!(replayed.viewport_size(), (300.0, 200.0));
+    }
+
+    #[test]
+    fn border_demos_match_golden() {
+        assert_matches_goldens(border_demos, (960.0, 360.0), "border_demos");
+    }
+
+    #[test]
+    fn toggle_demos_match_golden() {
+        assert_matches_goldens(toggle_demos, (360.0, 280.0), "toggle_demos");
+    }
+
+    #[test]
+    fn backdrop_filter_demo_matches_golden() {
+        assert_matches_goldens(backdrop_filter_demo, (384.0, 540.0), "backdrop_filter_demo");
+    }
+
+    #[test]
+    fn shadows_match_golden() {
+        assert_matches_goldens(shadows, (560.0, 88.0), "shadows");
+    }
+}
@@ -1,7 +1,9 @@
 pub mod middle_mouse;
+#[cfg(windows)]
 pub mod mouse;
 pub mod scrollbar;
 
 pub use middle_mouse::MiddleMouseScrollState;
+#[cfg(windows)]
 pub use mouse::MouseState;
 pub use scrollbar::ScrollbarDragState;
@@ -0,0 +1,46 @@
+//! Resources widgets create their state with.
+
+use std::fmt;
+use std::rc::Rc;
+
+#[cfg(windows)]
+use windows::Win32::Graphics::Direct2D::ID2D1DeviceContext6;
+
+use crate::gfx::text_engine::TextEngine;
+use crate::gfx::text_engine::ttf::TtfTextEngine;
+
+/// Font text is laid out with when nothing else is loaded
+const DEFAULT_FONT: &[u8] = include_bytes!("../../fonts/DejaVuSans.ttf");
+
+/// What widgets receive when creating their state, on every target.
+///
+/// The Win32 runtime fills it from its [`GpuResources`](crate::runtime::device::GpuResources).
+/// The default measures text with a [`TtfTextEngine`] that has DejaVu Sans loaded, so sizes
+/// don't depend on the fonts installed on the host.
+pub struct DeviceResources {
+    pub text_engine: Rc<dyn TextEngine>,
+    /// Direct2D context SVG documents are parsed with, `None` without a window
+    #[cfg(windows)]
+    pub d2d_device_context: Option<ID2D1DeviceContext6>,
+}
+
+impl Default for DeviceResources {
+    fn default() -> Self {
+        let text_engine = TtfTextEngine::new();
+        text_engine
+            .load_font(DEFAULT_FONT)
+            .expect("bundled font is valid");
+
+        Self {
+            text_engine: Rc::new(text_engine),
+            #[cfg(windows)]
+            d2d_device_context: None,
+        }
+    }
+}
+
+impl fmt::Debug for DeviceResources {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeviceResources").finish_non_exhaustive()
+    }
+}
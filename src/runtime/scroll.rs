@@ -1,12 +1,14 @@
 use std::{collections::HashMap, time::Instant};
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
     Animation, Shell,
     gfx::RectDIP,
     layout::model::{Axis, UIElement},
 };
 
-#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Default, Serialize, Deserialize)]
 pub struct ScrollPosition {
     pub x: f32,
     pub y: f32,
@@ -33,6 +35,9 @@ pub struct ScrollStateManager {
 /// The threshold in pixels (dips) for considering a scroll position to be at the bottom or right of the scrollable area.
 pub const SCROLL_SNAP_THRESHOLD: f32 = 5.0;
 
+/// The distance in pixels (dips) scrolled per wheel line.
+pub const LINE_HEIGHT: u32 = 32;
+
 impl ScrollStateManager {
     pub fn get_scroll_position(&self, element_id: u64) -> ScrollPosition {
         self.scroll_metadata
@@ -63,32 +68,32 @@ impl ScrollStateManager {
             });
     }
 
-    pub fn set_active(&mut self, element_id: u64, axis: Axis) {
+    pub fn set_active(&mut self, element_id: u64, axis: Axis, now: Instant) {
         if let Some((active_element_id, active_axis)) = self.active_scrollbar
             && (active_element_id != element_id || active_axis != axis)
         {
-            self.set_inactive();
+            self.set_inactive(now);
         }
 
         self.scroll_metadata
             .entry(element_id)
             .and_modify(|metadata| {
                 match axis {
-                    Axis::X => metadata.animation.0.update(true),
-                    Axis::Y => metadata.animation.1.update(true),
+                    Axis::X => metadata.animation.0.update(true, now),
+                    Axis::Y => metadata.animation.1.update(true, now),
                 }
 
                 self.active_scrollbar = Some((element_id, axis));
             });
     }
 
-    pub fn set_inactive(&mut self) -> bool {
+    pub fn set_inactive(&mut self, now: Instant) -> bool {
         if let Some((element_id, _axis)) = self.active_scrollbar {
             self.scroll_metadata
                 .entry(element_id)
                 .and_modify(|metadata| {
-                    metadata.animation.0.update(false);
-                    metadata.animation.1.update(false);
+                    metadata.animation.0.update(false, now);
+                    metadata.animation.1.update(false, now);
                 });
 
             self.active_scrollbar = None;
@@ -137,6 +142,7 @@ impl ScrollStateManager {
             });
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn update_scroll_metadata(
         &mut self,
         element_id: u64,
@@ -220,6 +226,8 @@ pub struct ScrollbarGeom {
     pub track_start: f32,
     // Maximum scroll value for this axis (content - viewport)
     pub max_scroll: f32,
+    // Whether the thumb starts at the track end, for right-to-left horizontal scrollbars
+    pub reversed: bool,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -264,6 +272,7 @@ pub fn compute_scrollbar_geom<Message>(
     let sc = element.scroll.as_ref()?;
 
     let scroll_metadata = shell.scroll_state_manager.get_scroll_metadata(id);
+    let now = shell.now();
 
     // Use element's custom scrollbar style if provided, otherwise use global style
     let active_style = sc.scrollbar_style.unwrap_or(shell.scrollbar_style);
@@ -283,7 +292,7 @@ pub fn compute_scrollbar_geom<Message>(
                 shell,
                 scrollbar_size.thin(),
                 scrollbar_size.thick(),
-                Instant::now(),
+                now,
             );
 
             let scrollbar_min_thumb_size = active_style.min_thumb_size;
@@ -306,14 +315,21 @@ pub fn compute_scrollbar_geom<Message>(
             let thumb_len = (safe_height * visible_ratio).max(scrollbar_min_thumb_size);
             let range = (safe_height - thumb_len).max(0.0);
 
+            // Right-to-left vertical scrollbars sit on the left edge
+            let track_x = if element.is_rtl() {
+                x
+            } else {
+                x + safe_width - scrollbar_size
+            };
+
             let track_rect = RectDIP {
-                x: x + safe_width - scrollbar_size,
+                x: track_x,
                 y,
                 width: scrollbar_size,
                 height: safe_height,
             };
             let thumb_rect = RectDIP {
-                x: x + safe_width - scrollbar_size,
+                x: track_x,
                 y: y + range * progress,
                 width: scrollbar_size,
                 height: thumb_len,
@@ -326,6 +342,7 @@ pub fn compute_scrollbar_geom<Message>(
                 range,
                 track_start: y,
                 max_scroll: max_scroll_y,
+                reversed: false,
             })
         }
         Axis::X if has_scroll_x => {
@@ -342,7 +359,7 @@ pub fn compute_scrollbar_geom<Message>(
                 shell,
                 scrollbar_size.thin(),
                 scrollbar_size.thick(),
-                Instant::now(),
+                now,
             );
 
             let scrollbar_min_thumb_size = active_style.min_thumb_size;
@@ -365,6 +382,10 @@ pub fn compute_scrollbar_geom<Message>(
             let thumb_len = (safe_width * visible_ratio).max(scrollbar_min_thumb_size);
             let range = (safe_width - thumb_len).max(0.0);
 
+            // Right-to-left content starts at the right, and so does the thumb
+            let reversed = element.is_rtl();
+            let progress = if reversed { 1.0 - progress } else { progress };
+
             let track_rect = RectDIP {
                 x,
                 y: y + safe_height - scrollbar_size,
@@ -385,6 +406,7 @@ pub fn compute_scrollbar_geom<Message>(
                 range,
                 track_start: x,
                 max_scroll: max_scroll_x,
+                reversed,
             })
         }
         _ => None,
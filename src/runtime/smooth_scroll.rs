@@ -1,6 +1,8 @@
 use std::collections::HashMap;
+use std::rc::Rc;
 use std::time::Instant;
 
+use super::clock::{Clock, SystemClock};
 use super::scroll::ScrollPosition;
 use crate::math::easing::Easing;
 
@@ -19,13 +21,14 @@ impl SmoothScrollAnimation {
     pub fn new(
         start: ScrollPosition,
         target: ScrollPosition,
+        start_time: Instant,
         duration_ms: u32,
         easing: Easing,
     ) -> Self {
         Self {
             start_position: start,
             target_position: target,
-            start_time: Instant::now(),
+            start_time,
             duration_ms,
             easing,
         }
@@ -85,20 +88,23 @@ impl SmoothScrollAnimation {
     }
 }
 
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub struct SmoothScrollManager {
     animations: HashMap<u64, SmoothScrollAnimation>,
     default_duration_ms: u32,
     default_easing: Easing,
+    clock: Rc<dyn Clock>,
+}
+
+impl Default for SmoothScrollManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SmoothScrollManager {
     pub fn new() -> Self {
-        Self {
-            animations: HashMap::new(),
-            default_duration_ms: SMOOTH_SCROLL_DURATION_MS,
-            default_easing: Easing::EaseOutCubic,
-        }
+        Self::with_defaults(SMOOTH_SCROLL_DURATION_MS, Easing::EaseOutCubic)
     }
 
     pub fn with_defaults(duration_ms: u32, easing: Easing) -> Self {
@@ -106,16 +112,27 @@ impl SmoothScrollManager {
             animations: HashMap::new(),
             default_duration_ms: duration_ms,
             default_easing: easing,
+            clock: Rc::new(SystemClock),
         }
     }
 
+    /// Measures animation progress with `clock`, usually the shell's.
+    pub fn with_clock(mut self, clock: Rc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
     pub fn accumulate_scroll_delta(
         &mut self,
         element_id: u64,
         current_pos: ScrollPosition,
         delta: ScrollPosition,
     ) {
-        let now = Instant::now();
+        let now = self.clock.now();
 
         if let Some(existing) = self.animations.get_mut(&element_id) {
             // Accumulate delta to existing animation
@@ -129,6 +146,7 @@ impl SmoothScrollManager {
             let animation = SmoothScrollAnimation::new(
                 current_pos,
                 target_pos,
+                now,
                 self.default_duration_ms,
                 self.default_easing,
             );
@@ -141,7 +159,7 @@ impl SmoothScrollManager {
         element_id: u64,
         fallback: ScrollPosition,
     ) -> ScrollPosition {
-        let now = Instant::now();
+        let now = self.clock.now();
         if let Some(animation) = self.animations.get(&element_id) {
             animation.current_position(now)
         } else {
@@ -150,7 +168,7 @@ impl SmoothScrollManager {
     }
 
     pub fn update_animations(&mut self) -> Vec<u64> {
-        let now = Instant::now();
+        let now = self.clock.now();
         let mut completed_animations = Vec::new();
 
         // Remove completed animations and collect their element IDs
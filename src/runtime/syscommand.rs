@@ -1,3 +1,4 @@
+#[cfg(windows)]
 use windows::Win32::UI::WindowsAndMessaging::{SC_CLOSE, SC_MAXIMIZE, SC_MINIMIZE, SC_RESTORE};
 
 /// System commands that can be intercepted from window caption buttons
@@ -15,6 +16,7 @@ pub enum SystemCommand {
     Other(u32),
 }
 
+#[cfg(windows)]
 impl SystemCommand {
     /// Parse a system command from wparam
     pub fn from_wparam(wparam: usize) -> Self {
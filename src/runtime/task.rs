@@ -31,14 +31,6 @@ use futures::channel::oneshot::Sender;
 use futures::future::{self, FutureExt};
 use futures::stream::BoxStream;
 use futures::stream::{self, Stream, StreamExt};
-use windows::Win32::UI::WindowsAndMessaging::IsIconic;
-use windows::Win32::UI::WindowsAndMessaging::IsWindowVisible;
-use windows::Win32::UI::WindowsAndMessaging::SC_CLOSE;
-use windows::Win32::UI::WindowsAndMessaging::SC_MAXIMIZE;
-use windows::Win32::UI::WindowsAndMessaging::SC_MINIMIZE;
-use windows::Win32::UI::WindowsAndMessaging::SC_RESTORE;
-use windows::Win32::UI::WindowsAndMessaging::SW_MINIMIZE;
-use windows::Win32::UI::WindowsAndMessaging::WM_SYSCOMMAND;
 // use futures::{BoxStream, Send, boxed_stream};
 
 // pub type BoxStream<'a, T> = Pin<Box<dyn Stream<Item = T> + Send + 'a>>;
@@ -671,6 +663,7 @@ pub fn show_context_menu<T: Clone + Send + Sync + 'static>(
 // }
 
 /// Runs the task executor loop on an async runtime
+#[cfg(windows)]
 pub fn run_task_executor<Message: Send + Clone + 'static>(
     task_receiver: std::sync::mpsc::Receiver<Task<Message>>,
     message_sender: std::sync::mpsc::Sender<Message>,
@@ -683,7 +676,8 @@ pub fn run_task_executor<Message: Send + Clone + 'static>(
     use windows::Win32::Foundation::{LPARAM, WPARAM};
     use windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
     use windows::Win32::UI::WindowsAndMessaging::{
-        IsZoomed, PostMessageW, SW_HIDE, SW_SHOW, ShowWindow, WM_CLOSE,
+        IsIconic, IsWindowVisible, IsZoomed, PostMessageW, SC_CLOSE, SC_MAXIMIZE, SC_MINIMIZE,
+        SC_RESTORE, SW_HIDE, SW_MINIMIZE, SW_SHOW, ShowWindow, WM_CLOSE, WM_SYSCOMMAND,
     };
 
     async fn process_task_stream<Message: Send + Clone + 'static>(
@@ -718,7 +712,7 @@ pub fn run_task_executor<Message: Send + Clone + 'static>(
                         let _ = clipboard::set_clipboard_text(hwnd.0, &text);
                     }
                     ClipboardAction::Get(sender) => {
-                        let text = clipboard::get_clipboard_text(hwnd.0);
+                        let text = clipboard::get_clipboard_text(Some(hwnd.0));
                         let _ = sender.send(text);
                     }
                 },
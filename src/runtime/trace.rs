@@ -0,0 +1,156 @@
+//! Recording of the events an application receives, for deterministic replay.
+//!
+//! While recording, [`Shell::dispatch_event`](crate::Shell::dispatch_event)
+//! appends every [`Event`] to a [`Trace`] along with the time it arrived, and
+//! the platform layer adds the viewport size whenever it changes. A trace saved
+//! from a misbehaving session can be replayed against the same view and update
+//! functions with `HeadlessApp::replay` to reproduce it.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::widgets::Event;
+
+#[derive(Debug, Error)]
+pub enum TraceError {
+    #[error("Failed to access trace file {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Malformed trace: {0}")]
+    Format(#[from] serde_json::Error),
+}
+
+pub type Result<T> = std::result::Result<T, TraceError>;
+
+/// Something the application received, in the order it was received
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TraceRecord {
+    Event(Event),
+    /// The viewport was resized, in DIPs
+    Viewport {
+        width: f32,
+        height: f32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceEntry {
+    /// Time since recording started
+    pub at: Duration,
+    pub record: TraceRecord,
+}
+
+/// A recorded session, stored as JSON
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trace {
+    pub entries: Vec<TraceEntry>,
+}
+
+impl Trace {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|source| TraceError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_json(&json)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_json()?).map_err(|source| TraceError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// The events in the trace, without timestamps or viewport changes
+    pub fn events(&self) -> impl Iterator<Item = &Event> {
+        self.entries.iter().filter_map(|entry| match &entry.record {
+            TraceRecord::Event(event) => Some(event),
+            TraceRecord::Viewport { .. } => None,
+        })
+    }
+}
+
+/// A trace being recorded, with timestamps relative to `started_at`
+#[derive(Debug)]
+pub(crate) struct TraceRecorder {
+    started_at: Instant,
+    trace: Trace,
+}
+
+impl TraceRecorder {
+    pub fn new(started_at: Instant) -> Self {
+        Self {
+            started_at,
+            trace: Trace::default(),
+        }
+    }
+
+    pub fn record(&mut self, now: Instant, record: TraceRecord) {
+        self.trace.entries.push(TraceEntry {
+            at: now.saturating_duration_since(self.started_at),
+            record,
+        });
+    }
+
+    pub fn finish(self) -> Trace {
+        self.trace
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::vkey::VKey;
+    use crate::widgets::Modifiers;
+
+    #[test]
+    fn round_trips_through_json() {
+        let start = Instant::now();
+        let mut recorder = TraceRecorder::new(start);
+        recorder.record(
+            start,
+            TraceRecord::Viewport {
+                width: 320.0,
+                height: 240.0,
+            },
+        );
+        recorder.record(
+            start + Duration::from_millis(16),
+            TraceRecord::Event(Event::KeyDown {
+                key: VKey::LEFT,
+                modifiers: Modifiers {
+                    shift: true,
+                    ..Default::default()
+                },
+            }),
+        );
+        recorder.record(
+            start + Duration::from_millis(20),
+            TraceRecord::Event(Event::Char { text: "é".into() }),
+        );
+        let trace = recorder.finish();
+
+        let parsed = Trace::from_json(&trace.to_json().unwrap()).unwrap();
+
+        assert_eq!(parsed, trace);
+        assert_eq!(parsed.entries[1].at, Duration::from_millis(16));
+        assert_eq!(parsed.events().count(), 2);
+    }
+}
@@ -1,13 +1,15 @@
+use std::path::PathBuf;
+
 use crate::gfx::effects::{EffectFactory, PixelShaderEffect};
 use crate::layout::model::ScrollbarStyle;
-use crate::runtime::device::DeviceResources;
+use crate::runtime::device::GpuResources;
 use crate::runtime::syscommand::{SystemCommand, SystemCommandResponse};
 use crate::runtime::task::Task;
 use crate::runtime::tray::{TrayEvent, TrayIconConfig};
 use crate::{EventMapperFn, UpdateFn, ViewFn};
 
 /// Type-erased effect registration function.
-pub(crate) type EffectRegistrationFn = Box<dyn Fn(&DeviceResources) -> windows::core::Result<()>>;
+pub(crate) type EffectRegistrationFn = Box<dyn Fn(&GpuResources) -> windows::core::Result<()>>;
 
 #[derive(Debug, Default)]
 pub enum Backdrop {
@@ -61,6 +63,9 @@ pub struct Application<
 
     /// Custom effects to register with Direct2D
     pub(crate) effect_registrations: Vec<EffectRegistrationFn>,
+
+    /// Where to write the recorded event trace when the window closes
+    pub(crate) trace_file: Option<PathBuf>,
 }
 
 impl<
@@ -102,6 +107,8 @@ impl<
             scrollbar_style: ScrollbarStyle::default(),
 
             effect_registrations: vec![],
+
+            trace_file: None,
         }
     }
 
@@ -197,6 +204,18 @@ impl<
         }
     }
 
+    /// Records every event the window receives and writes the trace to `path`
+    /// when the window is destroyed.
+    ///
+    /// The trace can be loaded with [`Trace::load`](crate::runtime::trace::Trace::load)
+    /// and replayed against the same view and update functions in a headless app.
+    pub fn with_trace_file(self, path: impl Into<PathBuf>) -> Self {
+        Self {
+            trace_file: Some(path.into()),
+            ..self
+        }
+    }
+
     /// Registers a custom pixel shader effect to be used with the application.
     ///
     /// Effects are automatically registered with Direct2D when device resources
@@ -215,7 +234,7 @@ impl<
     /// ```
     pub fn with_effect<E: PixelShaderEffect + EffectFactory>(mut self) -> Self {
         self.effect_registrations
-            .push(Box::new(|device_resources: &DeviceResources| {
+            .push(Box::new(|device_resources: &GpuResources| {
                 device_resources.register_effect::<E>()
             }));
         self
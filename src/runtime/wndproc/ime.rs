@@ -17,7 +17,7 @@ pub fn handle_ime_start_composition<State: 'static, Message: 'static + Send + Cl
 
         state
             .shell
-            .dispatch_event(hwnd, &mut state.ui_tree, Event::ImeStartComposition);
+            .dispatch_event(&mut state.ui_tree, Event::ImeStartComposition);
     }
     LRESULT(0)
 }
@@ -50,7 +50,6 @@ pub fn handle_ime_composition<State: 'static, Message: 'static + Send + Clone>(
                     let s = String::from_utf16_lossy(&buf);
 
                     state.shell.dispatch_event(
-                        hwnd,
                         &mut state.ui_tree,
                         Event::ImeComposition {
                             text: s.clone(),
@@ -58,11 +57,9 @@ pub fn handle_ime_composition<State: 'static, Message: 'static + Send + Clone>(
                         },
                     );
 
-                    state.shell.dispatch_event(
-                        hwnd,
-                        &mut state.ui_tree,
-                        Event::ImeCommit { text: s.clone() },
-                    );
+                    state
+                        .shell
+                        .dispatch_event(&mut state.ui_tree, Event::ImeCommit { text: s.clone() });
                 }
             }
             // Handle ongoing composition string
@@ -88,7 +85,6 @@ pub fn handle_ime_composition<State: 'static, Message: 'static + Send + Clone>(
                 };
 
                 state.shell.dispatch_event(
-                    hwnd,
                     &mut state.ui_tree,
                     Event::ImeComposition {
                         text: comp.clone(),
@@ -114,7 +110,7 @@ pub fn handle_ime_end_composition<State: 'static, Message: 'static + Send + Clon
 
         state
             .shell
-            .dispatch_event(hwnd, &mut state.ui_tree, Event::ImeEndComposition);
+            .dispatch_event(&mut state.ui_tree, Event::ImeEndComposition);
 
         let _ = unsafe { InvalidateRect(Some(hwnd), None, false) };
     }
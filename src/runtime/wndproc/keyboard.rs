@@ -50,7 +50,6 @@ pub fn handle_char<State: 'static, Message: 'static + Send + Clone>(
 
         if !to_insert.is_empty() {
             state.shell.dispatch_event(
-                hwnd,
                 &mut state.ui_tree,
                 Event::Char {
                     text: to_insert.into(),
@@ -74,11 +73,9 @@ pub fn handle_keydown<State: 'static, Message: 'static + Send + Clone>(
 
         let modifiers = get_modifiers();
         if let Ok(vk) = VKey::try_from(vk) {
-            state.shell.dispatch_event(
-                hwnd,
-                &mut state.ui_tree,
-                Event::KeyDown { key: vk, modifiers },
-            );
+            state
+                .shell
+                .dispatch_event(&mut state.ui_tree, Event::KeyDown { key: vk, modifiers });
         }
 
         let _ = unsafe { InvalidateRect(Some(hwnd), None, false) };
@@ -99,11 +96,9 @@ pub fn handle_keyup<State: 'static, Message: 'static + Send + Clone>(
 
         let modifiers = get_modifiers();
         if let Ok(vk) = VKey::try_from(vk) {
-            state.shell.dispatch_event(
-                hwnd,
-                &mut state.ui_tree,
-                Event::KeyUp { key: vk, modifiers },
-            );
+            state
+                .shell
+                .dispatch_event(&mut state.ui_tree, Event::KeyUp { key: vk, modifiers });
         }
 
         let _ = unsafe { InvalidateRect(Some(hwnd), None, false) };
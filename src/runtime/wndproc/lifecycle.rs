@@ -1,6 +1,5 @@
 use log::{error, warn};
 use std::ops::DerefMut;
-use std::time::Instant;
 use windows::Win32::Foundation::{D2DERR_RECREATE_TARGET, HWND, LPARAM, LRESULT, RECT};
 use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
 use windows::Win32::Graphics::Dwm::DwmExtendFrameIntoClientArea;
@@ -33,6 +32,10 @@ pub fn handle_size<State: 'static, Message: 'static + Send + Clone>(
         let state = state.deref_mut();
         let width = (lparam.0 & 0xFFFF) as u32;
         let height = ((lparam.0 >> 16) & 0xFFFF) as u32;
+        let to_dip = dips_scale_for_dpi(current_dpi(hwnd));
+        state
+            .shell
+            .record_viewport(width as f32 * to_dip, height as f32 * to_dip);
         if let Err(e) = state.on_resize(width, height) {
             error!("Failed to resize: {e}");
         }
@@ -122,10 +125,10 @@ pub fn handle_paint<State: 'static, Message: 'static + Send + Clone>(hwnd: HWND)
         let state = state.deref_mut();
         state.shell.replace_redraw_request(RedrawRequest::Wait);
 
-        let now = Instant::now();
+        let now = state.shell.now();
         state
             .shell
-            .dispatch_event(hwnd, &mut state.ui_tree, Event::Redraw { now });
+            .dispatch_event(&mut state.ui_tree, Event::Redraw { now });
 
         match state.on_paint(hwnd) {
             Ok(commands) => {
@@ -187,6 +190,7 @@ pub fn handle_paint<State: 'static, Message: 'static + Send + Clone>(hwnd: HWND)
                 rt,
                 brush,
                 &device_resources.shadow_cache,
+                &device_resources.resource_cache,
             );
 
             // Start frame for cache management
@@ -251,6 +255,10 @@ pub fn handle_displaychange<State: 'static, Message: 'static + Send + Clone>(
 pub fn handle_destroy<State: 'static, Message: 'static + Send + Clone>(hwnd: HWND) -> LRESULT {
     let _ = unsafe { RevokeDragDrop(hwnd) };
 
+    if let Some(mut state) = state_mut_from_hwnd::<State, Message>(hwnd) {
+        state.save_trace();
+    }
+
     clear_titlebar_hit_regions(hwnd);
 
     unsafe { PostQuitMessage(0) };
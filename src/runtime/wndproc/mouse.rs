@@ -14,6 +14,7 @@ use windows::Win32::UI::WindowsAndMessaging::{
 
 use crate::gfx::PointDIP;
 use crate::layout::model::Axis;
+use crate::layout::point_to_local;
 use crate::runtime::input::MiddleMouseScrollState;
 use crate::runtime::scroll::{
     ScrollDirection, ScrollPosition, can_scroll_further, compute_scrollbar_geom,
@@ -59,7 +60,6 @@ pub fn handle_lbuttondown<State: 'static, Message: 'static + Send + Clone>(
 
         let modifiers = get_modifiers();
         state.shell.dispatch_event(
-            hwnd,
             &mut state.ui_tree,
             Event::MouseButtonDown {
                 x,
@@ -119,6 +119,11 @@ pub fn handle_mousemove<State: 'static, Message: 'static + Send + Clone>(
                     } else {
                         0.0
                     };
+                    let progress = if geom.reversed {
+                        1.0 - progress
+                    } else {
+                        progress
+                    };
                     let new_scroll = progress * geom.max_scroll;
                     let cur = state
                         .shell
@@ -157,24 +162,25 @@ pub fn handle_mousemove<State: 'static, Message: 'static + Send + Clone>(
             middle_scroll.current_y = y;
 
             // Request continuous redraws while scrolling
-            state.shell.request_redraw(hwnd, RedrawRequest::Immediate);
+            state.shell.request_redraw(RedrawRequest::Immediate);
             return LRESULT(0);
         }
 
+        let now = state.shell.now();
         if let Some(drag) = state.hit_test_scrollbar_thumb(x, y, false) {
             state
                 .shell
                 .scroll_state_manager
-                .set_active(drag.element_id, drag.axis);
+                .set_active(drag.element_id, drag.axis, now);
             let _ = unsafe { InvalidateRect(Some(hwnd), None, false) };
-        } else if state.shell.scroll_state_manager.set_inactive() {
+        } else if state.shell.scroll_state_manager.set_inactive(now) {
             let _ = unsafe { InvalidateRect(Some(hwnd), None, false) };
         }
 
         // Continue manual drag (selection or preview drop position)
         state
             .shell
-            .dispatch_event(hwnd, &mut state.ui_tree, Event::MouseMove { x, y });
+            .dispatch_event(&mut state.ui_tree, Event::MouseMove { x, y });
     }
     LRESULT(0)
 }
@@ -203,7 +209,6 @@ pub fn handle_mousewheel<State: 'static, Message: 'static + Send + Clone>(
         let wheel_delta = -wheel_delta as f32 / 120.0;
         let modifiers = get_modifiers();
         state.shell.dispatch_event(
-            hwnd,
             &mut state.ui_tree,
             Event::MouseWheel {
                 x: x_dip,
@@ -281,7 +286,7 @@ pub fn handle_mousewheel<State: 'static, Message: 'static + Send + Clone>(
                                 delta,
                             );
 
-                            state.shell.request_redraw(hwnd, RedrawRequest::Immediate);
+                            state.shell.request_redraw(RedrawRequest::Immediate);
 
                             break;
                         }
@@ -311,7 +316,6 @@ pub fn handle_lbuttonup<State: 'static, Message: 'static + Send + Clone>(
 
             let modifiers = get_modifiers();
             state.shell.dispatch_event(
-                hwnd,
                 &mut state.ui_tree,
                 Event::MouseButtonUp {
                     x,
@@ -423,7 +427,7 @@ pub fn handle_ncmousemove<State: 'static, Message: 'static + Send + Clone>(
         // Dispatch as regular mouse move event
         state
             .shell
-            .dispatch_event(hwnd, &mut state.ui_tree, Event::MouseMove { x, y });
+            .dispatch_event(&mut state.ui_tree, Event::MouseMove { x, y });
 
         let _ = unsafe { InvalidateRect(Some(hwnd), None, false) };
     }
@@ -463,7 +467,6 @@ pub fn handle_nclbuttondown<State: 'static, Message: 'static + Send + Clone>(
 
             let modifiers = get_modifiers();
             state.shell.dispatch_event(
-                hwnd,
                 &mut state.ui_tree,
                 Event::MouseButtonDown {
                     x,
@@ -510,7 +513,6 @@ pub fn handle_nclbuttonup<State: 'static, Message: 'static + Send + Clone>(
 
         let modifiers = get_modifiers();
         state.shell.dispatch_event(
-            hwnd,
             &mut state.ui_tree,
             Event::MouseButtonUp {
                 x,
@@ -550,7 +552,6 @@ pub fn handle_mouseleave<State: 'static, Message: 'static + Send + Clone>(hwnd:
             if !inside_window {
                 // Dispatch mouse leave to clear hover states
                 state.shell.dispatch_event(
-                    hwnd,
                     &mut state.ui_tree,
                     Event::MouseMove {
                         x: -1000.0,
@@ -558,7 +559,8 @@ pub fn handle_mouseleave<State: 'static, Message: 'static + Send + Clone>(hwnd:
                     }, // Move far off-screen to clear hovers
                 );
 
-                state.shell.scroll_state_manager.set_inactive();
+                let now = state.shell.now();
+                state.shell.scroll_state_manager.set_inactive(now);
 
                 let _ = unsafe { InvalidateRect(Some(hwnd), None, false) };
             }
@@ -607,12 +609,8 @@ pub fn handle_setcursor<State: 'static, Message: 'static + Send + Clone>(
                 let bounds = state.ui_tree.slots[element].bounds();
 
                 if let Some(id) = state.ui_tree.slots[element].id
-                    && point.within(bounds.border_box)
-                    && Shell::is_point_visible_in_scroll_ancestors(
-                        &mut state.ui_tree,
-                        element,
-                        point,
-                    )
+                    && Shell::is_point_over_element(&mut state.ui_tree, element, point)
+                    && let Some(point) = point_to_local(&state.ui_tree.slots, element, point)
                     && let Some(instance) = state.ui_tree.widget_state.get(&id)
                     && let Some(ref widget) = state.ui_tree.slots[element].content
                 {
@@ -7,6 +7,7 @@ use windows::Win32::UI::WindowsAndMessaging::{
 };
 use windows_core::PCWSTR;
 
+use crate::platform;
 use crate::runtime::context_menu::{ContextMenu, ContextMenuRequest};
 use crate::runtime::syscommand::{SystemCommand, SystemCommandResponse};
 use crate::runtime::tray::TrayIcon;
@@ -149,7 +150,9 @@ pub fn handle_timer<State: 'static, Message: 'static + Send + Clone>(
     let timer_id = wparam.0;
     if let Some(mut state) = state_mut_from_hwnd::<State, Message>(hwnd) {
         let state = state.deref_mut();
-        state.shell.kill_redraw_timer(hwnd, timer_id);
+        if state.shell.kill_redraw_timer(timer_id) {
+            platform::kill_redraw_timer(hwnd);
+        }
     }
     unsafe {
         let _ = InvalidateRect(Some(hwnd), None, false);
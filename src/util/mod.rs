@@ -1,5 +1,6 @@
 pub mod str;
 pub mod unique;
+#[cfg(windows)]
 pub mod windows;
 
 #[macro_export]
@@ -2,8 +2,6 @@ use std::any::Any;
 use std::fmt::Debug;
 use std::time::Instant;
 
-use windows::Win32::Foundation::HWND;
-
 use crate::gfx::PointDIP;
 use crate::gfx::command_recorder::CommandRecorder;
 use crate::layout::UIArenas;
@@ -489,6 +487,11 @@ impl<Message> Widget<Message> for Button<Message> {
         }
     }
 
+    fn layout_key(&self, _arenas: &UIArenas, _instance: &Instance) -> Option<u64> {
+        // Sized by its element alone
+        super::layout_key_of(())
+    }
+
     fn adjust_style(
         &mut self,
         instance: &mut Instance,
@@ -533,7 +536,6 @@ impl<Message> Widget<Message> for Button<Message> {
         &mut self,
         arenas: &mut UIArenas,
         instance: &mut Instance,
-        hwnd: HWND,
         shell: &mut Shell<Message>,
         event: &super::Event,
         bounds: Bounds,
@@ -549,7 +551,7 @@ impl<Message> Widget<Message> for Button<Message> {
                     state.update_state(self.enabled);
                     shell.capture_event(instance.id);
                     shell.focus_manager.focus(instance.id);
-                    shell.request_redraw(hwnd, RedrawRequest::Immediate);
+                    shell.request_redraw(RedrawRequest::Immediate);
                 }
             }
             super::Event::MouseButtonUp { x, y, .. } => {
@@ -569,7 +571,7 @@ impl<Message> Widget<Message> for Button<Message> {
                     handler(arenas, shell);
                 }
 
-                shell.request_redraw(hwnd, RedrawRequest::Immediate);
+                shell.request_redraw(RedrawRequest::Immediate);
             }
             super::Event::MouseMove { x, y } | super::Event::MouseEnter { x, y } => {
                 let point = PointDIP { x: *x, y: *y };
@@ -578,7 +580,7 @@ impl<Message> Widget<Message> for Button<Message> {
 
                 if was_over != state.is_mouse_over {
                     state.update_state(self.enabled);
-                    shell.request_redraw(hwnd, RedrawRequest::Immediate);
+                    shell.request_redraw(RedrawRequest::Immediate);
                 }
             }
             super::Event::MouseLeave { .. } => {
@@ -587,7 +589,7 @@ impl<Message> Widget<Message> for Button<Message> {
 
                 if was_over {
                     state.update_state(self.enabled);
-                    shell.request_redraw(hwnd, RedrawRequest::Immediate);
+                    shell.request_redraw(RedrawRequest::Immediate);
                 }
             }
             _ => {}
@@ -1,10 +1,4 @@
-use windows::Win32::{
-    Foundation::POINTL,
-    System::{
-        Com::IDataObject,
-        Ole::{DROPEFFECT, DROPEFFECT_NONE},
-    },
-};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     Shell,
@@ -12,6 +6,24 @@ use crate::{
     widgets::{Bounds, Instance},
 };
 
+/// The set of operations a drag source allows, or the one a drop target picked.
+///
+/// Bit values match OLE's `DROPEFFECT` so they convert losslessly at the platform boundary.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DropEffect(pub u32);
+
+impl DropEffect {
+    pub const NONE: Self = Self(0);
+    pub const COPY: Self = Self(1);
+    pub const MOVE: Self = Self(2);
+    pub const LINK: Self = Self(4);
+
+    /// Returns true if every effect in `other` is also set in `self`
+    pub fn contains(self, other: Self) -> bool {
+        other.0 != 0 && (self.0 & other.0) == other.0
+    }
+}
+
 /// Represents the data being dragged
 #[derive(Debug, Clone)]
 pub enum DragData {
@@ -24,20 +36,20 @@ pub enum DragData {
 pub struct DragInfo {
     pub data: DragData,
     pub position: PointDIP,
-    pub allowed_effects: DROPEFFECT,
+    pub allowed_effects: DropEffect,
 }
 
 /// Result of a drag/drop operation
 #[derive(Debug, Clone)]
 pub struct DropResult {
-    pub effect: DROPEFFECT,
+    pub effect: DropEffect,
     pub handled: bool,
 }
 
 impl Default for DropResult {
     fn default() -> Self {
         Self {
-            effect: DROPEFFECT_NONE,
+            effect: DropEffect::NONE,
             handled: false,
         }
     }
@@ -53,9 +65,9 @@ pub trait WidgetDragDropTarget<Message> {
         instance: &mut Instance,
         drag_info: &DragInfo,
         widget_bounds: Bounds,
-    ) -> DROPEFFECT {
+    ) -> DropEffect {
         let _ = (drag_info, widget_bounds);
-        DROPEFFECT_NONE
+        DropEffect::NONE
     }
 
     /// Called when a drag operation moves within the widget's bounds
@@ -65,9 +77,9 @@ pub trait WidgetDragDropTarget<Message> {
         instance: &mut Instance,
         drag_info: &DragInfo,
         widget_bounds: Bounds,
-    ) -> DROPEFFECT {
+    ) -> DropEffect {
         let _ = (drag_info, widget_bounds);
-        DROPEFFECT_NONE
+        DropEffect::NONE
     }
 
     /// Called when a drag operation leaves the widget's bounds
@@ -86,70 +98,3 @@ pub trait WidgetDragDropTarget<Message> {
         DropResult::default()
     }
 }
-
-/// Helper to extract DragData from IDataObject
-pub fn extract_drag_data(data_object: &IDataObject) -> Option<DragData> {
-    use windows::Win32::System::{
-        Com::{DVASPECT_CONTENT, FORMATETC, STGMEDIUM, TYMED_HGLOBAL},
-        Memory::{GlobalLock, GlobalUnlock},
-        Ole::{CF_UNICODETEXT, ReleaseStgMedium},
-    };
-
-    unsafe {
-        // Try to get Unicode text
-        let fmt = FORMATETC {
-            cfFormat: CF_UNICODETEXT.0,
-            ptd: std::ptr::null_mut(),
-            dwAspect: DVASPECT_CONTENT.0,
-            lindex: -1,
-            tymed: TYMED_HGLOBAL.0 as u32,
-        };
-
-        if let Ok(mut medium) = data_object.GetData(&fmt) {
-            let h = medium.u.hGlobal;
-            let ptr = GlobalLock(h) as *const u16;
-            if !ptr.is_null() {
-                // Read until NUL
-                let mut out: Vec<u16> = Vec::new();
-                let mut i = 0isize;
-                loop {
-                    let v = *ptr.offset(i);
-                    if v == 0 {
-                        break;
-                    }
-                    out.push(v);
-                    i += 1;
-                }
-                let _ = GlobalUnlock(h);
-                let s = String::from_utf16_lossy(&out);
-                ReleaseStgMedium(&mut medium as *mut STGMEDIUM);
-                return Some(DragData::Text(s.replace("\r\n", "\n")));
-            }
-            ReleaseStgMedium(&mut medium as *mut STGMEDIUM);
-        }
-    }
-
-    None
-}
-
-/// Helper to convert screen coordinates to widget-relative coordinates
-pub fn screen_to_widget_coords(
-    hwnd: windows::Win32::Foundation::HWND,
-    screen_point: &POINTL,
-    widget_bounds: crate::gfx::RectDIP,
-) -> PointDIP {
-    use windows::Win32::{Foundation::POINT, Graphics::Gdi::ScreenToClient};
-
-    unsafe {
-        let mut p = POINT {
-            x: screen_point.x,
-            y: screen_point.y,
-        };
-        let _ = ScreenToClient(hwnd, &mut p);
-        let to_dip = crate::dips_scale(hwnd);
-        PointDIP {
-            x: (p.x as f32) * to_dip - widget_bounds.x,
-            y: (p.y as f32) * to_dip - widget_bounds.y,
-        }
-    }
-}
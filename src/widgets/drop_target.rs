@@ -3,7 +3,7 @@ use windows::{
         Foundation::{HWND, POINTL},
         System::{
             Com::IDataObject,
-            Ole::{DROPEFFECT, DROPEFFECT_NONE, IDropTarget, IDropTarget_Impl},
+            Ole::{DROPEFFECT, IDropTarget, IDropTarget_Impl},
             SystemServices::{MK_CONTROL, MODIFIERKEYS_FLAGS},
         },
     },
@@ -12,7 +12,7 @@ use windows::{
 
 use crate::{
     gfx::PointDIP,
-    widgets::{DragEvent, DragInfo, dragdrop::extract_drag_data},
+    widgets::{DragData, DragEvent, DragInfo, DropEffect},
 };
 
 /// Integrated drop target that works with the Shell and widget system
@@ -20,13 +20,13 @@ use crate::{
 pub struct DropTarget {
     hwnd: HWND,
     /// Callback to dispatch events to the Shell, receives HWND as parameter
-    event_dispatcher: Box<dyn Fn(HWND, DragEvent) -> DROPEFFECT + Send>,
+    event_dispatcher: Box<dyn Fn(HWND, DragEvent) -> DropEffect + Send>,
 }
 
 impl DropTarget {
     pub fn new<F>(hwnd: HWND, event_dispatcher: F) -> Self
     where
-        F: Fn(HWND, DragEvent) -> DROPEFFECT + Send + 'static,
+        F: Fn(HWND, DragEvent) -> DropEffect + Send + 'static,
     {
         Self {
             hwnd,
@@ -34,11 +34,11 @@ impl DropTarget {
         }
     }
 
-    fn choose_effect(&self, keys: MODIFIERKEYS_FLAGS) -> DROPEFFECT {
+    fn choose_effect(&self, keys: MODIFIERKEYS_FLAGS) -> DropEffect {
         if (keys.0 & MK_CONTROL.0) != 0 {
-            windows::Win32::System::Ole::DROPEFFECT_COPY
+            DropEffect::COPY
         } else {
-            windows::Win32::System::Ole::DROPEFFECT_MOVE
+            DropEffect::MOVE
         }
     }
 
@@ -62,7 +62,7 @@ impl DropTarget {
         &self,
         data_object: &IDataObject,
         point: &POINTL,
-        allowed_effects: DROPEFFECT,
+        allowed_effects: DropEffect,
     ) -> Option<DragInfo> {
         if let Some(data) = extract_drag_data(data_object) {
             let position = self.screen_to_client_dip(point);
@@ -95,15 +95,15 @@ impl IDropTarget_Impl for DropTarget_Impl {
                 let event = DragEvent::DragEnter { drag_info };
                 (self.event_dispatcher)(self.hwnd, event)
             } else {
-                DROPEFFECT_NONE
+                DropEffect::NONE
             }
         } else {
-            DROPEFFECT_NONE
+            DropEffect::NONE
         };
 
         if !pdwEffect.is_null() {
             unsafe {
-                *pdwEffect = effect;
+                *pdwEffect = effect.into();
             }
         }
         Ok(())
@@ -118,7 +118,7 @@ impl IDropTarget_Impl for DropTarget_Impl {
         // For DragOver, we create a placeholder drag info since we don't have the data object
         let position = self.screen_to_client_dip(pt);
         let drag_info = DragInfo {
-            data: crate::widgets::DragData::Text(String::new()), // Placeholder
+            data: DragData::Text(String::new()), // Placeholder
             position,
             allowed_effects: self.choose_effect(grfKeyState),
         };
@@ -128,7 +128,7 @@ impl IDropTarget_Impl for DropTarget_Impl {
 
         if !pdwEffect.is_null() {
             unsafe {
-                *pdwEffect = effect;
+                *pdwEffect = effect.into();
             }
         }
         Ok(())
@@ -154,17 +154,84 @@ impl IDropTarget_Impl for DropTarget_Impl {
                 let event = DragEvent::Drop { drag_info };
                 (self.event_dispatcher)(self.hwnd, event)
             } else {
-                DROPEFFECT_NONE
+                DropEffect::NONE
             }
         } else {
-            DROPEFFECT_NONE
+            DropEffect::NONE
         };
 
         if !pdwEffect.is_null() {
             unsafe {
-                *pdwEffect = effect;
+                *pdwEffect = effect.into();
             }
         }
         Ok(())
     }
 }
+
+/// Helper to extract DragData from IDataObject
+pub fn extract_drag_data(data_object: &IDataObject) -> Option<DragData> {
+    use windows::Win32::System::{
+        Com::{DVASPECT_CONTENT, FORMATETC, STGMEDIUM, TYMED_HGLOBAL},
+        Memory::{GlobalLock, GlobalUnlock},
+        Ole::{CF_UNICODETEXT, ReleaseStgMedium},
+    };
+
+    unsafe {
+        // Try to get Unicode text
+        let fmt = FORMATETC {
+            cfFormat: CF_UNICODETEXT.0,
+            ptd: std::ptr::null_mut(),
+            dwAspect: DVASPECT_CONTENT.0,
+            lindex: -1,
+            tymed: TYMED_HGLOBAL.0 as u32,
+        };
+
+        if let Ok(mut medium) = data_object.GetData(&fmt) {
+            let h = medium.u.hGlobal;
+            let ptr = GlobalLock(h) as *const u16;
+            if !ptr.is_null() {
+                // Read until NUL
+                let mut out: Vec<u16> = Vec::new();
+                let mut i = 0isize;
+                loop {
+                    let v = *ptr.offset(i);
+                    if v == 0 {
+                        break;
+                    }
+                    out.push(v);
+                    i += 1;
+                }
+                let _ = GlobalUnlock(h);
+                let s = String::from_utf16_lossy(&out);
+                ReleaseStgMedium(&mut medium as *mut STGMEDIUM);
+                return Some(DragData::Text(s.replace("\r\n", "\n")));
+            }
+            ReleaseStgMedium(&mut medium as *mut STGMEDIUM);
+        }
+    }
+
+    None
+}
+
+/// Helper to convert screen coordinates to widget-relative coordinates
+pub fn screen_to_widget_coords(
+    hwnd: windows::Win32::Foundation::HWND,
+    screen_point: &POINTL,
+    widget_bounds: crate::gfx::RectDIP,
+) -> PointDIP {
+    use windows::Win32::{Foundation::POINT, Graphics::Gdi::ScreenToClient};
+
+    unsafe {
+        let mut p = POINT {
+            x: screen_point.x,
+            y: screen_point.y,
+        };
+        let _ = ScreenToClient(hwnd, &mut p);
+        let to_dip = crate::dips_scale(hwnd);
+        PointDIP {
+            x: (p.x as f32) * to_dip - widget_bounds.x,
+            y: (p.y as f32) * to_dip - widget_bounds.y,
+        }
+    }
+}
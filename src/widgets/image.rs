@@ -1,25 +1,24 @@
 use crate::{
     Shell,
-    gfx::command_recorder::CommandRecorder,
+    gfx::{command_recorder::CommandRecorder, resources::BitmapResource},
     layout::{
         UIArenas,
         model::{Element, ElementStyle},
     },
     runtime::DeviceResources,
     util::str::StableString,
-    widgets::{Bounds, Cursor, Event, Instance, State, Widget, limit_response, widget},
+    widgets::{
+        Bounds, Cursor, Event, Instance, State, Widget, layout_key_of, limit_response, widget,
+    },
     with_state,
 };
 use std::{any::Any, time::Instant};
 use windows::Win32::{
-    Foundation::{GENERIC_READ, HWND},
-    Graphics::{
-        Direct2D::{ID2D1Bitmap, ID2D1DeviceContext6},
-        Imaging::{
-            CLSID_WICImagingFactory, GUID_WICPixelFormat32bppPBGRA, IWICFormatConverter,
-            IWICImagingFactory, WICBitmapDitherTypeNone, WICBitmapPaletteTypeCustom,
-            WICDecodeMetadataCacheOnDemand,
-        },
+    Foundation::GENERIC_READ,
+    Graphics::Imaging::{
+        CLSID_WICImagingFactory, GUID_WICPixelFormat32bppRGBA, IWICFormatConverter,
+        IWICImagingFactory, WICBitmapDitherTypeNone, WICBitmapPaletteTypeCustom,
+        WICDecodeMetadataCacheOnDemand,
     },
     System::Com::{CLSCTX_INPROC_SERVER, CoCreateInstance},
 };
@@ -55,12 +54,10 @@ pub struct Image {
     fit: ImageFit,
 }
 
-/// State for Image widget that caches the WIC bitmap and D2D bitmap
+/// State for Image widget that caches the decoded pixels
 struct ImageWidgetState {
-    /// Device context for creating D2D bitmap
-    device_context: ID2D1DeviceContext6,
-    /// Cached D2D bitmap
-    d2d_bitmap: Option<ID2D1Bitmap>,
+    /// Cached decoded bitmap
+    bitmap: Option<BitmapResource>,
     /// Cached image path to detect changes
     cached_image_path: String,
     /// Intrinsic width from image
@@ -70,10 +67,9 @@ struct ImageWidgetState {
 }
 
 impl ImageWidgetState {
-    pub fn new(device_context: ID2D1DeviceContext6) -> Self {
+    pub fn new() -> Self {
         Self {
-            device_context,
-            d2d_bitmap: None,
+            bitmap: None,
             cached_image_path: String::new(),
             intrinsic_width: 0.0,
             intrinsic_height: 0.0,
@@ -84,10 +80,10 @@ impl ImageWidgetState {
         Box::new(self)
     }
 
-    /// Load image from file and create D2D bitmap
+    /// Load and decode image from file
     fn load_image(&mut self, image_path: &str) -> windows::core::Result<bool> {
         // Only reload if path changed
-        if self.cached_image_path == image_path && self.d2d_bitmap.is_some() {
+        if self.cached_image_path == image_path && self.bitmap.is_some() {
             return Ok(false);
         }
 
@@ -120,23 +116,22 @@ impl ImageWidgetState {
             self.intrinsic_width = width as f32;
             self.intrinsic_height = height as f32;
 
-            // Convert to 32bppPBGRA format
+            // Convert to straight 32bppRGBA format
             let converter: IWICFormatConverter = wic_factory.CreateFormatConverter()?;
             converter.Initialize(
                 &frame,
-                &GUID_WICPixelFormat32bppPBGRA,
+                &GUID_WICPixelFormat32bppRGBA,
                 WICBitmapDitherTypeNone,
                 None,
                 0.0,
                 WICBitmapPaletteTypeCustom,
             )?;
 
-            // Create D2D bitmap from WIC bitmap
-            let d2d_bitmap = self
-                .device_context
-                .CreateBitmapFromWicBitmap(&converter, None)?;
+            // Copy the decoded pixels out
+            let mut pixels = vec![0u8; width as usize * height as usize * 4];
+            converter.CopyPixels(std::ptr::null(), width * 4, &mut pixels)?;
 
-            self.d2d_bitmap = Some(d2d_bitmap.into());
+            self.bitmap = Some(BitmapResource::new(width, height, pixels));
             self.cached_image_path = image_path.to_string();
 
             Ok(true)
@@ -258,8 +253,19 @@ impl<Message> Widget<Message> for Image {
         }
     }
 
-    fn state(&self, _arenas: &UIArenas, device_resources: &DeviceResources) -> State {
-        Some(ImageWidgetState::new(device_resources.d2d_device_context.clone()).into_any())
+    fn layout_key(&self, arenas: &UIArenas, instance: &Instance) -> Option<u64> {
+        let state = with_state!(instance as ImageWidgetState);
+        layout_key_of((
+            self.image_path.resolve(arenas),
+            self.width.map(f32::to_bits),
+            self.height.map(f32::to_bits),
+            state.intrinsic_width.to_bits(),
+            state.intrinsic_height.to_bits(),
+        ))
+    }
+
+    fn state(&self, _arenas: &UIArenas, _device_resources: &DeviceResources) -> State {
+        Some(ImageWidgetState::new().into_any())
     }
 
     fn paint(
@@ -277,14 +283,14 @@ impl<Message> Widget<Message> for Image {
 
             // Load image if needed
             if state.load_image(image_path).is_ok()
-                && let Some(ref bitmap) = state.d2d_bitmap
+                && let Some(bitmap) = &state.bitmap
             {
                 let dest_rect = self.calculate_dest_rect(
                     &bounds.content_box,
                     state.intrinsic_width,
                     state.intrinsic_height,
                 );
-                recorder.draw_bitmap(&dest_rect, bitmap, self.opacity);
+                recorder.draw_bitmap(&dest_rect, bitmap.clone(), self.opacity);
             }
         }
     }
@@ -293,7 +299,6 @@ impl<Message> Widget<Message> for Image {
         &mut self,
         _arenas: &mut UIArenas,
         _instance: &mut Instance,
-        _hwnd: HWND,
         _shell: &mut Shell<Message>,
         _event: &Event,
         _bounds: Bounds,
@@ -1,42 +1,44 @@
-use std::{any::Any, time::Instant};
+use std::{
+    any::Any,
+    hash::{DefaultHasher, Hash, Hasher},
+    time::Instant,
+};
 
+use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
-use windows::Win32::{
-    Foundation::HWND,
-    System::Ole::DROPEFFECT,
-    UI::WindowsAndMessaging::{
-        IDC_APPSTARTING, IDC_ARROW, IDC_CROSS, IDC_HAND, IDC_HELP, IDC_IBEAM, IDC_NO, IDC_SIZEALL,
-        IDC_SIZENESW, IDC_SIZENS, IDC_SIZENWSE, IDC_SIZEWE, IDC_UPARROW, IDC_WAIT, LoadCursorW,
-        SetCursor,
-    },
-};
 
 use crate::{
     Shell,
     gfx::{PointDIP, RectDIP, command_recorder::CommandRecorder},
     layout::{
         BorrowedUITree, UIArenas,
-        model::{ElementStyle, UIElement, WidgetContent},
+        model::{Color, ElementStyle, UIElement, WidgetContent},
         visitors,
     },
     runtime::{DeviceResources, vkey::VKey},
 };
 
-pub use dragdrop::{DragData, DragInfo, DropResult, WidgetDragDropTarget};
+pub use dragdrop::{DragData, DragInfo, DropEffect, DropResult, WidgetDragDropTarget};
 
 pub mod button;
 pub mod dragdrop;
+#[cfg(windows)]
 pub mod drop_target;
+#[cfg(windows)]
 pub mod image;
 pub mod mouse_area;
+#[cfg(windows)]
 pub mod renderer;
 pub mod rule;
 pub mod slider;
 pub mod spinner;
+#[cfg(windows)]
 pub mod svg;
+#[cfg(windows)]
 pub mod svg_path;
 pub mod text;
 pub mod text_input;
+#[cfg(windows)]
 pub mod titlebar_controls;
 pub mod toggle;
 
@@ -54,7 +56,7 @@ pub mod limit_response {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Modifiers {
     pub shift: bool,
     pub ctrl: bool,
@@ -62,7 +64,7 @@ pub struct Modifiers {
 }
 
 #[non_exhaustive]
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Event {
     ImeStartComposition,
     ImeComposition {
@@ -115,10 +117,12 @@ pub enum Event {
         text: SmolStr,
     },
     Redraw {
+        /// Not serialized: a replayed redraw happens at the replaying clock's time
+        #[serde(skip, default = "Instant::now")]
         now: Instant,
     },
     DragFinish {
-        effect: DROPEFFECT,
+        effect: DropEffect,
     },
 }
 
@@ -135,6 +139,20 @@ impl Event {
         )
     }
 
+    /// Whether the event is keyboard or text input, which goes to the focused widget
+    pub fn is_keyboard_event(&self) -> bool {
+        matches!(
+            self,
+            Event::KeyDown { .. }
+                | Event::KeyUp { .. }
+                | Event::Char { .. }
+                | Event::ImeStartComposition
+                | Event::ImeComposition { .. }
+                | Event::ImeCommit { .. }
+                | Event::ImeEndComposition
+        )
+    }
+
     pub fn mouse_position(&self) -> Option<(f32, f32)> {
         match self {
             Event::MouseButtonDown { x, y, .. } => Some((*x, *y)),
@@ -146,6 +164,45 @@ impl Event {
             _ => None,
         }
     }
+
+    /// The same event at another mouse position, events without one are returned as is
+    pub fn with_mouse_position(&self, x: f32, y: f32) -> Self {
+        let mut event = self.clone();
+        match &mut event {
+            Event::MouseButtonDown {
+                x: event_x,
+                y: event_y,
+                ..
+            }
+            | Event::MouseButtonUp {
+                x: event_x,
+                y: event_y,
+                ..
+            }
+            | Event::MouseMove {
+                x: event_x,
+                y: event_y,
+            }
+            | Event::MouseEnter {
+                x: event_x,
+                y: event_y,
+            }
+            | Event::MouseLeave {
+                x: event_x,
+                y: event_y,
+            }
+            | Event::MouseWheel {
+                x: event_x,
+                y: event_y,
+                ..
+            } => {
+                *event_x = x;
+                *event_y = y;
+            }
+            _ => {}
+        }
+        event
+    }
 }
 
 #[derive(Debug)]
@@ -178,58 +235,40 @@ pub enum Cursor {
 
 impl Cursor {
     pub fn set(self) {
-        unsafe {
-            match self {
-                Cursor::Arrow => {
-                    let _ = SetCursor(Some(LoadCursorW(None, IDC_ARROW).unwrap()));
-                }
-                Cursor::IBeam => {
-                    let _ = SetCursor(Some(LoadCursorW(None, IDC_IBEAM).unwrap()));
-                }
-                Cursor::Pointer => {
-                    let _ = SetCursor(Some(LoadCursorW(None, IDC_HAND).unwrap()));
-                }
-                Cursor::Cross => {
-                    let _ = SetCursor(Some(LoadCursorW(None, IDC_CROSS).unwrap()));
-                }
-                Cursor::Hand => {
-                    let _ = SetCursor(Some(LoadCursorW(None, IDC_HAND).unwrap()));
-                }
-                Cursor::Help => {
-                    let _ = SetCursor(Some(LoadCursorW(None, IDC_HELP).unwrap()));
-                }
-                Cursor::No => {
-                    let _ = SetCursor(Some(LoadCursorW(None, IDC_NO).unwrap()));
-                }
-                Cursor::SizeNS => {
-                    let _ = SetCursor(Some(LoadCursorW(None, IDC_SIZENS).unwrap()));
-                }
-                Cursor::SizeEW => {
-                    let _ = SetCursor(Some(LoadCursorW(None, IDC_SIZEWE).unwrap()));
-                }
-                Cursor::SizeNWSE => {
-                    let _ = SetCursor(Some(LoadCursorW(None, IDC_SIZENWSE).unwrap()));
-                }
-                Cursor::SizeNESW => {
-                    let _ = SetCursor(Some(LoadCursorW(None, IDC_SIZENESW).unwrap()));
-                }
-                Cursor::SizeAll => {
-                    let _ = SetCursor(Some(LoadCursorW(None, IDC_SIZEALL).unwrap()));
-                }
-                Cursor::UpArrow => {
-                    let _ = SetCursor(Some(LoadCursorW(None, IDC_UPARROW).unwrap()));
-                }
-                Cursor::Wait => {
-                    let _ = SetCursor(Some(LoadCursorW(None, IDC_WAIT).unwrap()));
-                }
-                Cursor::AppStarting => {
-                    let _ = SetCursor(Some(LoadCursorW(None, IDC_APPSTARTING).unwrap()));
-                }
-            }
+        crate::platform::set_cursor(self);
+    }
+}
+
+/// A color that can defer to the `color` of the element style
+#[derive(Debug, Clone, Copy)]
+pub enum ColorChoice {
+    None,
+    Color(Color),
+    CurrentColor,
+}
+
+impl ColorChoice {
+    pub fn or_current_color(self, current_color: Option<Color>) -> Option<Color> {
+        match self {
+            ColorChoice::CurrentColor => current_color,
+            ColorChoice::Color(color) => Some(color),
+            ColorChoice::None => None,
         }
     }
 }
 
+impl From<Color> for ColorChoice {
+    fn from(color: Color) -> Self {
+        ColorChoice::Color(color)
+    }
+}
+
+impl From<()> for ColorChoice {
+    fn from(_: ()) -> Self {
+        ColorChoice::None
+    }
+}
+
 pub type State = Option<Box<dyn Any>>;
 
 #[macro_export]
@@ -272,6 +311,13 @@ impl Instance {
     }
 }
 
+/// [`Widget::layout_key`] of a widget measured from `props` alone
+pub fn layout_key_of(props: impl Hash) -> Option<u64> {
+    let mut hasher = DefaultHasher::new();
+    props.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
 pub enum PaintOwnership {
     Contents,
     Full,
@@ -297,6 +343,27 @@ pub trait Widget<Message>: std::fmt::Debug {
         limit_response::SizingForY::default()
     }
 
+    /// Distance from the top of the content box to the first baseline, for
+    /// [`Alignment::Baseline`](crate::layout::model::Alignment::Baseline). Widgets
+    /// without text return `None` and sit on the baseline with their bottom edge.
+    fn baseline(
+        &self,
+        arenas: &UIArenas,
+        instance: &mut Instance,
+        content_width: f32,
+        content_height: f32,
+    ) -> Option<f32> {
+        None
+    }
+
+    /// Hash of everything `limits_x`, `limits_y` and `baseline` depend on. While it and the
+    /// element don't change, the layout of the previous frame is reused without measuring the
+    /// widget again. Widgets without a key are measured every frame, widgets measured from their
+    /// props alone can hash them with [`layout_key_of`].
+    fn layout_key(&self, arenas: &UIArenas, instance: &Instance) -> Option<u64> {
+        None
+    }
+
     fn state(&self, arenas: &UIArenas, device_resources: &DeviceResources) -> State {
         None
     }
@@ -314,6 +381,7 @@ pub trait Widget<Message>: std::fmt::Debug {
         style
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn paint(
         &mut self,
         arenas: &UIArenas,
@@ -329,7 +397,6 @@ pub trait Widget<Message>: std::fmt::Debug {
         &mut self,
         arenas: &mut UIArenas,
         instance: &mut Instance,
-        hwnd: HWND,
         shell: &mut Shell<Message>,
         event: &Event,
         bounds: Bounds,
@@ -1,8 +1,6 @@
 use std::fmt::Debug;
 use std::time::Instant;
 
-use windows::Win32::Foundation::HWND;
-
 use crate::gfx::PointDIP;
 use crate::layout::UIArenas;
 use crate::layout::model::{Element, ElementStyle};
@@ -283,6 +281,11 @@ where
         }
     }
 
+    fn layout_key(&self, _arenas: &UIArenas, _instance: &Instance) -> Option<u64> {
+        // Sized by its element alone
+        crate::widgets::layout_key_of(())
+    }
+
     fn state(&self, _arenas: &UIArenas, _device_resources: &DeviceResources) -> State {
         Some(Box::new(MouseAreaState::default()))
     }
@@ -304,7 +307,6 @@ where
         &mut self,
         _arenas: &mut UIArenas,
         instance: &mut Instance,
-        _hwnd: HWND,
         shell: &mut Shell<Message>,
         event: &Event,
         bounds: Bounds,
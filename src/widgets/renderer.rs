@@ -28,7 +28,11 @@ use windows_core::Interface;
 use windows_numerics::{Matrix3x2, Vector2, Vector4};
 
 use crate::{
-    gfx::RectDIP,
+    gfx::{
+        Matrix2D, RectDIP,
+        resource_cache::{RealizedResource, ResourceCache},
+        resources::{ResourceId, ResourceTable},
+    },
     layout::model::{
         Border, BorderPlacement, BorderRadius, Color, DropShadow, StrokeDashStyle, StrokeLineCap,
         StrokeLineJoin,
@@ -196,32 +200,54 @@ pub struct Renderer<'a> {
     pub render_target: &'a ID2D1DeviceContext6,
     pub brush: &'a ID2D1SolidColorBrush,
     shadow_cache: &'a RefCell<ShadowCache>,
+    resource_cache: &'a RefCell<ResourceCache>,
+    /// Transforms to restore when popping pushed transforms, innermost last
+    transforms: RefCell<Vec<Matrix3x2>>,
 }
 
 impl Renderer<'_> {
-    /// Create a new Renderer with a reference to the shadow cache
+    /// Create a new Renderer with references to the shadow and resource caches
     pub fn new<'a>(
         factory: &'a ID2D1Factory,
         render_target: &'a ID2D1DeviceContext6,
         brush: &'a ID2D1SolidColorBrush,
         shadow_cache: &'a RefCell<ShadowCache>,
+        resource_cache: &'a RefCell<ResourceCache>,
     ) -> Renderer<'a> {
         Renderer {
             factory,
             render_target,
             brush,
             shadow_cache,
+            resource_cache,
+            transforms: RefCell::new(Vec::new()),
         }
     }
 
     /// Mark the start of a new frame for cache management
     pub fn start_frame(&self) {
         self.shadow_cache.borrow_mut().start_frame();
+        self.resource_cache.borrow_mut().start_frame();
     }
 
     /// Evict unused cache entries to free memory
     pub fn evict_unused_cache_entries(&self) {
         self.shadow_cache.borrow_mut().evict_unused();
+        self.resource_cache.borrow_mut().evict_unused();
+    }
+
+    /// Get the Direct2D realization of a resource referenced by a draw command
+    pub fn realize_resource(
+        &self,
+        id: ResourceId,
+        resources: &ResourceTable,
+    ) -> Option<RealizedResource> {
+        self.resource_cache.borrow_mut().get_or_realize(
+            id,
+            resources,
+            self.factory,
+            self.render_target,
+        )
     }
 
     fn create_stroke_style(
@@ -1411,6 +1437,35 @@ impl Renderer<'_> {
         }
     }
 
+    /// Apply `matrix` on top of the current transform until [`Self::pop_transform`]
+    pub fn push_transform(&self, matrix: &Matrix2D) {
+        unsafe {
+            let mut current_transform = Matrix3x2::default();
+            self.render_target.GetTransform(&mut current_transform);
+            self.transforms.borrow_mut().push(current_transform);
+
+            let transform = Matrix3x2 {
+                M11: matrix.m11,
+                M12: matrix.m12,
+                M21: matrix.m21,
+                M22: matrix.m22,
+                M31: matrix.dx,
+                M32: matrix.dy,
+            };
+            self.render_target
+                .SetTransform(&(transform * current_transform));
+        }
+    }
+
+    /// Restore the transform from before the last [`Self::push_transform`]
+    pub fn pop_transform(&self) {
+        if let Some(previous_transform) = self.transforms.borrow_mut().pop() {
+            unsafe {
+                self.render_target.SetTransform(&previous_transform);
+            }
+        }
+    }
+
     /// Draw an SVG document at the specified rectangle
     pub fn draw_svg(
         &self,
@@ -1810,7 +1865,7 @@ impl Renderer<'_> {
 
     /// Creates a custom pixel shader effect instance.
     ///
-    /// The effect type must have been registered via [`DeviceResources::register_effect`]
+    /// The effect type must have been registered via [`GpuResources::register_effect`](crate::runtime::device::GpuResources::register_effect)
     /// before calling this method.
     ///
     /// # Example
@@ -1862,6 +1917,7 @@ impl Renderer<'_> {
     pub fn render_commands_to_bitmap(
         &self,
         commands: &[crate::gfx::draw_commands::DrawCommand],
+        resources: &ResourceTable,
         bounds: &RectDIP,
     ) -> windows::core::Result<ID2D1Bitmap> {
         use crate::gfx::command_executor::CommandExecutor;
@@ -1898,6 +1954,8 @@ impl Renderer<'_> {
                 render_target: &bitmap_rt.cast::<ID2D1DeviceContext6>().unwrap(),
                 brush: self.brush,
                 shadow_cache: self.shadow_cache,
+                resource_cache: self.resource_cache,
+                transforms: RefCell::new(Vec::new()),
             };
 
             // Execute commands, track clip state
@@ -1918,7 +1976,7 @@ impl Renderer<'_> {
                     _ => {}
                 }
 
-                CommandExecutor::execute_command(&offscreen_renderer, commands, i)?;
+                CommandExecutor::execute_command(&offscreen_renderer, resources, commands, i)?;
             }
 
             // Pop any remaining clips
@@ -1,5 +1,3 @@
-use windows::Win32::Foundation::HWND;
-
 use crate::Shell;
 use crate::gfx::command_recorder::CommandRecorder;
 use crate::layout::UIArenas;
@@ -7,7 +5,7 @@ use crate::layout::model::{Color, Element, ElementStyle, Sizing, StrokeDashStyle
 use crate::widgets::{Bounds, Event, Instance, Widget};
 
 /// Orientation of the rule/divider
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum RuleOrientation {
     #[default]
     Horizontal,
@@ -183,6 +181,10 @@ impl<Message> Widget<Message> for Rule {
         }
     }
 
+    fn layout_key(&self, _arenas: &UIArenas, _instance: &Instance) -> Option<u64> {
+        crate::widgets::layout_key_of((self.orientation, self.thickness.to_bits()))
+    }
+
     fn paint(
         &mut self,
         _arenas: &UIArenas,
@@ -241,7 +243,6 @@ impl<Message> Widget<Message> for Rule {
         &mut self,
         _arenas: &mut UIArenas,
         _instance: &mut Instance,
-        _hwnd: HWND,
         _shell: &mut Shell<Message>,
         _event: &Event,
         _bounds: Bounds,
@@ -2,8 +2,6 @@ use std::any::Any;
 use std::fmt::Debug;
 use std::time::Instant;
 
-use windows::Win32::Foundation::HWND;
-
 use crate::gfx::PointDIP;
 use crate::gfx::command_recorder::CommandRecorder;
 use crate::layout::UIArenas;
@@ -316,11 +314,14 @@ impl<Message> Widget<Message> for Slider<Message> {
         }
     }
 
+    fn layout_key(&self, _arenas: &UIArenas, _instance: &Instance) -> Option<u64> {
+        super::layout_key_of(self.thumb_size.to_bits())
+    }
+
     fn update(
         &mut self,
         arenas: &mut UIArenas,
         instance: &mut Instance,
-        hwnd: HWND,
         shell: &mut Shell<Message>,
         event: &super::Event,
         bounds: Bounds,
@@ -352,21 +353,19 @@ impl<Message> Widget<Message> for Slider<Message> {
                     }
 
                     shell.capture_event(instance.id);
-                    shell.request_redraw(hwnd, RedrawRequest::Immediate);
+                    shell.request_redraw(RedrawRequest::Immediate);
                 }
             }
-            super::Event::MouseButtonUp { .. } => {
-                if state.is_dragging {
-                    state.is_dragging = false;
-                    state.update_state(self.enabled);
+            super::Event::MouseButtonUp { .. } if state.is_dragging => {
+                state.is_dragging = false;
+                state.update_state(self.enabled);
 
-                    // Fire drag end callback
-                    if let Some(handler) = self.on_drag.as_ref() {
-                        handler(false, arenas, shell);
-                    }
-
-                    shell.request_redraw(hwnd, RedrawRequest::Immediate);
+                // Fire drag end callback
+                if let Some(handler) = self.on_drag.as_ref() {
+                    handler(false, arenas, shell);
                 }
+
+                shell.request_redraw(RedrawRequest::Immediate);
             }
             super::Event::MouseMove { x, y } | super::Event::MouseEnter { x, y } => {
                 let point = PointDIP { x: *x, y: *y };
@@ -381,11 +380,11 @@ impl<Message> Widget<Message> for Slider<Message> {
                         if let Some(handler) = self.on_value_change.as_ref() {
                             handler(self.value, arenas, shell);
                         }
-                        shell.request_redraw(hwnd, RedrawRequest::Immediate);
+                        shell.request_redraw(RedrawRequest::Immediate);
                     }
                 } else if was_hover != state.is_hover {
                     state.update_state(self.enabled);
-                    shell.request_redraw(hwnd, RedrawRequest::Immediate);
+                    shell.request_redraw(RedrawRequest::Immediate);
                 }
             }
             super::Event::MouseLeave { .. } => {
@@ -394,7 +393,7 @@ impl<Message> Widget<Message> for Slider<Message> {
 
                 if was_hover && !state.is_dragging {
                     state.update_state(self.enabled);
-                    shell.request_redraw(hwnd, RedrawRequest::Immediate);
+                    shell.request_redraw(RedrawRequest::Immediate);
                 }
             }
             _ => {}
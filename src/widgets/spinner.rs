@@ -1,9 +1,8 @@
 use std::{any::Any, time::Instant};
 
-use windows_numerics::Vector2;
-
 use crate::{
     Shell,
+    gfx::PointDIP,
     layout::{UIArenas, model::ElementStyle},
     math::easing::Easing,
     widgets::{Bounds, Instance, Widget},
@@ -16,7 +15,7 @@ use crate::{
 #[derive(Debug)]
 pub struct Spinner {
     // layout
-    center: Vector2,
+    center: PointDIP,
     radius: f32,
     stroke: f32,
 
@@ -30,7 +29,7 @@ pub struct Spinner {
 impl Default for Spinner {
     fn default() -> Self {
         Self {
-            center: Vector2 { X: 0.0, Y: 0.0 },
+            center: PointDIP { x: 0.0, y: 0.0 },
             radius: 50.0,
             stroke: 2.0,
 
@@ -47,7 +46,7 @@ struct WidgetState {
     anchor_deg: f32,    // the anchored endpoint angle (deg)
     phase_elapsed: f32, // time within current half-cycle [0, half)
     is_growing: bool,   // true: growing (10%->90%), false: shrinking (90%->10%)
-    last_update: Instant,
+    last_update: Option<Instant>,
 }
 
 impl WidgetState {
@@ -56,7 +55,7 @@ impl WidgetState {
             anchor_deg: 0.0,
             phase_elapsed: 0.0,
             is_growing: true,
-            last_update: Instant::now(),
+            last_update: None,
         }
     }
 
@@ -104,6 +103,10 @@ impl<Message> Widget<Message> for Spinner {
         }
     }
 
+    fn layout_key(&self, _arenas: &UIArenas, _instance: &Instance) -> Option<u64> {
+        super::layout_key_of(self.radius.to_bits())
+    }
+
     fn paint(
         &mut self,
         _arenas: &UIArenas,
@@ -115,9 +118,9 @@ impl<Message> Widget<Message> for Spinner {
         now: Instant,
     ) {
         let state = with_state!(mut instance as WidgetState);
-        let center = Vector2 {
-            X: bounds.content_box.x + bounds.content_box.width * 0.5,
-            Y: bounds.content_box.y + bounds.content_box.height * 0.5,
+        let center = PointDIP {
+            x: bounds.content_box.x + bounds.content_box.width * 0.5,
+            y: bounds.content_box.y + bounds.content_box.height * 0.5,
         };
         // let radius = bounds.width_dip.min(bounds.height_dip) * 0.5;
         // self.set_layout(center, radius);
@@ -165,13 +168,12 @@ impl<Message> Widget<Message> for Spinner {
         &mut self,
         _arenas: &mut UIArenas,
         _instance: &mut Instance,
-        hwnd: windows::Win32::Foundation::HWND,
         shell: &mut Shell<Message>,
         event: &super::Event,
         _bounds: Bounds,
     ) {
         if matches!(event, super::Event::Redraw { .. }) {
-            shell.request_redraw(hwnd, crate::RedrawRequest::Immediate);
+            shell.request_redraw(crate::RedrawRequest::Immediate);
         }
     }
 }
@@ -179,7 +181,7 @@ impl<Message> Widget<Message> for Spinner {
 impl Spinner {
     pub fn new(stroke: f32, base_speed_dps: f32, grow_period_s: f32, radius: f32) -> Self {
         Self {
-            center: Vector2 { X: 0.0, Y: 0.0 },
+            center: PointDIP { x: 0.0, y: 0.0 },
             radius,
             stroke,
             base_speed_dps,
@@ -202,8 +204,10 @@ impl Spinner {
 
 impl WidgetState {
     pub fn update(&mut self, config: &Spinner, now: Instant) {
-        let dt_seconds = now.duration_since(self.last_update).as_secs_f32();
-        self.last_update = now;
+        let dt_seconds = self.last_update.map_or(0.0, |last_update| {
+            now.duration_since(last_update).as_secs_f32()
+        });
+        self.last_update = Some(now);
 
         let half = config.grow_period_s * 0.5;
         // advance slow rotation
@@ -1,18 +1,19 @@
 use crate::{
     Shell,
-    gfx::command_recorder::CommandRecorder,
+    gfx::{command_recorder::CommandRecorder, resources::SvgResource},
     layout::{
         UIArenas,
         model::{Color, Element, ElementStyle},
     },
     runtime::DeviceResources,
     util::str::StableString,
-    widgets::{Bounds, Cursor, Event, Instance, State, Widget, limit_response, widget},
+    widgets::{
+        Bounds, Cursor, Event, Instance, State, Widget, layout_key_of, limit_response, widget,
+    },
     with_state,
 };
 use std::{any::Any, time::Instant};
 use windows::Win32::{
-    Foundation::HWND,
     Graphics::Direct2D::{
         Common::D2D1_COLOR_F, D2D1_SVG_ATTRIBUTE_POD_TYPE_COLOR,
         D2D1_SVG_ATTRIBUTE_POD_TYPE_VIEWBOX, D2D1_SVG_PAINT_TYPE_COLOR, D2D1_SVG_VIEWBOX,
@@ -76,167 +77,138 @@ impl ViewBox {
     }
 }
 
-/// State for SVG widget that caches the expensive SVG document
-struct SvgWidgetState {
-    /// Device context for creating SVG document
-    device_context: ID2D1DeviceContext6,
-    /// Cached SVG document
-    svg_document: Option<ID2D1SvgDocument>,
-    /// Cached SVG content string to detect changes
-    cached_svg_content: String,
-    /// Parsed viewBox for intrinsic sizing
-    viewbox: Option<ViewBox>,
-    /// Cached recolor to detect changes
-    cached_recolor: Option<Color>,
-}
+/// Parses `svg_content` into a document, recoloring it with `recolor` if given
+pub(crate) fn create_svg_document(
+    device_context: &ID2D1DeviceContext6,
+    svg_content: &str,
+    recolor: Option<Color>,
+) -> windows::core::Result<ID2D1SvgDocument> {
+    unsafe {
+        let stream = SHCreateMemStream(Some(svg_content.as_bytes())).unwrap();
+
+        let svg_document = device_context.CreateSvgDocument(
+            &stream,
+            windows::Win32::Graphics::Direct2D::Common::D2D_SIZE_F {
+                width: 100.0, // Default size, will be overridden by viewport
+                height: 100.0,
+            },
+        )?;
 
-impl SvgWidgetState {
-    pub fn new(device_context: ID2D1DeviceContext6) -> Self {
-        Self {
-            device_context,
-            svg_document: None,
-            cached_svg_content: String::new(),
-            viewbox: None,
-            cached_recolor: None,
+        if let Some(color) = recolor {
+            let root = svg_document.GetRoot()?;
+            recolor_subtree(
+                &root,
+                D2D1_COLOR_F {
+                    r: color.r,
+                    g: color.g,
+                    b: color.b,
+                    a: color.a,
+                },
+            )?;
         }
-    }
 
-    pub fn into_any(self) -> Box<dyn Any> {
-        Box::new(self)
+        Ok(svg_document)
     }
+}
 
-    /// Create or update SVG document if content has changed
-    fn ensure_svg_document(
-        &mut self,
-        svg_content: &str,
-        recolor: Option<Color>,
-    ) -> windows::core::Result<()> {
-        // Only recreate if content changed
-        if self.cached_svg_content != svg_content || self.svg_document.is_none() {
-            unsafe {
-                let svg_bytes = svg_content.as_bytes();
-
-                let stream = SHCreateMemStream(Some(svg_bytes)).unwrap();
-
-                let svg_document = self.device_context.CreateSvgDocument(
-                    &stream,
-                    windows::Win32::Graphics::Direct2D::Common::D2D_SIZE_F {
-                        width: 100.0, // Default size, will be overridden by viewport
-                        height: 100.0,
-                    },
-                )?;
-
-                // Parse viewBox using Direct2D SVG API
-                self.viewbox = ViewBox::from_svg_document(&svg_document);
+/// Recursively recolors the given subtree by changing fill and stroke colors
+fn recolor_subtree(
+    element: &ID2D1SvgElement,
+    new_color: D2D1_COLOR_F,
+) -> windows::core::Result<()> {
+    unsafe {
+        // Check if this SVG element has a "fill" attribute explicitly specified or inherited
+        if element
+            .IsAttributeSpecified(windows::core::w!("fill"), None)
+            .as_bool()
+        {
+            // Retrieve the value of this element's "fill" attribute, as a paint object
+            let paint: ID2D1SvgPaint = element.GetAttributeValue(windows::core::w!("fill"))?;
+
+            // Check the type of paint object that was set
+            let paint_type = paint.GetPaintType();
+            if paint_type == D2D1_SVG_PAINT_TYPE_COLOR {
+                paint.SetColor(&new_color)?;
+            }
 
-                self.svg_document = Some(svg_document);
-                self.cached_svg_content = svg_content.to_string();
-                self.cached_recolor = None;
+            // else if is path
+        } else {
+            let length = element.GetTagNameLength();
+            let mut name = vec![0u16; (length + 1) as usize];
+            element.GetTagName(&mut name).unwrap();
+            let name = String::from_utf16_lossy(&name);
+            if name == "path\0" {
+                element.SetAttributeValue2(
+                    windows::core::w!("fill"),
+                    D2D1_SVG_ATTRIBUTE_POD_TYPE_COLOR,
+                    &new_color as *const _ as *const std::ffi::c_void,
+                    std::mem::size_of::<D2D1_COLOR_F>() as u32,
+                )?;
             }
         }
 
-        // Apply recoloring if requested and color has changed
-        if let Some(color) = recolor {
-            if self.cached_recolor != Some(color) {
-                let d2d_color = D2D1_COLOR_F {
-                    r: color.r,
-                    g: color.g,
-                    b: color.b,
-                    a: color.a,
-                };
-                self.recolor_svg(d2d_color)?;
-                self.cached_recolor = Some(color);
+        // Check if this SVG element has a "stroke" attribute explicitly specified or inherited
+        if element
+            .IsAttributeSpecified(windows::core::w!("stroke"), None)
+            .as_bool()
+        {
+            // Retrieve the value of this element's "stroke" attribute, as a paint object
+            let paint: ID2D1SvgPaint = element.GetAttributeValue(windows::core::w!("stroke"))?;
+
+            // Check the type of paint object that was set
+            let paint_type = paint.GetPaintType();
+            if paint_type == D2D1_SVG_PAINT_TYPE_COLOR {
+                paint.SetColor(&new_color)?;
             }
-        } else if self.cached_recolor.is_some() {
-            // If recolor was removed, we need to recreate the document
-            // This is a simple approach - could be optimized to restore original colors
-            self.svg_document = None;
-            self.cached_svg_content.clear();
-            self.cached_recolor = None;
-            return self.ensure_svg_document(svg_content, recolor);
         }
 
-        Ok(())
-    }
+        // Now iterate through any child nodes and recursively recolor them
+        let mut child = element.GetFirstChild().ok();
 
-    /// Recolor the SVG document by changing fill colors of non-black elements
-    pub fn recolor_svg(&mut self, new_color: D2D1_COLOR_F) -> windows::core::Result<()> {
-        if let Some(ref svg_document) = self.svg_document {
-            unsafe {
-                // Get the root element of the SVG document
-                let root = svg_document.GetRoot()?;
+        while let Some(current_child) = child {
+            // Recursively recolor the subtree starting with this child node
+            recolor_subtree(&current_child, new_color)?;
 
-                // Recursively recolor the tree, beginning at the root
-                SvgWidgetState::recolor_subtree(&root, new_color)?;
-            }
+            // Move to the next child
+            child = element.GetNextChild(&current_child).ok();
         }
-        Ok(())
     }
+    Ok(())
+}
 
-    /// Helper method for recolor_svg which recursively recolors the given subtree
-    fn recolor_subtree(
-        element: &ID2D1SvgElement,
-        new_color: D2D1_COLOR_F,
-    ) -> windows::core::Result<()> {
-        unsafe {
-            // Check if this SVG element has a "fill" attribute explicitly specified or inherited
-            if element
-                .IsAttributeSpecified(windows::core::w!("fill"), None)
-                .as_bool()
-            {
-                // Retrieve the value of this element's "fill" attribute, as a paint object
-                let paint: ID2D1SvgPaint = element.GetAttributeValue(windows::core::w!("fill"))?;
-
-                // Check the type of paint object that was set
-                let paint_type = paint.GetPaintType();
-                if paint_type == D2D1_SVG_PAINT_TYPE_COLOR {
-                    paint.SetColor(&new_color)?;
-                }
-
-                // else if is path
-            } else {
-                let length = element.GetTagNameLength();
-                let mut name = vec![0u16; (length + 1) as usize];
-                element.GetTagName(&mut name).unwrap();
-                let name = String::from_utf16_lossy(&name);
-                if name == "path\0" {
-                    element.SetAttributeValue2(
-                        windows::core::w!("fill"),
-                        D2D1_SVG_ATTRIBUTE_POD_TYPE_COLOR,
-                        &new_color as *const _ as *const std::ffi::c_void,
-                        std::mem::size_of::<D2D1_COLOR_F>() as u32,
-                    )?;
-                }
-            }
-
-            // Check if this SVG element has a "stroke" attribute explicitly specified or inherited
-            if element
-                .IsAttributeSpecified(windows::core::w!("stroke"), None)
-                .as_bool()
-            {
-                // Retrieve the value of this element's "stroke" attribute, as a paint object
-                let paint: ID2D1SvgPaint =
-                    element.GetAttributeValue(windows::core::w!("stroke"))?;
-
-                // Check the type of paint object that was set
-                let paint_type = paint.GetPaintType();
-                if paint_type == D2D1_SVG_PAINT_TYPE_COLOR {
-                    paint.SetColor(&new_color)?;
-                }
-            }
+/// State for SVG widget that caches the parsed viewBox
+struct SvgWidgetState {
+    /// Device context for parsing SVG documents, `None` without a window
+    device_context: Option<ID2D1DeviceContext6>,
+    /// SVG content the viewBox was parsed from
+    cached_svg_content: Option<String>,
+    /// Parsed viewBox for intrinsic sizing
+    viewbox: Option<ViewBox>,
+}
 
-            // Now iterate through any child nodes and recursively recolor them
-            let mut child = element.GetFirstChild().ok();
+impl SvgWidgetState {
+    pub fn new(device_context: Option<ID2D1DeviceContext6>) -> Self {
+        Self {
+            device_context,
+            cached_svg_content: None,
+            viewbox: None,
+        }
+    }
 
-            while let Some(current_child) = child {
-                // Recursively recolor the subtree starting with this child node
-                SvgWidgetState::recolor_subtree(&current_child, new_color)?;
+    pub fn into_any(self) -> Box<dyn Any> {
+        Box::new(self)
+    }
 
-                // Move to the next child
-                child = element.GetNextChild(&current_child).ok();
-            }
+    /// Parse the viewBox if content has changed
+    fn ensure_viewbox(&mut self, svg_content: &str) {
+        if self.cached_svg_content.as_deref() != Some(svg_content) {
+            self.viewbox = self.device_context.as_ref().and_then(|device_context| {
+                create_svg_document(device_context, svg_content, None)
+                    .ok()
+                    .and_then(|svg_document| ViewBox::from_svg_document(&svg_document))
+            });
+            self.cached_svg_content = Some(svg_content.to_string());
         }
-        Ok(())
     }
 }
 
@@ -355,6 +327,19 @@ impl<Message> Widget<Message> for Svg {
         }
     }
 
+    fn layout_key(&self, _arenas: &UIArenas, instance: &Instance) -> Option<u64> {
+        let state = with_state!(instance as SvgWidgetState);
+        let viewbox = state
+            .viewbox
+            .as_ref()
+            .map(|viewbox| (viewbox.width.to_bits(), viewbox.height.to_bits()));
+        layout_key_of((
+            self.width.map(f32::to_bits),
+            self.height.map(f32::to_bits),
+            viewbox,
+        ))
+    }
+
     fn state(&self, _arenas: &UIArenas, device_resources: &DeviceResources) -> State {
         Some(SvgWidgetState::new(device_resources.d2d_device_context.clone()).into_any())
     }
@@ -371,13 +356,15 @@ impl<Message> Widget<Message> for Svg {
     ) {
         if let Some(svg_content) = self.svg_content.resolve(arenas) {
             let state = with_state!(mut instance as SvgWidgetState);
-
-            // Ensure SVG document is created/cached in state
-            if state.ensure_svg_document(svg_content, self.recolor).is_ok()
-                && let Some(ref svg_document) = state.svg_document
-            {
-                recorder.draw_svg(&bounds.content_box, svg_document);
-            }
+            state.ensure_viewbox(svg_content);
+
+            recorder.draw_svg(
+                &bounds.content_box,
+                SvgResource {
+                    content: svg_content.to_string(),
+                    recolor: self.recolor,
+                },
+            );
         }
     }
 
@@ -385,7 +372,6 @@ impl<Message> Widget<Message> for Svg {
         &mut self,
         _arenas: &mut UIArenas,
         _instance: &mut Instance,
-        _hwnd: HWND,
         _shell: &mut Shell<Message>,
         _event: &Event,
         _bounds: Bounds,
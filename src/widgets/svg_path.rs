@@ -3,49 +3,17 @@ use crate::{
     gfx::command_recorder::CommandRecorder,
     layout::{
         UIArenas,
-        model::{Color, Element, ElementStyle, StrokeLineCap, StrokeLineJoin},
+        model::{Element, ElementStyle, StrokeLineCap, StrokeLineJoin},
     },
-    runtime::DeviceResources,
     widgets::{
-        Bounds, Cursor, Event, Instance, State, Widget, limit_response, svg::ViewBox, widget,
+        Bounds, Cursor, Event, Instance, Widget, layout_key_of, limit_response, svg::ViewBox,
+        widget,
     },
-    with_state,
 };
 use raxis_core::SvgPathList;
-use std::{any::Any, time::Instant};
-use windows::Win32::{
-    Foundation::HWND,
-    Graphics::Direct2D::{ID2D1Factory7, ID2D1PathGeometry},
-};
-
-#[derive(Debug, Clone, Copy)]
-pub enum ColorChoice {
-    None,
-    Color(Color),
-    CurrentColor,
-}
 
-impl ColorChoice {
-    pub fn or_current_color(self, current_color: Option<Color>) -> Option<Color> {
-        match self {
-            ColorChoice::CurrentColor => current_color,
-            ColorChoice::Color(color) => Some(color),
-            ColorChoice::None => None,
-        }
-    }
-}
-
-impl From<Color> for ColorChoice {
-    fn from(color: Color) -> Self {
-        ColorChoice::Color(color)
-    }
-}
-
-impl From<()> for ColorChoice {
-    fn from(_: ()) -> Self {
-        ColorChoice::None
-    }
-}
+pub use crate::widgets::ColorChoice;
+use std::time::Instant;
 
 /// SVG Path widget for rendering procedurally generated SVG paths
 #[derive(Debug)]
@@ -70,43 +38,6 @@ pub struct SvgPath {
     height: Option<f32>,
 }
 
-/// State for SVG Path widget that caches the Direct2D geometry
-struct SvgPathWidgetState {
-    /// Direct2D factory for creating geometry
-    d2d_factory: ID2D1Factory7,
-    /// Cached Direct2D path geometry
-    path_geometry: Option<ID2D1PathGeometry>,
-    /// Whether the geometry needs to be recreated
-    geometry_dirty: bool,
-}
-
-impl SvgPathWidgetState {
-    pub fn new(d2d_factory: ID2D1Factory7, svg_path: &SvgPathList) -> Self {
-        // Create the geometry immediately during state creation
-        let path_geometry = svg_path.create_geometry(&d2d_factory).ok();
-
-        Self {
-            d2d_factory,
-            path_geometry,
-            geometry_dirty: false,
-        }
-    }
-
-    pub fn into_any(self) -> Box<dyn Any> {
-        Box::new(self)
-    }
-
-    /// Ensure path geometry is created
-    fn ensure_path_geometry(&mut self, svg_path: &SvgPathList) -> windows::core::Result<()> {
-        if self.geometry_dirty || self.path_geometry.is_none() {
-            let geometry = svg_path.create_geometry(&self.d2d_factory)?;
-            self.path_geometry = Some(geometry);
-            self.geometry_dirty = false;
-        }
-        Ok(())
-    }
-}
-
 impl SvgPath {
     /// Create a new SVG Path widget
     pub fn new(svg_path: SvgPathList, viewbox: ViewBox) -> Self {
@@ -225,57 +156,53 @@ impl<Message> Widget<Message> for SvgPath {
         }
     }
 
-    fn state(&self, _arenas: &UIArenas, device_resources: &DeviceResources) -> State {
-        Some(
-            SvgPathWidgetState::new(device_resources.d2d_factory.clone(), &self.svg_path)
-                .into_any(),
-        )
+    fn layout_key(&self, _arenas: &UIArenas, _instance: &Instance) -> Option<u64> {
+        layout_key_of((
+            self.width.map(f32::to_bits),
+            self.height.map(f32::to_bits),
+            self.viewbox.width.to_bits(),
+            self.viewbox.height.to_bits(),
+        ))
     }
 
     fn paint(
         &mut self,
         _arenas: &UIArenas,
-        instance: &mut Instance,
+        _instance: &mut Instance,
         _shell: &mut Shell<Message>,
         recorder: &mut CommandRecorder,
         style: ElementStyle,
         bounds: Bounds,
         _now: Instant,
     ) {
-        let state = with_state!(mut instance as SvgPathWidgetState);
-
         // Calculate scale factors based on viewport vs ViewBox
         let viewport_width = bounds.content_box.width;
         let viewport_height = bounds.content_box.height;
         let scale_x = viewport_width / self.viewbox.width;
         let scale_y = viewport_height / self.viewbox.height;
 
-        // Record path drawing commands using cached geometry with scale factors
-        if state.ensure_path_geometry(&self.svg_path).is_ok()
-            && let Some(ref geometry) = state.path_geometry
-        {
-            if let Some(fill_color) = self.fill_color.or_current_color(style.color) {
-                recorder.fill_path_geometry(
-                    &bounds.content_box,
-                    geometry,
-                    fill_color,
-                    scale_x,
-                    scale_y,
-                );
-            }
+        // Record path drawing commands with scale factors
+        if let Some(fill_color) = self.fill_color.or_current_color(style.color) {
+            recorder.fill_path_geometry(
+                &bounds.content_box,
+                self.svg_path,
+                fill_color,
+                scale_x,
+                scale_y,
+            );
+        }
 
-            if let Some(stroke_color) = self.stroke_color.or_current_color(style.color) {
-                recorder.stroke_path_geometry(
-                    &bounds.content_box,
-                    geometry,
-                    stroke_color,
-                    self.stroke_width,
-                    scale_x,
-                    scale_y,
-                    self.stroke_cap,
-                    self.stroke_join,
-                );
-            }
+        if let Some(stroke_color) = self.stroke_color.or_current_color(style.color) {
+            recorder.stroke_path_geometry(
+                &bounds.content_box,
+                self.svg_path,
+                stroke_color,
+                self.stroke_width,
+                scale_x,
+                scale_y,
+                self.stroke_cap,
+                self.stroke_join,
+            );
         }
     }
 
@@ -283,7 +210,6 @@ impl<Message> Widget<Message> for SvgPath {
         &mut self,
         _arenas: &mut UIArenas,
         _instance: &mut Instance,
-        _hwnd: HWND,
         _shell: &mut Shell<Message>,
         _event: &Event,
         _bounds: Bounds,
@@ -1,46 +1,25 @@
 use std::any::Any;
-use std::collections::hash_map::DefaultHasher;
 use std::fmt::Debug;
-use std::hash::{Hash, Hasher};
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::panic::Location;
+use std::rc::Rc;
 use std::time::Instant;
 
-use windows::Win32::Foundation::HWND;
-use windows::Win32::Graphics::Direct2D::ID2D1DeviceContext6;
-use windows::Win32::Graphics::DirectWrite::{
-    DWRITE_HIT_TEST_METRICS, DWRITE_PARAGRAPH_ALIGNMENT_NEAR, DWRITE_TEXT_ALIGNMENT_LEADING,
-    DWRITE_TEXT_METRICS, IDWriteFactory6, IDWriteTextFormat3, IDWriteTextLayout,
-};
-use windows::core::Result;
-
 use crate::gfx::RectDIP;
 use crate::gfx::command_recorder::CommandRecorder;
+use crate::gfx::resources::{TextResource, TextRun};
+use crate::gfx::text_engine::{Result, TextEngine, TextLayout, TextMetrics};
+use crate::gfx::text_style::{
+    FontAxes, FontIdentifier, FontStyle, FontWeight, FontWidth, LineSpacing, TextStyle,
+};
 use crate::layout::UIArenas;
 use crate::layout::model::{Color, Element, ElementStyle, TextShadow};
-use crate::runtime::font_manager::{
-    FontAxes, FontIdentifier, FontStyle, FontWeight, FontWidth, GlobalFontManager, LineSpacing,
-};
 use crate::util::str::StableString;
 use crate::util::unique::{combine_id, id_from_location};
-use crate::widgets::svg_path::ColorChoice;
-use crate::widgets::{Bounds, Instance, Widget, widget};
+use crate::widgets::{Bounds, ColorChoice, Instance, Widget, widget};
 use crate::{RedrawRequest, Shell, with_state};
 
-/// Text alignment options
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
-pub enum TextAlignment {
-    Leading,  // Left-aligned
-    Center,   // Center-aligned
-    Trailing, // Right-aligned
-}
-
-/// Paragraph alignment options
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
-pub enum ParagraphAlignment {
-    Top,
-    Center,
-    Bottom,
-}
+pub use crate::gfx::text_style::{ParagraphAlignment, TextAlignment};
 
 /// Text span with styling and optional hyperlink
 #[derive(Debug, Clone, PartialEq)]
@@ -304,6 +283,19 @@ impl Text {
         self
     }
 
+    /// The font and paragraph settings the text is laid out with
+    pub fn text_style(&self) -> TextStyle {
+        TextStyle {
+            font_id: self.font_id.clone(),
+            font_size: self.font_size,
+            font_axes: self.font_axes,
+            line_spacing: self.line_spacing,
+            text_alignment: self.text_alignment,
+            paragraph_alignment: self.paragraph_alignment,
+            word_wrap: self.word_wrap,
+        }
+    }
+
     pub fn as_element<Message>(self) -> Element<Message> {
         let id = id_from_location(self.caller);
         Element {
@@ -313,25 +305,6 @@ impl Text {
             ..Default::default()
         }
     }
-
-    /// Compute a hash of all properties that affect text layout rendering.
-    /// Used for shadow cache identity.
-    fn compute_layout_hash(&self, arenas: &UIArenas) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        self.text
-            .resolve(arenas)
-            .expect("intern string missing")
-            .hash(&mut hasher);
-        self.font_size.to_bits().hash(&mut hasher);
-        self.line_spacing.hash(&mut hasher);
-        self.text_shadows.hash(&mut hasher);
-        self.text_alignment.hash(&mut hasher);
-        self.paragraph_alignment.hash(&mut hasher);
-        self.font_id.hash(&mut hasher);
-        self.font_axes.hash(&mut hasher);
-        self.word_wrap.hash(&mut hasher);
-        hasher.finish()
-    }
 }
 
 impl<Message> From<Text> for Element<Message> {
@@ -347,245 +320,85 @@ impl Default for Text {
 }
 
 struct TextWidgetState {
-    // DirectWrite objects for text rendering
-    dwrite_factory: IDWriteFactory6,
-    d2d_device_context: ID2D1DeviceContext6,
-    text_format: IDWriteTextFormat3,
-    text_layout: Option<IDWriteTextLayout>,
+    text_engine: Rc<dyn TextEngine>,
+    text_layout: Option<Box<dyn TextLayout>>,
     cached_text: String,
-    cached_font_size: f32,
-    cached_line_spacing: Option<LineSpacing>,
-    cached_font_id: FontIdentifier,
-    cached_font_axes: FontAxes,
-    cached_text_alignment: TextAlignment,
-    cached_paragraph_alignment: ParagraphAlignment,
-    cached_word_wrap: bool,
+    cached_style: TextStyle,
 
     // Layout caching
     cached_bounds: RectDIP,
-    text_metrics: Option<DWRITE_TEXT_METRICS>,
+    text_metrics: Option<TextMetrics>,
     layout_invalidated: bool,
 
     // Sizing cache for limits_x/limits_y
     cached_preferred_width: Option<f32>,
     cached_preferred_height_for_width: Option<(f32, f32)>, // (width, height)
 
-    // Span caching - None means not yet applied, Some(hash) means applied with that hash
-    cached_spans_hash: Option<u64>,
-
     // Hyperlink hover state
     hovered_hyperlink_index: Option<usize>,
 }
 
 impl TextWidgetState {
-    pub fn new(
-        dwrite_factory: IDWriteFactory6,
-        d2d_device_context: ID2D1DeviceContext6,
-        font_id: &FontIdentifier,
-        font_size: f32,
-        font_axes: FontAxes,
-        line_spacing: Option<LineSpacing>,
-        text_alignment: TextAlignment,
-        paragraph_alignment: ParagraphAlignment,
-        word_wrap: bool,
-    ) -> Result<Self> {
-        let text_format = GlobalFontManager::create_text_format(
-            font_id,
-            font_size,
-            font_axes,
-            line_spacing,
-            "en-us",
-        )?;
-
-        let text_format = unsafe {
-            // Set text alignment
-            let dwrite_text_alignment = match text_alignment {
-                TextAlignment::Leading => DWRITE_TEXT_ALIGNMENT_LEADING,
-                TextAlignment::Center => {
-                    windows::Win32::Graphics::DirectWrite::DWRITE_TEXT_ALIGNMENT_CENTER
-                }
-                TextAlignment::Trailing => {
-                    windows::Win32::Graphics::DirectWrite::DWRITE_TEXT_ALIGNMENT_TRAILING
-                }
-            };
-            text_format.SetTextAlignment(dwrite_text_alignment)?;
-
-            // Set paragraph alignment
-            let dwrite_paragraph_alignment = match paragraph_alignment {
-                ParagraphAlignment::Top => DWRITE_PARAGRAPH_ALIGNMENT_NEAR,
-                ParagraphAlignment::Center => {
-                    windows::Win32::Graphics::DirectWrite::DWRITE_PARAGRAPH_ALIGNMENT_CENTER
-                }
-                ParagraphAlignment::Bottom => {
-                    windows::Win32::Graphics::DirectWrite::DWRITE_PARAGRAPH_ALIGNMENT_FAR
-                }
-            };
-            text_format.SetParagraphAlignment(dwrite_paragraph_alignment)?;
-
-            // Set word wrapping
-            let word_wrapping = if word_wrap {
-                windows::Win32::Graphics::DirectWrite::DWRITE_WORD_WRAPPING_WRAP
-            } else {
-                windows::Win32::Graphics::DirectWrite::DWRITE_WORD_WRAPPING_NO_WRAP
-            };
-            text_format.SetWordWrapping(word_wrapping)?;
-
-            text_format
-        };
-
-        let mut state = Self {
-            dwrite_factory,
-            d2d_device_context,
-            text_format,
+    pub fn new(text_engine: Rc<dyn TextEngine>, style: TextStyle) -> Self {
+        Self {
+            text_engine,
             text_layout: None,
             cached_text: String::new(),
-            cached_font_size: font_size,
-            cached_line_spacing: line_spacing,
-            cached_font_id: font_id.clone(),
-            cached_font_axes: font_axes,
-            cached_text_alignment: text_alignment,
-            cached_paragraph_alignment: paragraph_alignment,
-            cached_word_wrap: word_wrap,
+            cached_style: style,
             cached_bounds: RectDIP::default(),
             text_metrics: None,
             layout_invalidated: true,
             cached_preferred_width: None,
             cached_preferred_height_for_width: None,
-            cached_spans_hash: None,
             hovered_hyperlink_index: None,
-        };
-
-        state.build_text_layout("", RectDIP::default())?;
-        Ok(state)
+        }
     }
 
     pub fn into_any(self) -> Box<dyn Any> {
         Box::new(self)
     }
 
-    fn needs_text_format_rebuild(
-        &self,
-        font_id: &FontIdentifier,
-        font_size: f32,
-        font_axes: FontAxes,
-        line_spacing: Option<LineSpacing>,
-        text_alignment: TextAlignment,
-        paragraph_alignment: ParagraphAlignment,
-        word_wrap: bool,
-    ) -> bool {
-        self.cached_font_id != *font_id
-            || self.cached_font_size != font_size
-            || self.cached_font_axes != font_axes
-            || self.cached_line_spacing != line_spacing
-            || self.cached_text_alignment != text_alignment
-            || self.cached_paragraph_alignment != paragraph_alignment
-            || self.cached_word_wrap != word_wrap
-    }
-
-    fn rebuild_text_format(
-        &mut self,
-        font_id: &FontIdentifier,
-        font_size: f32,
-        font_axes: FontAxes,
-        line_spacing: Option<LineSpacing>,
-        text_alignment: TextAlignment,
-        paragraph_alignment: ParagraphAlignment,
-        word_wrap: bool,
-    ) -> Result<()> {
-        self.text_format = GlobalFontManager::create_text_format(
-            font_id,
-            font_size,
-            font_axes,
-            line_spacing,
-            "en-us",
-        )?;
-
-        unsafe {
-            // Set text alignment
-            let dwrite_text_alignment = match text_alignment {
-                TextAlignment::Leading => DWRITE_TEXT_ALIGNMENT_LEADING,
-                TextAlignment::Center => {
-                    windows::Win32::Graphics::DirectWrite::DWRITE_TEXT_ALIGNMENT_CENTER
-                }
-                TextAlignment::Trailing => {
-                    windows::Win32::Graphics::DirectWrite::DWRITE_TEXT_ALIGNMENT_TRAILING
-                }
-            };
-            self.text_format.SetTextAlignment(dwrite_text_alignment)?;
-
-            // Set paragraph alignment
-            let dwrite_paragraph_alignment = match paragraph_alignment {
-                ParagraphAlignment::Top => DWRITE_PARAGRAPH_ALIGNMENT_NEAR,
-                ParagraphAlignment::Center => {
-                    windows::Win32::Graphics::DirectWrite::DWRITE_PARAGRAPH_ALIGNMENT_CENTER
-                }
-                ParagraphAlignment::Bottom => {
-                    windows::Win32::Graphics::DirectWrite::DWRITE_PARAGRAPH_ALIGNMENT_FAR
-                }
-            };
-            self.text_format
-                .SetParagraphAlignment(dwrite_paragraph_alignment)?;
+    /// Switches to a new style, invalidating the layout if it changed.
+    fn update_style(&mut self, style: TextStyle) {
+        if self.cached_style != style {
+            self.cached_style = style;
 
-            // Set word wrapping
-            let word_wrapping = if word_wrap {
-                windows::Win32::Graphics::DirectWrite::DWRITE_WORD_WRAPPING_WRAP
-            } else {
-                windows::Win32::Graphics::DirectWrite::DWRITE_WORD_WRAPPING_NO_WRAP
-            };
-            self.text_format.SetWordWrapping(word_wrapping)?;
+            // Invalidate layout and sizing cache since the style changed
+            self.layout_invalidated = true;
+            self.invalidate_sizing_cache();
         }
-
-        // Update cached values
-        self.cached_font_id = font_id.clone();
-        self.cached_font_size = font_size;
-        self.cached_font_axes = font_axes;
-        self.cached_line_spacing = line_spacing;
-        self.cached_text_alignment = text_alignment;
-        self.cached_paragraph_alignment = paragraph_alignment;
-        self.cached_word_wrap = word_wrap;
-
-        // Invalidate layout and sizing cache since format changed
-        self.layout_invalidated = true;
-        self.invalidate_sizing_cache();
-
-        Ok(())
     }
 
     fn build_text_layout(&mut self, text: &str, bounds: RectDIP) -> Result<()> {
-        // Check if we need to rebuild the text layout (text or format changed)
+        // Check if we need to rebuild the text layout (text or style changed)
         let text_changed = text != self.cached_text;
         let needs_layout_rebuild = text_changed || self.layout_invalidated;
 
+        // Ensure a minimum size
+        let max_width = bounds.width.max(1.0);
+        let max_height = bounds.height.max(1.0);
+
         if needs_layout_rebuild {
-            unsafe {
-                let wtext: Vec<u16> = text.encode_utf16().collect();
-                self.text_layout = Some(self.dwrite_factory.CreateTextLayout(
-                    &wtext,
-                    &self.text_format,
-                    bounds.width.max(1.0),  // Ensure minimum width
-                    bounds.height.max(1.0), // Ensure minimum height
-                )?);
-            }
+            self.text_layout = Some(self.text_engine.create_layout(
+                text,
+                &self.cached_style,
+                max_width,
+                max_height,
+            )?);
             self.cached_text = text.to_string();
             self.layout_invalidated = false;
             self.invalidate_sizing_cache();
-            self.cached_spans_hash = None;
         }
 
         // Check if we need to update bounds (cheaper operation)
         let bounds_changed = bounds != self.cached_bounds;
-        if bounds_changed || needs_layout_rebuild {
-            if let Some(layout) = &self.text_layout {
-                unsafe {
-                    layout.SetMaxWidth(bounds.width.max(1.0))?;
-                    layout.SetMaxHeight(bounds.height.max(1.0))?;
-
-                    // Get text metrics for sizing calculations
-                    let mut metrics = DWRITE_TEXT_METRICS::default();
-                    layout.GetMetrics(&mut metrics)?;
-                    self.text_metrics = Some(metrics);
-                }
-            }
+        if (bounds_changed || needs_layout_rebuild)
+            && let Some(layout) = &mut self.text_layout
+        {
+            layout.set_max_size(max_width, max_height);
+
+            // Get text metrics for sizing calculations
+            self.text_metrics = Some(layout.metrics());
             self.cached_bounds = bounds;
         }
 
@@ -596,7 +409,7 @@ impl TextWidgetState {
         if let Some(metrics) = &self.text_metrics {
             (metrics.width, metrics.height)
         } else {
-            (0.0, self.cached_font_size * 1.2) // Fallback height based on font size
+            (0.0, self.cached_style.font_size * 1.2) // Fallback height based on font size
         }
     }
 
@@ -605,45 +418,6 @@ impl TextWidgetState {
         self.cached_preferred_height_for_width = None;
     }
 
-    fn spans_changed(&mut self, new_spans: &[TextSpan]) -> bool {
-        let mut hasher = DefaultHasher::new();
-        new_spans.hash(&mut hasher);
-        let new_hash = hasher.finish();
-
-        let changed = self.cached_spans_hash != Some(new_hash);
-        if changed {
-            self.cached_spans_hash = Some(new_hash);
-        }
-        changed
-    }
-
-    fn apply_spans(&self, layout: &IDWriteTextLayout, spans: &[TextSpan]) -> Result<()> {
-        use windows::Win32::Graphics::DirectWrite::DWRITE_TEXT_RANGE;
-
-        unsafe {
-            for span in spans {
-                let range = DWRITE_TEXT_RANGE {
-                    startPosition: span.start as u32,
-                    length: (span.end - span.start) as u32,
-                };
-
-                // Create a solid color brush for this span's color
-                let brush = self.d2d_device_context.CreateSolidColorBrush(
-                    &windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F {
-                        r: span.color.r,
-                        g: span.color.g,
-                        b: span.color.b,
-                        a: span.color.a,
-                    },
-                    None,
-                )?;
-
-                layout.SetDrawingEffect(&brush, range)?;
-            }
-        }
-        Ok(())
-    }
-
     fn get_preferred_width(&mut self, text: &str) -> Result<f32> {
         if let Some(width) = self.cached_preferred_width
             && text == self.cached_text
@@ -685,115 +459,18 @@ impl TextWidgetState {
         Ok(preferred_height)
     }
 
-    /// Hit-test a point in DIPs against the text layout, returning the UTF-16 character index.
-    fn hit_test_index(&self, x_dip: f32, y_dip: f32) -> Option<u32> {
-        unsafe {
-            let layout = self.text_layout.as_ref().expect("layout not built");
-            let mut trailing = windows::core::BOOL(0);
-            let mut inside = windows::core::BOOL(0);
-            let mut metrics = DWRITE_HIT_TEST_METRICS::default();
-            layout
-                .HitTestPoint(x_dip, y_dip, &mut trailing, &mut inside, &mut metrics)
-                .ok()?;
-
-            if !inside.as_bool() {
-                return None;
-            }
-
-            Some(metrics.textPosition)
-        }
-    }
-
-    /// Find which hyperlink span (if any) contains the given UTF-16 character index.
-    fn find_hyperlink_at_index(&self, idx: u32, spans: &[TextSpan]) -> Option<usize> {
-        // Convert UTF-16 index to byte index for comparison with span ranges
-        let byte_idx = self.utf16_to_byte_index(idx as usize);
-
-        for (i, span) in spans.iter().enumerate() {
-            if span.is_hyperlink() && byte_idx >= span.start && byte_idx < span.end {
-                return Some(i);
-            }
-        }
-        None
-    }
-
-    /// Convert UTF-16 code unit index to byte index in the cached text.
-    fn utf16_to_byte_index(&self, utf16_idx: usize) -> usize {
-        let mut byte_idx = 0;
-        let mut utf16_count = 0;
-
-        for ch in self.cached_text.chars() {
-            if utf16_count >= utf16_idx {
-                break;
-            }
-            utf16_count += ch.len_utf16();
-            byte_idx += ch.len_utf8();
-        }
-        byte_idx
-    }
-
-    fn apply_hyperlink_underlines(
-        &self,
-        layout: &IDWriteTextLayout,
-        spans: &[TextSpan],
-        hovered_index: Option<usize>,
-    ) -> Result<()> {
-        use windows::Win32::Graphics::DirectWrite::DWRITE_TEXT_RANGE;
-
-        unsafe {
-            for (i, span) in spans.iter().enumerate() {
-                if !span.is_hyperlink() {
-                    continue;
-                }
-
-                // Convert byte indices to UTF-16 indices for DirectWrite
-                let start_utf16 = self.byte_to_utf16_index(span.start);
-                let end_utf16 = self.byte_to_utf16_index(span.end);
-
-                let range = DWRITE_TEXT_RANGE {
-                    startPosition: start_utf16 as u32,
-                    length: (end_utf16 - start_utf16) as u32,
-                };
-
-                // Determine color based on hover state
-                let color = if Some(i) == hovered_index {
-                    span.hover_color.unwrap_or(span.color)
-                } else {
-                    span.color
-                };
-
-                let brush = self.d2d_device_context.CreateSolidColorBrush(
-                    &windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F {
-                        r: color.r,
-                        g: color.g,
-                        b: color.b,
-                        a: color.a,
-                    },
-                    None,
-                )?;
-
-                layout.SetDrawingEffect(&brush, range)?;
-
-                // Apply underline to hyperlinks
-                layout.SetUnderline(true, range)?;
-            }
-        }
-        Ok(())
+    /// Hit-test a point in DIPs against the text layout, returning the byte index
+    /// of the character under it.
+    fn hit_test_index(&self, x_dip: f32, y_dip: f32) -> Option<usize> {
+        let hit = self.text_layout.as_ref()?.hit_test_point(x_dip, y_dip);
+        hit.inside.then_some(hit.position)
     }
 
-    /// Convert byte index to UTF-16 code unit index.
-    fn byte_to_utf16_index(&self, byte_idx: usize) -> usize {
-        let mut utf16_count = 0;
-        let mut current_byte = 0;
-
-        for ch in self.cached_text.chars() {
-            if current_byte >= byte_idx {
-                break;
-            }
-            current_byte += ch.len_utf8();
-            utf16_count += ch.len_utf16();
-        }
-        utf16_count
+    /// Find which hyperlink span (if any) contains the given byte index.
+    fn find_hyperlink_at_index(&self, idx: usize, spans: &[TextSpan]) -> Option<usize> {
+        spans
+            .iter()
+            .position(|span| span.is_hyperlink() && idx >= span.start && idx < span.end)
     }
 }
 
@@ -803,20 +480,10 @@ impl<Message> Widget<Message> for Text {
         _arenas: &UIArenas,
         device_resources: &crate::runtime::DeviceResources,
     ) -> super::State {
-        match TextWidgetState::new(
-            device_resources.dwrite_factory.clone(),
-            device_resources.d2d_device_context.clone(),
-            &self.font_id,
-            self.font_size,
-            self.font_axes,
-            self.line_spacing,
-            self.text_alignment,
-            self.paragraph_alignment,
-            self.word_wrap,
-        ) {
-            Ok(state) => Some(state.into_any()),
-            Err(_) => None,
-        }
+        Some(
+            TextWidgetState::new(device_resources.text_engine.clone(), self.text_style())
+                .into_any(),
+        )
     }
 
     fn limits_x(
@@ -825,6 +492,7 @@ impl<Message> Widget<Message> for Text {
         instance: &mut Instance,
     ) -> super::limit_response::SizingForX {
         let state = with_state!(mut instance as TextWidgetState);
+        state.update_style(self.text_style());
 
         if let Some(text) = self.text.resolve(arenas)
             && let Ok(preferred_width) = state.get_preferred_width(text)
@@ -854,6 +522,7 @@ impl<Message> Widget<Message> for Text {
         content_width: f32,
     ) -> super::limit_response::SizingForY {
         let state = with_state!(mut instance as TextWidgetState);
+        state.update_style(self.text_style());
 
         if let Some(text) = self.text.resolve(arenas)
             && let Ok(preferred_height) = state.get_preferred_height_for_width(text, content_width)
@@ -870,11 +539,47 @@ impl<Message> Widget<Message> for Text {
         }
     }
 
+    fn baseline(
+        &self,
+        arenas: &UIArenas,
+        instance: &mut Instance,
+        content_width: f32,
+        content_height: f32,
+    ) -> Option<f32> {
+        let state = with_state!(mut instance as TextWidgetState);
+        state.update_style(self.text_style());
+
+        let text = self.text.resolve(arenas)?;
+        let bounds = RectDIP {
+            x: 0.0,
+            y: 0.0,
+            width: content_width,
+            height: content_height,
+        };
+        state.build_text_layout(text, bounds).ok()?;
+
+        let first_line = state.text_layout.as_ref()?.lines().into_iter().next()?;
+        Some(first_line.top + first_line.baseline)
+    }
+
+    fn layout_key(&self, arenas: &UIArenas, _instance: &Instance) -> Option<u64> {
+        let mut hasher = DefaultHasher::new();
+        self.text.resolve(arenas).hash(&mut hasher);
+        self.font_id.hash(&mut hasher);
+        self.font_size.to_bits().hash(&mut hasher);
+        self.font_axes.hash(&mut hasher);
+        self.line_spacing.hash(&mut hasher);
+        self.text_alignment.hash(&mut hasher);
+        self.paragraph_alignment.hash(&mut hasher);
+        self.word_wrap.hash(&mut hasher);
+        self.assisted_width.map(f32::to_bits).hash(&mut hasher);
+        Some(hasher.finish())
+    }
+
     fn update(
         &mut self,
         _arenas: &mut UIArenas,
         instance: &mut Instance,
-        hwnd: HWND,
         shell: &mut Shell<Message>,
         event: &super::Event,
         bounds: Bounds,
@@ -897,22 +602,17 @@ impl<Message> Widget<Message> for Text {
                     let new_hovered = state.find_hyperlink_at_index(idx, &self.spans);
                     if new_hovered != state.hovered_hyperlink_index {
                         state.hovered_hyperlink_index = new_hovered;
-                        // Invalidate to redraw with new hover state
-                        state.cached_spans_hash = None;
-                        shell.request_redraw(hwnd, RedrawRequest::Immediate);
+                        // Redraw with new hover state
+                        shell.request_redraw(RedrawRequest::Immediate);
                     }
                 } else if state.hovered_hyperlink_index.is_some() {
                     state.hovered_hyperlink_index = None;
-                    state.cached_spans_hash = None;
-                    shell.request_redraw(hwnd, RedrawRequest::Immediate);
+                    shell.request_redraw(RedrawRequest::Immediate);
                 }
             }
-            super::Event::MouseLeave { .. } => {
-                if state.hovered_hyperlink_index.is_some() {
-                    state.hovered_hyperlink_index = None;
-                    state.cached_spans_hash = None;
-                    shell.request_redraw(hwnd, RedrawRequest::Immediate);
-                }
+            super::Event::MouseLeave { .. } if state.hovered_hyperlink_index.is_some() => {
+                state.hovered_hyperlink_index = None;
+                shell.request_redraw(RedrawRequest::Immediate);
             }
             super::Event::MouseButtonUp { x, y, .. } => {
                 let widget_x = x - content_box.x;
@@ -941,26 +641,8 @@ impl<Message> Widget<Message> for Text {
     ) {
         let state = with_state!(mut instance as TextWidgetState);
 
-        // Rebuild text format if properties changed
-        if state.needs_text_format_rebuild(
-            &self.font_id,
-            self.font_size,
-            self.font_axes,
-            self.line_spacing,
-            self.text_alignment,
-            self.paragraph_alignment,
-            self.word_wrap,
-        ) {
-            let _ = state.rebuild_text_format(
-                &self.font_id,
-                self.font_size,
-                self.font_axes,
-                self.line_spacing,
-                self.text_alignment,
-                self.paragraph_alignment,
-                self.word_wrap,
-            );
-        }
+        // Invalidate the layout if properties changed
+        state.update_style(self.text_style());
 
         // Build text layout if needed
         let _ = state.build_text_layout(
@@ -968,22 +650,8 @@ impl<Message> Widget<Message> for Text {
             bounds.content_box,
         );
 
-        // Apply span styles (colors and hyperlink underlines) if they changed or layout was rebuilt
-        if !self.spans.is_empty()
-            && state.spans_changed(&self.spans)
-            && let Some(layout) = &state.text_layout
-        {
-            let _ = state.apply_spans(layout, &self.spans);
-            // Apply hyperlink underlines for spans with URLs
-            let _ = state.apply_hyperlink_underlines(
-                layout,
-                &self.spans,
-                state.hovered_hyperlink_index,
-            );
-        }
-
         // Draw the text
-        if let Some(layout) = &state.text_layout {
+        if let Some(text) = self.text.resolve(arenas) {
             // Combine widget shadows with style shadows (widget shadows have priority)
             let shadows = if !self.text_shadows.is_empty() {
                 &self.text_shadows
@@ -991,17 +659,40 @@ impl<Message> Widget<Message> for Text {
                 &style.text_shadows
             };
 
-            let text_hash = self.compute_layout_hash(arenas);
+            let runs = self
+                .spans
+                .iter()
+                .enumerate()
+                .map(|(i, span)| {
+                    // Hovered hyperlinks switch to their hover color
+                    let color = if Some(i) == state.hovered_hyperlink_index {
+                        span.hover_color.unwrap_or(span.color)
+                    } else {
+                        span.color
+                    };
+                    TextRun {
+                        start: span.start,
+                        end: span.end,
+                        color: Some(color),
+                        underline: span.is_hyperlink(),
+                    }
+                })
+                .collect();
 
             recorder.draw_text(
                 &bounds.content_box,
-                layout,
+                TextResource {
+                    text: text.to_string(),
+                    style: self.text_style(),
+                    max_width: bounds.content_box.width.max(1.0),
+                    max_height: bounds.content_box.height.max(1.0),
+                    runs,
+                },
                 self.color
                     .unwrap_or(ColorChoice::CurrentColor)
                     .or_current_color(style.color)
                     .unwrap_or_default(),
                 shadows,
-                text_hash,
             );
         }
     }
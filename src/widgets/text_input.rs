@@ -1,31 +1,22 @@
 use std::any::Any;
-use std::collections::hash_map::DefaultHasher;
 use std::fmt::Debug;
-use std::hash::{Hash, Hasher};
+use std::rc::Rc;
 use std::time::{Duration, Instant};
 
-use windows::Win32::Foundation::HWND;
-use windows::Win32::Graphics::DirectWrite::{
-    DWRITE_HIT_TEST_METRICS, DWRITE_PARAGRAPH_ALIGNMENT_NEAR, DWRITE_TEXT_ALIGNMENT_LEADING,
-    DWRITE_TEXT_METRICS, DWRITE_TEXT_RANGE, IDWriteFactory6, IDWriteTextFormat3, IDWriteTextLayout,
-};
-use windows::Win32::Graphics::Gdi::InvalidateRect;
-use windows::Win32::System::Ole::{DROPEFFECT_COPY, DROPEFFECT_MOVE, DROPEFFECT_NONE};
-use windows::Win32::UI::WindowsAndMessaging::STRSAFE_E_INSUFFICIENT_BUFFER;
-use windows::core::Result;
-
 use crate::gfx::command_recorder::CommandRecorder;
+use crate::gfx::resources::{TextResource, TextRun};
+use crate::gfx::text_engine::{self, Result, TextEngine, TextLayout};
+use crate::gfx::text_style::{FontAxes, FontIdentifier, LineSpacing, TextStyle};
 use crate::gfx::{PointDIP, RectDIP};
 use crate::layout::UIArenas;
 use crate::layout::model::{Element, ElementStyle};
-use crate::runtime::clipboard::get_clipboard_text;
-use crate::runtime::font_manager::{FontAxes, FontIdentifier, GlobalFontManager, LineSpacing};
+use crate::platform::clipboard_text;
 use crate::runtime::vkey::VKey;
 use crate::util::unique::WidgetId;
 use crate::widgets::text::{ParagraphAlignment, TextAlignment};
 use crate::widgets::{
-    Bounds, DragData, DragInfo, DropResult, Instance, Widget, WidgetDragDropTarget, limit_response,
-    widget,
+    Bounds, DragData, DragInfo, DropEffect, DropResult, Instance, Widget, WidgetDragDropTarget,
+    layout_key_of, limit_response, widget,
 };
 use crate::{DeferredControl, InputMethod, RedrawRequest, Shell, with_state};
 use unicode_segmentation::UnicodeSegmentation;
@@ -34,7 +25,7 @@ const BLINK_TIME: f64 = 0.5;
 const CARET_WIDTH: f32 = 1.0;
 const LINE_OFFSET: f32 = 1.0;
 const MAX_UNDO_LEVELS: usize = 100;
-const UNDO_MERGE_TIME_MS: u128 = 1000; // 1 second
+const UNDO_MERGE_TIME: Duration = Duration::from_secs(1);
 
 /// Represents a state that can be undone/redone
 #[derive(Debug, Clone)]
@@ -42,7 +33,7 @@ struct UndoState {
     text: String,
     selection_anchor: u32,
     selection_active: u32,
-    timestamp: u128,
+    timestamp: Option<Instant>,
     operation_type: UndoOperationType,
 }
 
@@ -193,9 +184,7 @@ impl<Message: 'static> TextInput<Message> {
 struct WidgetState<Message> {
     _marker: std::marker::PhantomData<Message>,
 
-    // DirectWrite objects (shared/cloneable COM interfaces)
-    dwrite_factory: IDWriteFactory6,
-    text_format: IDWriteTextFormat3,
+    text_engine: Rc<dyn TextEngine>,
     text: String,
     last_emitted_text: String,
 
@@ -208,7 +197,7 @@ struct WidgetState<Message> {
 
     // layout
     bounds: RectDIP,
-    layout: Option<IDWriteTextLayout>,
+    layout: Option<Box<dyn TextLayout>>,
 
     // Selection state (UTF-16 code unit indices)
     selection_anchor: u32,
@@ -220,7 +209,10 @@ struct WidgetState<Message> {
     // caret_blink_timer: f64,
     // caret_visible: bool,
     focused_at: Option<Instant>,
-    created_at: Instant,
+    /// Set when the caret moves, restarts the blink on the next update or paint
+    caret_moved: bool,
+    /// The shell's time of the event being handled, which undo steps are stamped with
+    event_time: Option<Instant>,
 
     // Preferred horizontal position (DIPs) for vertical navigation (sticky X)
     sticky_x_dip: Option<f32>,
@@ -276,7 +268,7 @@ impl<Message: 'static> Widget<Message> for TextInput<Message> {
         device_resources: &crate::runtime::DeviceResources,
     ) -> super::State {
         match WidgetState::<Message>::new(
-            device_resources.dwrite_factory.clone(),
+            device_resources.text_engine.clone(),
             &self.font_id,
             self.font_size,
             self.line_spacing,
@@ -290,20 +282,17 @@ impl<Message: 'static> Widget<Message> for TextInput<Message> {
     }
 
     fn limits_x(&self, _arenas: &UIArenas, instance: &mut Instance) -> limit_response::SizingForX {
-        let state = with_state!(instance as WidgetState<Message>);
-        if let Some(layout) = &state.layout {
-            let min_width = unsafe { layout.DetermineMinWidth().unwrap() };
-
-            unsafe {
-                layout.SetMaxWidth(f32::INFINITY).unwrap();
-            }
+        let state = with_state!(mut instance as WidgetState<Message>);
+        if let Some(layout) = &mut state.layout {
+            let min_width = layout.min_width();
 
-            let mut max_metrics = DWRITE_TEXT_METRICS::default();
-            unsafe { layout.GetMetrics(&mut max_metrics).unwrap() };
+            let (_, max_height) = layout.max_size();
+            layout.set_max_size(f32::INFINITY, max_height);
+            let max_metrics = layout.metrics();
 
             limit_response::SizingForX {
                 min_width,
-                preferred_width: max_metrics.widthIncludingTrailingWhitespace,
+                preferred_width: max_metrics.width_including_trailing_whitespace,
             }
         } else {
             limit_response::SizingForX {
@@ -320,14 +309,11 @@ impl<Message: 'static> Widget<Message> for TextInput<Message> {
         _border_width: f32,
         content_width: f32,
     ) -> limit_response::SizingForY {
-        let state = with_state!(instance as WidgetState<Message>);
-        if let Some(layout) = &state.layout {
-            unsafe {
-                layout.SetMaxWidth(content_width).unwrap();
-            }
-
-            let mut max_metrics = DWRITE_TEXT_METRICS::default();
-            unsafe { layout.GetMetrics(&mut max_metrics).unwrap() };
+        let state = with_state!(mut instance as WidgetState<Message>);
+        if let Some(layout) = &mut state.layout {
+            let (_, max_height) = layout.max_size();
+            layout.set_max_size(content_width, max_height);
+            let max_metrics = layout.metrics();
 
             limit_response::SizingForY {
                 min_height: max_metrics.height,
@@ -341,27 +327,56 @@ impl<Message: 'static> Widget<Message> for TextInput<Message> {
         }
     }
 
+    fn layout_key(&self, _arenas: &UIArenas, instance: &Instance) -> Option<u64> {
+        // Measured from the layout of the state, built from its text and format
+        let state = with_state!(instance as WidgetState<Message>);
+        layout_key_of((
+            state.layout.is_some(),
+            state.displayed_text().0,
+            &state.cached_font_id,
+            state.cached_font_size.to_bits(),
+            state.cached_line_spacing,
+            state.cached_text_alignment,
+            state.cached_paragraph_alignment,
+        ))
+    }
+
+    fn baseline(
+        &self,
+        _arenas: &UIArenas,
+        instance: &mut Instance,
+        content_width: f32,
+        content_height: f32,
+    ) -> Option<f32> {
+        let state = with_state!(mut instance as WidgetState<Message>);
+        let layout = state.layout.as_mut()?;
+        layout.set_max_size(content_width, content_height);
+
+        let first_line = layout.lines().into_iter().next()?;
+        Some(first_line.top + first_line.baseline)
+    }
+
     fn update(
         &mut self,
         _arenas: &mut UIArenas,
         instance: &mut Instance,
-        hwnd: HWND,
         shell: &mut Shell<Message>,
         event: &super::Event,
         bounds: Bounds,
     ) {
         let state = with_state!(mut instance as WidgetState<Message>);
+        state.event_time = Some(shell.now());
 
         if state.focused_at.is_none() && shell.focus_manager.is_focused(instance.id) {
-            state.focused_at = Some(Instant::now());
+            state.focused_at = Some(shell.now());
         } else if state.focused_at.is_some() && !shell.focus_manager.is_focused(instance.id) {
             state.focused_at = None;
-            if self.on_text_input.is_some() {
-                self.on_text_input.as_ref().unwrap()(state.text.as_str(), shell);
+            if let Some(on_text_input) = &self.on_text_input {
+                on_text_input(state.text.as_str(), shell);
 
                 // Request another view pass
                 // This will potentially update the passed text value
-                shell.request_redraw(hwnd, RedrawRequest::Immediate);
+                shell.request_redraw(RedrawRequest::Immediate);
             }
         }
 
@@ -369,7 +384,7 @@ impl<Message: 'static> Widget<Message> for TextInput<Message> {
         // Ignore first pass if notified to avoid prematurely updating to old state
         // i.e. wait for next view pass to determine if text has changed
         if state.ensure_text(shell, instance.id, self.text.as_deref()) {
-            shell.request_redraw(hwnd, RedrawRequest::Immediate);
+            shell.request_redraw(RedrawRequest::Immediate);
         }
 
         let RectDIP {
@@ -446,156 +461,148 @@ impl<Message: 'static> Widget<Message> for TextInput<Message> {
                 }
 
                 if state.update_drag(widget_x, widget_y) {
-                    let _ = unsafe { InvalidateRect(Some(hwnd), None, false) };
+                    shell.request_redraw(RedrawRequest::Immediate);
                 }
             }
-            super::Event::KeyDown { key, modifiers, .. } => {
-                if shell.focus_manager.is_focused(instance.id) {
-                    let shift_down = modifiers.shift;
-                    let ctrl_down = modifiers.ctrl;
-                    let _handled = match *key {
-                        VKey::LEFT => {
-                            if ctrl_down {
-                                state.move_word_left(shift_down);
-                            } else {
-                                state.move_left(shift_down);
-                            }
-                            true
-                        }
-                        VKey::RIGHT => {
-                            if ctrl_down {
-                                state.move_word_right(shift_down);
-                            } else {
-                                state.move_right(shift_down);
-                            }
-                            true
-                        }
-                        VKey::UP => {
-                            state.move_up(shift_down);
-                            true
-                        }
-                        VKey::DOWN => {
-                            state.move_down(shift_down);
-                            true
-                        }
-                        VKey::HOME => {
-                            state.move_to_start(shift_down);
-                            true
-                        }
-                        VKey::END => {
-                            state.move_to_end(shift_down);
-                            true
-                        }
-                        VKey::BACK => {
-                            if ctrl_down {
-                                let _ = state.backspace_word();
-                            } else {
-                                let _ = state.backspace();
-                            }
-                            true
-                        }
-                        VKey::DELETE => {
-                            if ctrl_down {
-                                let _ = state.delete_word_forward();
-                            } else {
-                                let _ = state.delete_forward();
-                            }
-                            true
+            super::Event::KeyDown { key, modifiers, .. }
+                if shell.focus_manager.is_focused(instance.id) =>
+            {
+                let shift_down = modifiers.shift;
+                let ctrl_down = modifiers.ctrl;
+                let _handled = match *key {
+                    VKey::LEFT => {
+                        if ctrl_down {
+                            state.move_word_left(shift_down);
+                        } else {
+                            state.move_left(shift_down);
                         }
-                        VKey::A if ctrl_down => {
-                            state.select_all();
-                            true
+                        true
+                    }
+                    VKey::RIGHT => {
+                        if ctrl_down {
+                            state.move_word_right(shift_down);
+                        } else {
+                            state.move_right(shift_down);
                         }
-                        VKey::C if ctrl_down => {
-                            if let Some(s) = state.selected_text() {
-                                // let _ = set_clipboard_text(hwnd, &s);
-                                shell.queue_deferred_control(DeferredControl::SetClipboardText(
-                                    s.to_string(),
-                                ));
-                            }
-                            true
+                        true
+                    }
+                    VKey::UP => {
+                        state.move_up(shift_down);
+                        true
+                    }
+                    VKey::DOWN => {
+                        state.move_down(shift_down);
+                        true
+                    }
+                    VKey::HOME => {
+                        state.move_to_start(shift_down);
+                        true
+                    }
+                    VKey::END => {
+                        state.move_to_end(shift_down);
+                        true
+                    }
+                    VKey::BACK => {
+                        if ctrl_down {
+                            let _ = state.backspace_word();
+                        } else {
+                            let _ = state.backspace();
                         }
-                        VKey::X if ctrl_down => {
-                            if let Some(s) = state.selected_text() {
-                                // let _ = set_clipboard_text(hwnd, &s);
-                                shell.queue_deferred_control(DeferredControl::SetClipboardText(
-                                    s.to_string(),
-                                ));
-                                let _ = state.insert_str("");
-                            }
-                            true
+                        true
+                    }
+                    VKey::DELETE => {
+                        if ctrl_down {
+                            let _ = state.delete_word_forward();
+                        } else {
+                            let _ = state.delete_forward();
                         }
-                        VKey::V if ctrl_down => {
-                            if !state.is_composing()
-                                && let Some(s) = get_clipboard_text(hwnd)
-                            {
-                                let _ = state.insert_str(&s);
-                            }
-                            true
+                        true
+                    }
+                    VKey::A if ctrl_down => {
+                        state.select_all();
+                        true
+                    }
+                    VKey::C if ctrl_down => {
+                        if let Some(s) = state.selected_text() {
+                            // let _ = set_clipboard_text(hwnd, &s);
+                            shell.queue_deferred_control(DeferredControl::SetClipboardText(
+                                s.to_string(),
+                            ));
                         }
-                        VKey::Z if ctrl_down && shift_down => {
-                            let _ = state.redo();
-                            true
+                        true
+                    }
+                    VKey::X if ctrl_down => {
+                        if let Some(s) = state.selected_text() {
+                            // let _ = set_clipboard_text(hwnd, &s);
+                            shell.queue_deferred_control(DeferredControl::SetClipboardText(
+                                s.to_string(),
+                            ));
+                            let _ = state.insert_str("");
                         }
-                        VKey::Z if ctrl_down => {
-                            let _ = state.undo();
-                            true
+                        true
+                    }
+                    VKey::V if ctrl_down => {
+                        if !state.is_composing()
+                            && let Some(s) = clipboard_text()
+                        {
+                            let _ = state.insert_str(&s);
                         }
-                        VKey::ESCAPE => {
-                            if state.has_selection() {
-                                state.clear_selection();
-                            } else {
-                                shell.focus_manager.release_focus(instance.id);
-                            }
-                            true
+                        true
+                    }
+                    VKey::Z if ctrl_down && shift_down => {
+                        let _ = state.redo();
+                        true
+                    }
+                    VKey::Z if ctrl_down => {
+                        let _ = state.undo();
+                        true
+                    }
+                    VKey::ESCAPE => {
+                        if state.has_selection() {
+                            state.clear_selection();
+                        } else {
+                            shell.focus_manager.release_focus(instance.id);
                         }
-                        _ => false,
-                    };
-                }
+                        true
+                    }
+                    _ => false,
+                };
             }
             super::Event::KeyUp { .. } => {}
-            super::Event::Char { text } => {
-                if shell.focus_manager.is_focused(instance.id) {
-                    let _ = state.insert_str(text.as_str());
-                }
+            super::Event::Char { text } if shell.focus_manager.is_focused(instance.id) => {
+                let _ = state.insert_str(text.as_str());
             }
-            super::Event::ImeStartComposition => {
-                if shell.focus_manager.is_focused(instance.id) {
-                    state.ime_begin();
-
-                    if let Ok((c_x_dip, c_y_dip, h)) = state.caret_pos_dip(state.caret_active16()) {
-                        shell.request_input_method(InputMethod::Enabled {
-                            position: PointDIP {
-                                x: x_dip + c_x_dip,
-                                y: y_dip + c_y_dip + h,
-                            },
-                        });
-                    }
+            super::Event::ImeStartComposition if shell.focus_manager.is_focused(instance.id) => {
+                state.ime_begin();
+
+                if let Ok((c_x_dip, c_y_dip, h)) = state.caret_pos_dip(state.caret_active16()) {
+                    shell.request_input_method(InputMethod::Enabled {
+                        position: PointDIP {
+                            x: x_dip + c_x_dip,
+                            y: y_dip + c_y_dip + h,
+                        },
+                    });
                 }
             }
-            super::Event::ImeComposition { text, caret_units } => {
-                if shell.focus_manager.is_focused(instance.id) {
-                    state.ime_update(text.clone(), *caret_units);
-
-                    if let Ok((c_x_dip, c_y_dip, h)) = state.caret_pos_dip(state.caret_active16()) {
-                        shell.request_input_method(InputMethod::Enabled {
-                            position: PointDIP {
-                                x: x_dip + c_x_dip,
-                                y: y_dip + c_y_dip + h,
-                            },
-                        });
-                    }
+            super::Event::ImeComposition { text, caret_units }
+                if shell.focus_manager.is_focused(instance.id) =>
+            {
+                state.ime_update(text.clone(), *caret_units);
+
+                if let Ok((c_x_dip, c_y_dip, h)) = state.caret_pos_dip(state.caret_active16()) {
+                    shell.request_input_method(InputMethod::Enabled {
+                        position: PointDIP {
+                            x: x_dip + c_x_dip,
+                            y: y_dip + c_y_dip + h,
+                        },
+                    });
                 }
             }
-            super::Event::ImeCommit { text } => {
-                if shell.focus_manager.is_focused(instance.id) {
-                    state.ime_commit(text.clone()).expect("ime commit failed");
-                }
+            super::Event::ImeCommit { text } if shell.focus_manager.is_focused(instance.id) => {
+                state.ime_commit(text.clone()).expect("ime commit failed");
             }
-            super::Event::ImeEndComposition => {
-                if shell.focus_manager.is_focused(instance.id) {
-                    state.ime_end();
-                }
+            super::Event::ImeEndComposition if shell.focus_manager.is_focused(instance.id) => {
+                state.ime_end();
             }
             super::Event::Redraw { now } => {
                 if shell.focus_manager.is_focused(instance.id)
@@ -603,22 +610,19 @@ impl<Message: 'static> Widget<Message> for TextInput<Message> {
                 {
                     let next_blink =
                         (0.05 + BLINK_TIME) - (*now - focused_at).as_secs_f64() % BLINK_TIME;
-                    shell.request_redraw(
-                        hwnd,
-                        RedrawRequest::At(*now + Duration::from_secs_f64(next_blink)),
-                    );
+                    shell.request_redraw(RedrawRequest::At(
+                        *now + Duration::from_secs_f64(next_blink),
+                    ));
                 }
             }
 
-            super::Event::DragFinish { effect } => {
-                if state.has_started_ole_drag {
-                    // If it was a move operation, delete the selected text
-                    if (effect.0 & DROPEFFECT_MOVE.0) != 0 && state.can_drag_drop() {
-                        let _ = state.insert_str("");
-                    }
-
-                    state.reset_drag_state();
+            super::Event::DragFinish { effect } if state.has_started_ole_drag => {
+                // If it was a move operation, delete the selected text
+                if effect.contains(DropEffect::MOVE) && state.can_drag_drop() {
+                    let _ = state.insert_str("");
                 }
+
+                state.reset_drag_state();
             }
 
             _ => {
@@ -633,6 +637,8 @@ impl<Message: 'static> Widget<Message> for TextInput<Message> {
             }
         }
 
+        state.restart_blink_if_moved(shell.now());
+
         // Request scroll-into-view if caret moved
         if state.needs_scroll_into_view {
             state.needs_scroll_into_view = false;
@@ -648,11 +654,12 @@ impl<Message: 'static> Widget<Message> for TextInput<Message> {
         recorder: &mut CommandRecorder,
         style: ElementStyle,
         bounds: Bounds,
-        _now: Instant,
+        now: Instant,
     ) {
         let state = with_state!(mut instance as WidgetState<Message>);
 
         state.ensure_text(shell, instance.id, self.text.as_deref());
+        state.restart_blink_if_moved(now);
 
         // Rebuild text format if needed
         if state.needs_text_format_rebuild(
@@ -676,14 +683,7 @@ impl<Message: 'static> Widget<Message> for TextInput<Message> {
             .expect("update bounds failed");
 
         state
-            .draw(
-                instance.id,
-                shell,
-                recorder,
-                style,
-                bounds.content_box,
-                _now,
-            )
+            .draw(instance.id, shell, recorder, style, bounds.content_box, now)
             .expect("draw failed");
     }
 
@@ -728,7 +728,7 @@ impl<Message: 'static> WidgetDragDropTarget<Message> for TextInput<Message> {
         instance: &mut Instance,
         drag_info: &DragInfo,
         widget_bounds: Bounds,
-    ) -> windows::Win32::System::Ole::DROPEFFECT {
+    ) -> DropEffect {
         let state = with_state!(mut instance as WidgetState<Message>);
         match &drag_info.data {
             DragData::Text(_) => {
@@ -739,7 +739,7 @@ impl<Message: 'static> WidgetDragDropTarget<Message> for TextInput<Message> {
                     state.set_ole_drop_preview(Some(idx));
                     state.get_drop_effect(&drag_info.allowed_effects)
                 } else {
-                    DROPEFFECT_NONE
+                    DropEffect::NONE
                 }
             }
         }
@@ -750,7 +750,7 @@ impl<Message: 'static> WidgetDragDropTarget<Message> for TextInput<Message> {
         instance: &mut Instance,
         drag_info: &DragInfo,
         widget_bounds: Bounds,
-    ) -> windows::Win32::System::Ole::DROPEFFECT {
+    ) -> DropEffect {
         let state = with_state!(mut instance as WidgetState<Message>);
         match &drag_info.data {
             DragData::Text(_) => {
@@ -779,6 +779,7 @@ impl<Message: 'static> WidgetDragDropTarget<Message> for TextInput<Message> {
         widget_bounds: Bounds,
     ) -> DropResult {
         let state = with_state!(mut instance as WidgetState<Message>);
+        state.event_time = Some(shell.now());
         match &drag_info.data {
             DragData::Text(text) => {
                 // Convert client coordinates to widget-relative coordinates
@@ -808,7 +809,7 @@ impl<Message: 'static> WidgetDragDropTarget<Message> for TextInput<Message> {
 
 impl<Message> WidgetState<Message> {
     pub fn new(
-        dwrite_factory: IDWriteFactory6,
+        text_engine: Rc<dyn TextEngine>,
         font_id: &FontIdentifier,
         font_size: f32,
         line_spacing: Option<LineSpacing>,
@@ -816,46 +817,9 @@ impl<Message> WidgetState<Message> {
         paragraph_alignment: ParagraphAlignment,
         initial_text: String,
     ) -> Result<Self> {
-        let text_format = GlobalFontManager::create_text_format(
-            font_id,
-            font_size,
-            FontAxes::default(),
-            line_spacing,
-            "en-us",
-        )?;
-
-        let text_format = unsafe {
-            // Set text alignment
-            let dwrite_text_alignment = match text_alignment {
-                TextAlignment::Leading => DWRITE_TEXT_ALIGNMENT_LEADING,
-                TextAlignment::Center => {
-                    windows::Win32::Graphics::DirectWrite::DWRITE_TEXT_ALIGNMENT_CENTER
-                }
-                TextAlignment::Trailing => {
-                    windows::Win32::Graphics::DirectWrite::DWRITE_TEXT_ALIGNMENT_TRAILING
-                }
-            };
-            text_format.SetTextAlignment(dwrite_text_alignment)?;
-
-            // Set paragraph alignment
-            let dwrite_paragraph_alignment = match paragraph_alignment {
-                ParagraphAlignment::Top => DWRITE_PARAGRAPH_ALIGNMENT_NEAR,
-                ParagraphAlignment::Center => {
-                    windows::Win32::Graphics::DirectWrite::DWRITE_PARAGRAPH_ALIGNMENT_CENTER
-                }
-                ParagraphAlignment::Bottom => {
-                    windows::Win32::Graphics::DirectWrite::DWRITE_PARAGRAPH_ALIGNMENT_FAR
-                }
-            };
-            text_format.SetParagraphAlignment(dwrite_paragraph_alignment)?;
-
-            text_format
-        };
-
         let mut s = Self {
             _marker: std::marker::PhantomData,
-            dwrite_factory,
-            text_format,
+            text_engine,
             text: initial_text,
             cached_font_size: font_size,
             cached_line_spacing: line_spacing,
@@ -870,7 +834,8 @@ impl<Message> WidgetState<Message> {
             has_started_ole_drag: false,
             drag_start_position: None,
             focused_at: None,
-            created_at: Instant::now(),
+            caret_moved: false,
+            event_time: None,
             sticky_x_dip: None,
             metric_bounds: RectDIP::default(),
             utf16_boundaries: Vec::new(),
@@ -892,18 +857,55 @@ impl<Message> WidgetState<Message> {
         Ok(s)
     }
 
-    /// Compute a hash of all properties that affect text layout rendering.
-    /// Used for shadow cache identity.
-    fn compute_layout_hash(&self, style: &ElementStyle) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        self.text.hash(&mut hasher);
-        self.cached_font_size.to_bits().hash(&mut hasher);
-        self.cached_line_spacing.hash(&mut hasher);
-        style.text_shadows.hash(&mut hasher);
-        self.cached_text_alignment.hash(&mut hasher);
-        self.cached_paragraph_alignment.hash(&mut hasher);
-        self.cached_font_id.hash(&mut hasher);
-        hasher.finish()
+    /// The font and paragraph settings the text is laid out with
+    fn text_style(&self) -> TextStyle {
+        TextStyle {
+            font_id: self.cached_font_id.clone(),
+            font_size: self.cached_font_size,
+            font_axes: FontAxes::default(),
+            line_spacing: self.cached_line_spacing,
+            text_alignment: self.cached_text_alignment,
+            paragraph_alignment: self.cached_paragraph_alignment,
+            word_wrap: true,
+        }
+    }
+
+    /// The displayed text, including any IME composition, and the runs styling it.
+    fn displayed_text(&self) -> (String, Vec<TextRun>) {
+        match &self.ime_text {
+            Some(ime_text) if self.is_composing() => {
+                let (start16, end16) = self.selection_range();
+                let start = self.utf16_index_to_byte(start16);
+                let end = self.utf16_index_to_byte(end16);
+
+                let mut composed = String::with_capacity(self.text.len() + ime_text.len());
+                composed.push_str(&self.text[..start]);
+                composed.push_str(ime_text);
+                composed.push_str(&self.text[end..]);
+
+                // Underline the composition
+                let underline = TextRun {
+                    start,
+                    end: start + ime_text.len(),
+                    color: None,
+                    underline: true,
+                };
+                (composed, vec![underline])
+            }
+            _ => (self.text.clone(), Vec::new()),
+        }
+    }
+
+    /// Describe the displayed text for drawing.
+    fn text_resource(&self) -> TextResource {
+        let (text, runs) = self.displayed_text();
+        TextResource {
+            text,
+            style: self.text_style(),
+            max_width: self.bounds.width,
+            max_height: self.bounds.height,
+            runs,
+        }
     }
 
     fn needs_text_format_rebuild(
@@ -929,89 +931,26 @@ impl<Message> WidgetState<Message> {
         text_alignment: TextAlignment,
         paragraph_alignment: ParagraphAlignment,
     ) -> Result<()> {
-        self.text_format = GlobalFontManager::create_text_format(
-            font_id,
-            font_size,
-            FontAxes::default(),
-            line_spacing,
-            "en-us",
-        )?;
-
-        unsafe {
-            // Set text alignment
-            let dwrite_text_alignment = match text_alignment {
-                TextAlignment::Leading => DWRITE_TEXT_ALIGNMENT_LEADING,
-                TextAlignment::Center => {
-                    windows::Win32::Graphics::DirectWrite::DWRITE_TEXT_ALIGNMENT_CENTER
-                }
-                TextAlignment::Trailing => {
-                    windows::Win32::Graphics::DirectWrite::DWRITE_TEXT_ALIGNMENT_TRAILING
-                }
-            };
-            self.text_format.SetTextAlignment(dwrite_text_alignment)?;
-
-            // Set paragraph alignment
-            let dwrite_paragraph_alignment = match paragraph_alignment {
-                ParagraphAlignment::Top => DWRITE_PARAGRAPH_ALIGNMENT_NEAR,
-                ParagraphAlignment::Center => {
-                    windows::Win32::Graphics::DirectWrite::DWRITE_PARAGRAPH_ALIGNMENT_CENTER
-                }
-                ParagraphAlignment::Bottom => {
-                    windows::Win32::Graphics::DirectWrite::DWRITE_PARAGRAPH_ALIGNMENT_FAR
-                }
-            };
-            self.text_format
-                .SetParagraphAlignment(dwrite_paragraph_alignment)?;
-        }
-
         // Update cached values
         self.cached_font_id = font_id.clone();
         self.cached_font_size = font_size;
+        self.cached_line_spacing = line_spacing;
         self.cached_text_alignment = text_alignment;
         self.cached_paragraph_alignment = paragraph_alignment;
 
-        Ok(())
+        self.build_text_layout()
     }
 
     /// Build a text layout for the given text and maximum size in DIPs.
     pub fn build_text_layout(&mut self) -> Result<()> {
-        unsafe {
-            let wtext: Vec<u16> = self.text.encode_utf16().collect();
-            let layout = if self.is_composing() {
-                let (start16, end16) = self.selection_range();
-                let base_w: Vec<u16> = self.text.encode_utf16().collect();
-                let ime_w: Vec<u16> = self.ime_text.as_ref().unwrap().encode_utf16().collect();
-                let mut composed = Vec::with_capacity(base_w.len() + ime_w.len());
-                composed.extend_from_slice(&base_w[..start16 as usize]);
-                let underline_start = composed.len() as u32;
-                composed.extend_from_slice(&ime_w);
-                let underline_len = (composed.len() as u32).saturating_sub(underline_start);
-                composed.extend_from_slice(&base_w[end16 as usize..]);
-
-                let composed_layout = self.dwrite_factory.CreateTextLayout(
-                    &composed,
-                    &self.text_format,
-                    self.bounds.width,
-                    self.bounds.height,
-                )?;
-                let range = DWRITE_TEXT_RANGE {
-                    startPosition: underline_start,
-                    length: underline_len,
-                };
-                composed_layout.SetUnderline(true, range)?;
-                composed_layout
-            } else {
-                self.dwrite_factory.CreateTextLayout(
-                    &wtext,
-                    &self.text_format,
-                    self.bounds.width,
-                    self.bounds.height,
-                )?
-            };
-
-            self.layout = Some(layout);
-            Ok(())
-        }
+        let (text, _) = self.displayed_text();
+        self.layout = Some(self.text_engine.create_layout(
+            &text,
+            &self.text_style(),
+            self.bounds.width,
+            self.bounds.height,
+        )?);
+        Ok(())
     }
 
     pub fn update_bounds(&mut self, bounds: RectDIP) -> Result<()> {
@@ -1020,82 +959,67 @@ impl<Message> WidgetState<Message> {
             self.build_text_layout()?;
         }
 
-        unsafe {
-            let layout = self.layout.as_ref().expect("layout not built");
-            layout.SetMaxWidth(bounds.width).unwrap();
-            layout.SetMaxHeight(bounds.height).unwrap();
+        let layout = self.layout.as_mut().expect("layout not built");
+        layout.set_max_size(bounds.width, bounds.height);
 
-            let mut metrics = DWRITE_TEXT_METRICS::default();
-            layout.GetMetrics(&mut metrics).unwrap();
-            self.metric_bounds = RectDIP {
-                x: metrics.left,
-                y: metrics.top,
-                width: metrics.width,
-                height: metrics.height,
-            };
-        }
+        let metrics = layout.metrics();
+        self.metric_bounds = RectDIP {
+            x: metrics.left,
+            y: metrics.top,
+            width: metrics.width,
+            height: metrics.height,
+        };
 
         Ok(())
     }
 
+    /// Converts a UTF-16 index into the displayed text to a byte index into it.
+    fn layout_byte_index(layout: &dyn TextLayout, idx16: u32) -> usize {
+        text_engine::utf16_to_byte_index(layout.text(), idx16 as usize)
+    }
+
+    /// Caret rectangle in front of a UTF-16 index into the displayed text.
+    fn caret_rect(layout: &dyn TextLayout, idx16: u32, bounds: RectDIP) -> RectDIP {
+        let caret = layout.caret_position(Self::layout_byte_index(layout, idx16));
+        RectDIP {
+            x: bounds.x + caret.x,
+            y: bounds.y + caret.y,
+            width: CARET_WIDTH,
+            height: caret.height,
+        }
+    }
+
     /// Draw selection highlight behind the text for the currently selected range.
     fn draw_selection_with_recorder(
         &self,
-        layout: &IDWriteTextLayout,
+        layout: &dyn TextLayout,
         recorder: &mut crate::gfx::command_recorder::CommandRecorder,
         bounds: RectDIP,
-    ) -> Result<()> {
-        unsafe {
-            let sel_start = self.selection_anchor.min(self.selection_active);
-            let sel_end = self.selection_anchor.max(self.selection_active);
-            let sel_len = sel_end.saturating_sub(sel_start);
-            if sel_len == 0 {
-                return Ok(());
-            }
+    ) {
+        let sel_start = self.selection_anchor.min(self.selection_active);
+        let sel_end = self.selection_anchor.max(self.selection_active);
+        if sel_end <= sel_start {
+            return;
+        }
 
-            let mut needed: u32 = 0;
-            match layout.HitTestTextRange(sel_start, sel_len, 0.0, 0.0, None, &mut needed) {
-                Ok(()) => {
-                    // Nothing visible to draw
-                    Ok(())
-                }
-                Err(e) if e.code() == STRSAFE_E_INSUFFICIENT_BUFFER => {
-                    let capacity = needed.max(1);
-                    let mut runs = vec![DWRITE_HIT_TEST_METRICS::default(); capacity as usize];
-                    let mut actual: u32 = 0;
-                    match layout.HitTestTextRange(
-                        sel_start,
-                        sel_len,
-                        0.0,
-                        0.0,
-                        Some(&mut runs),
-                        &mut actual,
-                    ) {
-                        Ok(()) => {
-                            // Selection color (light blue)
-                            for m in runs.iter().take(actual as usize) {
-                                recorder.fill_rectangle(
-                                    &RectDIP {
-                                        x: bounds.x + m.left,
-                                        y: bounds.y + m.top,
-                                        width: m.width,
-                                        height: m.height,
-                                    },
-                                    crate::layout::model::Color {
-                                        r: 0.2,
-                                        g: 0.4,
-                                        b: 1.0,
-                                        a: 0.35,
-                                    },
-                                );
-                            }
-                            Ok(())
-                        }
-                        Err(e) => Err(e),
-                    }
-                }
-                Err(e) => Err(e),
-            }
+        let range =
+            Self::layout_byte_index(layout, sel_start)..Self::layout_byte_index(layout, sel_end);
+        for rect in layout.range_rects(range) {
+            // Selection color (light blue)
+            recorder.fill_rectangle(
+                &RectDIP {
+                    x: bounds.x + rect.x,
+                    y: bounds.y + rect.y,
+                    width: rect.width,
+                    height: rect.height,
+                },
+                crate::layout::model::Color {
+                    r: 0.2,
+                    g: 0.4,
+                    b: 1.0,
+                    a: 0.35,
+                },
+            );
         }
     }
 
@@ -1108,111 +1032,69 @@ impl<Message> WidgetState<Message> {
         bounds: RectDIP,
         now: Instant,
     ) -> Result<()> {
-        unsafe {
-            let layout = self.layout.as_ref().expect("layout not built");
-
-            let caret_visible =
-                (((now - self.focused_at.unwrap_or(now)).as_secs_f64()) / BLINK_TIME) % 2.0 < 1.0;
-
-            // Normal rendering: selection, base text, caret
-            self.draw_selection_with_recorder(layout, recorder, bounds)?;
-
-            // Draw text using command recorder
-            let color = style.color.unwrap_or_default();
-            let text_hash = self.compute_layout_hash(&style);
-            recorder.draw_text(&bounds, layout, color, &style.text_shadows, text_hash);
-
-            // OLE drag-over preview caret
-            if let Some(drop) = self.ole_drop_preview16 {
-                let drop = self.snap_to_scalar_boundary(drop);
-                let (src_start, src_end) = self.selection_range();
-                if !(self.has_selection() && drop >= src_start && drop <= src_end) {
-                    let mut x = 0.0f32;
-                    let mut y = 0.0f32;
-                    let mut m = DWRITE_HIT_TEST_METRICS::default();
-                    layout.HitTestTextPosition(drop, false, &mut x, &mut y, &mut m)?;
-                    let caret_rect = RectDIP {
-                        x: bounds.x + x,
-                        y: bounds.y + m.top,
-                        width: CARET_WIDTH,
-                        height: m.height,
-                    };
-                    recorder.fill_rectangle(&caret_rect, color);
-                }
-            } else {
-                // Draw caret if there's no selection (1 DIP wide bar)
-                let sel_start = self.selection_anchor.min(self.selection_active);
-                let sel_end = self.selection_anchor.max(self.selection_active);
-                if shell.focus_manager.is_focused(id) && caret_visible {
-                    if self.is_composing() {
-                        let ime_caret_pos = sel_start + self.ime_cursor16;
-                        let mut x = 0.0f32;
-                        let mut y = 0.0f32;
-                        let mut m = DWRITE_HIT_TEST_METRICS::default();
-                        layout.HitTestTextPosition(ime_caret_pos, false, &mut x, &mut y, &mut m)?;
-                        let caret_rect = RectDIP {
-                            x: bounds.x + x,
-                            y: bounds.y + m.top,
-                            width: CARET_WIDTH,
-                            height: m.height,
-                        };
-                        recorder.fill_rectangle(&caret_rect, color);
-                    } else if sel_start == sel_end {
-                        let mut x = 0.0f32;
-                        let mut y = 0.0f32;
-                        let mut m = DWRITE_HIT_TEST_METRICS::default();
-
-                        layout.HitTestTextPosition(
-                            self.selection_active,
-                            false,
-                            &mut x,
-                            &mut y,
-                            &mut m,
-                        )?;
-                        let caret_rect = RectDIP {
-                            x: bounds.x + x,
-                            y: bounds.y + m.top,
-                            width: CARET_WIDTH,
-                            height: m.height,
-                        };
-                        recorder.fill_rectangle(&caret_rect, color);
-                    }
+        let layout = self.layout.as_deref().expect("layout not built");
+
+        let caret_visible =
+            (((now - self.focused_at.unwrap_or(now)).as_secs_f64()) / BLINK_TIME) % 2.0 < 1.0;
+
+        // Normal rendering: selection, base text, caret
+        self.draw_selection_with_recorder(layout, recorder, bounds);
+
+        // Draw text using command recorder
+        let color = style.color.unwrap_or_default();
+        recorder.draw_text(&bounds, self.text_resource(), color, &style.text_shadows);
+
+        // OLE drag-over preview caret
+        if let Some(drop) = self.ole_drop_preview16 {
+            let drop = self.snap_to_scalar_boundary(drop);
+            let (src_start, src_end) = self.selection_range();
+            if !(self.has_selection() && drop >= src_start && drop <= src_end) {
+                recorder.fill_rectangle(&Self::caret_rect(layout, drop, bounds), color);
+            }
+        } else {
+            // Draw caret if there's no selection (1 DIP wide bar)
+            let sel_start = self.selection_anchor.min(self.selection_active);
+            let sel_end = self.selection_anchor.max(self.selection_active);
+            if shell.focus_manager.is_focused(id) && caret_visible {
+                if self.is_composing() {
+                    let ime_caret_pos = sel_start + self.ime_cursor16;
+                    recorder
+                        .fill_rectangle(&Self::caret_rect(layout, ime_caret_pos, bounds), color);
+                } else if sel_start == sel_end {
+                    recorder.fill_rectangle(
+                        &Self::caret_rect(layout, self.selection_active, bounds),
+                        color,
+                    );
                 }
             }
-
-            Ok(())
         }
+
+        Ok(())
     }
 
     /// Hit-test a point in DIPs against the given text with the provided layout bounds.
     pub fn hit_test_index(&self, x_dip: f32, y_dip: f32) -> Result<u32> {
-        unsafe {
-            let layout = self.layout.as_ref().expect("layout not built");
-            let mut trailing = windows::core::BOOL(0);
-            let mut inside = windows::core::BOOL(0);
-            let mut metrics = DWRITE_HIT_TEST_METRICS::default();
-            layout.HitTestPoint(x_dip, y_dip, &mut trailing, &mut inside, &mut metrics)?;
-
-            let mut idx = if trailing.as_bool() {
-                metrics.textPosition.saturating_add(metrics.length)
-            } else {
-                metrics.textPosition
-            };
-            let total_len = self.text.encode_utf16().count() as u32;
-            if idx > total_len {
-                idx = total_len;
-            }
-            Ok(idx)
-        }
+        let layout = self.layout.as_ref().expect("layout not built");
+        let position = layout.hit_test_point(x_dip, y_dip).caret_position();
+
+        let idx = text_engine::byte_to_utf16_index(layout.text(), position) as u32;
+        let total_len = self.text.encode_utf16().count() as u32;
+        Ok(idx.min(total_len))
     }
 
     /// Reset blink timer and request scroll-into-view.
     /// Called whenever caret position changes.
     fn on_caret_move(&mut self) {
-        self.focused_at = Some(Instant::now());
+        self.caret_moved = true;
         self.needs_scroll_into_view = true;
     }
 
+    fn restart_blink_if_moved(&mut self, now: Instant) {
+        if std::mem::take(&mut self.caret_moved) {
+            self.focused_at = Some(now);
+        }
+    }
+
     fn clear_sticky_x(&mut self) {
         self.sticky_x_dip = None;
     }
@@ -1345,16 +1227,13 @@ impl<Message> WidgetState<Message> {
     }
 
     /// Helper method to determine the appropriate drop effect based on allowed effects
-    fn get_drop_effect(
-        &self,
-        allowed_effects: &windows::Win32::System::Ole::DROPEFFECT,
-    ) -> windows::Win32::System::Ole::DROPEFFECT {
-        if (allowed_effects.0 & DROPEFFECT_MOVE.0) != 0 {
-            DROPEFFECT_MOVE
-        } else if (allowed_effects.0 & DROPEFFECT_COPY.0) != 0 {
-            DROPEFFECT_COPY
+    fn get_drop_effect(&self, allowed_effects: &DropEffect) -> DropEffect {
+        if allowed_effects.contains(DropEffect::MOVE) {
+            DropEffect::MOVE
+        } else if allowed_effects.contains(DropEffect::COPY) {
+            DropEffect::COPY
         } else {
-            DROPEFFECT_NONE
+            DropEffect::NONE
         }
     }
 
@@ -1372,7 +1251,7 @@ impl<Message> WidgetState<Message> {
         &mut self,
         text: &str,
         drop_idx: u32,
-        effect: windows::Win32::System::Ole::DROPEFFECT,
+        effect: DropEffect,
     ) -> Option<DropResult> {
         let is_same_widget = self.has_started_ole_drag;
         let (current_sel_start, current_sel_end) = self.selection_range();
@@ -1380,7 +1259,7 @@ impl<Message> WidgetState<Message> {
             && drop_idx >= current_sel_start
             && drop_idx <= current_sel_end;
 
-        if is_same_widget && effect == DROPEFFECT_MOVE {
+        if is_same_widget && effect == DropEffect::MOVE {
             // Handle move within the same widget
             if !dropping_over_selection
                 && drop_idx >= current_sel_start
@@ -1467,14 +1346,9 @@ impl<Message> WidgetState<Message> {
 
     /// Caret DIP position for a given UTF-16 index in the base layout.
     pub fn caret_pos_dip(&self, idx16: u32) -> Result<(f32, f32, f32)> {
-        unsafe {
-            let layout = self.layout.as_ref().expect("layout not built");
-            let mut x = 0.0f32;
-            let mut y = 0.0f32;
-            let mut m = DWRITE_HIT_TEST_METRICS::default();
-            layout.HitTestTextPosition(idx16, false, &mut x, &mut y, &mut m)?;
-            Ok((x, m.top, m.height))
-        }
+        let layout = self.layout.as_deref().expect("layout not built");
+        let caret = layout.caret_position(Self::layout_byte_index(layout, idx16));
+        Ok((caret.x, caret.y, caret.height))
     }
 
     /// Get the active caret position in UTF-16 code units.
@@ -1715,20 +1589,14 @@ impl<Message> WidgetState<Message> {
     }
 
     fn recalc_metrics(&mut self) -> Result<()> {
-        unsafe {
-            let mut metrics = DWRITE_TEXT_METRICS::default();
-            self.layout
-                .as_ref()
-                .expect("layout not built")
-                .GetMetrics(&mut metrics)?;
-            self.metric_bounds = RectDIP {
-                x: metrics.left,
-                y: metrics.top,
-                width: metrics.width,
-                height: metrics.height,
-            };
-            Ok(())
-        }
+        let metrics = self.layout.as_ref().expect("layout not built").metrics();
+        self.metric_bounds = RectDIP {
+            x: metrics.left,
+            y: metrics.top,
+            width: metrics.width,
+            height: metrics.height,
+        };
+        Ok(())
     }
 
     /// Rebuild text layout and recalculate metrics in one operation
@@ -2050,14 +1918,17 @@ impl<Message> WidgetState<Message> {
 
     /// Save the current state to the undo stack before making modifications
     fn save_undo_state(&mut self, operation_type: UndoOperationType) {
-        let current_time = self.created_at.elapsed().as_millis();
+        let current_time = self.event_time;
 
         // Check if we can merge with the previous operation
         if let Some(last_state) = self.undo_stack.last_mut() {
-            let time_diff = current_time - last_state.timestamp;
+            let within_merge_time = match (last_state.timestamp, current_time) {
+                (Some(last), Some(now)) => now.duration_since(last) < UNDO_MERGE_TIME,
+                _ => false,
+            };
 
             // Merge successive character insertions/deletions within time window
-            if time_diff < UNDO_MERGE_TIME_MS
+            if within_merge_time
                 && last_state.operation_type == operation_type
                 && (operation_type == UndoOperationType::CharacterInsertion
                     || operation_type == UndoOperationType::CharacterDeletion)
@@ -2096,7 +1967,7 @@ impl<Message> WidgetState<Message> {
                 text: self.text.clone(),
                 selection_anchor: self.selection_anchor,
                 selection_active: self.selection_active,
-                timestamp: self.created_at.elapsed().as_millis(),
+                timestamp: self.event_time,
                 operation_type: UndoOperationType::Other,
             };
             self.redo_stack.push(current_state);
@@ -2126,7 +1997,7 @@ impl<Message> WidgetState<Message> {
                 text: self.text.clone(),
                 selection_anchor: self.selection_anchor,
                 selection_active: self.selection_active,
-                timestamp: self.created_at.elapsed().as_millis(),
+                timestamp: self.event_time,
                 operation_type: UndoOperationType::Other,
             };
             self.undo_stack.push(current_state);
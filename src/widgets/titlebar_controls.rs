@@ -6,7 +6,7 @@
 
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 use crate::util::windows::is_windows_11;
 use crate::widgets::svg_path::ColorChoice;
@@ -111,13 +111,14 @@ pub fn minimize_button<Message: 'static + Send + Clone>(
         .clear()
         .with_bg_color(Color::TRANSPARENT)
         .with_adjust_style(move |state, _focused, shell, mut style| {
-            let now = Instant::now();
-            hover_anim
-                .borrow_mut()
-                .update(matches!(state, ButtonState::Hover | ButtonState::Pressed));
+            let now = shell.now();
+            hover_anim.borrow_mut().update(
+                matches!(state, ButtonState::Hover | ButtonState::Pressed),
+                now,
+            );
             press_anim
                 .borrow_mut()
-                .update(matches!(state, ButtonState::Pressed));
+                .update(matches!(state, ButtonState::Pressed), now);
 
             // Interpolate from normal -> hover
             let base_color = hover_anim.borrow().interpolate_using(
@@ -183,13 +184,14 @@ pub fn maximize_button<Message: 'static + Send + Clone>(
         .clear()
         .with_bg_color(Color::TRANSPARENT)
         .with_adjust_style(move |state, _focused, shell, mut style| {
-            let now = Instant::now();
-            hover_anim
-                .borrow_mut()
-                .update(matches!(state, ButtonState::Hover | ButtonState::Pressed));
+            let now = shell.now();
+            hover_anim.borrow_mut().update(
+                matches!(state, ButtonState::Hover | ButtonState::Pressed),
+                now,
+            );
             press_anim
                 .borrow_mut()
-                .update(matches!(state, ButtonState::Pressed));
+                .update(matches!(state, ButtonState::Pressed), now);
 
             let base_color = hover_anim.borrow().interpolate_using(
                 shell,
@@ -257,13 +259,14 @@ pub fn close_button<Message: 'static + Send + Clone>(
         .clear()
         .with_bg_color(Color::TRANSPARENT)
         .with_adjust_style(move |state, _focused, shell, mut style| {
-            let now = Instant::now();
-            hover_anim
-                .borrow_mut()
-                .update(matches!(state, ButtonState::Hover | ButtonState::Pressed));
+            let now = shell.now();
+            hover_anim.borrow_mut().update(
+                matches!(state, ButtonState::Hover | ButtonState::Pressed),
+                now,
+            );
             press_anim
                 .borrow_mut()
-                .update(matches!(state, ButtonState::Pressed));
+                .update(matches!(state, ButtonState::Pressed), now);
 
             let base_color = hover_anim.borrow().interpolate_using(
                 shell,
@@ -2,8 +2,6 @@ use std::any::Any;
 use std::fmt::Debug;
 use std::time::{Duration, Instant};
 
-use windows::Win32::Foundation::HWND;
-
 use crate::gfx::PointDIP;
 use crate::gfx::command_recorder::CommandRecorder;
 use crate::layout::UIArenas;
@@ -288,11 +286,14 @@ impl<Message> Widget<Message> for Toggle<Message> {
         }
     }
 
+    fn layout_key(&self, _arenas: &UIArenas, _instance: &Instance) -> Option<u64> {
+        super::layout_key_of((self.width.to_bits(), self.height.to_bits()))
+    }
+
     fn update(
         &mut self,
         arenas: &mut UIArenas,
         instance: &mut Instance,
-        hwnd: HWND,
         shell: &mut Shell<Message>,
         event: &super::Event,
         bounds: Bounds,
@@ -307,14 +308,14 @@ impl<Message> Widget<Message> for Toggle<Message> {
                     self.checked = !self.checked;
 
                     // Update animation to new state
-                    state.animation.update(self.checked);
+                    state.animation.update(self.checked, shell.now());
 
                     // Fire toggle callback
                     if let Some(handler) = self.on_toggle.as_ref() {
                         handler(self.checked, arenas, shell);
                     }
 
-                    shell.request_redraw(hwnd, RedrawRequest::Immediate);
+                    shell.request_redraw(RedrawRequest::Immediate);
                 }
             }
             super::Event::MouseMove { x, y } | super::Event::MouseEnter { x, y } => {
@@ -324,7 +325,7 @@ impl<Message> Widget<Message> for Toggle<Message> {
 
                 if was_hover != state.is_hover {
                     state.update_state(self.enabled);
-                    shell.request_redraw(hwnd, RedrawRequest::Immediate);
+                    shell.request_redraw(RedrawRequest::Immediate);
                 }
             }
             super::Event::MouseLeave { .. } => {
@@ -333,7 +334,7 @@ impl<Message> Widget<Message> for Toggle<Message> {
 
                 if was_hover {
                     state.update_state(self.enabled);
-                    shell.request_redraw(hwnd, RedrawRequest::Immediate);
+                    shell.request_redraw(RedrawRequest::Immediate);
                 }
             }
             _ => {}